@@ -0,0 +1,82 @@
+// Like bevy_basic, but the planner only makes a new plan when the state of the
+// entity or its goals changed, instead of every frame.
+//
+// The entity gets hungry again every fifth frame, which is the only thing that
+// changes its state apart from eating, so it plans once for every time it gets
+// hungry and once after eating, when the goal is met
+
+use bevy::{log::LogPlugin, prelude::*};
+
+use bevy_dogoap::prelude::*;
+
+#[derive(Component, Clone, DatumComponent)]
+struct IsHungry(bool);
+
+#[derive(Component, Reflect, Clone, Default, ActionComponent)]
+struct EatAction;
+
+fn startup(mut commands: Commands) {
+    let goal = Goal::from_reqs(&[IsHungry::is(false)]);
+
+    let eat_action = EatAction::new().add_mutator(IsHungry::set(false));
+
+    let (mut planner, components) = create_planner!({
+        actions: [(EatAction, eat_action)],
+        state: [IsHungry(true)],
+        goals: [goal],
+    });
+
+    // Don't plan every frame, only once something changed
+    planner.always_plan = false;
+    planner.replan_on_change = true;
+
+    commands.spawn((Name::new("Planner"), planner, components));
+}
+
+fn handle_eat_action(
+    mut commands: Commands,
+    mut query: Query<(Entity, &EatAction, &mut IsHungry)>,
+) {
+    for (entity, _eat_action, mut need) in query.iter_mut() {
+        need.0 = false;
+        commands.entity(entity).remove::<EatAction>();
+        info!("Ate, IsHungry is false again");
+    }
+}
+
+fn get_hungry(mut frames: Local<usize>, mut query: Query<&mut IsHungry>) {
+    *frames += 1;
+    if *frames % 5 == 0 {
+        for mut need in query.iter_mut() {
+            need.0 = true;
+            info!("Got hungry");
+        }
+    }
+}
+
+// IsPlanning is added whenever the planner starts on a new plan
+fn count_plans(mut plans: Local<usize>, query: Query<Entity, Added<IsPlanning>>) {
+    for entity in query.iter() {
+        *plans += 1;
+        info!("Planning for {entity}, {} plans so far", *plans);
+    }
+}
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(DogoapPlugin);
+    app.add_plugins(LogPlugin {
+        filter: "replan_on_change=info".to_string(),
+        ..default()
+    });
+
+    register_components!(app, vec![IsHungry]);
+
+    app.add_systems(Startup, startup);
+    app.add_systems(Update, (handle_eat_action, get_hungry, count_plans));
+
+    for _i in 0..20 {
+        app.update();
+    }
+}
@@ -47,9 +47,14 @@ pub struct Planner {
     /// then turn it to false. Combine with always_plan set to false and you can manually decide when
     /// new plans should be made.
     pub plan_next_tick: bool,
+    /// Makes a new plan whenever `state` or `goals` changed since the last one, and
+    /// not otherwise. Set `always_plan` to false to only plan then
+    pub replan_on_change: bool,
 
     /// Internal prepared vector of just [`Action`]
     actions_for_dogoap: Vec<Action>,
+    /// The state and goals the last plan was made for, for `replan_on_change`
+    planned_for: Option<(LocalState, Vec<Goal>)>,
 }
 
 impl fmt::Debug for Planner {
@@ -105,13 +110,37 @@ impl Planner {
             current_plan: VecDeque::new(),
             always_plan: true,
             plan_next_tick: false,
+            replan_on_change: false,
             actions_for_dogoap,
+            planned_for: None,
         }
     }
 
     pub fn sort_goals(&mut self) {
         self.goals.sort_by(|a,b| b.priority.cmp(&a.priority));
     }
+
+    /// Whether `state` or `goals` are different from when the last plan was made,
+    /// or no plan was made yet
+    pub fn changed_since_last_plan(&self) -> bool {
+        match &self.planned_for {
+            Some((state, goals)) => *state != self.state || *goals != self.goals,
+            None => true,
+        }
+    }
+
+    /// Call after changing `actions_map`, like when reloading actions from data files,
+    /// with what changed (see [`diff_domains`]). The current plan is only thrown away
+    /// when it goes through an action that was removed or changed, new actions alone
+    /// leave it be
+    pub fn apply_domain_diff(&mut self, diff: &DomainDiff) {
+        self.actions_for_dogoap = self.actions_map.values().map(|(action, _)| action.clone()).collect();
+
+        if self.current_plan.iter().any(|key| diff.affects(key)) {
+            self.current_plan.clear();
+            self.plan_next_tick = true;
+        }
+    }
 }
 
 /// This system "syncs" our [`DatumComponent`]s with the LocalState in the [`Planner`]
@@ -140,11 +169,14 @@ pub fn create_planner_tasks(
     let thread_pool = AsyncComputeTaskPool::get();
 
     for (entity, mut planner) in query.iter_mut() {
-        if planner.always_plan {
+        let changed = planner.replan_on_change && planner.changed_since_last_plan();
+        if planner.always_plan || planner.plan_next_tick || changed {
+            planner.plan_next_tick = false;
             planner.sort_goals();
             let state = planner.state.clone();
             let actions = planner.actions_for_dogoap.clone();
             let goals = planner.goals.clone();
+            planner.planned_for = Some((state.clone(), goals.clone()));
 
             #[cfg(feature = "compute-pool")]
             let task = thread_pool.spawn(async move {
@@ -215,7 +247,7 @@ pub fn handle_planner_tasks(
         commands.entity(entity).remove::<ComputePlan>();
         match p {
             Some((plan, _cost)) => {
-                let effects = get_effects_from_plan(plan);
+                let effects: Vec<&Effect> = effects_of_plan(&plan).collect();
 
                 let effect_names: VecDeque<String> =
                     effects.iter().map(|i| i.action.to_string()).collect();
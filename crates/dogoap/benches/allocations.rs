@@ -0,0 +1,165 @@
+#![cfg_attr(feature = "bench", feature(test))]
+
+// Counts heap allocations while building a domain and while planning with it.
+// Preconditions, effects and mutators use inline storage, so building a typical
+// action should only allocate for the Strings it owns.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Returns the result of `f` together with how many allocations it did on this thread
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(|count| count.get());
+    let result = f();
+    let after = ALLOCATIONS.with(|count| count.get());
+    (result, after - before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::count_allocations;
+    use dogoap::prelude::*;
+
+    #[cfg(feature = "bench")]
+    extern crate test;
+    #[cfg(feature = "bench")]
+    use test::Bencher;
+
+    fn build_domain() -> Vec<Action> {
+        let sleep_action = Action::new("sleep")
+            .add_mutator(Mutator::Increment("energy".to_string(), Datum::I64(10)));
+
+        let eat_action = Action::new("eat")
+            .add_mutator(Mutator::Decrement("hunger".to_string(), Datum::I64(10)))
+            .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(25)));
+
+        let rob_people = Action::new("rob")
+            .add_mutator(Mutator::Increment("gold".to_string(), Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("energy".to_string(), Datum::I64(5)))
+            .with_precondition("hunger", Compare::LessThanEquals(Datum::I64(50)))
+            .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(50)))
+            .with_precondition("gold", Compare::LessThanEquals(Datum::I64(10)));
+
+        vec![sleep_action, eat_action, rob_people]
+    }
+
+    fn small_start() -> LocalState {
+        LocalState::new()
+            .with_datum("energy", Datum::I64(30))
+            .with_datum("hunger", Datum::I64(70))
+            .with_datum("gold", Datum::I64(0))
+    }
+
+    // The same state with sixty keys the actions never touch, like an agent that
+    // knows a lot about the world. Every successor still only changes one or two
+    // keys.
+    fn wide_start() -> LocalState {
+        let mut start = small_start();
+        for i in 0..60 {
+            start = start.with_datum(format!("fact_{}", i), Datum::Bool(i % 2 == 0));
+        }
+        start
+    }
+
+    fn plan_from(start: &LocalState, actions: &[Action]) -> Option<(Vec<Node>, usize)> {
+        let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(7)));
+
+        make_plan(start, actions, &goal)
+    }
+
+    #[test]
+    fn domain_construction_only_allocates_strings() {
+        let (actions, allocations) = count_allocations(build_domain);
+
+        // Every action owns its key, one Effect label and one String per
+        // mutator and precondition, plus the outer Vec holding the domain.
+        let strings: usize = actions
+            .iter()
+            .map(|action| {
+                2 + action.preconditions.len()
+                    + action
+                        .effects
+                        .iter()
+                        .map(|effect| effect.mutators.len())
+                        .sum::<usize>()
+            })
+            .sum();
+        assert_eq!(strings + 1, allocations);
+
+        for action in &actions {
+            assert!(!action.preconditions.spilled());
+            assert!(!action.effects.spilled());
+            for effect in &action.effects {
+                assert!(!effect.mutators.spilled());
+            }
+        }
+    }
+
+    #[test]
+    fn report_planning_allocations() {
+        let actions = build_domain();
+        let start = small_start();
+        let (plan, allocations) = count_allocations(|| plan_from(&start, &actions));
+        let (nodes, _cost) = plan.unwrap();
+        println!(
+            "planning allocated {} times for a plan of {} nodes",
+            allocations,
+            nodes.len()
+        );
+    }
+
+    // Successors copy the values of the state they come from once and share its
+    // keys, so the keys nothing touches don't cost any allocations
+    #[test]
+    fn wide_state_allocates_like_a_small_one() {
+        let actions = build_domain();
+        let (small, wide) = (small_start(), wide_start());
+        let (small_plan, small_allocations) = count_allocations(|| plan_from(&small, &actions));
+        let (wide_plan, wide_allocations) = count_allocations(|| plan_from(&wide, &actions));
+
+        assert_eq!(small_plan.unwrap().1, wide_plan.unwrap().1);
+        assert_eq!(small_allocations, wide_allocations);
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_domain_construction(b: &mut Bencher) {
+        b.iter(build_domain);
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_successor_generation(b: &mut Bencher) {
+        let actions = build_domain();
+        let start = small_start();
+        b.iter(|| plan_from(&start, &actions));
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_wide_state(b: &mut Bencher) {
+        let actions = build_domain();
+        let start = wide_start();
+        b.iter(|| plan_from(&start, &actions));
+    }
+}
@@ -0,0 +1,73 @@
+#![cfg_attr(feature = "bench", feature(test))]
+
+#[cfg(test)]
+mod tests {
+    use dogoap::prelude::*;
+
+    #[cfg(feature = "bench")]
+    extern crate test;
+    #[cfg(feature = "bench")]
+    use test::Bencher;
+
+    const TIERS: usize = 16;
+    const CHORES: usize = 6;
+
+    // Ore worked up through sixteen tiers, one craft each, next to chores that
+    // never help but cost as much. The forward search tries every mix of chores
+    // at every tier, the backward one keeps undoing crafts it already undid, so
+    // meeting in the middle saves both of them most of the way.
+    fn crafting_chain() -> (LocalState, Vec<Action>, Goal) {
+        let mut start = LocalState::new().with_datum("ore", Datum::I64(1));
+        let mut actions = vec![];
+        let mut previous = "ore".to_string();
+        for tier in 0..TIERS {
+            let item = format!("tier_{}", tier);
+            start = start.with_datum(&item, Datum::I64(0));
+            actions.push(
+                Action::new(&format!("craft_{}", item))
+                    .with_precondition(&previous, Compare::GreaterThanEquals(Datum::I64(1)))
+                    .add_mutator(Mutator::Decrement(previous.clone(), Datum::I64(1)))
+                    .add_mutator(Mutator::Increment(item.clone(), Datum::I64(1))),
+            );
+            previous = item;
+        }
+        for chore in 0..CHORES {
+            let done = format!("chore_{}", chore);
+            start = start.with_datum(&done, Datum::Bool(false));
+            actions.push(
+                Action::new(&format!("do_{}", done))
+                    .add_mutator(Mutator::Set(done.clone(), Datum::Bool(true))),
+            );
+        }
+        let goal = Goal::new().with_req(&previous, Compare::GreaterThanEquals(Datum::I64(1)));
+        (start, actions, goal)
+    }
+
+    fn plan_with(strategy: PlanningStrategy) -> Option<Plan> {
+        let (start, actions, goal) = crafting_chain();
+        make_plan_with_strategy(strategy, &start, &actions, &goal)
+    }
+
+    #[test]
+    fn test_crafting_chain() {
+        let forward = plan_with(PlanningStrategy::StartToGoal).unwrap();
+        assert_eq!(TIERS, forward.1);
+        assert_eq!(Some(forward), plan_with(PlanningStrategy::Bidirectional));
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_crafting_chain_start_to_goal(b: &mut Bencher) {
+        let (start, actions, goal) = crafting_chain();
+        b.iter(|| make_plan_with_strategy(PlanningStrategy::StartToGoal, &start, &actions, &goal));
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_crafting_chain_bidirectional(b: &mut Bencher) {
+        let (start, actions, goal) = crafting_chain();
+        b.iter(|| {
+            make_plan_with_strategy(PlanningStrategy::Bidirectional, &start, &actions, &goal)
+        });
+    }
+}
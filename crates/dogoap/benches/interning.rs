@@ -0,0 +1,85 @@
+#![cfg_attr(feature = "bench", feature(test))]
+
+#[cfg(test)]
+mod tests {
+    use dogoap::prelude::*;
+
+    #[cfg(feature = "bench")]
+    extern crate test;
+    #[cfg(feature = "bench")]
+    use test::Bencher;
+
+    const KEYS: usize = 20;
+    const ACTIONS: usize = 10;
+
+    // Ten actions over twenty counters with long names, the kind of keys that are
+    // slow to compare. Each fills a counter up to a point, as long as the one next
+    // to it stays low
+    fn counters<K: Key + From<String>>() -> (LocalState<K>, Vec<Action<K>>, Goal<K>) {
+        let key = |i: usize| format!("village.storehouse.counter_{}", i % KEYS);
+        let mut start = LocalState::default();
+        for i in 0..KEYS {
+            start = start.with_datum(key(i), Datum::I64(0));
+        }
+
+        let actions = (0..ACTIONS)
+            .map(|i| {
+                Action::named(&format!("fill_{}", i))
+                    .add_mutator(Mutator::Increment(key(i * 2).into(), Datum::I64(1)))
+                    .with_precondition(key(i * 2), Compare::LessThanEquals(Datum::I64(2)))
+                    .with_precondition(key(i * 2 + 1), Compare::LessThanEquals(Datum::I64(3)))
+            })
+            .collect();
+
+        let mut goal = Goal::default();
+        for i in 0..ACTIONS {
+            goal = goal.with_req(key(i * 2), Compare::GreaterThanEquals(Datum::I64(1)));
+        }
+
+        (start, actions, goal)
+    }
+
+    #[test]
+    fn test_interned_plan_matches() {
+        let (start, actions, goal) = counters::<String>();
+        let config = PlannerConfig::new();
+        let context = PlanningContext::new(&start, &actions, &goal, &config);
+
+        assert!(context.is_interned());
+        assert_eq!(
+            make_plan_with_stats(&start, &actions, &goal, &config),
+            context.make_plan_with_stats()
+        );
+
+        let (start, actions, goal) = counters::<Symbol>();
+        let (plan, stats) =
+            make_plan_with_stats(&start, &actions, &goal, &PlannerConfig::default());
+        assert_eq!(plan.unwrap().1, context.make_plan().unwrap().1);
+        assert_eq!(
+            stats.expanded_nodes,
+            context.make_plan_with_stats().1.expanded_nodes
+        );
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_string_keys(b: &mut Bencher) {
+        let (start, actions, goal) = counters::<String>();
+        b.iter(|| make_plan(&start, &actions, &goal));
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_interned_keys(b: &mut Bencher) {
+        let (start, actions, goal) = counters::<String>();
+        let context = PlanningContext::new(&start, &actions, &goal, &PlannerConfig::default());
+        b.iter(|| context.make_plan());
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_symbol_keys(b: &mut Bencher) {
+        let (start, actions, goal) = counters::<Symbol>();
+        b.iter(|| make_plan(&start, &actions, &goal));
+    }
+}
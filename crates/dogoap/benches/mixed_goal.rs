@@ -0,0 +1,87 @@
+#![cfg_attr(feature = "bench", feature(test))]
+
+#[cfg(test)]
+mod tests {
+    use dogoap::{
+        prelude::*,
+        simple::{simple_action, simple_increment_action},
+    };
+
+    #[cfg(feature = "bench")]
+    extern crate test;
+    #[cfg(feature = "bench")]
+    use test::Bencher;
+
+    const CHORES: usize = 5;
+
+    // One numeric requirement with a large gap next to a handful of booleans.
+    // Mining closes the gap in big steps but needs a pickaxe, while digging works
+    // right away in smaller, pricier steps, only near the surface, and floods the
+    // mine. Without normalization the gap makes every dig look like great progress,
+    // so the search digs itself into a dead end along every order of the chores
+    // before buying the pickaxe.
+    fn mixed_goal() -> (LocalState, Vec<Action>, Goal) {
+        let mut start = LocalState::new()
+            .with_datum("gold", Datum::I64(0))
+            .with_datum("has_pickaxe", Datum::Bool(false))
+            .with_datum("flooded", Datum::Bool(false));
+        let mut goal = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(500)));
+        let mut actions = vec![
+            simple_action("buy_pickaxe", "has_pickaxe", Datum::Bool(true)),
+            simple_increment_action("mine", "gold", Datum::I64(100))
+                .with_precondition("has_pickaxe", Compare::Equals(Datum::Bool(true)))
+                .with_precondition("flooded", Compare::Equals(Datum::Bool(false)))
+                .with_precondition("gold", Compare::LessThanEquals(Datum::I64(500))),
+            simple_increment_action("dig", "gold", Datum::I64(50))
+                .set_cost(2)
+                .add_mutator(Mutator::Set("flooded".to_string(), Datum::Bool(true)))
+                .with_precondition("gold", Compare::LessThanEquals(Datum::I64(200))),
+        ];
+
+        for i in 0..CHORES {
+            let key = format!("chore_{}", i);
+            start = start.with_datum(&key, Datum::Bool(false));
+            goal = goal.with_req(&key, Compare::Equals(Datum::Bool(true)));
+            actions.push(simple_action(
+                &format!("do_chore_{}", i),
+                &key,
+                Datum::Bool(true),
+            ));
+        }
+
+        (start, actions, goal)
+    }
+
+    fn plan(normalize: bool) -> (Option<Plan>, PlanStats) {
+        let (start, actions, goal) = mixed_goal();
+        let config = PlannerConfig::new().with_normalized_heuristic(normalize);
+        make_plan_with_stats(&start, &actions, &goal, &config)
+    }
+
+    #[test]
+    fn test_mixed_goal() {
+        let (raw, raw_stats) = plan(false);
+        let (normalized, normalized_stats) = plan(true);
+        let (raw, normalized) = (raw.unwrap(), normalized.unwrap());
+
+        assert_eq!(raw.1, normalized.1);
+        assert!(
+            normalized_stats.expanded_nodes < raw_stats.expanded_nodes,
+            "{} >= {}",
+            normalized_stats.expanded_nodes,
+            raw_stats.expanded_nodes
+        );
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_mixed_goal_raw(b: &mut Bencher) {
+        b.iter(|| plan(false));
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_mixed_goal_normalized(b: &mut Bencher) {
+        b.iter(|| plan(true));
+    }
+}
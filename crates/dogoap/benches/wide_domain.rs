@@ -0,0 +1,60 @@
+#![cfg_attr(feature = "bench", feature(test))]
+
+#[cfg(test)]
+mod tests {
+    use dogoap::{prelude::*, simple::simple_increment_action};
+
+    #[cfg(feature = "bench")]
+    extern crate test;
+    #[cfg(feature = "bench")]
+    use test::Bencher;
+
+    const KEYS: usize = 20;
+    const ACTIONS: usize = 50;
+
+    // Fifty actions over twenty counters, each with a precondition on another
+    // counter. Every expansion checks the preconditions of all fifty and applies
+    // the ones that pass, so this is mostly successor generation.
+    fn wide_domain() -> (LocalState, Vec<Action>, Goal) {
+        let mut start = LocalState::new();
+        for i in 0..KEYS {
+            start = start.with_datum(format!("counter_{}", i), Datum::I64(0));
+        }
+
+        let actions = (0..ACTIONS)
+            .map(|i| {
+                let key = format!("counter_{}", i % KEYS);
+                let other = format!("counter_{}", (i + 1) % KEYS);
+                let amount = 1 + (i / KEYS) as i64;
+                simple_increment_action(&format!("bump_{}", i), &key, Datum::I64(amount))
+                    .set_cost(1 + i % 3)
+                    .with_precondition(&other, Compare::LessThanEquals(Datum::I64(10)))
+            })
+            .collect();
+
+        let mut goal = Goal::new();
+        for i in (0..KEYS).step_by(5) {
+            goal = goal.with_req(
+                format!("counter_{}", i),
+                Compare::GreaterThanEquals(Datum::I64(4)),
+            );
+        }
+
+        (start, actions, goal)
+    }
+
+    #[test]
+    fn test_wide_domain() {
+        let (start, actions, goal) = wide_domain();
+        let (nodes, _cost) = make_plan(&start, &actions, &goal).unwrap();
+        let reached = nodes.last().unwrap().state();
+        assert!(goal.is_satisfied(reached));
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_wide_domain(b: &mut Bencher) {
+        let (start, actions, goal) = wide_domain();
+        b.iter(|| make_plan(&start, &actions, &goal));
+    }
+}
@@ -0,0 +1,53 @@
+#![cfg_attr(feature = "bench", feature(test))]
+
+#[cfg(test)]
+mod tests {
+    use dogoap::{prelude::*, simple::simple_increment_action};
+
+    #[cfg(feature = "bench")]
+    extern crate test;
+    #[cfg(feature = "bench")]
+    use test::Bencher;
+
+    const KEYS: usize = 12;
+
+    // Twelve counters that each need to reach 3, with one action per counter.
+    // Every node only changes one key, which is where computing the heuristic
+    // incrementally pays off compared to walking all twelve requirements.
+    fn wide_goal() -> (LocalState, Vec<Action>, Goal) {
+        let mut start = LocalState::new();
+        let mut goal = Goal::new();
+        let mut actions = vec![];
+
+        for i in 0..KEYS {
+            let key = format!("counter_{}", i);
+            start = start.with_datum(&key, Datum::I64(0));
+            goal = goal.with_req(&key, Compare::Equals(Datum::I64(3)));
+            actions.push(
+                simple_increment_action(&format!("bump_{}", i), &key, Datum::I64(1))
+                    .with_precondition(&key, Compare::LessThanEquals(Datum::I64(2))),
+            );
+        }
+
+        (start, actions, goal)
+    }
+
+    #[test]
+    fn test_wide_goal() {
+        let (start, actions, goal) = wide_goal();
+        let (_nodes, cost) = make_plan(&start, &actions, &goal).unwrap();
+        assert_eq!(KEYS * 3, cost);
+
+        // Normalizing only caps what each counter contributes, the plan stays the same
+        let config = PlannerConfig::new().with_normalized_heuristic(true);
+        let (_nodes, cost) = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+        assert_eq!(KEYS * 3, cost);
+    }
+
+    #[cfg(feature = "bench")]
+    #[bench]
+    fn bench_wide_goal(b: &mut Bencher) {
+        let (start, actions, goal) = wide_goal();
+        b.iter(|| make_plan(&start, &actions, &goal));
+    }
+}
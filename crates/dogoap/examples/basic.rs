@@ -11,20 +11,21 @@ fn main() {
 
     let eat_action = Action {
         key: "eat".to_string(),
-        preconditions: vec![],
-        effects: vec![Effect {
-            action: "eat".to_string(),
-            mutators: vec![Mutator::Set("is_hungry".to_string(), Datum::Bool(false))],
-            state: LocalState::new(),
-            cost: 1,
-        }],
+        preconditions: smallvec![],
+        dynamic_preconditions: vec![],
+        effects: smallvec![Effect::new("eat")
+            .with_mutator(Mutator::Set("is_hungry".to_string(), Datum::Bool(false)))],
+        dynamic_effects: vec![],
+        dynamic_cost: None,
+        subgoal: None,
+        tags: vec![],
     };
 
     let actions: Vec<Action> = vec![eat_action];
 
     let plan = make_plan(&start, &actions[..], &goal);
 
-    print_plan(plan.unwrap());
+    print_plan(&plan.unwrap());
 
     println!("");
     println!("[Everything went as expected!]");
@@ -0,0 +1,77 @@
+use std::fmt;
+
+use dogoap::prelude::*;
+use rust_decimal::prelude::*;
+
+// Money doesn't fit any of the `Datum` variants: `f64` can't hold 0.10 exactly,
+// and counting cents in an `i64` everywhere gets old fast. Wrapping a decimal
+// type and implementing `DatumLike` for it lets the planner work with it
+// directly, with every key holding a `Money`.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
+struct Money(Decimal);
+
+impl Money {
+    fn new(units: i64, cents: i64) -> Self {
+        Money(Decimal::new(units * 100 + cents, 2))
+    }
+}
+
+impl DatumLike for Money {
+    // There's only one kind of money, so nothing for `typecheck_domain` to tell apart
+    type Kind = ();
+
+    fn kind(&self) {}
+
+    // Whole units, so the heuristic doesn't consider 0.01 to be as far as 1.00
+    fn distance(&self, other: &Money) -> u64 {
+        (self.0 - other.0).abs().ceil().to_u64().unwrap_or(u64::MAX)
+    }
+
+    fn magnitude(&self) -> u64 {
+        self.0.abs().ceil().to_u64().unwrap_or(u64::MAX)
+    }
+
+    fn checked_add(&self, other: &Money) -> Option<Money> {
+        self.0.checked_add(other.0).map(Money)
+    }
+
+    fn checked_sub(&self, other: &Money) -> Option<Money> {
+        self.0.checked_sub(other.0).map(Money)
+    }
+}
+
+// Only needed for printing the plan
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "${}", self.0)
+    }
+}
+
+fn main() {
+    // `new()` is only there for `Datum` values, other value types start from
+    // `default()` and `named()`
+    let start: LocalState<String, Money> = LocalState::default()
+        .with_datum("wallet", Money::new(0, 0))
+        .with_datum("piggy_bank", Money::new(2, 0));
+
+    let goal = Goal::default()
+        .with_req("wallet", Compare::GreaterThanEquals(Money::new(3, 50)))
+        .with_req("piggy_bank", Compare::GreaterThanEquals(Money::new(1, 0)));
+
+    let actions = [
+        Action::named("sell_lemonade")
+            .add_mutator(Mutator::Increment("wallet".to_string(), Money::new(0, 75))),
+        Action::named("raid_piggy_bank")
+            .with_precondition("piggy_bank", Compare::GreaterThanEquals(Money::new(1, 0)))
+            .add_mutator(Mutator::Decrement(
+                "piggy_bank".to_string(),
+                Money::new(1, 0),
+            ))
+            .add_mutator(Mutator::Increment("wallet".to_string(), Money::new(1, 0))),
+    ];
+
+    let plan = make_plan(&start, &actions, &goal).expect("there should be a plan");
+
+    print_plan(&plan);
+}
@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use dogoap::{
+    prelude::*,
+    simple::{simple_action, simple_decrement_action, simple_increment_action},
+};
+
+// Watches the search and counts which actions led to the nodes it expanded, to see
+// where it spends its time
+
+fn main() {
+    let start = LocalState::new()
+        .with_datum("energy", Datum::I64(30))
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("has_pickaxe", Datum::Bool(false));
+
+    let goal = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(5)));
+
+    let actions = vec![
+        simple_action("buy_pickaxe", "has_pickaxe", Datum::Bool(true)).set_cost(3),
+        simple_increment_action("mine", "gold", Datum::I64(2))
+            .with_precondition("has_pickaxe", Compare::Equals(Datum::Bool(true)))
+            .add_mutator(Mutator::Decrement("energy".to_string(), Datum::I64(10)))
+            .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(10))),
+        simple_increment_action("beg", "gold", Datum::I64(1)).set_cost(2),
+        simple_increment_action("sleep", "energy", Datum::I64(10)),
+        simple_decrement_action("wander", "energy", Datum::I64(5)),
+    ];
+
+    let expansions = Arc::new(Mutex::new(BTreeMap::<String, usize>::new()));
+    let counted = expansions.clone();
+    let config = PlannerConfig::new()
+        .with_on_expand(move |node: &Node, _cost, _heuristic| {
+            let action = node.action_key().unwrap_or("(start)").to_string();
+            *counted.lock().unwrap().entry(action).or_default() += 1;
+        })
+        .with_on_finish(|plan, stats| {
+            println!(
+                "Search done after expanding {} nodes, found a plan: {}",
+                stats.expanded_nodes,
+                plan.is_some()
+            );
+        });
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+    print_plan(&plan);
+
+    println!("Nodes expanded, by the action that led to them:");
+    for (action, count) in expansions.lock().unwrap().iter() {
+        println!("{:>12} {:>3} {}", action, count, "#".repeat(*count));
+    }
+}
@@ -23,27 +23,23 @@ fn main() {
         .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(25)));
 
     let rob_people = simple_increment_action("rob", "gold", Datum::I64(1))
-        .with_effect(Effect {
-            action: "rob".to_string(),
-            mutators: vec![
-                Mutator::Decrement("energy".to_string(), Datum::I64(5)),
-                Mutator::Increment("hunger".to_string(), Datum::I64(5)),
-            ],
-            state: LocalState::default(),
-            cost: 1,
-        })
+        .with_effect(
+            Effect::new("rob")
+                .with_mutator(Mutator::Decrement("energy".to_string(), Datum::I64(5)))
+                .with_mutator(Mutator::Increment("hunger".to_string(), Datum::I64(5))),
+        )
         .with_precondition("hunger", Compare::LessThanEquals(Datum::I64(50)))
         .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(50)));
 
     let actions: Vec<Action> = vec![sleep_action, eat_action, rob_people];
 
-    let plan = make_plan(&start, &actions[..], &goal);
-    let effects = get_effects_from_plan(plan.clone().unwrap().0);
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    let effects: Vec<&Effect> = effects_of_plan(&plan.0).collect();
     assert_eq!(11, effects.len());
 
-    print_plan(plan.clone().unwrap());
+    print_plan(&plan);
 
     // visualize_plan(plan.unwrap(), "my-plan.dot");
 
-    assert_eq!(expected_state, effects.last().unwrap().state);
+    assert_eq!(Some(expected_state), effects.last().unwrap().state);
 }
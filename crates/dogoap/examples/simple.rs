@@ -19,7 +19,7 @@ fn main() {
 
     println!("{:#?}", plan);
 
-    print_plan(plan.unwrap());
+    print_plan(&plan.unwrap());
 
     println!("");
     println!("[Everything went as expected!]");
@@ -0,0 +1,111 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dogoap::prelude::*;
+
+// This example plans the same thing twice, once with the usual `String` keys and
+// once with a fieldless enum as the key type. Copies of a state share its keys
+// whatever their type, but enum keys are `Copy`, so nothing else the planner does
+// with them allocates a key either. The allocations that are left come from the
+// states themselves and from the action names, which stay `String`s whatever the
+// key type is.
+
+/// Counts every allocation made by the program
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Runs `f` and returns what it returned and how many allocations it made
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    (result, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Need {
+    Hunger,
+    Energy,
+    Gold,
+}
+
+// Only needed for printing the plan
+impl fmt::Display for Need {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+fn main() {
+    // With `String` keys, like in the other examples
+    let start = LocalState::new()
+        .with_datum("hunger", Datum::I64(60))
+        .with_datum("energy", Datum::I64(10))
+        .with_datum("gold", Datum::I64(0));
+    let goal = Goal::new()
+        .with_req("hunger", Compare::LessThanEquals(Datum::I64(10)))
+        .with_req("gold", Compare::GreaterThanEquals(Datum::I64(5)));
+    let actions = vec![
+        Action::new("eat")
+            .with_precondition("gold", Compare::GreaterThanEquals(Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("hunger".to_string(), Datum::I64(25)))
+            .add_mutator(Mutator::Decrement("gold".to_string(), Datum::I64(1))),
+        Action::new("sleep").add_mutator(Mutator::Increment("energy".to_string(), Datum::I64(20))),
+        Action::new("work")
+            .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Decrement("energy".to_string(), Datum::I64(10)))
+            .add_mutator(Mutator::Increment("gold".to_string(), Datum::I64(3))),
+    ];
+    let (string_plan, string_allocations) =
+        count_allocations(|| make_plan(&start, &actions, &goal));
+
+    // The same domain with `Need` as the key type. `new()` is only there for
+    // `String` keys, so the other key types start from `default()` and `named()`
+    let start = LocalState::default()
+        .with_datum(Need::Hunger, Datum::I64(60))
+        .with_datum(Need::Energy, Datum::I64(10))
+        .with_datum(Need::Gold, Datum::I64(0));
+    let goal = Goal::default()
+        .with_req(Need::Hunger, Compare::LessThanEquals(Datum::I64(10)))
+        .with_req(Need::Gold, Compare::GreaterThanEquals(Datum::I64(5)));
+    let actions = vec![
+        Action::named("eat")
+            .with_precondition(Need::Gold, Compare::GreaterThanEquals(Datum::I64(1)))
+            .add_mutator(Mutator::Decrement(Need::Hunger, Datum::I64(25)))
+            .add_mutator(Mutator::Decrement(Need::Gold, Datum::I64(1))),
+        Action::named("sleep").add_mutator(Mutator::Increment(Need::Energy, Datum::I64(20))),
+        Action::named("work")
+            .with_precondition(Need::Energy, Compare::GreaterThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Decrement(Need::Energy, Datum::I64(10)))
+            .add_mutator(Mutator::Increment(Need::Gold, Datum::I64(3))),
+    ];
+    let (enum_plan, enum_allocations) = count_allocations(|| make_plan(&start, &actions, &goal));
+
+    let string_plan = string_plan.expect("String keys should find a plan");
+    let enum_plan = enum_plan.expect("enum keys should find a plan");
+    assert_eq!(
+        action_keys_of_plan(&string_plan.0).collect::<Vec<_>>(),
+        action_keys_of_plan(&enum_plan.0).collect::<Vec<_>>(),
+    );
+
+    print_plan(&enum_plan);
+
+    println!();
+    println!("Allocations while planning with String keys: {string_allocations}");
+    println!("Allocations while planning with enum keys:   {enum_allocations}");
+}
@@ -1,97 +1,284 @@
-use std::fmt::Debug;
+use core::fmt::Debug;
 // crate "dogoap" file action.rs
-use std::hash::{Hash, Hasher};
-use std::sync::Arc;
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "bevy_reflect")]
 use bevy_reflect::Reflect;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 
 use crate::compare::Compare;
+use crate::composite::Subgoal;
+use crate::datum::{Datum, DatumLike};
 use crate::effect::Effect;
-use crate::localstate::LocalState;
+use crate::goal::Goal;
+use crate::localstate::{Key, LocalState};
 use crate::mutator::Mutator;
 
+/// Inline storage for the static preconditions of an [`Action`]. Sized after
+/// the examples, where actions rarely have more than three preconditions.
+pub type Preconditions<K = String, D = Datum> = SmallVec<[(K, Compare<D>); 3]>;
+
+/// Inline storage for the [`Effect`]s of an [`Action`], usually just one.
+pub type Effects<K = String, D = Datum> = SmallVec<[Effect<K, D>; 1]>;
+
+/// Works out a precondition from the state it's checked against
+pub type DynamicPrecondition<K = String, D = Datum> =
+    Arc<dyn Fn(&LocalState<K, D>) -> Compare<D> + Send + Sync>;
+
+/// Works out an effect from the state it's taken in
+pub type DynamicEffect<K = String, D = Datum> =
+    Arc<dyn Fn(&LocalState<K, D>) -> Effect<K, D> + Send + Sync>;
+
+/// Works out what an action costs from the state it's taken in
+pub type DynamicCost<K = String, D = Datum> = Arc<dyn Fn(&LocalState<K, D>) -> usize + Send + Sync>;
+
 /// An `Action` represents something your Entity can do, granted the LocalState
 /// is as defined in the `preconditions`. It has a list of `Effect`s that apply
 /// if the NPC successfully executed the task.
-#[derive(Reflect, Clone, Default)]
-pub struct Action {
+#[derive(Clone)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "K: Key + Deserialize<'de>, D: Deserialize<'de>"))
+)]
+pub struct Action<K = String, D = Datum> {
     /// String like `eat_action`
     pub key: String,
     // TODO arguments coupled with Effects, maybe
     // pub argument: Option<Datum>,
     /// What preconditions need to be true before we can execute this action
-    pub preconditions: Vec<(String, Compare)>,
-    /// What preconditions need to be true before we can execute this action
-    pub dynamic_preconditions: Vec<(String, Arc<dyn Fn(&LocalState) -> Compare + Send + Sync>)>,
-    /// What is the outcome from doing this action
-    // TODO temporarily plural effects, as maybe we want to implement arguments with many effects...
-    pub effects: Vec<Effect>,
+    pub preconditions: Preconditions<K, D>,
+    /// What preconditions need to be true before we can execute this action. Closures
+    /// can't be serialized, so these are left out with the `serde` feature and an
+    /// action read back has none
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub dynamic_preconditions: Vec<(K, DynamicPrecondition<K, D>)>,
+    /// What is the outcome from doing this action. With more than one, the planner
+    /// picks whichever branch suits the plan, each costing what its own effect does.
+    /// [`make_contingent_plan`](crate::prelude::make_contingent_plan) sees them as
+    /// outcomes it doesn't get to pick instead
+    pub effects: Effects<K, D>,
+    /// Effects worked out from the state the action is taken in, like eating
+    /// restoring however much the food at hand is worth. Each is another branch
+    /// next to [`Action::effects`]. Left out with the `serde` feature as well
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub dynamic_effects: Vec<DynamicEffect<K, D>>,
+    /// What the action costs, worked out from the state it's taken in. Takes the
+    /// place of the cost of every effect. Like the dynamic preconditions, it's left
+    /// out with the `serde` feature
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub dynamic_cost: Option<DynamicCost<K, D>>,
+    /// Makes this a composite action, reaching a goal of its own with a plan over
+    /// other actions, see [`Action::with_subgoal`]
+    #[cfg_attr(feature = "bevy_reflect", reflect(ignore))]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subgoal: Option<Subgoal<K, D>>,
+    /// Labels like `combat` for picking out a group of actions, see
+    /// [`make_plan_with_filter`](crate::prelude::make_plan_with_filter). The planner
+    /// itself doesn't look at them
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
 }
 
-impl Debug for Action {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<K, D> Default for Action<K, D> {
+    fn default() -> Self {
+        Self {
+            key: String::new(),
+            preconditions: Preconditions::new(),
+            dynamic_preconditions: vec![],
+            effects: Effects::new(),
+            dynamic_effects: vec![],
+            dynamic_cost: None,
+            subgoal: None,
+            tags: vec![],
+        }
+    }
+}
 
+impl<K: Key, D: DatumLike> Debug for Action<K, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Action")
             .field("key", &self.key)
             .field("preconditions", &self.preconditions)
             .field("dynamic_preconditions", &self.get_dynamic_precondition())
             .field("effects", &self.effects)
+            .field("dynamic_effects", &self.dynamic_effects.len())
+            .field("dynamic_cost", &self.dynamic_cost.is_some())
+            .field("subgoal", &self.subgoal)
+            .field("tags", &self.tags)
             .finish()
     }
 }
 
-impl PartialEq for Action {
+impl<K: Key, D: DatumLike> PartialEq for Action<K, D> {
     fn eq(&self, other: &Self) -> bool {
-        self.key == other.key &&
-        self.preconditions == other.preconditions &&
-        self.effects == other.effects &&
-        self.get_dynamic_precondition() == other.get_dynamic_precondition()
+        self.key == other.key
+            && self.preconditions == other.preconditions
+            && self.effects == other.effects
+            && self.get_dynamic_precondition() == other.get_dynamic_precondition()
+            && self.dynamic_effects.len() == other.dynamic_effects.len()
+            && self.dynamic_cost.is_some() == other.dynamic_cost.is_some()
+            && self.subgoal == other.subgoal
+            && self.tags == other.tags
     }
 }
 
-impl Hash for Action {
+impl<K: Key, D: DatumLike> Hash for Action<K, D> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.key.hash(state);
         self.preconditions.hash(state);
         self.effects.hash(state);
+        self.dynamic_effects.len().hash(state);
+        self.dynamic_cost.is_some().hash(state);
+        self.subgoal.hash(state);
+        self.tags.hash(state);
     }
 }
 
 impl Action {
+    /// An action with `String` keys. Use [`Action::named`] for other keys
     pub fn new(key: &str) -> Self {
+        Self::named(key)
+    }
+}
+
+impl<K: Key, D: DatumLike> Action<K, D> {
+    pub fn named(key: &str) -> Self {
         Self {
             key: key.to_string(),
-            preconditions: vec![],
-            dynamic_preconditions: vec![],
-            effects: vec![],
+            ..Default::default()
         }
     }
 
-    fn get_dynamic_precondition(&self) -> Vec<String> {
-        self.dynamic_preconditions.iter().map(|(k, _)| k.to_string()).collect()
+    fn get_dynamic_precondition(&self) -> Vec<K> {
+        self.dynamic_preconditions
+            .iter()
+            .map(|(k, _)| k.clone())
+            .collect()
     }
 
-    pub fn with_precondition(mut self, key: &str, compare: Compare) -> Self {
-        self.preconditions.push((key.to_string(), compare));
+    pub fn with_precondition(mut self, key: impl Into<K>, compare: Compare<D>) -> Self {
+        self.preconditions.push((key.into(), compare));
         self
     }
 
-    pub fn with_effect(mut self, effect: Effect) -> Self {
+    pub fn with_effect(mut self, effect: Effect<K, D>) -> Self {
         self.effects.push(effect);
         self
     }
 
-    pub fn add_precondition(mut self, precondition: (String, Compare)) -> Self {
+    pub fn add_precondition(mut self, precondition: (K, Compare<D>)) -> Self {
         self.preconditions.push(precondition);
         self
     }
 
-    pub fn add_dynamic_precondition(mut self, precondition: (String, Arc<dyn Fn(&LocalState) -> Compare + Send + Sync>)) -> Self {
+    pub fn add_dynamic_precondition(
+        mut self,
+        precondition: (K, DynamicPrecondition<K, D>),
+    ) -> Self {
         self.dynamic_preconditions.push(precondition);
         self
     }
 
-    pub fn get_preconditions(&self, state: &LocalState) -> Vec<(String, Compare)> {
-        let mut preconditions = self.preconditions.clone();
+    /// Adds an effect that's worked out from the state the action is taken in, see
+    /// [`Action::dynamic_effects`]
+    pub fn with_dynamic_effect(mut self, effect: DynamicEffect<K, D>) -> Self {
+        self.dynamic_effects.push(effect);
+        self
+    }
+
+    /// Makes this a composite action, like `prepare_meal` standing for whatever gets
+    /// `goal` met with `sub_actions`. It can be taken wherever there's a plan for
+    /// `goal` that takes at least one of them, costs what that plan does, and ends
+    /// up in the state that plan ends in. Plans are worked out with the default
+    /// [`PlannerConfig`](crate::planner::PlannerConfig) every time the action is
+    /// considered, and [`flatten_plan`](crate::prelude::flatten_plan) swaps the step
+    /// for them afterwards. Like a dynamic effect, this keeps the planner searching
+    /// forwards
+    pub fn with_subgoal(mut self, goal: Goal<K, D>, sub_actions: Vec<Action<K, D>>) -> Self {
+        self.subgoal = Some(Subgoal {
+            goal,
+            actions: sub_actions,
+        });
+        self
+    }
+
+    /// Whether the action has any effect at all, static, dynamic or composite
+    pub fn has_effects(&self) -> bool {
+        !self.effects.is_empty() || self.has_dynamic_effects()
+    }
+
+    /// Whether any of the effects of the action are only known once there's a state
+    /// to take it in, as it has dynamic effects or a subgoal
+    pub fn has_dynamic_effects(&self) -> bool {
+        !self.dynamic_effects.is_empty() || self.subgoal.is_some()
+    }
+
+    /// The effects of the action when it's taken in `state`: the static ones, then
+    /// what the dynamic ones work out to, then the plan for the subgoal if there is
+    /// one
+    pub fn effects_in<'a>(
+        &'a self,
+        state: &'a LocalState<K, D>,
+    ) -> impl Iterator<Item = Cow<'a, Effect<K, D>>> + 'a {
+        let dynamic = self
+            .dynamic_effects
+            .iter()
+            .map(move |effect| Cow::Owned(effect(state)));
+        let composite = self
+            .subgoal
+            .iter()
+            .filter_map(move |subgoal| subgoal.effect_in(&self.key, state))
+            .map(Cow::Owned);
+        self.effects
+            .iter()
+            .map(Cow::Borrowed)
+            .chain(dynamic)
+            .chain(composite)
+    }
+
+    /// Works out the cost of the action from the state it's taken in, in place of
+    /// the cost of its effects. A cost of 0 makes it free in that state, which
+    /// like [`Action::set_free`] can let the search go around in circles
+    pub fn with_dynamic_cost(mut self, cost: DynamicCost<K, D>) -> Self {
+        self.dynamic_cost = Some(cost);
+        self
+    }
+
+    /// What taking `effect`, one of the effects of this action, costs in `state`.
+    /// That's the dynamic cost if there is one, and the cost of `effect` otherwise
+    pub fn cost_in(&self, state: &LocalState<K, D>, effect: &Effect<K, D>) -> usize {
+        match &self.dynamic_cost {
+            Some(cost) => cost(state),
+            None => effect.cost,
+        }
+    }
+
+    /// The preconditions of the action when it's taken in `state`: the static ones,
+    /// then what the dynamic ones work out to. Unlike [`Action::get_preconditions`]
+    /// nothing is copied, apart from the dynamic ones
+    pub fn preconditions_in<'a>(
+        &'a self,
+        state: &'a LocalState<K, D>,
+    ) -> impl Iterator<Item = (&'a K, Cow<'a, Compare<D>>)> + 'a {
+        let dynamic = self
+            .dynamic_preconditions
+            .iter()
+            .map(move |(key, getter)| (key, Cow::Owned(getter(state))));
+        self.preconditions
+            .iter()
+            .map(|(key, compare)| (key, Cow::Borrowed(compare)))
+            .chain(dynamic)
+    }
+
+    pub fn get_preconditions(&self, state: &LocalState<K, D>) -> Vec<(K, Compare<D>)> {
+        let mut preconditions = self.preconditions.to_vec();
 
         for (key, getter) in &self.dynamic_preconditions {
             preconditions.push((key.clone(), getter(state)));
@@ -100,22 +287,142 @@ impl Action {
         preconditions
     }
 
-    // TODO currently only handles one effect
-    pub fn add_mutator(mut self, mutator: Mutator) -> Self {
-        if self.effects.len() == 0 {
-            self.effects = vec![Effect::new(&self.key.clone()).with_mutator(mutator)];
-        } else {
-            let mut effect = self.effects[0].clone();
-            effect.mutators.push(mutator);
-            self.effects[0] = effect;
+    /// Adds `mutator` to the first effect, see [`Action::add_mutator_at`]
+    pub fn add_mutator(self, mutator: Mutator<K, D>) -> Self {
+        self.add_mutator_at(0, mutator)
+    }
+
+    /// Adds `mutator` to the effect at `index`. An `index` right past the last
+    /// effect starts a new one, named after the action, for another branch
+    ///
+    /// # Panics
+    ///
+    /// If `index` is further past the last effect than that
+    pub fn add_mutator_at(mut self, index: usize, mutator: Mutator<K, D>) -> Self {
+        if index == self.effects.len() {
+            self.effects.push(Effect::named(&self.key));
+        }
+        self.effects[index].mutators.push(mutator);
+        self
+    }
+
+    /// Sets the cost of the first effect, see [`Action::set_cost_at`]
+    ///
+    /// # Panics
+    ///
+    /// If the action has no effect yet, add a mutator first
+    pub fn set_cost(self, new_cost: usize) -> Self {
+        self.set_cost_at(0, new_cost)
+    }
+
+    /// Sets the cost of the first effect to a cost with a fraction, like a distance
+    /// in meters, in fixed point: `cost` in units of `1 / cost_scale`, rounded to
+    /// the nearest whole one. Plan with the same
+    /// [`PlannerConfig::cost_scale`](crate::planner::PlannerConfig::cost_scale), so
+    /// the heuristic weighs costs in the same units. Negative costs count as 0
+    ///
+    /// # Panics
+    ///
+    /// If the action has no effect yet, add a mutator first
+    pub fn set_cost_f32(self, cost: f32, cost_scale: usize) -> Self {
+        // Rounds half away from zero like `f32::round`, which needs std. Adding the
+        // half in f64 keeps it from rounding up what's just under one
+        let scaled = f64::from(cost * cost_scale as f32);
+        self.set_cost_at(0, (scaled + 0.5) as usize)
+    }
+
+    /// Sets the cost of the effect at `index`. Warns when `new_cost` is 0, as free
+    /// actions can send the search around in circles. Use [`Action::set_free`] for
+    /// actions that are meant to be free
+    ///
+    /// # Panics
+    ///
+    /// If the action has no effect at `index`
+    pub fn set_cost_at(mut self, index: usize, new_cost: usize) -> Self {
+        if new_cost == 0 {
+            log::warn!(
+                "Action {:?} costs nothing, use set_free if that's intended",
+                self.key
+            );
         }
+        self.effects[index].cost = new_cost;
         self
     }
 
-    pub fn set_cost(mut self, new_cost: usize) -> Self {
-        let mut effect = self.effects[0].clone();
-        effect.cost = new_cost;
-        self.effects[0] = effect;
+    /// How long this action takes, see [`Effect::duration`]
+    ///
+    /// # Panics
+    ///
+    /// If the action has no effect yet, add a mutator first
+    pub fn set_duration(mut self, duration: usize) -> Self {
+        self.effects[0].duration = duration;
+        self
+    }
+
+    /// Makes this action a last resort: it costs `usize::MAX`, so any plan without it
+    /// is picked over one with it. Costs add up without overflowing, plans that
+    /// can't do without it cost `usize::MAX` too
+    ///
+    /// # Panics
+    ///
+    /// If the action has no effect yet, add a mutator first
+    pub fn set_cost_max(mut self) -> Self {
+        self.effects[0].cost = usize::MAX;
+        self
+    }
+
+    /// Makes this action cost nothing. See [`find_zero_cost_cycles`](crate::prelude::find_zero_cost_cycles)
+    /// for checking it can't be taken back and forth for free
+    ///
+    /// # Panics
+    ///
+    /// If the action has no effect yet, add a mutator first
+    pub fn set_free(mut self) -> Self {
+        self.effects[0].cost = 0;
+        self
+    }
+
+    /// Makes the outcome of this action uncertain: its effect is split into one
+    /// outcome per value, each setting `key` to that value on top of what the effect
+    /// already does. Only [`make_contingent_plan`](crate::prelude::make_contingent_plan)
+    /// plans for every outcome, the other planners pick the one that suits them as if
+    /// it were a branch. Call this once the effect is complete
+    pub fn with_uncertain(
+        mut self,
+        key: impl Into<K>,
+        values: impl IntoIterator<Item = D>,
+    ) -> Self {
+        let key = key.into();
+        let effect = self.effects.remove(0);
+        self.effects = values
+            .into_iter()
+            .map(|value| {
+                effect
+                    .clone()
+                    .with_mutator(Mutator::Set(key.clone(), value))
+            })
+            .collect();
+        self
+    }
+
+    /// Adds `tag` to [`Action::tags`]
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// Whether `tag` is one of [`Action::tags`]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Takes `reward` off the cost of this action, see [`Effect::reward`]
+    ///
+    /// # Panics
+    ///
+    /// If the action has no effect yet, add a mutator first
+    pub fn set_reward(mut self, reward: usize) -> Self {
+        self.effects[0].reward = reward;
         self
     }
 }
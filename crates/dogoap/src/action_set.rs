@@ -0,0 +1,152 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+
+use crate::{
+    action::Action,
+    datum::{Datum, DatumLike},
+    error::PlanError,
+    localstate::Key,
+};
+
+/// [`Action`]s with at most one per key, for layering libraries of them, like a
+/// shared base with overrides for each kind of NPC. Actions stay in the order they
+/// were first inserted, and [`ActionSet::as_slice`] passes them to the planner
+#[derive(Clone)]
+pub struct ActionSet<K = String, D = Datum> {
+    actions: Vec<Action<K, D>>,
+}
+
+impl<K, D> Default for ActionSet<K, D> {
+    fn default() -> Self {
+        Self {
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl<K: Key, D: DatumLike> Debug for ActionSet<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.actions).finish()
+    }
+}
+
+impl<K: Key, D: DatumLike> PartialEq for ActionSet<K, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.actions == other.actions
+    }
+}
+
+impl ActionSet {
+    /// An empty set with `String` keys. Use [`ActionSet::default`] for other keys
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Key, D: DatumLike> ActionSet<K, D> {
+    /// Adds `action`, replacing the one with the same key if there is one. The
+    /// replacement takes its place in the order, and the replaced one is returned
+    pub fn insert(&mut self, action: Action<K, D>) -> Option<Action<K, D>> {
+        match self.position(&action.key) {
+            Some(index) => Some(core::mem::replace(&mut self.actions[index], action)),
+            None => {
+                self.actions.push(action);
+                None
+            }
+        }
+    }
+
+    pub fn with_action(mut self, action: Action<K, D>) -> Self {
+        self.insert(action);
+        self
+    }
+
+    /// Inserts every action of `other`, so they override the ones with the same key
+    pub fn merge(&mut self, other: ActionSet<K, D>) {
+        for action in other.actions {
+            self.insert(action);
+        }
+    }
+
+    /// Like [`ActionSet::merge`], but fails with [`PlanError::DuplicateActionKey`]
+    /// instead of overriding anything, leaving this set as it was
+    pub fn merge_strict(&mut self, other: ActionSet<K, D>) -> Result<(), PlanError> {
+        if let Some(action) = other.iter().find(|action| self.contains(&action.key)) {
+            return Err(PlanError::DuplicateActionKey(action.key.clone()));
+        }
+        self.actions.extend(other.actions);
+        Ok(())
+    }
+
+    /// Takes out the action with `key`, if there is one
+    pub fn remove(&mut self, key: &str) -> Option<Action<K, D>> {
+        let index = self.position(key)?;
+        Some(self.actions.remove(index))
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Action<K, D>> {
+        self.actions.iter().find(|action| action.key == key)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// The actions, for passing to [`make_plan`](crate::planner::make_plan) and
+    /// the other planners
+    pub fn as_slice(&self) -> &[Action<K, D>] {
+        &self.actions
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Action<K, D>> {
+        self.actions.iter()
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.actions.iter().position(|action| action.key == key)
+    }
+}
+
+impl<K, D> AsRef<[Action<K, D>]> for ActionSet<K, D> {
+    fn as_ref(&self) -> &[Action<K, D>] {
+        &self.actions
+    }
+}
+
+/// Later actions override earlier ones with the same key
+impl<K: Key, D: DatumLike> FromIterator<Action<K, D>> for ActionSet<K, D> {
+    fn from_iter<I: IntoIterator<Item = Action<K, D>>>(iter: I) -> Self {
+        let mut set = ActionSet::default();
+        for action in iter {
+            set.insert(action);
+        }
+        set
+    }
+}
+
+impl<K, D> IntoIterator for ActionSet<K, D> {
+    type Item = Action<K, D>;
+    type IntoIter = alloc::vec::IntoIter<Action<K, D>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.actions.into_iter()
+    }
+}
+
+impl<'a, K, D> IntoIterator for &'a ActionSet<K, D> {
+    type Item = &'a Action<K, D>;
+    type IntoIter = core::slice::Iter<'a, Action<K, D>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.actions.iter()
+    }
+}
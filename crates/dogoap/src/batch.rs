@@ -0,0 +1,102 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action,
+    compiled::CompiledAction,
+    datum::DatumLike,
+    goal::Goal,
+    localstate::{Key, LocalState},
+    planner::{
+        reading_unset_keys, search_plan, search_plan_compiled, validate_actions, with_defaults,
+        Interrupt, Plan, PlannerConfig,
+    },
+};
+
+/// One request for [`plan_many`]: where to start from and what to reach
+type Request<K, D> = (LocalState<K, D>, Goal<K, D>);
+
+/// One request for [`plan_many_with_actions`], with the actions it can plan with
+type RequestWithActions<'a, K, D> = (LocalState<K, D>, Goal<K, D>, &'a [Action<K, D>]);
+
+/// Makes one plan for each `(LocalState, Goal)` request, all sharing the same `actions`.
+///
+/// With the `rayon` feature enabled the searches are spread over rayon's thread pool,
+/// otherwise they run one after another. The `actions` are only read, so nothing is
+/// cloned per request.
+///
+/// What only depends on the actions is worked out once for the whole batch: they're
+/// checked for [`PlanError`](crate::prelude::PlanError)s, and their preconditions
+/// are compiled into [`CompiledAction`]s against the start state of the first
+/// request. Requests whose start states have the same keys as that one check
+/// preconditions straight from those, the others look their keys up as usual.
+///
+/// [`PlannerConfig::time_budget`] is a budget for the whole batch: once it runs out,
+/// searches still in progress are abandoned and return `None`. The same goes for
+/// cancelling the [`PlannerConfig::cancellation`] token.
+///
+/// The returned plans are in the same order as `requests`, no matter in which order
+/// the searches finish.
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`](crate::prelude::PlanError), which is checked
+/// once for the whole batch before any searching
+pub fn plan_many<K: Key + Send + Sync, D: DatumLike + Send + Sync>(
+    requests: &[Request<K, D>],
+    actions: &[Action<K, D>],
+    config: &PlannerConfig<K, D>,
+) -> Vec<Option<Plan<K, D>>> {
+    let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+    let config = &reading_unset_keys(config, &actions);
+    let Some((layout, _)) = requests.first() else {
+        return Vec::new();
+    };
+    let layout = with_defaults(layout, &config.defaults);
+    let compiled = CompiledAction::compile_all(&actions, &layout);
+    let interrupt = Interrupt::from_config(config);
+    let plan = |(start, goal): &Request<K, D>| {
+        search_plan_compiled(start, &actions, &compiled, goal, config, interrupt).0
+    };
+    map_requests(requests, plan)
+}
+
+/// Like [`plan_many`], but every request brings its own `actions`, for agents that
+/// can't all do the same things. Requests can share slices, they're only read.
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`](crate::prelude::PlanError) for any of the
+/// requests, which is checked for all of them before any searching
+pub fn plan_many_with_actions<K: Key + Send + Sync, D: DatumLike + Send + Sync>(
+    requests: &[RequestWithActions<K, D>],
+    config: &PlannerConfig<K, D>,
+) -> Vec<Option<Plan<K, D>>> {
+    let requests: Vec<_> = requests
+        .iter()
+        .map(|(start, goal, actions)| {
+            let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+            (start, goal, actions)
+        })
+        .collect();
+    let interrupt = Interrupt::from_config(config);
+    let plan = |(start, goal, actions): &(_, _, Cow<[Action<K, D>]>)| {
+        search_plan(*start, actions, *goal, config, interrupt, None, None).0
+    };
+    map_requests(&requests, plan)
+}
+
+/// Runs `plan` for every request, on rayon's thread pool if there is one, keeping
+/// the results in order
+fn map_requests<T: Sync, R: Send>(requests: &[T], plan: impl Fn(&T) -> R + Sync + Send) -> Vec<R> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        requests.par_iter().map(plan).collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        requests.iter().map(plan).collect()
+    }
+}
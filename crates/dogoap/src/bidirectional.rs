@@ -0,0 +1,183 @@
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action,
+    compare::compare_entry,
+    compiled::CompiledAction,
+    datum::DatumLike,
+    goal::Goal,
+    heuristic::Heuristic,
+    localstate::{InternalData, Key, LocalState},
+    planner::{
+        forward_plan, forward_successors, is_goal, Interrupt, Node, Plan, PlanStats, PlannerConfig,
+    },
+    regression::{
+        backward_successors, goal_requirements, replay, requirements_distance, BackwardStep,
+        Requirements,
+    },
+    search::{Astar, SearchEvent},
+};
+
+/// How many requirements the backward search expands at most. Every state the
+/// forward search expands is checked against all of them, and the other way around,
+/// so past that, looking for where the two meet costs more than it saves
+const MAX_BACKWARD_EXPANSIONS: usize = 256;
+
+/// Searches forwards from `start` and backwards from the [`Goal`] at the same time,
+/// one expansion each in turn, until a state the forward search took off its open
+/// list meets the requirements of one the backward search expanded, or the other
+/// way around. The plan goes forwards to that state and carries on with the actions
+/// the backward search found from there.
+///
+/// Returns `Some(None)` when the forward search ran out of states, as there's no
+/// plan then. Returns `None` when the actions found backwards can't be replayed, so
+/// callers can fall back to searching forwards. Running out of requirements to
+/// regress isn't the end, the forward search carries on alone.
+pub(crate) fn plan_bidirectional<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+    stats: &mut PlanStats,
+) -> Option<Option<Plan<K, D>>> {
+    let mut poll = interrupt.poll();
+    let unit = config.distance_unit();
+    let Some(goal_requirements) = goal_requirements(goal) else {
+        return Some(None);
+    };
+
+    let heuristic = &Heuristic::new(actions, goal, config);
+    let compiled = CompiledAction::compile_all(actions, start);
+    let mut expand = forward_successors(&compiled, heuristic, config, None);
+    let mut forward = Astar::new(
+        &Node::State(start.clone()),
+        (),
+        heuristic.distance(start) as usize,
+    );
+    let mut backward: Astar<Requirements<K, D>, BackwardStep> = Astar::new(
+        &goal_requirements,
+        None,
+        requirements_distance(start, &goal_requirements, unit),
+    );
+    // What each search expanded, for the other one to check against
+    let mut forward_seen: Vec<Node<K, D>> = Vec::new();
+    let mut backward_seen: Vec<Seen<K, D>> = Vec::new();
+    let mut backward_done = false;
+
+    loop {
+        // Which of `backward_seen` the node the forward search stopped at meets
+        let mut met = None;
+        let found = forward.resume(
+            1,
+            config.max_cost,
+            config.tie_seed,
+            stats,
+            || poll.is_due(),
+            &mut expand,
+            |node| {
+                met = None;
+                if is_goal(node, goal, &config.computed) {
+                    return true;
+                }
+                met = backward_seen
+                    .iter()
+                    .position(|seen| seen.met_in(node.state()));
+                met.is_some()
+            },
+            |event| {
+                if let SearchEvent::Expanded { expanded, .. } = event {
+                    forward_seen.push(expanded.clone());
+                }
+            },
+        );
+        match found {
+            Some(Ok((path, cost))) => {
+                let Some(met) = met else {
+                    return Some(Some(forward_plan(path, cost, stats)));
+                };
+                let (back, _) = backward.path_to(&backward_seen[met].requirements)?;
+                let nodes = path.into_iter().map(|(node, _)| node).collect();
+                return replay(nodes, &back, actions, goal, config, stats).map(Some);
+            }
+            Some(Err(_)) => return Some(None),
+            None => {}
+        }
+        if backward_done || backward_seen.len() >= MAX_BACKWARD_EXPANSIONS {
+            continue;
+        }
+
+        // Which of `forward_seen` meets the requirements the backward search stopped at
+        let mut met = None;
+        let found = backward.resume(
+            1,
+            config.max_cost,
+            config.tie_seed,
+            stats,
+            || poll.is_due(),
+            |requirements, _h, out| {
+                backward_successors(requirements, start, actions, unit, out);
+            },
+            |requirements| {
+                let seen = Seen::new(requirements, start);
+                met = forward_seen
+                    .iter()
+                    .position(|node| seen.met_in(node.state()));
+                met.is_some()
+            },
+            |event| {
+                if let SearchEvent::Expanded { expanded, .. } = event {
+                    backward_seen.push(Seen::new(expanded, start));
+                }
+            },
+        );
+        match found {
+            Some(Ok((back, _))) => {
+                // Cannot fail, `success` only holds when it met one
+                let met = met.unwrap();
+                let (path, _) = forward.path_to(&forward_seen[met])?;
+                let nodes = path.into_iter().map(|(node, _)| node).collect();
+                return replay(nodes, &back, actions, goal, config, stats).map(Some);
+            }
+            Some(Err(_)) => backward_done = true,
+            None => {}
+        }
+    }
+}
+
+/// Requirements the backward search expanded, with their keys looked up among those
+/// of `start` like a [`CompiledAction`] does, as every state the forward search
+/// expanded is checked against them
+struct Seen<K, D> {
+    requirements: Requirements<K, D>,
+    layout: InternalData<K, D>,
+    indices: Vec<Option<usize>>,
+}
+
+impl<K: Key, D: DatumLike> Seen<K, D> {
+    fn new(requirements: &Requirements<K, D>, start: &LocalState<K, D>) -> Self {
+        Self {
+            requirements: requirements.clone(),
+            layout: start.data.clone(),
+            indices: requirements
+                .iter()
+                .map(|(key, _)| start.data.index_of(key))
+                .collect(),
+        }
+    }
+
+    fn met_in(&self, state: &LocalState<K, D>) -> bool {
+        let shared = state.data.shares_keys_with(&self.layout);
+        self.requirements
+            .iter()
+            .zip(&self.indices)
+            .all(|((key, compare), index)| {
+                let value = match index {
+                    Some(index) if shared => Some(state.data.value_at(*index)),
+                    _ if shared => None,
+                    _ => state.data.get(key),
+                };
+                compare_entry(compare, value)
+            })
+    }
+}
@@ -0,0 +1,142 @@
+use alloc::string::String;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use foldhash::fast::FixedState;
+use indexmap::IndexMap;
+
+use crate::{
+    action::Action,
+    datum::{Datum, DatumLike},
+    goal::Goal,
+    localstate::{Key, LocalState},
+    planner::{make_plan_with_config, Plan, PlannerConfig},
+};
+
+/// Remembers the plans it made, for when many agents keep planning from the same
+/// state towards the same goal, like villagers in a colony sim. Plans are looked up
+/// by start state, goal and a fingerprint of the actions, so changing an action
+/// makes a new plan. Dynamic preconditions, effects and costs are closures, which
+/// the fingerprint can't see into, so [`PlanCache::clear`] it when those change.
+///
+/// Holds at most `capacity` plans, forgetting the one used longest ago to make
+/// room for a new one. No plan being found is remembered too
+#[derive(Clone, Debug)]
+pub struct PlanCache<K: Key = String, D: DatumLike = Datum> {
+    capacity: usize,
+    config: PlannerConfig<K, D>,
+    /// Least recently used first
+    entries: IndexMap<u64, Entry<K, D>, FixedState>,
+}
+
+#[derive(Clone, Debug)]
+struct Entry<K: Key, D: DatumLike> {
+    start: LocalState<K, D>,
+    goal: Goal<K, D>,
+    actions: u64,
+    plan: Option<Plan<K, D>>,
+}
+
+impl PlanCache {
+    /// A cache for `String` keys, see [`PlanCache::with_capacity`]
+    pub fn new(capacity: usize) -> Self {
+        Self::with_capacity(capacity)
+    }
+}
+
+impl<K: Key, D: DatumLike> PlanCache<K, D> {
+    /// A cache holding at most `capacity` plans, made with the default
+    /// [`PlannerConfig`]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            config: PlannerConfig::default(),
+            entries: IndexMap::default(),
+        }
+    }
+
+    /// Makes plans with `config` instead. It isn't part of what plans are looked up
+    /// by, so this clears the cache
+    pub fn with_config(mut self, config: PlannerConfig<K, D>) -> Self {
+        self.config = config;
+        self.clear();
+        self
+    }
+
+    /// The plan from `start` to `goal` with `actions`, like
+    /// [`make_plan_with_config`] makes it, only made again if it isn't remembered
+    pub fn make_plan(
+        &mut self,
+        start: &LocalState<K, D>,
+        actions: &[Action<K, D>],
+        goal: &Goal<K, D>,
+    ) -> Option<Plan<K, D>> {
+        let fingerprint = fingerprint(actions);
+        let key = FixedState::default().hash_one((start, goal, fingerprint));
+        if let Some(index) = self.entries.get_index_of(&key) {
+            let entry = &self.entries[index];
+            // Two different searches can hash the same, the newer one wins then
+            if entry.actions == fingerprint && entry.start == *start && entry.goal == *goal {
+                let last = self.entries.len() - 1;
+                self.entries.move_index(index, last);
+                return self.entries[last].plan.clone();
+            }
+            self.entries.shift_remove_index(index);
+        }
+
+        let plan = make_plan_with_config(start, actions, goal, &self.config);
+        if self.capacity == 0 {
+            return plan;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.shift_remove_index(0);
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                start: start.clone(),
+                goal: goal.clone(),
+                actions: fingerprint,
+                plan: plan.clone(),
+            },
+        );
+        plan
+    }
+
+    /// Forgets the plans from `start` to `goal`, whatever actions they were made with
+    pub fn invalidate(&mut self, start: &LocalState<K, D>, goal: &Goal<K, D>) {
+        self.entries
+            .retain(|_, entry| entry.start != *start || entry.goal != *goal);
+    }
+
+    /// Forgets every plan, for when the actions change in ways the cache can't tell
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// How many plans are remembered
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// Hashes everything about `actions` that can change a plan, apart from what's
+/// behind their closures. [`Effect`](crate::effect::Effect)s hash without their
+/// costs, so those are added here
+fn fingerprint<K: Key, D: DatumLike>(actions: &[Action<K, D>]) -> u64 {
+    let mut hasher = FixedState::default().build_hasher();
+    for action in actions {
+        action.hash(&mut hasher);
+        for effect in &action.effects {
+            (effect.cost, effect.reward, effect.duration).hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
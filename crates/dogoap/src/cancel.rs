@@ -0,0 +1,30 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Tells searches to stop, from any thread. Clones share the same flag, so give
+/// one to the [`PlannerConfig`](crate::planner::PlannerConfig) and keep another
+/// to call [`CancellationToken::cancel`] on when the plan isn't wanted anymore.
+///
+/// Once cancelled a token stays cancelled, make a new one for the next search.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks every search using this token to give up. They stop the next time they
+    /// check, see [`PlannerConfig::check_interval`](crate::planner::PlannerConfig::check_interval)
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn flag(&self) -> &AtomicBool {
+        &self.0
+    }
+}
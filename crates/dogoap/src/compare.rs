@@ -1,29 +1,85 @@
-use crate::{action::Action, datum::Datum, localstate::LocalState};
+use crate::{
+    action::Action,
+    datum::{Datum, DatumLike, FLOAT_EPSILON},
+    localstate::{Key, LocalState},
+};
+#[cfg(feature = "bevy_reflect")]
 use bevy_reflect::Reflect;
-use std::hash::{Hash, Hasher};
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Allows you to Compare [`Datum`] between each other. Used for Preconditions
 /// and in path finding until we reach our goal.
-#[derive(Reflect, Clone, Debug, PartialEq)]
-pub enum Compare {
-    Equals(Datum),
-    NotEquals(Datum),
-    GreaterThanEquals(Datum),
-    LessThanEquals(Datum),
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Compare<D = Datum> {
+    /// Equal to the value. `f64`s only have to be [`FLOAT_EPSILON`] apart
+    Equals(D),
+    /// Not [`Compare::Equals`] the value
+    NotEquals(D),
+    GreaterThanEquals(D),
+    LessThanEquals(D),
+    /// Anywhere from one bound to the other, both included. Bounds given the wrong
+    /// way around are swapped
+    Between(D, D),
+    /// A set with all of these members, see [`DatumLike::contains`]
+    Contains(D),
+    /// At most the second value apart from the value, see [`DatumLike::approx_eq`]
+    ApproxEquals(D, f64),
+    /// The key isn't in the [`LocalState`] at all, see
+    /// [`Mutator::Unset`](crate::mutator::Mutator::Unset)
+    Absent,
 }
 
-impl Compare {
-    pub fn value(&self) -> Datum {
+impl<D: Clone> Compare<D> {
+    /// The value compared against, the first bound for [`Compare::Between`]
+    ///
+    /// # Panics
+    ///
+    /// For [`Compare::Absent`], which has no value
+    pub fn value(&self) -> D {
         match self {
-            Compare::Equals(f) => *f,
-            Compare::NotEquals(f) => *f,
-            Compare::GreaterThanEquals(f) => *f,
-            Compare::LessThanEquals(f) => *f,
+            Compare::Equals(f) => f.clone(),
+            Compare::NotEquals(f) => f.clone(),
+            Compare::GreaterThanEquals(f) => f.clone(),
+            Compare::LessThanEquals(f) => f.clone(),
+            Compare::Between(f, _) => f.clone(),
+            Compare::Contains(f) => f.clone(),
+            Compare::ApproxEquals(f, _) => f.clone(),
+            Compare::Absent => panic!("Compare::Absent has no value"),
         }
     }
 }
 
-impl Hash for Compare {
+impl<D: DatumLike> Compare<D> {
+    /// The bounds of a [`Compare::Between`], lowest first
+    pub fn bounds(&self) -> Option<(&D, &D)> {
+        match self {
+            Compare::Between(a, b) if b < a => Some((b, a)),
+            Compare::Between(a, b) => Some((a, b)),
+            _ => None,
+        }
+    }
+
+    /// The value closest to `value` that the heuristic measures the distance to.
+    /// For [`Compare::Between`] that's `value` itself when it's in range, and the
+    /// nearest bound when it isn't
+    pub(crate) fn nearest(&self, value: &D) -> D {
+        match self.bounds() {
+            Some((low, _)) if value < low => low.clone(),
+            Some((_, high)) if value > high => high.clone(),
+            Some(_) => value.clone(),
+            None => self.value(),
+        }
+    }
+}
+
+// DatumLike values are Eq, so Compare can be too
+impl<D: DatumLike> Eq for Compare<D> {}
+
+impl<D: DatumLike> Hash for Compare<D> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
             Compare::Equals(datum) => {
@@ -42,32 +98,67 @@ impl Hash for Compare {
                 3_u8.hash(state);
                 datum.hash(state);
             }
+            Compare::Between(a, b) => {
+                4_u8.hash(state);
+                a.hash(state);
+                b.hash(state);
+            }
+            Compare::Contains(datum) => {
+                5_u8.hash(state);
+                datum.hash(state);
+            }
+            Compare::ApproxEquals(datum, epsilon) => {
+                6_u8.hash(state);
+                datum.hash(state);
+                epsilon.to_bits().hash(state);
+            }
+            Compare::Absent => {
+                7_u8.hash(state);
+            }
         }
     }
 }
 
-pub fn compare_values(comparison: &Compare, value: &Datum) -> bool {
+pub fn compare_values<D: DatumLike>(comparison: &Compare<D>, value: &D) -> bool {
     match comparison {
-        Compare::Equals(v) => value == v,
-        Compare::NotEquals(v) => value != v,
+        Compare::Equals(v) => value.approx_eq(v, FLOAT_EPSILON),
+        Compare::NotEquals(v) => !value.approx_eq(v, FLOAT_EPSILON),
         Compare::GreaterThanEquals(v) => value >= v,
         Compare::LessThanEquals(v) => value <= v,
+        Compare::Between(..) => {
+            let (low, high) = comparison.bounds().unwrap();
+            value >= low && value <= high
+        }
+        Compare::Contains(members) => value.contains(members),
+        Compare::ApproxEquals(v, epsilon) => value.approx_eq(v, *epsilon),
+        Compare::Absent => false,
+    }
+}
+
+/// Compares what a [`LocalState`] has for a key, `None` if it doesn't have the key.
+/// Only [`Compare::Absent`] holds for a missing key
+pub fn compare_entry<D: DatumLike>(comparison: &Compare<D>, value: Option<&D>) -> bool {
+    match value {
+        Some(value) => compare_values(comparison, value),
+        None => *comparison == Compare::Absent,
     }
 }
 
 /// Checks all the preconditions from the `Action` against passed in `LocalState`
 /// Returns `true` if all the preconditions pass (or if there is none), otherwise `false`
-pub fn check_preconditions(state: &LocalState, action: &Action) -> bool {
-    action.get_preconditions(state).iter().all(|(key, value)| {
-        let state_value = state
-            .data
-            .get(key)
-            .unwrap_or_else(|| panic!("Couldn't find key {:#?} in LocalState", key));
-        compare_values(value, state_value)
+pub fn check_preconditions<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    action: &Action<K, D>,
+) -> bool {
+    action.preconditions_in(state).all(|(key, value)| {
+        let state_value = state.data.get(key);
+        if state_value.is_none() && *value != Compare::Absent {
+            panic!("Couldn't find key {:#?} in LocalState", key);
+        }
+        compare_entry(&value, state_value)
     })
 }
 
-
 #[cfg(test)]
 mod test {
     use crate::compare::check_preconditions;
@@ -76,7 +167,7 @@ mod test {
 
     #[test]
     fn test_check_preconditions_empty() {
-        let state = LocalState::default().with_datum("is_hungry", Datum::Bool(true));
+        let state = LocalState::new().with_datum("is_hungry", Datum::Bool(true));
         let action = Action::default();
 
         let result = check_preconditions(&state, &action);
@@ -85,7 +176,7 @@ mod test {
 
     #[test]
     fn test_check_preconditions_true() {
-        let state = LocalState::default().with_datum("is_hungry", Datum::Bool(true));
+        let state = LocalState::new().with_datum("is_hungry", Datum::Bool(true));
         let action =
             Action::default().with_precondition("is_hungry", Compare::Equals(Datum::Bool(true)));
 
@@ -95,7 +186,7 @@ mod test {
 
     #[test]
     fn test_check_preconditions_false() {
-        let state = LocalState::default().with_datum("is_hungry", Datum::Bool(true));
+        let state = LocalState::new().with_datum("is_hungry", Datum::Bool(true));
         let action =
             Action::default().with_precondition("is_hungry", Compare::Equals(Datum::Bool(false)));
 
@@ -105,7 +196,7 @@ mod test {
 
     #[test]
     fn test_check_preconditions_conflicting_preconditions() {
-        let state = LocalState::default().with_datum("is_hungry", Datum::Bool(true));
+        let state = LocalState::new().with_datum("is_hungry", Datum::Bool(true));
 
         // False + True
         let action = Action::default()
@@ -186,4 +277,30 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn test_between() {
+        let cases = vec![
+            // is Y between 10 and 20?
+            (9, false),
+            (10, true),
+            (15, true),
+            (20, true),
+            (21, false),
+        ];
+
+        for (val, expected) in cases {
+            for between in [
+                Compare::Between(Datum::I64(10), Datum::I64(20)),
+                Compare::Between(Datum::I64(20), Datum::I64(10)),
+            ] {
+                let ret = compare_values(&between, &Datum::I64(val));
+                assert_eq!(
+                    ret, expected,
+                    "Expected {:?} to hold for {} to be {}, but compare_values returned {:#?}",
+                    between, val, expected, ret
+                );
+            }
+        }
+    }
 }
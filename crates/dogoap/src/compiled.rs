@@ -0,0 +1,161 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action,
+    compare::{check_preconditions, compare_entry, Compare},
+    datum::{Datum, DatumLike},
+    localstate::{InternalData, Key, LocalState},
+};
+
+/// An [`Action`] with the keys of its preconditions looked up ahead of time among
+/// the keys of a [`LocalState`]. A state with those very keys, like any state a
+/// search reaches from it until an action adds or removes a key, is checked by going
+/// straight to each value instead of searching for it. Other states are checked by
+/// looking each key up as usual, with the same result.
+///
+/// Dynamic preconditions are still worked out for every state, only their keys are
+/// looked up ahead of time.
+pub struct CompiledAction<'a, K = String, D = Datum> {
+    action: &'a Action<K, D>,
+    layout: InternalData<K, D>,
+    /// Where the key of each precondition is in `layout`, static ones first, in the
+    /// order they're checked in
+    indices: Vec<Option<usize>>,
+}
+
+impl<'a, K: Key, D: DatumLike> CompiledAction<'a, K, D> {
+    /// Looks up the keys of the preconditions of `action` in `layout`
+    pub fn new(action: &'a Action<K, D>, layout: &LocalState<K, D>) -> Self {
+        let keys = action
+            .preconditions
+            .iter()
+            .map(|(key, _)| key)
+            .chain(action.dynamic_preconditions.iter().map(|(key, _)| key));
+        Self {
+            action,
+            layout: layout.data.clone(),
+            indices: keys.map(|key| layout.data.index_of(key)).collect(),
+        }
+    }
+
+    /// [`CompiledAction::new`] for each of `actions`, in order
+    pub fn compile_all(actions: &'a [Action<K, D>], layout: &LocalState<K, D>) -> Vec<Self> {
+        actions
+            .iter()
+            .map(|action| Self::new(action, layout))
+            .collect()
+    }
+
+    /// Makes `state` share its keys with the state the action was compiled against,
+    /// if it has the same keys. Checking it, and every state reached from it, then
+    /// goes straight to the values even though it was built separately
+    pub(crate) fn share_layout(&self, state: &mut LocalState<K, D>) {
+        state.data.share_keys_from(&self.layout);
+    }
+
+    /// The action that was compiled
+    pub fn action(&self) -> &'a Action<K, D> {
+        self.action
+    }
+
+    /// Whether the preconditions of the action hold in `state`
+    ///
+    /// # Panics
+    ///
+    /// If a precondition other than [`Compare::Absent`] needs a key `state` doesn't
+    /// have
+    pub fn check_preconditions(&self, state: &LocalState<K, D>) -> bool {
+        if !state.data.shares_keys_with(&self.layout) {
+            return check_preconditions(state, self.action);
+        }
+
+        let mut indices = self.indices.iter();
+        let mut holds = |key: &K, compare: &Compare<D>| {
+            let value = indices
+                .next()
+                .and_then(|index| index.map(|index| state.data.value_at(index)));
+            if value.is_none() && *compare != Compare::Absent {
+                panic!("Couldn't find key {:#?} in LocalState", key);
+            }
+            compare_entry(compare, value)
+        };
+        self.action
+            .preconditions
+            .iter()
+            .all(|(key, compare)| holds(key, compare))
+            && self
+                .action
+                .dynamic_preconditions
+                .iter()
+                .all(|(key, getter)| holds(key, &getter(state)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_compiled_and_uncompiled_checks_agree() {
+        let actions = [
+            Action::new("eat")
+                .with_precondition("hunger", Compare::GreaterThanEquals(Datum::I64(2)))
+                .with_precondition("food", Compare::NotEquals(Datum::I64(0))),
+            Action::new("rest")
+                .with_precondition("energy", Compare::LessThanEquals(Datum::I64(3)))
+                .with_precondition("threat", Compare::Absent),
+            Action::new("flee").add_dynamic_precondition((
+                "energy".to_string(),
+                Arc::new(|state: &LocalState| match state.data.get("hunger") {
+                    Some(Datum::I64(hunger)) => Compare::GreaterThanEquals(Datum::I64(*hunger)),
+                    _ => Compare::Equals(Datum::I64(0)),
+                }),
+            )),
+        ];
+        let layout = LocalState::new()
+            .with_datum("energy", Datum::I64(0))
+            .with_datum("food", Datum::I64(0))
+            .with_datum("hunger", Datum::I64(0));
+        let compiled = CompiledAction::compile_all(&actions, &layout);
+
+        // xorshift, so the states are the same every run
+        let mut seed = 0x2545_f491_u64;
+        let mut random = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % 5) as i64
+        };
+        for _ in 0..500 {
+            let mut state = layout.clone();
+            for key in ["energy", "food", "hunger"] {
+                state.data.insert(key.to_string(), Datum::I64(random()));
+            }
+            // Same values, but keys of its own
+            let mut rebuilt = LocalState::new();
+            for (key, value) in state.data.iter() {
+                rebuilt.data.insert(key.clone(), *value);
+            }
+            // A key more, for the absent precondition
+            let threatened = state.clone().with_datum("threat", Datum::Bool(true));
+
+            assert!(state.data.shares_keys_with(&layout.data));
+            assert!(!rebuilt.data.shares_keys_with(&layout.data));
+            for state in [&state, &rebuilt, &threatened] {
+                for (compiled, action) in compiled.iter().zip(&actions) {
+                    assert_eq!(
+                        check_preconditions(state, action),
+                        compiled.check_preconditions(state),
+                        "{} in {:?}",
+                        action.key,
+                        state
+                    );
+                }
+            }
+        }
+    }
+}
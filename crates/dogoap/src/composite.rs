@@ -0,0 +1,170 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    action::Action,
+    datum::{Datum, DatumLike},
+    effect::Effect,
+    goal::Goal,
+    localstate::{Key, LocalState},
+    mutator::Mutator,
+    planner::{plan_duration, plan_totals, try_make_plan, Node, Plan},
+};
+
+/// What a composite [`Action`] does: reach `goal` with a plan of its own over
+/// `actions`, see [`Action::with_subgoal`]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "K: Key + Deserialize<'de>, D: Deserialize<'de>"))
+)]
+pub struct Subgoal<K = String, D = Datum> {
+    pub goal: Goal<K, D>,
+    pub actions: Vec<Action<K, D>>,
+}
+
+impl<K: Clone, D: Clone> Clone for Subgoal<K, D> {
+    fn clone(&self) -> Self {
+        Self {
+            goal: self.goal.clone(),
+            actions: self.actions.clone(),
+        }
+    }
+}
+
+impl<K: Key, D: DatumLike> Debug for Subgoal<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subgoal")
+            .field("goal", &self.goal)
+            .field("actions", &self.actions)
+            .finish()
+    }
+}
+
+impl<K: Key, D: DatumLike> PartialEq for Subgoal<K, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.goal == other.goal && self.actions == other.actions
+    }
+}
+
+impl<K: Key, D: DatumLike> Hash for Subgoal<K, D> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.goal.hash(state);
+        self.actions.hash(state);
+    }
+}
+
+impl<K: Key, D: DatumLike> Subgoal<K, D> {
+    /// The plan reaching the goal from `state`, planned like [`try_make_plan`] does.
+    /// `None` if there's none, or it doesn't take a single action as the goal is
+    /// met already
+    pub fn plan_from(&self, state: &LocalState<K, D>) -> Option<Plan<K, D>> {
+        try_make_plan(state, &self.actions, &self.goal)
+            .ok()
+            .flatten()
+            .filter(|(nodes, _)| nodes.len() > 1)
+    }
+
+    /// The plan from `state` as one effect of the action `key`: what the plan
+    /// costs, and a mutator for every key it changes
+    pub(crate) fn effect_in(&self, key: &str, state: &LocalState<K, D>) -> Option<Effect<K, D>> {
+        let (nodes, _) = self.plan_from(state)?;
+        let end = nodes.last().unwrap().state(); // Cannot fail, there's the start
+        let mut effect = Effect::named(key);
+        (effect.cost, effect.reward) = plan_totals(&nodes);
+        effect.duration = plan_duration(&nodes);
+        for (key, value) in end.data.iter() {
+            if state.data.get(key) != Some(value) {
+                effect
+                    .mutators
+                    .push(Mutator::Set(key.clone(), value.clone()));
+            }
+        }
+        for key in state.data.keys() {
+            if !end.data.contains_key(key) {
+                effect.mutators.push(Mutator::Unset(key.clone()));
+            }
+        }
+        Some(effect)
+    }
+}
+
+/// `plan` with every step of a composite action out of `actions` replaced by the
+/// steps of its own plan, down to actions that aren't composite, so every step can
+/// be carried out as it is. The plans are worked out again from the state before
+/// each step, which gets the same plan as planning does
+pub fn flatten_plan<K: Key, D: DatumLike>(
+    plan: &[Node<K, D>],
+    actions: &[Action<K, D>],
+) -> Vec<Node<K, D>> {
+    let mut flat: Vec<Node<K, D>> = Vec::with_capacity(plan.len());
+    for node in plan {
+        let subgoal = match node {
+            Node::Effect(effect) => actions
+                .iter()
+                .find(|action| action.key == effect.action)
+                .and_then(|action| action.subgoal.as_ref()),
+            Node::State(_) => None,
+        };
+        let steps = subgoal.zip(flat.last()).and_then(|(subgoal, before)| {
+            let (steps, _) = subgoal.plan_from(before.state())?;
+            Some(flatten_plan(&steps, &subgoal.actions))
+        });
+        match steps {
+            // Leaving out the state it starts from, which the step before ended in
+            Some(steps) => flat.extend(steps.into_iter().skip(1)),
+            None => flat.push(node.clone()),
+        }
+    }
+    flat
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    use super::*;
+    use crate::compare::Compare;
+
+    #[test]
+    fn test_effect_sets_what_the_plan_changed() {
+        let subgoal = Subgoal {
+            goal: Goal::new().with_req("cooked", Compare::Equals(Datum::Bool(true))),
+            actions: vec![
+                Action::new("light_fire")
+                    .add_mutator(Mutator::Set("fire".to_string(), Datum::Bool(true)))
+                    .add_mutator(Mutator::Unset("kindling".to_string())),
+                Action::new("cook")
+                    .with_precondition("fire", Compare::Equals(Datum::Bool(true)))
+                    .add_mutator(Mutator::Set("cooked".to_string(), Datum::Bool(true)))
+                    .set_cost(3),
+            ],
+        };
+        let state = LocalState::new()
+            .with_datum("cooked", Datum::Bool(false))
+            .with_datum("fire", Datum::Bool(false))
+            .with_datum("kindling", Datum::I64(1))
+            .with_datum("hungry", Datum::Bool(true));
+
+        let effect = subgoal.effect_in("prepare_meal", &state).unwrap();
+
+        assert_eq!("prepare_meal", effect.action);
+        assert_eq!(4, effect.cost);
+        assert_eq!(
+            vec![
+                Mutator::Set("cooked".to_string(), Datum::Bool(true)),
+                Mutator::Set("fire".to_string(), Datum::Bool(true)),
+                Mutator::Unset("kindling".to_string()),
+            ],
+            effect.mutators.to_vec()
+        );
+        let done = state.with_datum("cooked", Datum::Bool(true));
+        assert_eq!(None, subgoal.effect_in("prepare_meal", &done));
+    }
+}
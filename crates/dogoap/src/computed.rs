@@ -0,0 +1,63 @@
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::fmt::{self, Debug};
+
+use crate::{
+    datum::{Datum, DatumLike},
+    localstate::{Key, LocalState},
+};
+
+/// Works out the value of a computed key from the rest of the state
+pub type ComputedKey<K = String, D = Datum> = Arc<dyn Fn(&LocalState<K, D>) -> D + Send + Sync>;
+
+/// Keys whose value is derived from other keys instead of being stored, like a total
+/// weight made up of several item counts. See [`PlannerConfig::computed`](crate::planner::PlannerConfig::computed)
+pub struct ComputedKeys<K = String, D = Datum> {
+    pub keys: BTreeMap<K, ComputedKey<K, D>>,
+}
+
+impl<K, D> Default for ComputedKeys<K, D> {
+    fn default() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K, D> Clone for ComputedKeys<K, D>
+where
+    K: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+        }
+    }
+}
+
+impl<K: Debug, D> Debug for ComputedKeys<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.keys.keys()).finish()
+    }
+}
+
+impl<K: Key, D: DatumLike> ComputedKeys<K, D> {
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    /// The value of `key` in `state`. Keys the state has are read as they are, the
+    /// ones it doesn't have are derived if they're computed
+    pub fn value<'a>(&self, state: &'a LocalState<K, D>, key: &K) -> Option<Cow<'a, D>> {
+        match state.data.get(key) {
+            Some(value) => Some(Cow::Borrowed(value)),
+            None => self.keys.get(key).map(|compute| Cow::Owned(compute(state))),
+        }
+    }
+}
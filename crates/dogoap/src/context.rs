@@ -0,0 +1,370 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action,
+    compare::Compare,
+    computed::ComputedKeys,
+    datum::{Datum, DatumLike},
+    effect::Effect,
+    error::PlanError,
+    goal::Goal,
+    invariant::Invariant,
+    localstate::{Key, LocalState},
+    mutator::Mutator,
+    planner::{
+        make_plan_with_stats, try_make_plan_with_config, Node, Plan, PlanStats, PlannerConfig,
+    },
+};
+
+/// A planning problem with its `String` keys swapped for numbers, which are quicker
+/// to compare and hash. The numbers are handed out in the order of the keys, so the
+/// search goes exactly like it does with the strings and the plans come back the
+/// same, with their `String` keys.
+///
+/// Closures are written against states with `String` keys, so a problem with
+/// dynamic preconditions, effects or costs, computed keys, a custom heuristic or
+/// trace callbacks plans with its strings as usual. So does one with a
+/// [`PlannerConfig::tie_seed`], which would break ties differently with numbers.
+pub struct PlanningContext<D: DatumLike = Datum> {
+    problem: Problem<D>,
+}
+
+enum Problem<D: DatumLike> {
+    Interned {
+        /// The keys, sorted, so a key's number is where it is in here
+        names: Vec<String>,
+        start: LocalState<u32, D>,
+        actions: Vec<Action<u32, D>>,
+        goal: Goal<u32, D>,
+        config: PlannerConfig<u32, D>,
+    },
+    Strings {
+        start: LocalState<String, D>,
+        actions: Vec<Action<String, D>>,
+        goal: Goal<String, D>,
+        config: PlannerConfig<String, D>,
+    },
+}
+
+impl<D: DatumLike> PlanningContext<D> {
+    /// Swaps the keys of everything in the problem for numbers, if it can
+    pub fn new(
+        start: &LocalState<String, D>,
+        actions: &[Action<String, D>],
+        goal: &Goal<String, D>,
+        config: &PlannerConfig<String, D>,
+    ) -> Self {
+        if !can_intern(actions, config) {
+            let problem = Problem::Strings {
+                start: start.clone(),
+                actions: actions.to_vec(),
+                goal: goal.clone(),
+                config: config.clone(),
+            };
+            return Self { problem };
+        }
+
+        let names: Vec<String> = keys_of(start, actions, goal, config)
+            .into_iter()
+            .cloned()
+            .collect();
+        let id = |key: &String| names.binary_search(key).unwrap() as u32;
+        let problem = Problem::Interned {
+            start: map_state(start, &id),
+            actions: actions
+                .iter()
+                .map(|action| map_action(action, &id))
+                .collect(),
+            goal: map_goal(goal, &id),
+            config: map_config(config, &id),
+            names,
+        };
+        Self { problem }
+    }
+
+    /// Whether planning uses numbers for the keys, or has to stick to the strings
+    pub fn is_interned(&self) -> bool {
+        matches!(self.problem, Problem::Interned { .. })
+    }
+
+    /// The plan [`make_plan_with_config`](crate::planner::make_plan_with_config)
+    /// makes for the problem
+    pub fn make_plan(&self) -> Option<Plan<String, D>> {
+        self.make_plan_with_stats().0
+    }
+
+    /// Like [`PlanningContext::make_plan`], but also returns [`PlanStats`] about
+    /// the search
+    pub fn make_plan_with_stats(&self) -> (Option<Plan<String, D>>, PlanStats) {
+        match &self.problem {
+            Problem::Interned {
+                names,
+                start,
+                actions,
+                goal,
+                config,
+            } => {
+                let (plan, stats) = make_plan_with_stats(start, actions, goal, config);
+                (plan.map(|plan| unintern_plan(plan, names)), stats)
+            }
+            Problem::Strings {
+                start,
+                actions,
+                goal,
+                config,
+            } => make_plan_with_stats(start, actions, goal, config),
+        }
+    }
+
+    /// Like [`PlanningContext::make_plan`], but returns a [`PlanError`] instead of
+    /// panicking, like [`try_make_plan_with_config`]
+    pub fn try_make_plan(&self) -> Result<Option<Plan<String, D>>, PlanError> {
+        match &self.problem {
+            Problem::Interned {
+                names,
+                start,
+                actions,
+                goal,
+                config,
+            } => match try_make_plan_with_config(start, actions, goal, config) {
+                Ok(plan) => Ok(plan.map(|plan| unintern_plan(plan, names))),
+                Err(err) => Err(err.map_key(|id| names[id as usize].clone())),
+            },
+            Problem::Strings {
+                start,
+                actions,
+                goal,
+                config,
+            } => try_make_plan_with_config(start, actions, goal, config),
+        }
+    }
+}
+
+fn can_intern<D: DatumLike>(
+    actions: &[Action<String, D>],
+    config: &PlannerConfig<String, D>,
+) -> bool {
+    actions.iter().all(|action| {
+        action.dynamic_preconditions.is_empty()
+            && !action.has_dynamic_effects()
+            && action.dynamic_cost.is_none()
+    }) && config.computed.keys.is_empty()
+        && config.heuristic.is_none()
+        && config.on_expand.is_none()
+        && config.on_finish.is_none()
+        && config.tie_seed.is_none()
+}
+
+/// Every key anything in the problem mentions
+fn keys_of<'a, D: DatumLike>(
+    start: &'a LocalState<String, D>,
+    actions: &'a [Action<String, D>],
+    goal: &'a Goal<String, D>,
+    config: &'a PlannerConfig<String, D>,
+) -> BTreeSet<&'a String> {
+    let mut keys: BTreeSet<&String> = start.data.keys().collect();
+    for action in actions {
+        keys.extend(action.preconditions.iter().map(|(key, _)| key));
+        for effect in &action.effects {
+            for mutator in &effect.mutators {
+                keys.insert(mutator.key());
+                if let Mutator::CopyFrom(_, source) = mutator {
+                    keys.insert(source);
+                }
+            }
+            if let Some(state) = &effect.state {
+                keys.extend(state.data.keys());
+            }
+        }
+    }
+    keys.extend(goal.requirements.keys());
+    keys.extend(goal.alternatives.iter().flat_map(|group| group.keys()));
+    keys.extend(
+        config
+            .avoid
+            .iter()
+            .flat_map(|(conditions, _)| conditions.iter().map(|(key, _)| key)),
+    );
+    keys.extend(config.defaults.keys());
+    keys.extend(
+        config
+            .invariants
+            .iter()
+            .flat_map(|invariant| invariant.conditions.iter().map(|(key, _)| key)),
+    );
+    keys.extend(config.resources.keys());
+    keys
+}
+
+fn unintern_plan<D: DatumLike>((nodes, cost): Plan<u32, D>, names: &[String]) -> Plan<String, D> {
+    let name = |id: &u32| names[*id as usize].clone();
+    let nodes = nodes
+        .iter()
+        .map(|node| match node {
+            Node::Effect(effect) => Node::Effect(map_effect(effect, &name)),
+            Node::State(state) => Node::State(map_state(state, &name)),
+        })
+        .collect();
+    (nodes, cost)
+}
+
+fn map_conditions<A, B, D: DatumLike>(
+    conditions: &[(A, Compare<D>)],
+    key: &impl Fn(&A) -> B,
+) -> Vec<(B, Compare<D>)> {
+    conditions
+        .iter()
+        .map(|(name, compare)| (key(name), compare.clone()))
+        .collect()
+}
+
+fn map_requirements<A, B: Key, D: DatumLike>(
+    requirements: &BTreeMap<A, Compare<D>>,
+    key: &impl Fn(&A) -> B,
+) -> BTreeMap<B, Compare<D>> {
+    requirements
+        .iter()
+        .map(|(name, compare)| (key(name), compare.clone()))
+        .collect()
+}
+
+fn map_state<A: Key, B: Key, D: DatumLike>(
+    state: &LocalState<A, D>,
+    key: &impl Fn(&A) -> B,
+) -> LocalState<B, D> {
+    LocalState {
+        data: state
+            .data
+            .iter()
+            .map(|(name, value)| (key(name), value.clone()))
+            .collect(),
+    }
+}
+
+fn map_mutator<A, B, D: DatumLike>(
+    mutator: &Mutator<A, D>,
+    key: &impl Fn(&A) -> B,
+) -> Mutator<B, D> {
+    match mutator {
+        Mutator::Set(name, value) => Mutator::Set(key(name), value.clone()),
+        Mutator::Increment(name, value) => Mutator::Increment(key(name), value.clone()),
+        Mutator::Decrement(name, value) => Mutator::Decrement(key(name), value.clone()),
+        Mutator::Multiply(name, value) => Mutator::Multiply(key(name), value.clone()),
+        Mutator::Divide(name, value) => Mutator::Divide(key(name), value.clone()),
+        Mutator::Toggle(name) => Mutator::Toggle(key(name)),
+        Mutator::Insert(name, value) => Mutator::Insert(key(name), value.clone()),
+        Mutator::Remove(name, value) => Mutator::Remove(key(name), value.clone()),
+        Mutator::CopyFrom(name, source) => Mutator::CopyFrom(key(name), key(source)),
+        Mutator::Unset(name) => Mutator::Unset(key(name)),
+    }
+}
+
+fn map_effect<A: Key, B: Key, D: DatumLike>(
+    effect: &Effect<A, D>,
+    key: &impl Fn(&A) -> B,
+) -> Effect<B, D> {
+    Effect {
+        action: effect.action.clone(),
+        mutators: effect
+            .mutators
+            .iter()
+            .map(|mutator| map_mutator(mutator, key))
+            .collect(),
+        state: effect.state.as_ref().map(|state| map_state(state, key)),
+        cost: effect.cost,
+        reward: effect.reward,
+        duration: effect.duration,
+    }
+}
+
+/// Only for actions without closures
+fn map_action<A: Key, B: Key, D: DatumLike>(
+    action: &Action<A, D>,
+    key: &impl Fn(&A) -> B,
+) -> Action<B, D> {
+    Action {
+        key: action.key.clone(),
+        preconditions: action
+            .preconditions
+            .iter()
+            .map(|(name, compare)| (key(name), compare.clone()))
+            .collect(),
+        dynamic_preconditions: Vec::new(),
+        effects: action
+            .effects
+            .iter()
+            .map(|effect| map_effect(effect, key))
+            .collect(),
+        dynamic_effects: Vec::new(),
+        dynamic_cost: None,
+        subgoal: None,
+        tags: action.tags.clone(),
+    }
+}
+
+fn map_goal<A, B: Key, D: DatumLike>(goal: &Goal<A, D>, key: &impl Fn(&A) -> B) -> Goal<B, D> {
+    Goal {
+        requirements: map_requirements(&goal.requirements, key),
+        alternatives: goal
+            .alternatives
+            .iter()
+            .map(|group| map_requirements(group, key))
+            .collect(),
+        priority: goal.priority,
+        deadline: goal.deadline,
+    }
+}
+
+/// Only for configs without closures
+fn map_config<A, B: Key, D: DatumLike>(
+    config: &PlannerConfig<A, D>,
+    key: &impl Fn(&A) -> B,
+) -> PlannerConfig<B, D> {
+    PlannerConfig {
+        strategy: config.strategy,
+        #[cfg(feature = "std")]
+        time_budget: config.time_budget,
+        duplicate_keys: config.duplicate_keys,
+        avoid: config
+            .avoid
+            .iter()
+            .map(|(conditions, penalty)| (map_conditions(conditions, key), *penalty))
+            .collect(),
+        normalize_heuristic: config.normalize_heuristic,
+        heuristic: None,
+        heuristic_weight: config.heuristic_weight,
+        cost_scale: config.cost_scale,
+        defaults: config
+            .defaults
+            .iter()
+            .map(|(name, value)| (key(name), value.clone()))
+            .collect(),
+        missing_keys: config.missing_keys,
+        cancellation: config.cancellation.clone(),
+        check_interval: config.check_interval,
+        max_expanded_nodes: config.max_expanded_nodes,
+        rewards: config.rewards,
+        max_cost: config.max_cost,
+        invariants: config
+            .invariants
+            .iter()
+            .map(|invariant| Invariant {
+                conditions: map_conditions(&invariant.conditions, key),
+            })
+            .collect(),
+        retry_without_invariants: config.retry_without_invariants,
+        computed: ComputedKeys::default(),
+        minimize: config.minimize,
+        resources: config
+            .resources
+            .iter()
+            .map(|(name, floor)| (key(name), floor.clone()))
+            .collect(),
+        tie_seed: None,
+        max_trace_events: config.max_trace_events,
+        on_expand: None,
+        on_finish: None,
+    }
+}
@@ -0,0 +1,285 @@
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+
+use foldhash::fast::FixedState;
+
+use crate::{
+    action::Action,
+    compiled::CompiledAction,
+    datum::{Datum, DatumLike},
+    effect::Effect,
+    goal::Goal,
+    heuristic::Heuristic,
+    invariant::{invariants_hold, resources_hold},
+    localstate::{Key, LocalState},
+    mutator::Mutator,
+    planner::{
+        avoid_penalty, is_goal, plan_totals, preconditions_met, reading_unset_keys, successors,
+        validate_actions, with_defaults, Interrupt, Node, Plan, PlanStats, PlannerConfig,
+    },
+    search,
+};
+
+/// A plan that forks wherever an action's outcome is uncertain, made by
+/// [`make_contingent_plan`]. Execute the [`PlanTree::plan`], then the uncertain
+/// action of the [`PlanTree::branches`] if there is one, and carry on with the
+/// tree [`PlanTree::branch_for`] the state that came out of it. Every leaf reaches
+/// the goal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanTree<K: Key = String, D: DatumLike = Datum> {
+    /// What to do before the next uncertain action, or until the goal is reached
+    pub plan: Plan<K, D>,
+    /// Where the plan forks, after the last node of `plan`
+    pub branches: Option<Branches<K, D>>,
+}
+
+/// The uncertain action a [`PlanTree`] forks on, with a tree for each outcome
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Branches<K: Key = String, D: DatumLike = Datum> {
+    /// Key of the uncertain [`Action`]
+    pub action: String,
+    /// Every outcome of the action, with its state filled in, and how to carry on
+    /// from there. The tree's plan starts from the state of the outcome
+    pub outcomes: Vec<(Effect<K, D>, PlanTree<K, D>)>,
+}
+
+impl<K: Key, D: DatumLike> PlanTree<K, D> {
+    /// The tree to carry on with once the uncertain action is done and `observed`
+    /// is the state it left behind. Outcomes are told apart by the values their
+    /// [`Mutator::Set`]s wrote, the first one matching `observed` wins. `None` at a
+    /// leaf, or if no outcome matches
+    pub fn branch_for(&self, observed: &LocalState<K, D>) -> Option<&PlanTree<K, D>> {
+        let branches = self.branches.as_ref()?;
+        branches
+            .outcomes
+            .iter()
+            .find(|(outcome, _)| {
+                outcome.mutators.iter().all(|mutator| match mutator {
+                    Mutator::Set(key, value) => observed.data.get(key) == Some(value),
+                    _ => true,
+                })
+            })
+            .map(|(_, tree)| tree)
+    }
+
+    /// The cost of the most expensive way through the tree, which is what
+    /// [`make_contingent_plan`] keeps as low as it can
+    pub fn worst_case_cost(&self) -> usize {
+        let forked = self.branches.as_ref().map_or(0, |branches| {
+            branches
+                .outcomes
+                .iter()
+                .map(|(outcome, tree)| outcome.cost.saturating_add(tree.worst_case_cost()))
+                .max()
+                .unwrap_or(0)
+        });
+        self.plan.1.saturating_add(forked)
+    }
+
+    /// How many uncertain actions the longest way through the tree goes through
+    pub fn depth(&self) -> usize {
+        self.branches.as_ref().map_or(0, |branches| {
+            1 + branches
+                .outcomes
+                .iter()
+                .map(|(_, tree)| tree.depth())
+                .max()
+                .unwrap_or(0)
+        })
+    }
+}
+
+/// Makes a [`PlanTree`] that reaches `goal` whichever way uncertain actions turn
+/// out. An [`Action`] with more than one [`Effect`] is uncertain, every effect is
+/// one of its possible outcomes, see [`Action::with_uncertain`]. Other planners
+/// treat the effects as branches they pick from.
+///
+/// The tree with the lowest [`PlanTree::worst_case_cost`] is returned, forking on
+/// at most `max_branching` uncertain actions along any way through it. Every fork
+/// plans each outcome on its own, so the work grows quickly with the bound.
+///
+/// This always searches forwards and ignores [`PlannerConfig::strategy`] and
+/// [`PlannerConfig::rewards`]. `None` if there's no such tree.
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`](crate::prelude::PlanError)
+pub fn make_contingent_plan<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    max_branching: usize,
+) -> Option<PlanTree<K, D>> {
+    let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+    let config = &reading_unset_keys(config, &actions);
+    let (certain, uncertain): (Vec<_>, Vec<_>) = actions
+        .iter()
+        .filter(|action| action.has_effects())
+        .cloned()
+        .partition(|action| {
+            action.effects.len()
+                + action.dynamic_effects.len()
+                + usize::from(action.subgoal.is_some())
+                == 1
+        });
+    let mut planner = Contingent {
+        certain: &certain,
+        uncertain: &uncertain,
+        goal,
+        heuristic: Heuristic::new(&certain, goal, config),
+        config,
+        interrupt: Interrupt::from_config(config),
+        solved: Solved::default(),
+    };
+    let start = with_defaults(start, &config.defaults);
+    if !invariants_hold(&start, &config.invariants) {
+        return None;
+    }
+    planner.solve(&start, max_branching)
+}
+
+/// Trees found so far, by the state they start from and how many forks they may
+/// still take
+type Solved<K, D> =
+    indexmap::IndexMap<(LocalState<K, D>, usize), Option<PlanTree<K, D>>, FixedState>;
+
+struct Contingent<'a, K: Key, D: DatumLike> {
+    certain: &'a [Action<K, D>],
+    uncertain: &'a [Action<K, D>],
+    goal: &'a Goal<K, D>,
+    heuristic: Heuristic<'a, K, D>,
+    config: &'a PlannerConfig<K, D>,
+    interrupt: Interrupt<'a>,
+    solved: Solved<K, D>,
+}
+
+/// A node of the search for one [`PlanTree`]. Taking an uncertain action that
+/// every outcome of can be planned for ends the search in a `Fork`
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Step<K: Key, D: DatumLike> {
+    Node(Node<K, D>),
+    /// Index into the forks found during the search
+    Fork(usize),
+}
+
+impl<K: Key, D: DatumLike> Contingent<'_, K, D> {
+    fn solve(&mut self, start: &LocalState<K, D>, branches_left: usize) -> Option<PlanTree<K, D>> {
+        // Forks only ever plan with fewer forks left, so nothing we're still
+        // working on can come up again here
+        let memo = (start.clone(), branches_left);
+        if let Some(tree) = self.solved.get(&memo) {
+            return tree.clone();
+        }
+        let tree = self.search(start, branches_left);
+        self.solved.insert(memo, tree.clone());
+        tree
+    }
+
+    fn search(&mut self, start: &LocalState<K, D>, branches_left: usize) -> Option<PlanTree<K, D>> {
+        let mut poll = self.interrupt.poll();
+        let mut forks: Vec<Branches<K, D>> = vec![];
+        let goal = self.goal;
+        let config = self.config;
+        let start_node = Step::Node(Node::State(start.clone()));
+        let certain = CompiledAction::compile_all(self.certain, start);
+
+        let (path, _) = search::astar(
+            &start_node,
+            (),
+            self.heuristic.distance(start) as usize,
+            None,
+            config.tie_seed,
+            &mut PlanStats::default(),
+            || poll.is_due(),
+            |step, h, next| {
+                let Step::Node(node) = step else {
+                    return;
+                };
+                next.extend(
+                    successors(node, h, &certain, &self.heuristic, config, |_| {}).map(
+                        |(node, cost, h)| {
+                            let penalty = avoid_penalty(node.state(), &self.config.avoid);
+                            (Step::Node(node), (), cost.saturating_add(penalty), h)
+                        },
+                    ),
+                );
+                if branches_left > 0 {
+                    for action in self.uncertain {
+                        if let Some((fork, cost)) = self.fork(node.state(), action, branches_left) {
+                            forks.push(fork);
+                            next.push((Step::Fork(forks.len() - 1), (), cost, 0));
+                        }
+                    }
+                }
+            },
+            |step| match step {
+                Step::Node(node) => is_goal(node, goal, &config.computed),
+                Step::Fork(_) => true,
+            },
+            |_| {},
+        )?;
+
+        let mut nodes = vec![];
+        let mut branches = None;
+        for (step, _) in path {
+            match step {
+                Step::Node(node) => nodes.push(node),
+                Step::Fork(index) => branches = Some(forks.swap_remove(index)),
+            }
+        }
+        let (cost, _) = plan_totals(&nodes);
+        Some(PlanTree {
+            plan: (nodes, cost),
+            branches,
+        })
+    }
+
+    /// Plans for every outcome of the uncertain `action` taken in `state`. Returns
+    /// them together with the worst case cost from `state` on
+    fn fork(
+        &mut self,
+        state: &LocalState<K, D>,
+        action: &Action<K, D>,
+        branches_left: usize,
+    ) -> Option<(Branches<K, D>, usize)> {
+        if !preconditions_met(
+            state,
+            action,
+            self.config.missing_keys,
+            &self.config.computed,
+        ) {
+            return None;
+        }
+        let mut outcomes = vec![];
+        let mut worst = 0;
+        for effect in action.effects_in(state) {
+            let outcome = effect.apply(state, self.config.missing_keys.unwrap_or_default())?;
+            // Every outcome could happen, so one breaking an invariant rules out the action
+            if !invariants_hold(&outcome, &self.config.invariants)
+                || !resources_hold(state, &outcome, &self.config.resources)
+            {
+                return None;
+            }
+            let tree = self.solve(&outcome, branches_left - 1)?;
+            let penalty = avoid_penalty(&outcome, &self.config.avoid);
+            let outcome = Effect {
+                action: action.key.clone(),
+                cost: action.cost_in(state, &effect),
+                state: Some(outcome),
+                ..effect.into_owned()
+            };
+            let cost = outcome
+                .discounted_cost()
+                .saturating_add(penalty)
+                .saturating_add(tree.worst_case_cost());
+            worst = worst.max(cost);
+            outcomes.push((outcome, tree));
+        }
+        let branches = Branches {
+            action: action.key.clone(),
+            outcomes,
+        };
+        Some((branches, worst))
+    }
+}
@@ -0,0 +1,137 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::fmt::{self, Display};
+
+use foldhash::fast::FixedState;
+
+use crate::{
+    action::Action,
+    computed::ComputedKeys,
+    datum::DatumLike,
+    effect::{Effect, MissingKeyPolicy},
+    localstate::{Key, LocalState},
+    planner::{apply_action, preconditions_met},
+};
+
+/// How many states [`find_zero_cost_cycles`] looks at before giving up
+const MAX_STATES: usize = 1024;
+
+/// An action together with one of its effects
+type Branch<'a, K, D> = (&'a Action<K, D>, &'a Effect<K, D>);
+
+/// Actions that cost nothing and, taken one after another, lead back to the state
+/// they started from, found by [`find_zero_cost_cycles`]. A search can go around
+/// them without its cost ever going up
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ZeroCostCycle<K = String> {
+    /// Keys of the actions, in the order they go around the cycle
+    pub actions: Vec<String>,
+    /// The keys the actions keep changing back and forth
+    pub keys: Vec<K>,
+}
+
+impl<K: Key> Display for ZeroCostCycle<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "actions {:?} cost nothing and lead back to where they started, changing {:?} back and forth",
+            self.actions, self.keys
+        )
+    }
+}
+
+/// Looks for cycles of actions that cost nothing, like a free `pick_up` and
+/// `put_down`. Depending on the heuristic, a search can spend a long time going
+/// around those. Planning doesn't look for them itself, run this once on a domain
+/// as a lint before planning with it.
+///
+/// Only the states reachable from `start` through free actions alone are looked at,
+/// up to a limit. Each cycle is reported once, however many states it shows up in.
+pub fn find_zero_cost_cycles<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+) -> Vec<ZeroCostCycle<K>> {
+    // An action with several effects is free if any of them is
+    let free: Vec<Branch<K, D>> = actions
+        .iter()
+        .flat_map(|action| action.effects.iter().map(move |effect| (action, effect)))
+        .filter(|(_, effect)| effect.discounted_cost() == 0)
+        .collect();
+    if free.is_empty() {
+        return vec![];
+    }
+
+    let mut states: indexmap::IndexSet<LocalState<K, D>, FixedState> = Default::default();
+    states.insert(start.clone());
+    // Where each state on the current path is in `path`
+    let mut on_path: BTreeMap<usize, usize> = BTreeMap::from([(0, 0)]);
+    // The states we're going through: the state, the next free action to try from
+    // it, and the action that led to it
+    let mut path: Vec<(usize, usize, Option<usize>)> = vec![(0, 0, None)];
+    let mut seen_cycles: BTreeSet<Vec<&str>> = BTreeSet::new();
+    let mut cycles = vec![];
+
+    while let Some((state, next, _)) = path.last_mut() {
+        let state = *state;
+        let Some(&(action, effect)) = free.get(*next) else {
+            on_path.remove(&state);
+            path.pop();
+            continue;
+        };
+        let action_index = *next;
+        *next += 1;
+
+        let current = &states[state];
+        let computed = ComputedKeys::default();
+        if !preconditions_met(current, action, Some(MissingKeyPolicy::Insert), &computed) {
+            continue;
+        }
+        let Some(effect) = apply_action(current, action, effect, MissingKeyPolicy::Insert) else {
+            continue;
+        };
+        let reached = effect.state.unwrap();
+        match states.get_index_of(&reached) {
+            Some(index) => {
+                // Actions that change nothing don't lead anywhere the search would
+                // look at again
+                if index == state {
+                    continue;
+                }
+                let Some(&from) = on_path.get(&index) else {
+                    continue;
+                };
+                let taken: Vec<usize> = path[from + 1..]
+                    .iter()
+                    .filter_map(|(_, _, action)| *action)
+                    .chain([action_index])
+                    .collect();
+                let mut names: Vec<&str> = taken.iter().map(|i| free[*i].0.key.as_str()).collect();
+                names.sort_unstable();
+                if seen_cycles.insert(names) {
+                    cycles.push(cycle_of(&free, &taken));
+                }
+            }
+            None if states.len() < MAX_STATES => {
+                let (index, _) = states.insert_full(reached);
+                on_path.insert(index, path.len());
+                path.push((index, 0, Some(action_index)));
+            }
+            None => {}
+        }
+    }
+    cycles
+}
+
+fn cycle_of<K: Key, D: DatumLike>(free: &[Branch<K, D>], taken: &[usize]) -> ZeroCostCycle<K> {
+    let mut keys = BTreeSet::new();
+    for index in taken {
+        for mutator in &free[*index].1.mutators {
+            keys.insert(mutator.key().clone());
+        }
+    }
+    ZeroCostCycle {
+        actions: taken.iter().map(|i| free[*i].0.key.clone()).collect(),
+        keys: keys.into_iter().collect(),
+    }
+}
@@ -1,26 +1,44 @@
-use std::fmt::Display;
-use std::hash::Hash;
-use std::ops::{Add, AddAssign, Sub, SubAssign};
+use core::fmt::{Debug, Display};
+use core::hash::Hash;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
 
+#[cfg(feature = "bevy_reflect")]
 use bevy_reflect::Reflect;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-/// Represents one value of either `bool`, `i64`, `f64` or a `Enum` as `usize`.
-#[derive(Reflect, Clone, Debug, PartialOrd, Copy)]
+/// How far apart two `f64`s can be and still count as equal for
+/// [`Compare::Equals`](crate::compare::Compare::Equals) and
+/// [`Compare::NotEquals`](crate::compare::Compare::NotEquals), so rounding along a
+/// chain of increments doesn't keep a plan from reaching its goal. Use
+/// [`Compare::ApproxEquals`](crate::compare::Compare::ApproxEquals) for another
+/// tolerance
+pub const FLOAT_EPSILON: f64 = 1e-9;
+
+/// Represents one value of either `bool`, `i64`, `f64`, a `Enum` as `usize` or a
+/// `Set` of enums.
+#[derive(Clone, Debug, PartialOrd, Copy)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Datum {
     Bool(bool),
     I64(i64),
     F64(f64),
     Enum(usize),
+    /// Which of up to 64 enum values are members, like the items carried, with
+    /// member `n` as bit `n`. See [`Datum::set_of`]
+    Set(u64),
 }
 
 impl Hash for Datum {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
         match self {
             Datum::Bool(b) => b.hash(state),
             Datum::I64(i) => i.hash(state),
             Datum::F64(f) => f.to_bits().hash(state),
             Datum::Enum(u) => u.hash(state),
+            Datum::Set(bits) => bits.hash(state),
         }
     }
 }
@@ -32,6 +50,7 @@ impl PartialEq for Datum {
             (Self::I64(l0), Self::I64(r0)) => l0 == r0,
             (Self::F64(l0), Self::F64(r0)) => l0 == r0,
             (Self::Enum(l0), Self::Enum(r0)) => l0 == r0,
+            (Self::Set(l0), Self::Set(r0)) => l0 == r0,
             _ => false,
         }
     }
@@ -39,7 +58,62 @@ impl PartialEq for Datum {
 
 impl Eq for Datum {}
 
+/// Which variant of [`Datum`] a value is, without the value itself
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+pub enum DatumKind {
+    Bool,
+    I64,
+    F64,
+    Enum,
+    Set,
+}
+
+impl Display for DatumKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Bool => write!(f, "Bool"),
+            Self::I64 => write!(f, "I64"),
+            Self::F64 => write!(f, "F64"),
+            Self::Enum => write!(f, "Enum"),
+            Self::Set => write!(f, "Set"),
+        }
+    }
+}
+
 impl Datum {
+    /// A [`Datum::Set`] of `members`, like `Datum::set_of([Item::Axe as usize])`
+    ///
+    /// # Panics
+    ///
+    /// If a member is 64 or more
+    pub fn set_of(members: impl IntoIterator<Item = usize>) -> Datum {
+        Datum::Set(members.into_iter().fold(0, |bits, member| {
+            assert!(member < 64, "A Datum::Set can't hold member {}", member);
+            bits | (1 << member)
+        }))
+    }
+
+    pub fn kind(&self) -> DatumKind {
+        match self {
+            Datum::Bool(_) => DatumKind::Bool,
+            Datum::I64(_) => DatumKind::I64,
+            Datum::F64(_) => DatumKind::F64,
+            Datum::Enum(_) => DatumKind::Enum,
+            Datum::Set(_) => DatumKind::Set,
+        }
+    }
+
+    /// The members of a [`Datum::Set`], or of a set holding just the
+    /// [`Datum::Enum`]. `None` for other values and enums of 64 or more
+    fn member_bits(&self) -> Option<u64> {
+        match self {
+            Datum::Set(bits) => Some(*bits),
+            Datum::Enum(member) if *member < 64 => Some(1 << member),
+            _ => None,
+        }
+    }
+
     pub fn distance(&self, other: &Datum) -> u64 {
         match (self, other) {
             (Datum::Bool(a), Datum::Bool(b)) => {
@@ -50,7 +124,8 @@ impl Datum {
                 }
             }
             (Datum::I64(a), Datum::I64(b)) => (a - b).unsigned_abs(),
-            (Datum::F64(a), Datum::F64(b)) => (a - b).abs() as u64,
+            // Without std there's no `f64::abs`
+            (Datum::F64(a), Datum::F64(b)) => (if a > b { a - b } else { b - a }) as u64,
             (Datum::Enum(a), Datum::Enum(b)) => {
                 if a == b {
                     0
@@ -58,13 +133,236 @@ impl Datum {
                     1
                 }
             }
+            // How many members one has that the other doesn't
+            (Datum::Set(a), Datum::Set(b)) => u64::from((a ^ b).count_ones()),
             _ => panic!("Cannot calculate distance between different Datum types"),
         }
     }
 }
 
+/// What the planner needs from the values in a [`LocalState`](crate::localstate::LocalState).
+/// [`Datum`] covers the usual cases, implement this for your own type when it
+/// doesn't, like fixed-point currency or ids that don't fit in an `i64`.
+pub trait DatumLike: Clone + Debug + PartialEq + Eq + PartialOrd + Hash {
+    /// Which kind of value this is, for [`typecheck_domain`](crate::typecheck::typecheck_domain).
+    /// Types that only have one kind of value can use `()`
+    type Kind: Copy + Debug + PartialEq + Eq + Hash;
+
+    fn kind(&self) -> Self::Kind;
+
+    /// How far apart two values are, which the planner uses to estimate how much
+    /// is left to do. 0 means they're the same
+    fn distance(&self, other: &Self) -> u64;
+
+    /// How far a single `Increment` or `Decrement` by this value moves a key, in the
+    /// same units as [`DatumLike::distance`]. 0 if it can't be used for either
+    fn magnitude(&self) -> u64;
+
+    /// [`DatumLike::distance`] in units of `1 / scale`, so the planner can still tell
+    /// values apart when they're less than a unit apart. Scales up `distance` by
+    /// default, override it when values have a fractional part
+    fn scaled_distance(&self, other: &Self, scale: u64) -> u64 {
+        self.distance(other).saturating_mul(scale)
+    }
+
+    /// [`DatumLike::magnitude`] in units of `1 / scale`, like [`DatumLike::scaled_distance`]
+    fn scaled_magnitude(&self, scale: u64) -> u64 {
+        self.magnitude().saturating_mul(scale)
+    }
+
+    /// `self + other`, or `None` if the two can't be added together or the sum
+    /// doesn't fit
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    /// `self - other`, or `None` if the two can't be subtracted or the difference
+    /// doesn't fit
+    fn checked_sub(&self, other: &Self) -> Option<Self>;
+
+    /// `self * other`, or `None` if the two can't be multiplied. Values can't be by
+    /// default, implement this to use [`Mutator::Multiply`](crate::mutator::Mutator::Multiply)
+    fn checked_mul(&self, _other: &Self) -> Option<Self> {
+        None
+    }
+
+    /// `self / other`, or `None` if the two can't be divided. Values can't be by
+    /// default, implement this to use [`Mutator::Divide`](crate::mutator::Mutator::Divide)
+    fn checked_div(&self, _other: &Self) -> Option<Self> {
+        None
+    }
+
+    /// Whether `self` and `other` are at most `epsilon` apart, for
+    /// [`Compare::ApproxEquals`](crate::compare::Compare::ApproxEquals). Values are
+    /// only equal to themselves by default, implement this for fractional values
+    fn approx_eq(&self, other: &Self, _epsilon: f64) -> bool {
+        self == other
+    }
+
+    /// The opposite of a boolean, or `None` for values that can't be flipped.
+    /// Values can't be by default, implement this to use
+    /// [`Mutator::Toggle`](crate::mutator::Mutator::Toggle)
+    fn toggled(&self) -> Option<Self> {
+        None
+    }
+
+    /// Whether `self` is a set that `members` are all in, for
+    /// [`Compare::Contains`](crate::compare::Compare::Contains). Values aren't sets
+    /// by default
+    fn contains(&self, _members: &Self) -> bool {
+        false
+    }
+
+    /// How many of `members` are missing from `self`, in the same units as
+    /// [`DatumLike::distance`]. 1 when they aren't all in it by default
+    fn missing_members(&self, members: &Self) -> u64 {
+        u64::from(!self.contains(members))
+    }
+
+    /// `self` with `members` added to it, or `None` if it isn't a set. For
+    /// [`Mutator::Insert`](crate::mutator::Mutator::Insert)
+    fn with_members(&self, _members: &Self) -> Option<Self> {
+        None
+    }
+
+    /// `self` with `members` taken out of it, or `None` if it isn't a set. For
+    /// [`Mutator::Remove`](crate::mutator::Mutator::Remove)
+    fn without_members(&self, _members: &Self) -> Option<Self> {
+        None
+    }
+}
+
+impl DatumLike for Datum {
+    type Kind = DatumKind;
+
+    fn kind(&self) -> DatumKind {
+        Datum::kind(self)
+    }
+
+    fn distance(&self, other: &Datum) -> u64 {
+        Datum::distance(self, other)
+    }
+
+    fn magnitude(&self) -> u64 {
+        match self {
+            Datum::I64(v) => v.unsigned_abs(),
+            Datum::F64(v) => ceil_abs(*v),
+            Datum::Bool(_) | Datum::Enum(_) | Datum::Set(_) => 0,
+        }
+    }
+
+    fn scaled_distance(&self, other: &Datum, scale: u64) -> u64 {
+        match (self, other) {
+            (Datum::F64(a), Datum::F64(b)) => {
+                let gap = if a > b { a - b } else { b - a };
+                (gap * scale as f64) as u64
+            }
+            _ => Datum::distance(self, other).saturating_mul(scale),
+        }
+    }
+
+    fn scaled_magnitude(&self, scale: u64) -> u64 {
+        match self {
+            Datum::F64(v) => ceil_abs(*v * scale as f64),
+            _ => self.magnitude().saturating_mul(scale),
+        }
+    }
+
+    fn checked_add(&self, other: &Datum) -> Option<Datum> {
+        match (self, other) {
+            (Datum::I64(a), Datum::I64(b)) => a.checked_add(*b).map(Datum::I64),
+            (Datum::F64(a), Datum::F64(b)) => Some(Datum::F64(a + b)),
+            _ => None,
+        }
+    }
+
+    fn checked_sub(&self, other: &Datum) -> Option<Datum> {
+        match (self, other) {
+            (Datum::I64(a), Datum::I64(b)) => a.checked_sub(*b).map(Datum::I64),
+            (Datum::F64(a), Datum::F64(b)) => Some(Datum::F64(a - b)),
+            _ => None,
+        }
+    }
+
+    /// Integers saturate at `i64::MIN` and `i64::MAX`, as a key that keeps being
+    /// multiplied during a search would overflow soon enough
+    fn checked_mul(&self, other: &Datum) -> Option<Datum> {
+        match (self, other) {
+            (Datum::I64(a), Datum::I64(b)) => Some(Datum::I64(a.saturating_mul(*b))),
+            (Datum::F64(a), Datum::F64(b)) => Some(Datum::F64(a * b)),
+            _ => None,
+        }
+    }
+
+    /// Integers round towards zero. Dividing by zero leaves the value as it is
+    fn checked_div(&self, other: &Datum) -> Option<Datum> {
+        match (self, other) {
+            (Datum::I64(_), Datum::I64(0)) => Some(*self),
+            (Datum::I64(a), Datum::I64(b)) => Some(Datum::I64(a.saturating_div(*b))),
+            (Datum::F64(_), Datum::F64(b)) if *b == 0.0 => Some(*self),
+            (Datum::F64(a), Datum::F64(b)) => Some(Datum::F64(a / b)),
+            _ => None,
+        }
+    }
+
+    fn approx_eq(&self, other: &Datum, epsilon: f64) -> bool {
+        match (self, other) {
+            (Datum::F64(a), Datum::F64(b)) => {
+                let gap = a - b;
+                -epsilon <= gap && gap <= epsilon
+            }
+            _ => self == other,
+        }
+    }
+
+    fn toggled(&self) -> Option<Datum> {
+        match self {
+            Datum::Bool(value) => Some(Datum::Bool(!value)),
+            _ => None,
+        }
+    }
+
+    /// Members are a [`Datum::Set`] or a single [`Datum::Enum`]
+    fn contains(&self, members: &Datum) -> bool {
+        match (self, members.member_bits()) {
+            (Datum::Set(bits), Some(members)) => bits & members == members,
+            _ => false,
+        }
+    }
+
+    fn missing_members(&self, members: &Datum) -> u64 {
+        match (self, members.member_bits()) {
+            (Datum::Set(bits), Some(members)) => u64::from((members & !bits).count_ones()),
+            _ => 1,
+        }
+    }
+
+    fn with_members(&self, members: &Datum) -> Option<Datum> {
+        match (self, members.member_bits()) {
+            (Datum::Set(bits), Some(members)) => Some(Datum::Set(bits | members)),
+            _ => None,
+        }
+    }
+
+    fn without_members(&self, members: &Datum) -> Option<Datum> {
+        match (self, members.member_bits()) {
+            (Datum::Set(bits), Some(members)) => Some(Datum::Set(bits & !members)),
+            _ => None,
+        }
+    }
+}
+
+/// `x.abs().ceil() as u64`, which needs std
+fn ceil_abs(x: f64) -> u64 {
+    let x = if x < 0.0 { -x } else { x };
+    let truncated = x as u64;
+    if (truncated as f64) < x {
+        truncated.saturating_add(1)
+    } else {
+        truncated
+    }
+}
+
 impl Display for Datum {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::Bool(v) => {
                 write!(f, "Datum:Bool({})", v)
@@ -78,6 +376,17 @@ impl Display for Datum {
             Self::Enum(v) => {
                 write!(f, "Datum:Enum({})", v)
             }
+            Self::Set(bits) => {
+                write!(f, "Datum:Set(")?;
+                let members = (0..64).filter(|member| bits & (1 << member) != 0);
+                for (i, member) in members.enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", member)?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
@@ -231,4 +540,41 @@ mod test {
         assert_eq!(Datum::Enum(1).distance(&Datum::Enum(0)), 1);
         assert_eq!(Datum::Enum(1).distance(&Datum::Enum(5)), 1);
     }
+
+    #[test]
+    fn test_scaled_distance() {
+        assert_eq!(
+            Datum::F64(0.0).scaled_distance(&Datum::F64(0.75), 1000),
+            750
+        );
+        assert_eq!(
+            Datum::F64(2.5).scaled_distance(&Datum::F64(-2.5), 1000),
+            5000
+        );
+        assert_eq!(Datum::F64(0.25).scaled_magnitude(1000), 250);
+        assert_eq!(Datum::F64(-0.0001).scaled_magnitude(1000), 1);
+
+        assert_eq!(Datum::I64(5).scaled_distance(&Datum::I64(-5), 1000), 10_000);
+        assert_eq!(
+            Datum::Bool(true).scaled_distance(&Datum::Bool(false), 1000),
+            1000
+        );
+        assert_eq!(Datum::I64(3).scaled_magnitude(1000), 3000);
+        assert_eq!(Datum::I64(i64::MAX).scaled_magnitude(1000), u64::MAX);
+    }
+
+    #[test]
+    fn test_checked_add_and_sub() {
+        assert_eq!(
+            Datum::I64(2).checked_add(&Datum::I64(3)),
+            Some(Datum::I64(5))
+        );
+        assert_eq!(
+            Datum::I64(2).checked_sub(&Datum::I64(3)),
+            Some(Datum::I64(-1))
+        );
+        assert_eq!(Datum::I64(i64::MAX).checked_add(&Datum::I64(1)), None);
+        assert_eq!(Datum::I64(i64::MIN).checked_sub(&Datum::I64(1)), None);
+        assert_eq!(Datum::Bool(true).checked_add(&Datum::Bool(true)), None);
+    }
 }
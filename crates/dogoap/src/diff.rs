@@ -0,0 +1,319 @@
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::fmt::{self, Display};
+
+use crate::{
+    action::Action,
+    compare::Compare,
+    datum::{Datum, DatumLike},
+    effect::Effect,
+    localstate::Key,
+    mutator::Mutator,
+};
+
+/// What changed between two versions of a domain, made by [`diff_domains`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DomainDiff<K = String, D = Datum> {
+    /// Keys of the actions only the new domain has, in its order
+    pub added: Vec<String>,
+    /// Keys of the actions only the old domain has, in its order
+    pub removed: Vec<String>,
+    /// Actions both domains have, but that changed, in the order of the new domain
+    pub modified: Vec<ActionDiff<K, D>>,
+}
+
+/// How one [`Action`] changed between two versions of a domain
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActionDiff<K = String, D = Datum> {
+    pub key: String,
+    pub changes: Vec<ActionChange<K, D>>,
+}
+
+/// One change to an [`Action`]. Effects are told apart by their index
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionChange<K = String, D = Datum> {
+    PreconditionAdded(K, Compare<D>),
+    PreconditionRemoved(K, Compare<D>),
+    /// The only precondition for the key is a different one now
+    PreconditionAltered {
+        key: K,
+        old: Compare<D>,
+        new: Compare<D>,
+    },
+    /// Dynamic preconditions can only be told apart by their key
+    DynamicPreconditionAdded(K),
+    DynamicPreconditionRemoved(K),
+    EffectAdded(usize),
+    EffectRemoved(usize),
+    MutatorAdded {
+        effect: usize,
+        mutator: Mutator<K, D>,
+    },
+    MutatorRemoved {
+        effect: usize,
+        mutator: Mutator<K, D>,
+    },
+    CostChanged {
+        effect: usize,
+        old: usize,
+        new: usize,
+    },
+    RewardChanged {
+        effect: usize,
+        old: usize,
+        new: usize,
+    },
+    DurationChanged {
+        effect: usize,
+        old: usize,
+        new: usize,
+    },
+}
+
+impl<K, D> Default for DomainDiff<K, D> {
+    fn default() -> Self {
+        Self {
+            added: vec![],
+            removed: vec![],
+            modified: vec![],
+        }
+    }
+}
+
+impl<K, D> DomainDiff<K, D> {
+    /// Whether the two domains plan the same
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// Whether the action with `key` was removed or changed, so plans going through
+    /// it may not work anymore
+    pub fn affects(&self, key: &str) -> bool {
+        self.removed.iter().any(|removed| removed == key)
+            || self.modified.iter().any(|modified| modified.key == key)
+    }
+}
+
+/// Compares two versions of a domain, for reviewing what reloading actions changes
+/// and for telling which plans made with the old one still hold up. Actions are
+/// matched by key, the first one wins if there's more than one with the same key.
+///
+/// Only what planning looks at is compared, so the label an [`Effect`] was built
+/// with doesn't count, nor does the order of preconditions and mutators
+pub fn diff_domains<K: Key, D: DatumLike>(
+    old: &[Action<K, D>],
+    new: &[Action<K, D>],
+) -> DomainDiff<K, D> {
+    let find = |actions: &'_ [Action<K, D>], key: &str| {
+        actions.iter().position(|action| action.key == key)
+    };
+    let firsts = |actions: &[Action<K, D>]| {
+        let mut seen = BTreeSet::new();
+        (0..actions.len())
+            .filter(|index| seen.insert(actions[*index].key.clone()))
+            .collect::<Vec<_>>()
+    };
+
+    let mut diff = DomainDiff::default();
+    for index in firsts(new) {
+        let action = &new[index];
+        match find(old, &action.key) {
+            None => diff.added.push(action.key.clone()),
+            Some(old_index) => {
+                let changes = diff_action(&old[old_index], action);
+                if !changes.is_empty() {
+                    diff.modified.push(ActionDiff {
+                        key: action.key.clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    for index in firsts(old) {
+        if find(new, &old[index].key).is_none() {
+            diff.removed.push(old[index].key.clone());
+        }
+    }
+    diff
+}
+
+fn diff_action<K: Key, D: DatumLike>(
+    old: &Action<K, D>,
+    new: &Action<K, D>,
+) -> Vec<ActionChange<K, D>> {
+    let mut changes = vec![];
+
+    let keys: BTreeSet<&K> = old
+        .preconditions
+        .iter()
+        .chain(&new.preconditions)
+        .map(|(key, _)| key)
+        .collect();
+    for key in keys {
+        let for_key = |preconditions: &[(K, Compare<D>)]| -> Vec<Compare<D>> {
+            preconditions
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, compare)| compare.clone())
+                .collect()
+        };
+        let (removed, added) = unmatched(for_key(&old.preconditions), for_key(&new.preconditions));
+        if let ([old], [new]) = (removed.as_slice(), added.as_slice()) {
+            changes.push(ActionChange::PreconditionAltered {
+                key: key.clone(),
+                old: old.clone(),
+                new: new.clone(),
+            });
+            continue;
+        }
+        for compare in removed {
+            changes.push(ActionChange::PreconditionRemoved(key.clone(), compare));
+        }
+        for compare in added {
+            changes.push(ActionChange::PreconditionAdded(key.clone(), compare));
+        }
+    }
+
+    let dynamic = |action: &Action<K, D>| -> Vec<K> {
+        action
+            .dynamic_preconditions
+            .iter()
+            .map(|(key, _)| key.clone())
+            .collect()
+    };
+    let (removed, added) = unmatched(dynamic(old), dynamic(new));
+    changes.extend(
+        removed
+            .into_iter()
+            .map(ActionChange::DynamicPreconditionRemoved),
+    );
+    changes.extend(
+        added
+            .into_iter()
+            .map(ActionChange::DynamicPreconditionAdded),
+    );
+
+    for index in 0..old.effects.len().max(new.effects.len()) {
+        match (old.effects.get(index), new.effects.get(index)) {
+            (Some(old), Some(new)) => diff_effect(index, old, new, &mut changes),
+            (Some(_), None) => changes.push(ActionChange::EffectRemoved(index)),
+            (None, Some(_)) => changes.push(ActionChange::EffectAdded(index)),
+            (None, None) => {}
+        }
+    }
+    changes
+}
+
+fn diff_effect<K: Key, D: DatumLike>(
+    effect: usize,
+    old: &Effect<K, D>,
+    new: &Effect<K, D>,
+    changes: &mut Vec<ActionChange<K, D>>,
+) {
+    let (removed, added) = unmatched(old.mutators.to_vec(), new.mutators.to_vec());
+    changes.extend(
+        removed
+            .into_iter()
+            .map(|mutator| ActionChange::MutatorRemoved { effect, mutator }),
+    );
+    changes.extend(
+        added
+            .into_iter()
+            .map(|mutator| ActionChange::MutatorAdded { effect, mutator }),
+    );
+    if old.cost != new.cost {
+        changes.push(ActionChange::CostChanged {
+            effect,
+            old: old.cost,
+            new: new.cost,
+        });
+    }
+    if old.reward != new.reward {
+        changes.push(ActionChange::RewardChanged {
+            effect,
+            old: old.reward,
+            new: new.reward,
+        });
+    }
+    if old.duration != new.duration {
+        changes.push(ActionChange::DurationChanged {
+            effect,
+            old: old.duration,
+            new: new.duration,
+        });
+    }
+}
+
+/// What's left of `old` and `new` once every item they both have is taken out of
+/// each, counting duplicates
+fn unmatched<T: PartialEq>(old: Vec<T>, mut new: Vec<T>) -> (Vec<T>, Vec<T>) {
+    let mut removed = vec![];
+    for item in old {
+        match new.iter().position(|other| *other == item) {
+            Some(index) => {
+                new.remove(index);
+            }
+            None => removed.push(item),
+        }
+    }
+    (removed, new)
+}
+
+impl<K: Key, D: DatumLike> Display for DomainDiff<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for key in &self.added {
+            writeln!(f, "+ {}", key)?;
+        }
+        for key in &self.removed {
+            writeln!(f, "- {}", key)?;
+        }
+        for action in &self.modified {
+            writeln!(f, "~ {}", action.key)?;
+            for change in &action.changes {
+                writeln!(f, "    {}", change)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K: Key, D: DatumLike> Display for ActionChange<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionChange::PreconditionAdded(key, compare) => {
+                write!(f, "precondition added: {:?} {:?}", key, compare)
+            }
+            ActionChange::PreconditionRemoved(key, compare) => {
+                write!(f, "precondition removed: {:?} {:?}", key, compare)
+            }
+            ActionChange::PreconditionAltered { key, old, new } => {
+                write!(f, "precondition on {:?}: {:?} -> {:?}", key, old, new)
+            }
+            ActionChange::DynamicPreconditionAdded(key) => {
+                write!(f, "dynamic precondition added: {:?}", key)
+            }
+            ActionChange::DynamicPreconditionRemoved(key) => {
+                write!(f, "dynamic precondition removed: {:?}", key)
+            }
+            ActionChange::EffectAdded(effect) => write!(f, "effect {} added", effect),
+            ActionChange::EffectRemoved(effect) => write!(f, "effect {} removed", effect),
+            ActionChange::MutatorAdded { effect, mutator } => {
+                write!(f, "effect {}: mutator added: {:?}", effect, mutator)
+            }
+            ActionChange::MutatorRemoved { effect, mutator } => {
+                write!(f, "effect {}: mutator removed: {:?}", effect, mutator)
+            }
+            ActionChange::CostChanged { effect, old, new } => {
+                write!(f, "effect {}: cost {} -> {}", effect, old, new)
+            }
+            ActionChange::RewardChanged { effect, old, new } => {
+                write!(f, "effect {}: reward {} -> {}", effect, old, new)
+            }
+            ActionChange::DurationChanged { effect, old, new } => {
+                write!(f, "effect {}: duration {} -> {}", effect, old, new)
+            }
+        }
+    }
+}
@@ -1,35 +1,185 @@
-use crate::{localstate::LocalState, mutator::Mutator};
+use crate::{
+    datum::{Datum, DatumLike},
+    localstate::{Key, LocalState},
+    mutator::{apply_mutator, Mutator},
+};
+use alloc::string::{String, ToString};
+#[cfg(feature = "bevy_reflect")]
 use bevy_reflect::Reflect;
-use std::hash::{Hash, Hasher};
+use core::fmt::Display;
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+/// Inline storage for the [`Mutator`]s of an [`Effect`]. Most effects only
+/// change one or two keys, so those never touch the heap.
+pub type Mutators<K = String, D = Datum> = SmallVec<[Mutator<K, D>; 2]>;
 
 /// The effect is what happens when an Action is applied
 /// It's separated from Action in order to separate the
 /// data structures for the Planner's Node that is used
 /// for the pathfinding part.
-#[derive(Reflect, Clone, Debug, PartialEq, Eq, Default)]
-pub struct Effect {
+///
+/// Build it with [`Effect::new`] and [`Effect::with_mutator`], new fields may be
+/// added to it without breaking those.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "K: Key + Deserialize<'de>, D: Deserialize<'de>"))
+)]
+pub struct Effect<K = String, D = Datum> {
+    /// Key of the [`Action`](crate::action::Action) this effect belongs to. In plans
+    /// from the planner it's always the key of the action that was picked, even if
+    /// the effect was built with a different label.
     pub action: String,
-    pub mutators: Vec<Mutator>,
-    pub state: LocalState,
+    pub mutators: Mutators<K, D>,
+    /// The [`LocalState`] after applying this effect. Filled in by the planner for
+    /// the effects in a plan, leave it as `None` when building actions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub state: Option<LocalState<K, D>>,
     pub cost: usize,
+    /// Taken off the `cost` of this effect, for actions that pay off later in the
+    /// plan. A reward that cancels out the cost has to be allowed with
+    /// [`Rewards::BoundedDepth`](crate::planner::Rewards::BoundedDepth)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reward: usize,
+    /// How long the effect takes to happen, only counted against a
+    /// [`Goal::deadline`](crate::goal::Goal::deadline) and with
+    /// [`Minimize::Duration`](crate::planner::Minimize::Duration)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub duration: usize,
+}
+
+impl<K, D> Default for Effect<K, D> {
+    fn default() -> Self {
+        Self {
+            action: String::new(),
+            mutators: Mutators::new(),
+            state: None,
+            cost: 0,
+            reward: 0,
+            duration: 0,
+        }
+    }
 }
 
 impl Effect {
+    /// An effect with `String` keys. Use [`Effect::named`] for other keys
     pub fn new(action_name: &str) -> Self {
+        Self::named(action_name)
+    }
+
+    /// Builds an [`Effect`] from the fields struct literals used to give it, before
+    /// it got more of them
+    #[deprecated(
+        note = "the planner fills in `state` itself, use `Effect::new` with `with_mutator` instead"
+    )]
+    pub fn from_parts(
+        action: &str,
+        mutators: impl Into<Mutators>,
+        state: LocalState,
+        cost: usize,
+    ) -> Self {
+        Self {
+            action: action.to_string(),
+            mutators: mutators.into(),
+            state: Some(state),
+            cost,
+            reward: 0,
+            duration: 0,
+        }
+    }
+}
+
+impl<K: Key, D: DatumLike> Effect<K, D> {
+    pub fn named(action_name: &str) -> Self {
         Self {
             action: action_name.to_string(),
-            mutators: vec![],
-            state: LocalState::new(),
+            mutators: Mutators::new(),
+            state: None,
             cost: 1,
+            reward: 0,
+            duration: 0,
         }
     }
-    pub fn with_mutator(mut self, mutator: Mutator) -> Self {
+
+    /// The `cost` minus the `reward`, negative when the reward outweighs the cost
+    pub fn net_cost(&self) -> i64 {
+        let cost = i64::try_from(self.cost).unwrap_or(i64::MAX);
+        let reward = i64::try_from(self.reward).unwrap_or(i64::MAX);
+        cost - reward
+    }
+
+    /// The [`Effect::net_cost`] for searches that can't go below zero, which only
+    /// see effects whose reward doesn't outweigh their cost
+    pub(crate) fn discounted_cost(&self) -> usize {
+        self.cost.saturating_sub(self.reward)
+    }
+
+    pub fn with_mutator(mut self, mutator: Mutator<K, D>) -> Self {
         self.mutators.push(mutator);
         self
     }
+
+    /// Applies the mutators of this effect to `state`, in order, returning the new
+    /// state. Mutators for keys `state` doesn't have are handled according to
+    /// `missing_keys`, which makes this return `None` with [`MissingKeyPolicy::Reject`]
+    pub fn apply(
+        &self,
+        state: &LocalState<K, D>,
+        missing_keys: MissingKeyPolicy,
+    ) -> Option<LocalState<K, D>> {
+        let mut new_state = state.clone();
+        for mutator in &self.mutators {
+            if !new_state.data.contains_key(mutator.key()) {
+                match (missing_keys, mutator) {
+                    // Already gone
+                    (_, Mutator::Unset(_)) => continue,
+                    (MissingKeyPolicy::Reject, _) => return None,
+                    (MissingKeyPolicy::Ignore, _) => continue,
+                    (MissingKeyPolicy::Insert, _) => {}
+                }
+            }
+            apply_mutator(&mut new_state.data, mutator);
+        }
+        Some(new_state)
+    }
+}
+
+/// What applying an [`Effect`] does with a [`Mutator`] for a key that isn't in the
+/// [`LocalState`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissingKeyPolicy {
+    /// The effect can't be applied, so the planner won't pick actions that would
+    /// write to a missing key
+    Reject,
+    /// The mutator is skipped and the key stays missing
+    Ignore,
+    /// [`Mutator::Set`] creates the key. The other mutators have no value to change
+    /// and are skipped, give the key a
+    /// default with [`PlannerConfig::with_default`](crate::planner::PlannerConfig::with_default)
+    /// to count from
+    #[default]
+    Insert,
+}
+
+/// The action and cost, then the mutators, like `chop (cost 2): wood + Datum:I64(1)`
+impl<K: Display, D: Display> Display for Effect<K, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} (cost {})", self.action, self.cost)?;
+        for (index, mutator) in self.mutators.iter().enumerate() {
+            let separator = if index == 0 { ": " } else { ", " };
+            write!(f, "{}{}", separator, mutator)?;
+        }
+        Ok(())
+    }
 }
 
-impl Hash for Effect {
+impl<K: Key, D: DatumLike> Hash for Effect<K, D> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.action.hash(state);
         self.mutators.hash(state);
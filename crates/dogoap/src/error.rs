@@ -0,0 +1,193 @@
+use alloc::string::String;
+use core::fmt::{self, Debug, Display};
+
+use crate::planner::PlanStats;
+
+/// Reasons planning can fail, as opposed to there simply being no plan
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanError<K = String> {
+    /// More than one [`Action`](crate::action::Action) has this key, so a plan
+    /// couldn't tell them apart. See [`DuplicateKeys`](crate::planner::DuplicateKeys)
+    DuplicateActionKey(String),
+    /// The search was stopped by the [`CancellationToken`](crate::prelude::CancellationToken)
+    /// of its [`PlannerConfig`](crate::planner::PlannerConfig) before it found a plan.
+    /// Holds what the search got through until then
+    Cancelled(PlanStats),
+    /// The reward of this [`Action`](crate::action::Action) is at least its cost, or
+    /// it has a dynamic cost that could be. See [`Rewards`](crate::planner::Rewards)
+    RewardOutweighsCost(String),
+    /// Every plan the search came across costs more than
+    /// [`PlannerConfig::max_cost`](crate::planner::PlannerConfig::max_cost). Holds
+    /// the cheapest of them, if the search got far enough to see one
+    TooExpensive { cheapest_found: Option<usize> },
+    /// The search expanded more nodes than
+    /// [`PlannerConfig::max_expanded_nodes`](crate::planner::PlannerConfig::max_expanded_nodes)
+    /// allows before it found a plan. Holds what the search got through until then
+    BudgetExceeded(PlanStats),
+    /// The search ran past
+    /// [`PlannerConfig::time_budget`](crate::planner::PlannerConfig::time_budget)
+    /// before it found a plan, so there may still be one. Holds what the search got
+    /// through until then
+    TimedOut(PlanStats),
+    /// No plan meets the [`Invariant`](crate::prelude::Invariant)s, but there's one
+    /// costing `relaxed_cost` without them. Only reported with
+    /// [`PlannerConfig::retry_without_invariants`](crate::planner::PlannerConfig::retry_without_invariants)
+    BlockedByInvariants { relaxed_cost: usize },
+    /// A mutator of this [`Action`](crate::action::Action) changes a key that's
+    /// [computed](crate::planner::PlannerConfig::computed) from other keys
+    MutatesComputedKey(String),
+    /// A precondition needs this key, but the start state doesn't have it, it has no
+    /// default and it isn't computed. Or the [`Goal`](crate::goal::Goal) does, and no
+    /// mutator sets it either. Only reported without a
+    /// [`MissingKeyPolicy`](crate::prelude::MissingKeyPolicy)
+    MissingKey(K),
+}
+
+impl<K> PlanError<K> {
+    /// The same error, with the key of [`PlanError::MissingKey`] passed through `f`
+    pub(crate) fn map_key<L>(self, f: impl FnOnce(K) -> L) -> PlanError<L> {
+        match self {
+            PlanError::DuplicateActionKey(key) => PlanError::DuplicateActionKey(key),
+            PlanError::Cancelled(stats) => PlanError::Cancelled(stats),
+            PlanError::RewardOutweighsCost(key) => PlanError::RewardOutweighsCost(key),
+            PlanError::TooExpensive { cheapest_found } => {
+                PlanError::TooExpensive { cheapest_found }
+            }
+            PlanError::BudgetExceeded(stats) => PlanError::BudgetExceeded(stats),
+            PlanError::TimedOut(stats) => PlanError::TimedOut(stats),
+            PlanError::BlockedByInvariants { relaxed_cost } => {
+                PlanError::BlockedByInvariants { relaxed_cost }
+            }
+            PlanError::MutatesComputedKey(key) => PlanError::MutatesComputedKey(key),
+            PlanError::MissingKey(key) => PlanError::MissingKey(f(key)),
+        }
+    }
+}
+
+impl<K: Debug> Display for PlanError<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::DuplicateActionKey(key) => write!(
+                f,
+                "More than one action has the key {:?}. Give them unique keys, or plan with DuplicateKeys::Disambiguate",
+                key
+            ),
+            PlanError::Cancelled(stats) => write!(
+                f,
+                "Planning was cancelled after expanding {} nodes",
+                stats.expanded_nodes
+            ),
+            PlanError::RewardOutweighsCost(key) => write!(
+                f,
+                "The reward of action {:?} is (or could be) at least its cost, so the search could loop forever. Plan with Rewards::BoundedDepth to allow it",
+                key
+            ),
+            PlanError::TooExpensive {
+                cheapest_found: Some(cost),
+            } => write!(
+                f,
+                "No plan fits in the cost budget, the cheapest one found costs {}",
+                cost
+            ),
+            PlanError::TooExpensive {
+                cheapest_found: None,
+            } => write!(f, "No plan fits in the cost budget"),
+            PlanError::BudgetExceeded(stats) => write!(
+                f,
+                "Planning gave up after expanding {} nodes, more than the budget allows",
+                stats.expanded_nodes
+            ),
+            PlanError::TimedOut(stats) => write!(
+                f,
+                "Planning ran out of time after expanding {} nodes",
+                stats.expanded_nodes
+            ),
+            PlanError::BlockedByInvariants { relaxed_cost } => write!(
+                f,
+                "No plan keeps to the invariants, without them there's one costing {}",
+                relaxed_cost
+            ),
+            PlanError::MutatesComputedKey(key) => write!(
+                f,
+                "Action {:?} changes a computed key. Change the keys it's computed from instead",
+                key
+            ),
+            PlanError::MissingKey(key) => write!(
+                f,
+                "The key {:?} is needed to plan, but no state in the plan would have it. Give it a default, or plan with a MissingKeyPolicy",
+                key
+            ),
+        }
+    }
+}
+
+impl<K: Debug> core::error::Error for PlanError<K> {}
+
+/// Why [`check_plan`](crate::prelude::check_plan) rejected a plan. Steps count the
+/// actions of the plan from 0
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanInvalid {
+    /// No [`Action`](crate::action::Action) has the key of this step
+    UnknownAction { step: usize, action: String },
+    /// The action doesn't have the effect this step took, or not in the state the
+    /// step was taken in
+    UnknownEffect { step: usize, action: String },
+    /// The preconditions of the action don't hold where the step is taken. Holds
+    /// the first key that fails, printed with `{:?}`, or `None` when it's one of
+    /// the dynamic preconditions
+    PreconditionFailed {
+        step: usize,
+        action: String,
+        key: Option<String>,
+    },
+    /// The step leads to a different state than the plan says it does
+    StateMismatch { step: usize, action: String },
+    /// Every step can be taken, but the state at the end doesn't meet the
+    /// [`Goal`](crate::goal::Goal)
+    GoalUnmet,
+}
+
+impl Display for PlanInvalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanInvalid::UnknownAction { step, action } => {
+                write!(
+                    f,
+                    "Step {} takes action {:?}, which doesn't exist",
+                    step, action
+                )
+            }
+            PlanInvalid::UnknownEffect { step, action } => write!(
+                f,
+                "Step {} takes an effect action {:?} doesn't have",
+                step, action
+            ),
+            PlanInvalid::PreconditionFailed {
+                step,
+                action,
+                key: Some(key),
+            } => write!(
+                f,
+                "Step {} takes action {:?}, but its precondition on {} doesn't hold",
+                step, action, key
+            ),
+            PlanInvalid::PreconditionFailed {
+                step,
+                action,
+                key: None,
+            } => write!(
+                f,
+                "Step {} takes action {:?}, but one of its dynamic preconditions doesn't hold",
+                step, action
+            ),
+            PlanInvalid::StateMismatch { step, action } => write!(
+                f,
+                "Step {} takes action {:?}, which leads somewhere else than the plan says",
+                step, action
+            ),
+            PlanInvalid::GoalUnmet => write!(f, "The plan doesn't reach the goal"),
+        }
+    }
+}
+
+impl core::error::Error for PlanInvalid {}
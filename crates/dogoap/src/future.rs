@@ -0,0 +1,130 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+use crate::{
+    action::Action,
+    cancel::CancellationToken,
+    datum::DatumLike,
+    error::PlanError,
+    goal::Goal,
+    localstate::{Key, LocalState},
+    planner::{
+        check_keys_exist, search_plan_or_cancelled, validate_actions, Interrupt, Plan,
+        PlannerConfig,
+    },
+};
+
+type Outcome<K, D> = Result<Option<Plan<K, D>>, PlanError<K>>;
+
+/// What the planning thread and the [`PlanFuture`] share
+struct Shared<K, D> {
+    outcome: Option<Outcome<K, D>>,
+    /// Set once the thread is done, even if it panicked
+    finished: bool,
+    waker: Option<Waker>,
+}
+
+/// Wakes the future when the planning thread ends, however it ends
+struct WakeOnExit<K, D>(Arc<Mutex<Shared<K, D>>>);
+
+impl<K, D> Drop for WakeOnExit<K, D> {
+    fn drop(&mut self) {
+        let mut shared = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        shared.finished = true;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Plans without blocking the async executor it's awaited on. The search runs on
+/// a thread of its own like with [`spawn_plan`](crate::prelude::spawn_plan), and
+/// wakes the future once it's done, so this works with any executor.
+///
+/// Dropping the future before it's done cancels the search. So does the
+/// [`PlannerConfig::cancellation`] token, if the config has one.
+pub fn plan_async<K: Key + Send + Sync + 'static, D: DatumLike + Send + Sync + 'static>(
+    start: LocalState<K, D>,
+    actions: Arc<[Action<K, D>]>,
+    goal: Goal<K, D>,
+    mut config: PlannerConfig<K, D>,
+) -> PlanFuture<K, D> {
+    let cancellation = config
+        .cancellation
+        .get_or_insert_with(CancellationToken::new)
+        .clone();
+    let shared = Arc::new(Mutex::new(Shared {
+        outcome: None,
+        finished: false,
+        waker: None,
+    }));
+    let thread = {
+        let guard = WakeOnExit(shared.clone());
+        thread::spawn(move || {
+            let outcome = validate_actions(&actions, &config).and_then(|actions| {
+                check_keys_exist(&start, &actions, &goal, &config)?;
+                let interrupt = Interrupt::from_config(&config);
+                search_plan_or_cancelled(&start, &actions, &goal, &config, interrupt)
+            });
+            guard.0.lock().unwrap().outcome = Some(outcome);
+        })
+    };
+    PlanFuture {
+        shared,
+        thread: Some(thread),
+        cancellation,
+    }
+}
+
+/// A plan being made on another thread, see [`plan_async`]
+pub struct PlanFuture<K = String, D = crate::datum::Datum> {
+    shared: Arc<Mutex<Shared<K, D>>>,
+    thread: Option<JoinHandle<()>>,
+    cancellation: CancellationToken,
+}
+
+impl<K, D> Future for PlanFuture<K, D> {
+    type Output = Outcome<K, D>;
+
+    /// # Panics
+    ///
+    /// If planning panicked, or when polled again after it returned the plan
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+        if let Some(outcome) = shared.outcome.take() {
+            shared.finished = true;
+            return Poll::Ready(outcome);
+        }
+        if !shared.finished {
+            shared.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        drop(shared);
+
+        // The thread ended without an outcome, so the search panicked
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            _ => panic!("PlanFuture polled after it was done"),
+        }
+    }
+}
+
+impl<K, D> Drop for PlanFuture<K, D> {
+    fn drop(&mut self) {
+        // Nobody is waiting for the plan anymore. A token from the config is left
+        // alone if the search is done, in case it's used for something else
+        let finished = match self.shared.lock() {
+            Ok(shared) => shared.finished,
+            Err(_) => true,
+        };
+        if !finished {
+            self.cancellation.cancel();
+        }
+    }
+}
@@ -1,42 +1,85 @@
-use std::collections::BTreeMap;
-use std::hash::{Hash, Hasher};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
 
-use bevy_reflect::*;
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::Reflect;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use crate::action::Action;
-use crate::compare::{compare_values, Compare};
-use crate::localstate::LocalState;
+use crate::compare::{compare_entry, Compare};
+use crate::datum::{Datum, DatumLike};
+use crate::localstate::{Key, LocalState};
+use crate::planner::{make_plan, Plan};
 
-/// Goal is a map of what we want our final [`LocalState`](crate::localstate::LocalState) to be, using String as
-/// keys and [`Compare`] to assert what we want the [`Datum`](crate::datum::Datum) to be
-#[derive(Reflect, Clone, Debug, PartialEq)]
-pub struct Goal {
+/// Goal is a map of what we want our final [`LocalState`](crate::localstate::LocalState) to be, using String (or another
+/// [`Key`]) as keys and [`Compare`] to assert what we want the [`Datum`](crate::datum::Datum) to be
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "K: Key + Deserialize<'de>, D: Deserialize<'de>"))
+)]
+pub struct Goal<K = String, D = Datum> {
     /// All the requirements needed to be met in order to consider us to be at our final state
-    pub requirements: BTreeMap<String, Compare>,
+    pub requirements: BTreeMap<K, Compare<D>>,
+
+    /// Groups of requirements of which at least one has to be met as well, for goals
+    /// that can be reached more than one way. Empty if there's only the one way
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub alternatives: Vec<BTreeMap<K, Compare<D>>>,
 
     /// The priority of the goal, determining which goal the planner will focus on.
     /// The goal with the highest priority will be executed first.
     /// If two goals have the same priority, the one added first will be chosen.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub priority: usize,
+
+    /// How much time the plan may take at most, counting the
+    /// [`Effect::duration`](crate::effect::Effect::duration) of its actions
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub deadline: Option<usize>,
 }
 
-impl Hash for Goal {
+impl<K: Key, D: DatumLike> Hash for Goal<K, D> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.requirements.len().hash(state);
         for (key, value) in &self.requirements {
             key.hash(state);
             value.hash(state);
         }
+        self.alternatives.len().hash(state);
+        for group in &self.alternatives {
+            group.len().hash(state);
+            for (key, value) in group {
+                key.hash(state);
+                value.hash(state);
+            }
+        }
     }
 }
 
-impl Goal {
-    pub fn new() -> Self {
+impl<K: Key, D: DatumLike> Default for Goal<K, D> {
+    fn default() -> Self {
         Self {
             requirements: BTreeMap::new(),
+            alternatives: Vec::new(),
             priority: 0,
+            deadline: None,
         }
     }
+}
+
+impl Goal {
+    /// A goal with `String` keys. Use [`Goal::default`] for other keys
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
 
+impl<K: Key, D: DatumLike> Goal<K, D> {
     /// The priority of the goal, determining which goal the planner will focus on.
     /// The goal with the highest priority will be executed first.
     /// If two goals have the same priority, the one added first will be chosen.
@@ -45,28 +88,96 @@ impl Goal {
         self
     }
 
-    pub fn with_req(mut self, key: &str, compare: Compare) -> Self {
-        self.requirements.insert(key.to_string(), compare);
+    pub fn with_deadline(mut self, deadline: usize) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_req(mut self, key: impl Into<K>, compare: Compare<D>) -> Self {
+        self.requirements.insert(key.into(), compare);
         self
     }
 
-    pub fn from_reqs(preconditions: &[(String, Compare)]) -> Goal {
-        let mut goal = Goal::new();
+    pub fn from_reqs(preconditions: &[(K, Compare<D>)]) -> Goal<K, D> {
+        let mut goal = Goal::default();
         for (k, v) in preconditions {
-            goal = goal.with_req(k, v.clone());
+            goal = goal.with_req(k.clone(), v.clone());
         }
         goal
     }
+
+    /// A goal that's met as soon as any one of `goals` is. A goal that has
+    /// alternatives of its own becomes one group for each of them
+    pub fn any_of(goals: impl IntoIterator<Item = Goal<K, D>>) -> Self {
+        let mut any = Goal::default();
+        for goal in goals {
+            if goal.alternatives.is_empty() {
+                any.alternatives.push(goal.requirements);
+                continue;
+            }
+            for group in goal.alternatives {
+                let mut requirements = goal.requirements.clone();
+                requirements.extend(group);
+                any.alternatives.push(requirements);
+            }
+        }
+        any
+    }
+
+    /// Adds a group of requirements that's met by meeting all of it, see
+    /// [`Goal::alternatives`]
+    pub fn with_alternative(mut self, requirements: &[(K, Compare<D>)]) -> Self {
+        self.alternatives.push(requirements.iter().cloned().collect());
+        self
+    }
+
+    /// Whether `state` meets the goal, for polling every frame. Same as
+    /// [`check_goal`]: a requirement on a key the state doesn't have isn't met,
+    /// it doesn't panic
+    pub fn is_satisfied(&self, state: &LocalState<K, D>) -> bool {
+        check_goal(state, self)
+    }
+
+    /// Whether `met` holds for all the requirements, and for all of at least one
+    /// group of the alternatives
+    pub(crate) fn is_met(&self, met: impl Fn(&K, &Compare<D>) -> bool) -> bool {
+        self.requirements.iter().all(|(key, compare)| met(key, compare))
+            && (self.alternatives.is_empty()
+                || self
+                    .alternatives
+                    .iter()
+                    .any(|group| group.iter().all(|(key, compare)| met(key, compare))))
+    }
 }
 
 /// Checks all the requirements from the `Goal` against the provided `LocalState`.
 /// Returns `true` if all the requirements pass (or if there are none), otherwise `false`.
-pub fn check_goal(state: &LocalState, goal: &Goal) -> bool {
-    goal.requirements.iter().all(|(key, value)| {
-        let state_value = state
-            .data
-            .get(key)
-            .unwrap_or_else(|| panic!("Couldn't find key {:#?} in LocalState", key));
-        compare_values(value, state_value)
+/// A requirement on a key the state doesn't have doesn't pass, and with
+/// [`Goal::alternatives`] all of one group has to pass as well
+pub fn check_goal<K: Key, D: DatumLike>(state: &LocalState<K, D>, goal: &Goal<K, D>) -> bool {
+    goal.is_met(|key, value| {
+        compare_entry(value, state.data.get(key))
     })
-}
\ No newline at end of file
+}
+
+/// Picks the goal to work on: the one with the highest [`Goal::priority`] that
+/// `state` doesn't meet yet and that there's a plan for, the one that comes first
+/// in `goals` on ties. Returns its index in `goals` and the plan, or `None` if
+/// every goal is met or out of reach.
+///
+/// # Panics
+///
+/// If more than one [`Action`] has the same key, see [`make_plan`]
+pub fn select_goal<K: Key, D: DatumLike>(
+    goals: &[Goal<K, D>],
+    state: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+) -> Option<(usize, Plan<K, D>)> {
+    let mut order: Vec<usize> = (0..goals.len()).collect();
+    // Stable, so goals with the same priority keep their order
+    order.sort_by(|a, b| goals[*b].priority.cmp(&goals[*a].priority));
+    order
+        .into_iter()
+        .filter(|index| !check_goal(state, &goals[*index]))
+        .find_map(|index| Some((index, make_plan(state, actions, &goals[index])?)))
+}
@@ -0,0 +1,409 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+
+use crate::{
+    action::Action,
+    compare::{compare_entry, compare_values, Compare},
+    computed::ComputedKeys,
+    datum::{Datum, DatumLike},
+    effect::Effect,
+    goal::Goal,
+    localstate::{Key, LocalState},
+    mutator::Mutator,
+    planner::{PlannerConfig, PlanningStrategy},
+    search::HEURISTIC_SCALE,
+};
+
+type Estimate<K, D> = dyn Fn(&LocalState<K, D>, &Goal<K, D>) -> usize + Send + Sync;
+
+/// Estimates what reaching the [`Goal`] from a [`LocalState`] still costs, in place
+/// of the built-in heuristic. See [`PlannerConfig::heuristic`]
+pub struct CustomHeuristic<K = String, D = Datum>(Arc<Estimate<K, D>>);
+
+impl<K, D> CustomHeuristic<K, D> {
+    pub fn new(
+        estimate: impl Fn(&LocalState<K, D>, &Goal<K, D>) -> usize + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(estimate))
+    }
+}
+
+impl<K, D> Clone for CustomHeuristic<K, D> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<K, D> Debug for CustomHeuristic<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CustomHeuristic")
+    }
+}
+
+/// Estimates how far a [`LocalState`] is from the [`Goal`], for the forward search.
+///
+/// By default that's [`LocalState::distance_to_goal`], leaving out the requirements
+/// that are already met. With normalization, every requirement contributes at most
+/// what the cheapest way of fixing it would cost, so one numeric requirement with a
+/// large gap can't drown out the others. A requirement that only actions spending
+/// one of the [`PlannerConfig::resources`] can fix, with nothing to replenish it,
+/// makes the state a dead end once the stock left can't close the gap.
+///
+/// Distances are in units of `1 / HEURISTIC_SCALE` of a cost, so a float key 0.7
+/// away from its requirement still counts for something, and every unit of distance
+/// counts as [`PlannerConfig::cost_scale`] of cost. A [`CustomHeuristic`] replaces
+/// all of this, and is scaled up to those units. Either is multiplied by
+/// [`PlannerConfig::heuristic_weight`] last. With [`PlanningStrategy::UniformCost`],
+/// every distance is 0.
+pub(crate) struct Heuristic<'a, K, D> {
+    goal: &'a Goal<K, D>,
+    fixes: Option<BTreeMap<&'a K, Fix>>,
+    /// The requirements that spending resources limits, only with normalization
+    limits: BTreeMap<&'a K, Limit<'a, K, D>>,
+    computed: &'a ComputedKeys<K, D>,
+    custom: Option<&'a CustomHeuristic<K, D>>,
+    /// See [`PlannerConfig::distance_unit`]
+    unit: u64,
+    /// See [`PlannerConfig::heuristic_weight`], at least 1
+    weight: f64,
+    /// See [`PlanningStrategy::UniformCost`]
+    uniform: bool,
+}
+
+/// What we know about fixing one key of the goal, from the actions that touch it
+#[derive(Default)]
+struct Fix {
+    /// Cost of the cheapest action that sets the key outright
+    set_cost: Option<usize>,
+    /// Largest amount a single action moves the key by, in the same units as the
+    /// distances
+    max_step: u64,
+    /// Cost of the cheapest action that moves the key
+    step_cost: Option<usize>,
+}
+
+impl Fix {
+    /// Lower bound on the cost of closing `gap`, `None` if no action can close it.
+    /// The cost is in units of `1 / HEURISTIC_SCALE`
+    fn estimate(&self, gap: u64) -> Option<u64> {
+        let stepping = self.step_cost.filter(|_| self.max_step > 0).map(|cost| {
+            gap.div_ceil(self.max_step)
+                .saturating_mul(cost as u64)
+                .saturating_mul(HEURISTIC_SCALE)
+        });
+        let setting = self
+            .set_cost
+            .map(|cost| (cost as u64).saturating_mul(HEURISTIC_SCALE));
+        match (setting, stepping) {
+            (Some(setting), Some(stepping)) => Some(setting.min(stepping)),
+            (setting, stepping) => setting.or(stepping),
+        }
+    }
+}
+
+/// How far a requirement can still be moved, when every action that moves it spends
+/// a resource nothing replenishes
+struct Limit<'a, K, D> {
+    /// Largest amount a single action moves the key by, in the same units as the
+    /// distances
+    max_step: u64,
+    /// The resources every one of those actions spends, with their floor and the
+    /// least any of the actions spends of them, in the same units as the distances
+    spends: Vec<(&'a K, Option<&'a D>, u64)>,
+}
+
+impl<K: Key, D: DatumLike> Limit<'_, K, D> {
+    /// Whether what's left of the resources in `state` could pay for closing `gap`
+    fn reachable(&self, state: &LocalState<K, D>, gap: u64, unit: u64) -> bool {
+        self.spends.iter().all(|&(resource, floor, spend)| {
+            let Some(stock) = state.data.get(resource) else {
+                return true;
+            };
+            let left = match floor {
+                Some(floor) => stock.checked_sub(floor),
+                None => Some(stock.clone()),
+            };
+            let Some(left) = left else {
+                return true;
+            };
+            // Below the floor, no step may take the resource any lower
+            let left = match left.checked_sub(&left) {
+                Some(zero) if left <= zero => 0,
+                _ => left.scaled_magnitude(unit),
+            };
+            // Rounding the spend down keeps this from ruling out a gap that can be
+            // closed, as float magnitudes are rounded up
+            let steps = left / spend.saturating_sub(1).max(1);
+            steps.saturating_mul(self.max_step) >= gap
+        })
+    }
+}
+
+impl<'a, K: Key, D: DatumLike> Heuristic<'a, K, D> {
+    pub(crate) fn new(
+        actions: &[Action<K, D>],
+        goal: &'a Goal<K, D>,
+        config: &'a PlannerConfig<K, D>,
+    ) -> Self {
+        let computed = &config.computed;
+        let custom = config.heuristic.as_ref();
+        let unit = config.distance_unit();
+        let weight = f64::from(config.heuristic_weight).max(1.0);
+        let uniform = config.strategy == PlanningStrategy::UniformCost;
+        // There's no telling which keys a dynamic effect fixes, or for how much
+        let dynamic = actions.iter().any(Action::has_dynamic_effects);
+        if !config.normalize_heuristic || custom.is_some() || dynamic || uniform {
+            return Self {
+                goal,
+                fixes: None,
+                limits: BTreeMap::new(),
+                computed,
+                custom,
+                unit,
+                weight,
+                uniform,
+            };
+        }
+
+        let mut fixes: BTreeMap<&'a K, Fix> = BTreeMap::new();
+        let effects = actions
+            .iter()
+            .flat_map(|action| action.effects.iter().map(move |effect| (action, effect)));
+        for (action, effect) in effects {
+            // There's no telling how little a dynamic cost comes to
+            let cost = match action.dynamic_cost {
+                Some(_) => 0,
+                None => effect.discounted_cost(),
+            };
+            for mutator in &effect.mutators {
+                let Some(key) = goal_key(goal, mutator.key()) else {
+                    continue;
+                };
+                let fix = fixes.entry(key).or_default();
+                match mutator {
+                    // Scaling can move a key any distance in one step, like setting it,
+                    // a boolean is only ever one toggle away and a set can gain or
+                    // lose any number of members at once. A copy is as good as a
+                    // set when the source happens to hold the right value
+                    Mutator::Set(..)
+                    | Mutator::CopyFrom(..)
+                    | Mutator::Multiply(..)
+                    | Mutator::Divide(..)
+                    | Mutator::Toggle(..)
+                    | Mutator::Insert(..)
+                    | Mutator::Remove(..) => {
+                        fix.set_cost = Some(fix.set_cost.map_or(cost, |c| c.min(cost)));
+                    }
+                    // Removing a key never gets it closer to meeting the goal
+                    Mutator::Unset(_) => {}
+                    Mutator::Increment(_, amount) | Mutator::Decrement(_, amount) => {
+                        fix.max_step = fix.max_step.max(amount.scaled_magnitude(unit));
+                        fix.step_cost = Some(fix.step_cost.map_or(cost, |c| c.min(cost)));
+                    }
+                }
+            }
+        }
+
+        Self {
+            goal,
+            fixes: Some(fixes),
+            limits: limits(actions, goal, config, unit),
+            computed,
+            custom,
+            unit,
+            weight,
+            uniform,
+        }
+    }
+
+    /// Whether the distance of a successor can be worked out from the keys its
+    /// effect changed. A [`CustomHeuristic`] has to look at the whole state, and
+    /// which of the [`Goal::alternatives`] is closest can change with any key
+    pub(crate) fn is_incremental(&self) -> bool {
+        self.custom.is_none() && self.goal.alternatives.is_empty()
+    }
+
+    /// How much `key` contributes to the distance of `state`, 0 if the goal has no
+    /// requirement for `key`
+    pub(crate) fn key_distance(&self, state: &LocalState<K, D>, key: &K) -> u64 {
+        if self.uniform {
+            return 0;
+        }
+        match self.goal.requirements.get(key) {
+            Some(compare) => self.weighted(self.requirement_distance(state, key, compare)),
+            None => 0,
+        }
+    }
+
+    /// `distance` multiplied by the weight. Every key is weighted on its own, so
+    /// the incremental heuristic of a successor adds up the same
+    fn weighted(&self, distance: u64) -> u64 {
+        if self.weight == 1.0 {
+            return distance;
+        }
+        // Casting saturates at `u64::MAX`
+        (distance as f64 * self.weight) as u64
+    }
+
+    /// How far `key` of `state` is from meeting `compare`
+    fn requirement_distance(&self, state: &LocalState<K, D>, key: &K, compare: &Compare<D>) -> u64 {
+        if self.computed.contains(key) && !state.data.contains_key(key) {
+            // There's no telling how far off a computed key is, only whether it's met
+            let met = self
+                .computed
+                .value(state, key)
+                .is_none_or(|value| compare_values(compare, &value));
+            return if met { 0 } else { self.unit };
+        }
+        // A value past the bound of a `>=` or `<=` is as done as one right on it
+        if compare_entry(compare, state.data.get(key)) {
+            return 0;
+        }
+        let distance = state.requirement_distance(key, compare, self.unit);
+        if let Some(limit) = self.limits.get(key) {
+            if !limit.reachable(state, distance, self.unit) {
+                return u64::MAX;
+            }
+        }
+        let Some(fixes) = &self.fixes else {
+            return distance;
+        };
+        match fixes.get(key).and_then(|fix| fix.estimate(distance)) {
+            Some(estimate) => distance.min(estimate),
+            None => distance,
+        }
+    }
+
+    /// The keys the goal has requirements for that are computed
+    pub(crate) fn computed_requirements(&self) -> impl Iterator<Item = &K> {
+        self.goal
+            .requirements
+            .keys()
+            .filter(|key| self.computed.contains(key))
+    }
+
+    /// The keys the goal has requirements for that spending resources limits. How
+    /// far off they are changes along with the resources
+    pub(crate) fn limited_requirements(&self) -> impl Iterator<Item = &K> {
+        self.limits.keys().copied()
+    }
+
+    pub(crate) fn distance(&self, state: &LocalState<K, D>) -> u64 {
+        if self.uniform {
+            return 0;
+        }
+        if let Some(custom) = self.custom {
+            return self
+                .weighted((custom.0(state, self.goal) as u64).saturating_mul(HEURISTIC_SCALE));
+        }
+        let required = self
+            .goal
+            .requirements
+            .keys()
+            .map(|key| self.key_distance(state, key))
+            .fold(0, u64::saturating_add);
+        // Keys that are required anyway were counted already
+        let closest = self
+            .goal
+            .alternatives
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .filter(|(key, _)| !self.goal.requirements.contains_key(key))
+                    .map(|(key, compare)| self.requirement_distance(state, key, compare))
+                    .fold(0, u64::saturating_add)
+            })
+            .min()
+            .unwrap_or(0);
+        required.saturating_add(self.weighted(closest))
+    }
+}
+
+/// The requirements of `goal` that only actions spending a resource nothing
+/// replenishes can fix, by moving them up or down
+fn limits<'a, K: Key, D: DatumLike>(
+    actions: &[Action<K, D>],
+    goal: &'a Goal<K, D>,
+    config: &'a PlannerConfig<K, D>,
+    unit: u64,
+) -> BTreeMap<&'a K, Limit<'a, K, D>> {
+    let effects = || actions.iter().flat_map(|action| &action.effects);
+    // How much of `resource` `effect` spends, if that's all it does with it
+    let spent = |effect: &Effect<K, D>, resource: &K| {
+        let mut mutators = effect.mutators.iter().filter(|m| m.key() == resource);
+        let spend = match mutators.next()? {
+            Mutator::Decrement(_, amount) => amount,
+            _ => return None,
+        };
+        let zero = spend.checked_sub(spend)?;
+        (*spend > zero && mutators.next().is_none()).then(|| spend.scaled_magnitude(unit))
+    };
+    let resources: Vec<(&K, Option<&D>)> = config
+        .resources
+        .iter()
+        .filter(|(resource, _)| !config.computed.contains(resource))
+        .filter(|(resource, _)| {
+            effects().all(|effect| {
+                !effect.mutators.iter().any(|m| m.key() == *resource)
+                    || spent(effect, resource).is_some()
+            })
+        })
+        .map(|(resource, floor)| (resource, floor.as_ref()))
+        .collect();
+    if resources.is_empty() {
+        return BTreeMap::new();
+    }
+
+    let mut limits = BTreeMap::new();
+    for key in goal.requirements.keys() {
+        if config.resources.contains_key(key) || config.computed.contains(key) {
+            continue;
+        }
+        let mut max_step = 0;
+        let mut spends: Vec<(&K, Option<&D>, u64)> = resources
+            .iter()
+            .map(|&(resource, floor)| (resource, floor, u64::MAX))
+            .collect();
+        let mut moved = false;
+        for effect in effects() {
+            let mut steps = effect.mutators.iter().filter(|m| m.key() == key).peekable();
+            if steps.peek().is_none() {
+                continue;
+            }
+            moved = true;
+            for mutator in steps {
+                match mutator {
+                    Mutator::Increment(_, amount) | Mutator::Decrement(_, amount) => {
+                        max_step = max_step.max(amount.scaled_magnitude(unit));
+                    }
+                    // Setting the key can move it any distance
+                    _ => max_step = u64::MAX,
+                }
+            }
+            spends.retain_mut(|(resource, _, least)| match spent(effect, resource) {
+                Some(spend) => {
+                    *least = (*least).min(spend);
+                    true
+                }
+                None => false,
+            });
+        }
+        if moved && max_step < u64::MAX && !spends.is_empty() {
+            limits.insert(key, Limit { max_step, spends });
+        }
+    }
+    limits
+}
+
+/// The key of `goal` that's the same as `key`, from its requirements or any of its
+/// alternatives
+fn goal_key<'a, K: Key, D>(goal: &'a Goal<K, D>, key: &K) -> Option<&'a K> {
+    core::iter::once(&goal.requirements)
+        .chain(&goal.alternatives)
+        .find_map(|requirements| requirements.get_key_value(key))
+        .map(|(key, _)| key)
+}
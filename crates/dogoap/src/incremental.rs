@@ -0,0 +1,180 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action,
+    compiled::CompiledAction,
+    datum::{Datum, DatumLike},
+    goal::Goal,
+    heuristic::Heuristic,
+    invariant::invariants_hold,
+    localstate::{Key, LocalState},
+    planner::{
+        forward_observer, forward_plan, forward_successors, is_goal, reading_unset_keys,
+        search_plan, validate_actions, with_defaults, Interrupt, Node, PlanStats, PlannerConfig,
+        PlanningStrategy, Rewards,
+    },
+    search::Astar,
+    temporal,
+};
+
+/// What an [`IncrementalPlanner`] got to after a [`IncrementalPlanner::step`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepResult<K: Key = String, D: DatumLike = Datum> {
+    /// Out of expansions for this step, call it again to search on
+    InProgress,
+    /// The plan and its cost, the same one [`make_plan`](crate::planner::make_plan)
+    /// finds searching forwards
+    Done(Vec<Node<K, D>>, usize),
+    /// There's no plan
+    Impossible,
+}
+
+/// The forward search of [`make_plan`](crate::planner::make_plan), spread over as
+/// many calls to [`IncrementalPlanner::step`] as it takes, for when there's only a
+/// little time to plan every frame. It expands the same nodes in the same order as
+/// planning in one go with [`PlanningStrategy::StartToGoal`], so the plan comes out
+/// the same.
+///
+/// [`PlannerConfig::max_expanded_nodes`] counts the expansions of every step
+/// together. The [`PlannerConfig::time_budget`] and cancellation are left to
+/// whoever calls `step`. With [`Rewards::BoundedDepth`] or a timed goal, which
+/// search differently, the first step plans in one go
+pub struct IncrementalPlanner<K: Key = String, D: DatumLike = Datum> {
+    start: LocalState<K, D>,
+    actions: Vec<Action<K, D>>,
+    goal: Goal<K, D>,
+    config: PlannerConfig<K, D>,
+    stats: PlanStats,
+    /// Counted against [`PlannerConfig::max_expanded_nodes`]
+    expansions: usize,
+    progress: Progress<K, D>,
+}
+
+enum Progress<K: Key, D: DatumLike> {
+    Searching(Astar<Node<K, D>, ()>),
+    InOneGo,
+    Finished(StepResult<K, D>),
+}
+
+impl<K: Key, D: DatumLike> IncrementalPlanner<K, D> {
+    /// Gets ready to plan from `start` to `goal`, without expanding anything yet
+    ///
+    /// # Panics
+    ///
+    /// If planning fails with a [`PlanError`](crate::error::PlanError), like
+    /// [`make_plan`](crate::planner::make_plan)
+    pub fn new(
+        start: &LocalState<K, D>,
+        actions: &[Action<K, D>],
+        goal: &Goal<K, D>,
+        config: &PlannerConfig<K, D>,
+    ) -> Self {
+        let actions = validate_actions(actions, config)
+            .unwrap_or_else(|err| panic!("{}", err))
+            .into_owned();
+        let config = reading_unset_keys(config, &actions)
+            .into_owned()
+            .with_strategy(PlanningStrategy::StartToGoal);
+        let start = with_defaults(start, &config.defaults).into_owned();
+        let progress = if !invariants_hold(&start, &config.invariants) {
+            Progress::Finished(StepResult::Impossible)
+        } else if config.rewards != Rewards::Reject || temporal::is_timed(goal, &config) {
+            Progress::InOneGo
+        } else {
+            let heuristic = Heuristic::new(&actions, goal, &config);
+            let distance = heuristic.distance(&start) as usize;
+            Progress::Searching(Astar::new(&Node::State(start.clone()), (), distance))
+        };
+        Self {
+            start,
+            actions,
+            goal: goal.clone(),
+            stats: PlanStats {
+                strategy: PlanningStrategy::StartToGoal,
+                ..Default::default()
+            },
+            config,
+            expansions: 0,
+            progress,
+        }
+    }
+
+    /// Searches on for at most `max_expansions` expansions. Once it's done, every
+    /// step returns the same result again
+    pub fn step(&mut self, max_expansions: usize) -> StepResult<K, D> {
+        let config = &self.config;
+        let result = match &mut self.progress {
+            Progress::Finished(result) => return result.clone(),
+            Progress::InOneGo => {
+                let interrupt = Interrupt::from_config(config);
+                let (plan, stats) = search_plan(
+                    &self.start,
+                    &self.actions,
+                    &self.goal,
+                    config,
+                    interrupt,
+                    None,
+                    None,
+                );
+                self.stats = stats;
+                match plan {
+                    Some((nodes, cost)) => StepResult::Done(nodes, cost),
+                    None => StepResult::Impossible,
+                }
+            }
+            Progress::Searching(search) => {
+                let heuristic = &Heuristic::new(&self.actions, &self.goal, config);
+                let compiled = CompiledAction::compile_all(&self.actions, &self.start);
+                let expand = forward_successors(&compiled, heuristic, config, None);
+                let expansions = &mut self.expansions;
+                let found = search.resume(
+                    max_expansions,
+                    config.max_cost,
+                    config.tie_seed,
+                    &mut self.stats,
+                    || {
+                        // Like the one-shot search, it stops once the budget is spent
+                        *expansions += 1;
+                        config
+                            .max_expanded_nodes
+                            .is_some_and(|max| *expansions > max)
+                    },
+                    expand,
+                    |node| is_goal(node, &self.goal, &config.computed),
+                    forward_observer(config, None),
+                );
+                match found {
+                    Some(Ok((path, cost))) => {
+                        let (nodes, cost) = forward_plan(path, cost, &mut self.stats);
+                        StepResult::Done(nodes, cost)
+                    }
+                    Some(Err(_)) => StepResult::Impossible,
+                    None => return StepResult::InProgress,
+                }
+            }
+        };
+        // Planning in one go already called it
+        if !matches!(self.progress, Progress::InOneGo) {
+            if let Some(on_finish) = &self.config.on_finish {
+                let plan = match &result {
+                    StepResult::Done(nodes, cost) => Some((nodes.clone(), *cost)),
+                    _ => None,
+                };
+                (on_finish.0)(plan.as_ref(), &self.stats);
+            }
+        }
+        self.progress = Progress::Finished(result.clone());
+        result
+    }
+
+    /// Whether stepping is over, with a plan or without
+    pub fn is_finished(&self) -> bool {
+        matches!(self.progress, Progress::Finished(_))
+    }
+
+    /// Numbers about the search so far, added up over every step
+    pub fn stats(&self) -> &PlanStats {
+        &self.stats
+    }
+}
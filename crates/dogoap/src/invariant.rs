@@ -0,0 +1,78 @@
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    compare::{compare_entry, Compare},
+    datum::{Datum, DatumLike},
+    localstate::{Key, LocalState},
+};
+
+/// Conditions that have to hold in every state of a plan, not just the last one like
+/// a [`Goal`](crate::goal::Goal) does: "health stays above 0", "never carry more than
+/// 10". See [`PlannerConfig::invariants`](crate::planner::PlannerConfig::invariants)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invariant<K = String, D = Datum> {
+    /// All of them have to hold. A key the state doesn't have breaks the invariant
+    pub conditions: Vec<(K, Compare<D>)>,
+}
+
+impl<K, D> Default for Invariant<K, D> {
+    fn default() -> Self {
+        Self { conditions: vec![] }
+    }
+}
+
+impl Invariant {
+    /// An invariant with `String` keys. Use [`Invariant::default`] for other keys
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Key, D: DatumLike> Invariant<K, D> {
+    pub fn with_condition(mut self, key: impl Into<K>, compare: Compare<D>) -> Self {
+        self.conditions.push((key.into(), compare));
+        self
+    }
+
+    /// Whether every condition holds in `state`
+    pub fn holds(&self, state: &LocalState<K, D>) -> bool {
+        self.conditions
+            .iter()
+            .all(|(key, compare)| compare_entry(compare, state.data.get(key)))
+    }
+}
+
+/// Whether every one of `invariants` holds in `state`
+pub(crate) fn invariants_hold<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    invariants: &[Invariant<K, D>],
+) -> bool {
+    invariants.iter().all(|invariant| invariant.holds(state))
+}
+
+/// Whether the step from `before` to `after` keeps every one of `resources` at or
+/// above its floor. A resource that was below its floor already may stay there, as
+/// long as the step doesn't take it any lower
+pub(crate) fn resources_hold<K: Key, D: DatumLike>(
+    before: &LocalState<K, D>,
+    after: &LocalState<K, D>,
+    resources: &BTreeMap<K, Option<D>>,
+) -> bool {
+    resources.iter().all(|(key, floor)| {
+        let Some(value) = after.data.get(key) else {
+            return true;
+        };
+        let floor = match floor {
+            Some(floor) => Cow::Borrowed(floor),
+            // Zero of the same kind as the value
+            None => match value.checked_sub(value) {
+                Some(zero) => Cow::Owned(zero),
+                None => return true,
+            },
+        };
+        value >= &floor || before.data.get(key).is_some_and(|before| value >= before)
+    })
+}
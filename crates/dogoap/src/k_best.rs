@@ -0,0 +1,104 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action,
+    compiled::CompiledAction,
+    datum::DatumLike,
+    goal::Goal,
+    heuristic::Heuristic,
+    invariant::invariants_hold,
+    localstate::{Key, LocalState},
+    planner::{
+        avoid_penalty, is_goal, plan_totals, successors, validate_actions, with_defaults,
+        Interrupt, Node, Plan, PlanStats, PlannerConfig,
+    },
+    search,
+};
+
+/// Up to `k` plans from `start` to `goal`, cheapest first, for picking between
+/// when always taking the cheapest would make every NPC act the same. No two of
+/// them take the same actions in the same order, though the same actions in a
+/// different order count as a different plan. See [`make_plans_with_config`]
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`](crate::prelude::PlanError)
+pub fn make_plans<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    k: usize,
+) -> Vec<Plan<K, D>> {
+    make_plans_with_config(start, actions, goal, &PlannerConfig::default(), k)
+}
+
+/// Like [`make_plans`], but with the given config. Fewer than `k` plans come back
+/// when there aren't that many, where plans never go through the same state twice.
+///
+/// The search goes through plans rather than states, so it gets slower the more
+/// plans there are to go past, bound it with [`PlannerConfig::max_expanded_nodes`]
+/// or [`PlannerConfig::time_budget`]. This always searches forwards and ignores
+/// [`PlannerConfig::strategy`] and [`PlannerConfig::rewards`].
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`](crate::prelude::PlanError)
+pub fn make_plans_with_config<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    k: usize,
+) -> Vec<Plan<K, D>> {
+    let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+    let start = with_defaults(start, &config.defaults);
+    if k == 0 || !invariants_hold(&start, &config.invariants) {
+        return Vec::new();
+    }
+    let mut poll = Interrupt::from_config(config).poll();
+    let heuristic = &Heuristic::new(&actions, goal, config);
+    let compiled = CompiledAction::compile_all(&actions, &start);
+    let mut taken: Vec<Vec<String>> = Vec::new();
+
+    let paths = search::k_best(
+        &Node::State(start.clone().into_owned()),
+        (),
+        heuristic.distance(&start) as usize,
+        k,
+        &mut PlanStats::default(),
+        |node, h| {
+            if poll.is_due() {
+                return Vec::new();
+            }
+            successors(node, h, &compiled, heuristic, config, |_| {})
+                .map(|(node, cost, h)| {
+                    let penalty = avoid_penalty(node.state(), &config.avoid);
+                    (node, (), cost.saturating_add(penalty), h)
+                })
+                .collect()
+        },
+        |node| is_goal(node, goal, &config.computed),
+        |node, other| node.state() == other.state(),
+        |path| {
+            let keys = path
+                .iter()
+                .filter_map(|(node, _)| node.action_key().map(String::from))
+                .collect();
+            if taken.contains(&keys) {
+                return false;
+            }
+            taken.push(keys);
+            true
+        },
+    );
+
+    paths
+        .into_iter()
+        .map(|(path, _)| {
+            let nodes: Vec<Node<K, D>> = path.into_iter().map(|(node, _)| node).collect();
+            let (cost, _) = plan_totals(&nodes);
+            (nodes, cost)
+        })
+        .collect()
+}
@@ -1,12 +1,50 @@
-#![doc = include_str!("../README.md")]
+// The examples in the README print plans, which needs std
+#![cfg_attr(feature = "std", doc = include_str!("../README.md"))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 mod action;
+mod action_set;
+mod batch;
+mod bidirectional;
+mod cache;
+mod cancel;
 mod compare;
+mod compiled;
+mod composite;
+mod computed;
+mod context;
+mod contingent;
+mod cycles;
 mod datum;
+mod diff;
 mod effect;
+mod error;
+#[cfg(feature = "async")]
+mod future;
 mod goal;
+mod heuristic;
+mod incremental;
+mod invariant;
+mod k_best;
 mod localstate;
 mod mutator;
+mod objective;
+mod optimize;
+mod path;
+mod regression;
+mod relevance;
+mod search;
+#[cfg(feature = "std")]
+mod spawn;
+#[cfg(feature = "std")]
+mod symbol;
+mod temporal;
+mod trace;
+mod typecheck;
 
 pub mod planner;
 pub mod prelude;
 pub mod simple;
+pub mod testing;
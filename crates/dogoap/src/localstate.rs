@@ -1,6 +1,14 @@
-use std::hash::{Hash, Hasher};
+use core::borrow::Borrow;
+use core::fmt::Debug;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::{self, Zip};
+use core::ops::{Bound, Index, RangeBounds};
+use core::{mem, slice};
 
-use std::collections::BTreeMap;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 
 // List of other hashmap-likes we've tried, but none faster than BTreeMap
 // use scc::HashMap as BTreeMap;
@@ -13,60 +21,446 @@ use std::collections::BTreeMap;
 // use ahash::AHashMap as BTreeMap;
 // use indexmap::IndexMap; // 37,873.88 ns/iter
 // use micromap::Map; // 30,480.55 ns/iter
+// use rpds::RedBlackTreeMapSync; // cheap to clone, but slower, iterating it allocates
 
+#[cfg(feature = "bevy_reflect")]
 use bevy_reflect::Reflect;
+use foldhash::fast::FixedState;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-use crate::datum::Datum;
+use crate::compare::Compare;
+use crate::datum::{Datum, DatumLike};
 use crate::goal::Goal;
 
-pub type InternalData = BTreeMap<String, Datum>;
+/// What the keys of a [`LocalState`] (and everything referring to them) are. That's
+/// `String` unless you pick something else, like a fieldless enum or a
+/// [`Symbol`](crate::prelude::Symbol), which are quicker to compare.
+pub trait Key: Ord + Hash + Clone + Debug {}
+
+impl<K: Ord + Hash + Clone + Debug> Key for K {}
+
+/// The values of a [`LocalState`], by key and in key order, with the API of a
+/// `BTreeMap`. The keys and the values are each shared between clones until one of
+/// them changes, so the planner copying a state for every successor only copies its
+/// values, and states with the same keys (every state of a search, usually) keep
+/// sharing a single list of them
+#[cfg_attr(
+    feature = "bevy_reflect",
+    derive(Reflect),
+    reflect(opaque, Debug, PartialEq, where K: Key, D: DatumLike)
+)]
+pub struct InternalData<K = String, D = Datum> {
+    keys: Arc<Keys<K>>,
+    /// In the order of the keys
+    values: Arc<[D]>,
+}
+
+#[derive(Clone)]
+struct Keys<K> {
+    sorted: Vec<K>,
+    /// Sum of the hashes of the keys, so hashing a state doesn't go over them
+    hash: u64,
+}
+
+/// Iterator over the entries of an [`InternalData`], in key order
+pub type Iter<'a, K, D> = Zip<slice::Iter<'a, K>, slice::Iter<'a, D>>;
+
+fn key_hash<K: Hash>(key: &K) -> u64 {
+    FixedState::default().hash_one(key)
+}
+
+impl<K, D> InternalData<K, D> {
+    pub fn new() -> Self {
+        Self {
+            keys: Arc::new(Keys {
+                sorted: Vec::new(),
+                hash: 0,
+            }),
+            values: Arc::new([]),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, D> {
+        self.keys.sorted.iter().zip(self.values.iter())
+    }
+
+    pub fn keys(&self) -> slice::Iter<'_, K> {
+        self.keys.sorted.iter()
+    }
+
+    pub fn values(&self) -> slice::Iter<'_, D> {
+        self.values.iter()
+    }
+}
+
+impl<K: Ord, D> InternalData<K, D> {
+    fn position<Q>(&self, key: &Q) -> Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.keys
+            .sorted
+            .binary_search_by(|probe| probe.borrow().cmp(key))
+    }
+
+    /// Where `key` is among the keys, for [`InternalData::value_at`]
+    pub(crate) fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.position(key).ok()
+    }
+
+    /// The value of the key at `index`
+    pub(crate) fn value_at(&self, index: usize) -> &D {
+        &self.values[index]
+    }
+
+    /// Starts sharing the keys of `other` if they're equal to ours, so from then on
+    /// [`InternalData::shares_keys_with`] holds for the two
+    pub(crate) fn share_keys_from(&mut self, other: &Self) {
+        if !self.shares_keys_with(other) && self.keys.sorted == other.keys.sorted {
+            self.keys = other.keys.clone();
+        }
+    }
+
+    /// Whether `other` has the very same keys, shared rather than just equal, so an
+    /// index into one is an index into the other
+    pub(crate) fn shares_keys_with(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.keys, &other.keys)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&D>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.position(key).ok().map(|index| &self.values[index])
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.position(key).is_ok()
+    }
+
+    /// The entries with keys in `range`, in key order
+    pub fn range<Q, R>(&self, range: R) -> Iter<'_, K, D>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let keys = &self.keys.sorted;
+        let start = match range.start_bound() {
+            Bound::Included(start) => keys.partition_point(|key| key.borrow() < start),
+            Bound::Excluded(start) => keys.partition_point(|key| key.borrow() <= start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => keys.partition_point(|key| key.borrow() <= end),
+            Bound::Excluded(end) => keys.partition_point(|key| key.borrow() < end),
+            Bound::Unbounded => keys.len(),
+        }
+        .max(start);
+        keys[start..end].iter().zip(self.values[start..end].iter())
+    }
+}
+
+impl<K: Key, D: Clone> InternalData<K, D> {
+    /// Copies the values first if another clone still shares them
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut D>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.position(key).ok()?;
+        if Arc::get_mut(&mut self.values).is_none() {
+            self.values = Arc::from(&self.values[..]);
+        }
+        Arc::get_mut(&mut self.values).map(|values| &mut values[index])
+    }
+
+    /// Sets `key` to `value`, returning the value it had before
+    pub fn insert(&mut self, key: K, value: D) -> Option<D> {
+        match self.position(&key) {
+            Ok(_) => self.get_mut(&key).map(|old| mem::replace(old, value)),
+            Err(index) => {
+                let keys = Arc::make_mut(&mut self.keys);
+                keys.hash = keys.hash.wrapping_add(key_hash(&key));
+                keys.sorted.insert(index, key);
+                let (before, after) = self.values.split_at(index);
+                self.values = before
+                    .iter()
+                    .cloned()
+                    .chain(iter::once(value))
+                    .chain(after.iter().cloned())
+                    .collect();
+                None
+            }
+        }
+    }
+
+    /// Takes `key` out, returning the value it had
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<D>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self.position(key).ok()?;
+        let keys = Arc::make_mut(&mut self.keys);
+        let removed = keys.sorted.remove(index);
+        keys.hash = keys.hash.wrapping_sub(key_hash(&removed));
+        let value = self.values[index].clone();
+        let (before, after) = self.values.split_at(index);
+        self.values = before.iter().chain(&after[1..]).cloned().collect();
+        Some(value)
+    }
+}
+
+impl<K, D> Clone for InternalData<K, D> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<K, D> Default for InternalData<K, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Debug, D: Debug> Debug for InternalData<K, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: PartialEq, D: PartialEq> PartialEq for InternalData<K, D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+            && (Arc::ptr_eq(&self.keys, &other.keys) || self.keys.sorted == other.keys.sorted)
+    }
+}
+
+impl<K: Eq, D: Eq> Eq for InternalData<K, D> {}
+
+impl<K, D: Hash> Hash for InternalData<K, D> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.keys.hash.hash(state);
+        self.values.hash(state);
+    }
+}
+
+impl<K: Ord, D, Q> Index<&Q> for InternalData<K, D>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = D;
+
+    fn index(&self, key: &Q) -> &D {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+impl<'a, K, D> IntoIterator for &'a InternalData<K, D> {
+    type Item = (&'a K, &'a D);
+    type IntoIter = Iter<'a, K, D>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: Ord + Hash, D> FromIterator<(K, D)> for InternalData<K, D> {
+    fn from_iter<I: IntoIterator<Item = (K, D)>>(iter: I) -> Self {
+        let mut entries: Vec<(K, D)> = iter.into_iter().collect();
+        // Stable, so the last value for a key is the one kept, like inserting them
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let mut sorted: Vec<K> = Vec::with_capacity(entries.len());
+        let mut values: Vec<D> = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            if sorted.last() == Some(&key) {
+                *values.last_mut().unwrap() = value;
+            } else {
+                sorted.push(key);
+                values.push(value);
+            }
+        }
+        let hash = sorted.iter().map(key_hash).fold(0, u64::wrapping_add);
+        Self {
+            keys: Arc::new(Keys { sorted, hash }),
+            values: values.into(),
+        }
+    }
+}
+
+impl<K: Key, D: Clone> Extend<(K, D)> for InternalData<K, D> {
+    fn extend<I: IntoIterator<Item = (K, D)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: Serialize, D: Serialize> Serialize for InternalData<K, D> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K: Ord + Hash + Deserialize<'de>, D: Deserialize<'de>> Deserialize<'de>
+    for InternalData<K, D>
+{
+    fn deserialize<De: serde::Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        BTreeMap::<K, D>::deserialize(deserializer).map(|data| data.into_iter().collect())
+    }
+}
 
 /// This is our internal state that the planner uses to progress in the path finding,
 /// until we reach our [`Goal`]
-#[derive(Reflect, Debug, Clone, Eq, PartialEq, Default)]
-pub struct LocalState {
-    pub data: InternalData,
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "K: Key + Deserialize<'de>, D: Deserialize<'de>"))
+)]
+pub struct LocalState<K = String, D = Datum> {
+    pub data: InternalData<K, D>,
 }
 
-impl LocalState {
-    pub fn new() -> Self {
+impl<K: Key, D: DatumLike> Default for LocalState<K, D> {
+    fn default() -> Self {
         Self {
             data: InternalData::new(),
         }
     }
+}
 
-    pub fn with_datum(mut self, key: &str, value: Datum) -> Self {
-        self.data.insert(key.to_string(), value);
+impl LocalState {
+    /// An empty state with `String` keys. Use [`LocalState::default`] for other keys
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<K: Key, D: DatumLike> LocalState<K, D> {
+    pub fn with_datum(mut self, key: impl Into<K>, value: D) -> Self {
+        self.data.insert(key.into(), value);
         self
     }
 
-    pub fn distance_to_goal(&self, goal: &Goal) -> u64 {
-        goal.requirements
-            .iter()
-            .map(|(key, goal_val)| {
-                match self.data.get(key) {
-                    Some(state_val) => state_val.distance(&goal_val.value()),
-                    None => 1, // Penalty for missing keys
+    /// How far off the requirements are, plus how far off the closest group of
+    /// [`Goal::alternatives`] is
+    pub fn distance_to_goal(&self, goal: &Goal<K, D>) -> u64 {
+        let distance = |requirements: &BTreeMap<K, Compare<D>>| -> u64 {
+            requirements
+                .iter()
+                .map(|(key, goal_val)| self.requirement_distance(key, goal_val, 1))
+                .sum()
+        };
+        let closest = goal.alternatives.iter().map(distance).min().unwrap_or(0);
+        distance(&goal.requirements) + closest
+    }
+
+    /// The part of [`LocalState::distance_to_goal`] that comes from `key`, or 0
+    /// if the [`Goal`] has no requirement for `key`
+    pub fn key_distance_to_goal<Q>(&self, key: &Q, goal: &Goal<K, D>) -> u64
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.scaled_key_distance_to_goal(key, goal, 1)
+    }
+
+    /// [`LocalState::key_distance_to_goal`] in units of `1 / scale`, see
+    /// [`DatumLike::scaled_distance`]
+    pub(crate) fn scaled_key_distance_to_goal<Q>(
+        &self,
+        key: &Q,
+        goal: &Goal<K, D>,
+        scale: u64,
+    ) -> u64
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match goal.requirements.get(key) {
+            Some(goal_val) => self.requirement_distance(key, goal_val, scale),
+            None => 0,
+        }
+    }
+
+    /// The keys whose values differ between this state and `other`, in key order,
+    /// with the value here and the one in `other`. `None` where a key is only in
+    /// one of the two
+    pub fn diff<'a>(&'a self, other: &'a Self) -> Vec<(&'a K, Option<&'a D>, Option<&'a D>)> {
+        let mut keys: Vec<&K> = self.data.keys().chain(other.data.keys()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys.into_iter()
+            .map(|key| (key, self.data.get(key), other.data.get(key)))
+            .filter(|(_, ours, theirs)| ours != theirs)
+            .collect()
+    }
+
+    /// Overwrites the keys of this state with the values `other` has for them,
+    /// adding the ones it doesn't have yet
+    pub fn merge(&mut self, other: &Self) {
+        for (key, value) in &other.data {
+            self.data.insert(key.clone(), value.clone());
+        }
+    }
+
+    pub(crate) fn requirement_distance<Q>(&self, key: &Q, goal_val: &Compare<D>, scale: u64) -> u64
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match self.data.get(key) {
+            Some(_) if *goal_val == Compare::Absent => scale,
+            None if *goal_val == Compare::Absent => 0,
+            Some(state_val) => match goal_val {
+                Compare::Contains(members) => {
+                    state_val.missing_members(members).saturating_mul(scale)
                 }
-            })
-            .sum()
+                _ => state_val.scaled_distance(&goal_val.nearest(state_val), scale),
+            },
+            None => scale, // Penalty for missing keys
+        }
     }
 }
 
-impl Hash for LocalState {
+impl<K: Key, D: DatumLike> Hash for LocalState<K, D> {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.data.len().hash(state);
-        for (key, value) in &self.data {
-            key.hash(state);
-            value.hash(state);
-        }
+        self.data.hash(state);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{compare::Compare, goal::Goal};
+    use crate::mutator::{apply_mutator, Mutator};
 
     #[test]
     fn test_distance_to_goal() {
@@ -89,4 +483,111 @@ mod tests {
         let distance = state.distance_to_goal(&goal_state.clone());
         assert_eq!(distance, 50);
     }
+
+    #[test]
+    fn test_data_behaves_like_btree_map() {
+        let mut data = InternalData::new();
+        let mut expected = BTreeMap::new();
+        for (i, key) in ["c", "a", "d", "b", "a", "e"].into_iter().enumerate() {
+            assert_eq!(
+                expected.insert(key.to_string(), i),
+                data.insert(key.to_string(), i)
+            );
+        }
+        assert_eq!(expected.remove("d"), data.remove("d"));
+        assert_eq!(expected.remove("z"), data.remove("z"));
+        *expected.get_mut("b").unwrap() += 10;
+        *data.get_mut("b").unwrap() += 10;
+
+        assert!(data.iter().eq(expected.iter()));
+        let range = || (Bound::Included("b"), Bound::Excluded("e"));
+        assert!(data
+            .range::<str, _>(range())
+            .eq(expected.range::<str, _>(range())));
+        assert_eq!(expected.get("a"), data.get("a"));
+        assert_eq!(expected.len(), data.len());
+        assert_eq!(format!("{:?}", expected), format!("{:?}", data));
+        assert_eq!(data, expected.into_iter().rev().collect());
+    }
+
+    #[test]
+    fn test_clones_dont_see_changes() {
+        let state = LocalState::new()
+            .with_datum("energy", Datum::I64(50))
+            .with_datum("is_hungry", Datum::Bool(true));
+        let mut changed = state.clone();
+        apply_mutator(
+            &mut changed.data,
+            &Mutator::Increment("energy".to_string(), Datum::I64(5)),
+        );
+        let mut unset = state.clone();
+        apply_mutator(&mut unset.data, &Mutator::Unset("is_hungry".to_string()));
+
+        assert_eq!(Some(&Datum::I64(50)), state.data.get("energy"));
+        assert_eq!(Some(&Datum::I64(55)), changed.data.get("energy"));
+        assert_eq!(2, state.data.len());
+        assert!(!unset.data.contains_key("is_hungry"));
+
+        // States built in a different order are the same state
+        let rebuilt = LocalState::new()
+            .with_datum("is_hungry", Datum::Bool(true))
+            .with_datum("energy", Datum::I64(55));
+        assert_eq!(changed, rebuilt);
+        let hash = |state: &LocalState| FixedState::default().hash_one(state);
+        assert_eq!(hash(&changed), hash(&rebuilt));
+        assert_ne!(hash(&state), hash(&changed));
+    }
+
+    #[test]
+    fn test_diff() {
+        let state = LocalState::new()
+            .with_datum("energy", Datum::I64(50))
+            .with_datum("is_hungry", Datum::Bool(true));
+        assert!(state.diff(&state.clone()).is_empty());
+
+        let other = state
+            .clone()
+            .with_datum("energy", Datum::I64(25))
+            .with_datum("gold", Datum::I64(3));
+        assert_eq!(
+            vec![
+                (
+                    &"energy".to_string(),
+                    Some(&Datum::I64(50)),
+                    Some(&Datum::I64(25))
+                ),
+                (&"gold".to_string(), None, Some(&Datum::I64(3))),
+            ],
+            state.diff(&other)
+        );
+        let (key, ours, theirs) = other.diff(&state).pop().unwrap();
+        assert_eq!(
+            ("gold", Some(&Datum::I64(3)), None),
+            (key.as_str(), ours, theirs)
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut state = LocalState::new()
+            .with_datum("energy", Datum::I64(50))
+            .with_datum("is_hungry", Datum::Bool(true));
+        let overrides = LocalState::new()
+            .with_datum("energy", Datum::I64(10))
+            .with_datum("gold", Datum::I64(3));
+
+        state.merge(&overrides);
+
+        assert_eq!(
+            LocalState::new()
+                .with_datum("energy", Datum::I64(10))
+                .with_datum("is_hungry", Datum::Bool(true))
+                .with_datum("gold", Datum::I64(3)),
+            state
+        );
+        assert!(state
+            .diff(&overrides)
+            .iter()
+            .all(|(key, ..)| *key == "is_hungry"));
+    }
 }
@@ -1,46 +1,164 @@
-use crate::{datum::Datum, localstate::InternalData};
+use alloc::string::String;
+use core::fmt::Display;
 
-use bevy_reflect::*;
+use crate::{
+    datum::{Datum, DatumLike},
+    localstate::{InternalData, Key},
+};
+
+#[cfg(feature = "bevy_reflect")]
+use bevy_reflect::Reflect;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Describes a change in [`LocalState`](crate::localstate::LocalState), based on
-/// the String key + a [`Datum`]
-#[derive(Reflect, Clone, Debug, PartialEq, Hash, Eq)]
-pub enum Mutator {
-    Set(String, Datum),       // :key, :value
-    Increment(String, Datum), // :key, :increment-by
-    Decrement(String, Datum), // :key, :decrement-by
+/// a key + a [`Datum`]
+#[derive(Clone, Debug, PartialEq, Hash, Eq)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Mutator<K = String, D = Datum> {
+    Set(K, D),       // :key, :value
+    Increment(K, D), // :key, :increment-by
+    Decrement(K, D), // :key, :decrement-by
+    Multiply(K, D),  // :key, :factor
+    Divide(K, D),    // :key, :divisor
+    Toggle(K),       // :key, flips a boolean and leaves other values as they are
+    Insert(K, D),    // :key, :members
+    Remove(K, D),    // :key, :members
+    CopyFrom(K, K),  // :key, :source-key, left as it is if the source key is missing
+    Unset(K),        // :key, removed from the state altogether
 }
 
-pub fn apply_mutator(data: &mut InternalData, mutator: &Mutator) {
-    match mutator {
-        Mutator::Set(key, value) => {
-            data.insert(key.to_string(), *value);
+impl<K, D> Mutator<K, D> {
+    /// The key in [`LocalState`](crate::localstate::LocalState) this mutator changes
+    pub fn key(&self) -> &K {
+        match self {
+            Mutator::Set(key, _) => key,
+            Mutator::Increment(key, _) => key,
+            Mutator::Decrement(key, _) => key,
+            Mutator::Multiply(key, _) => key,
+            Mutator::Divide(key, _) => key,
+            Mutator::Toggle(key) => key,
+            Mutator::Insert(key, _) => key,
+            Mutator::Remove(key, _) => key,
+            Mutator::CopyFrom(key, _) => key,
+            Mutator::Unset(key) => key,
         }
+    }
+
+    /// Whether this is a [`Mutator::Multiply`] or [`Mutator::Divide`], which the
+    /// search can't undo when planning backwards
+    pub fn is_scaling(&self) -> bool {
+        matches!(self, Mutator::Multiply(..) | Mutator::Divide(..))
+    }
+
+    /// Whether this is a [`Mutator::Insert`] or [`Mutator::Remove`], which the
+    /// search can't undo when planning backwards either
+    pub fn is_membership(&self) -> bool {
+        matches!(self, Mutator::Insert(..) | Mutator::Remove(..))
+    }
+}
+
+pub fn apply_mutator<K: Key, D: DatumLike>(data: &mut InternalData<K, D>, mutator: &Mutator<K, D>) {
+    match mutator {
+        // Only clones the key when it's new
+        Mutator::Set(key, value) => match data.get_mut(key) {
+            Some(current_value) => *current_value = value.clone(),
+            None => {
+                data.insert(key.clone(), value.clone());
+            }
+        },
         Mutator::Increment(key, value) => {
             if let Some(current_value) = data.get_mut(key) {
-                *current_value += *value;
+                *current_value = current_value.checked_add(value).unwrap_or_else(|| {
+                    panic!(
+                        "Can't add {:?} to {:?} for key {:?}",
+                        value, current_value, key
+                    )
+                });
             }
         }
         Mutator::Decrement(key, value) => {
             if let Some(current_value) = data.get_mut(key) {
-                *current_value -= *value;
+                *current_value = current_value.checked_sub(value).unwrap_or_else(|| {
+                    panic!(
+                        "Can't subtract {:?} from {:?} for key {:?}",
+                        value, current_value, key
+                    )
+                });
             }
         }
-    }
-}
-
-pub fn print_mutators(mutators: Vec<Mutator>) {
-    for mutator in mutators {
-        match mutator {
-            Mutator::Set(k, v) => {
-                println!("\t\t{} = {}", k, v);
+        Mutator::Multiply(key, value) => {
+            if let Some(current_value) = data.get_mut(key) {
+                *current_value = current_value.checked_mul(value).unwrap_or_else(|| {
+                    panic!(
+                        "Can't multiply {:?} by {:?} for key {:?}",
+                        current_value, value, key
+                    )
+                });
+            }
+        }
+        Mutator::Divide(key, value) => {
+            if let Some(current_value) = data.get_mut(key) {
+                *current_value = current_value.checked_div(value).unwrap_or_else(|| {
+                    panic!(
+                        "Can't divide {:?} by {:?} for key {:?}",
+                        current_value, value, key
+                    )
+                });
+            }
+        }
+        Mutator::Insert(key, members) => {
+            if let Some(current_value) = data.get_mut(key) {
+                *current_value = current_value.with_members(members).unwrap_or_else(|| {
+                    panic!(
+                        "Can't insert {:?} into {:?} for key {:?}",
+                        members, current_value, key
+                    )
+                });
+            }
+        }
+        Mutator::Remove(key, members) => {
+            if let Some(current_value) = data.get_mut(key) {
+                *current_value = current_value.without_members(members).unwrap_or_else(|| {
+                    panic!(
+                        "Can't remove {:?} from {:?} for key {:?}",
+                        members, current_value, key
+                    )
+                });
             }
-            Mutator::Increment(k, v) => {
-                println!("\t\t{} + {}", k, v);
+        }
+        Mutator::CopyFrom(key, source) => {
+            if let Some(value) = data.get(source).cloned() {
+                data.insert(key.clone(), value);
             }
-            Mutator::Decrement(k, v) => {
-                println!("\t\t{} - {}", k, v);
+        }
+        Mutator::Unset(key) => {
+            data.remove(key);
+        }
+        Mutator::Toggle(key) => {
+            if let Some(current_value) = data.get_mut(key) {
+                if let Some(toggled) = current_value.toggled() {
+                    *current_value = toggled;
+                }
             }
         }
     }
 }
+
+impl<K: Display, D: Display> Display for Mutator<K, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Mutator::Set(k, v) => write!(f, "{} = {}", k, v),
+            Mutator::Increment(k, v) => write!(f, "{} + {}", k, v),
+            Mutator::Decrement(k, v) => write!(f, "{} - {}", k, v),
+            Mutator::Multiply(k, v) => write!(f, "{} * {}", k, v),
+            Mutator::Divide(k, v) => write!(f, "{} / {}", k, v),
+            Mutator::Toggle(k) => write!(f, "{} = !{}", k, k),
+            Mutator::Insert(k, v) => write!(f, "{} += {}", k, v),
+            Mutator::Remove(k, v) => write!(f, "{} -= {}", k, v),
+            Mutator::CopyFrom(k, source) => write!(f, "{} = {}", k, source),
+            Mutator::Unset(k) => write!(f, "unset {}", k),
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::{
+    action::Action,
+    datum::DatumLike,
+    goal::Goal,
+    invariant::invariants_hold,
+    localstate::{Key, LocalState},
+    planner::{
+        applicable_effects, is_goal, plan_totals, validate_actions, with_defaults, Node, Plan,
+        PlanStats, PlannerConfig,
+    },
+    search,
+};
+
+/// A number to get as high or as low as possible, for when there's no threshold a
+/// [`Goal`] could ask for. See [`make_plan_for_objective`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Objective<K = String> {
+    Maximize(K),
+    Minimize(K),
+}
+
+/// How far [`make_plan_for_objective`] may look. There's no goal to stop at, so
+/// every plan within the budget is considered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Budget {
+    /// The most the plan may cost, counting [`PlannerConfig::avoid`] penalties and
+    /// rewards like the search does
+    Cost(usize),
+    /// The most actions the plan may have
+    Depth(usize),
+}
+
+/// Finds the plan within `budget` whose final state scores best on `objective`,
+/// taking the cheapest plan when several score the same. Only final states that
+/// meet `requirements` count, pass [`Goal::default`] if anything goes.
+///
+/// States without the key of the objective don't count either. The search goes
+/// through everything within the budget, so keep it tight. This always searches
+/// forwards and ignores [`PlannerConfig::strategy`]. `None` if no plan within the
+/// budget meets the requirements.
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`](crate::prelude::PlanError)
+pub fn make_plan_for_objective<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    objective: &Objective<K>,
+    budget: Budget,
+    requirements: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> Option<Plan<K, D>> {
+    let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+    let start = with_defaults(start, &config.defaults);
+    if !invariants_hold(&start, &config.invariants) {
+        return None;
+    }
+    match objective {
+        Objective::Maximize(key) => {
+            best_plan(&start, &actions, budget, requirements, config, |state| {
+                state.data.get(key).cloned()
+            })
+        }
+        Objective::Minimize(key) => {
+            best_plan(&start, &actions, budget, requirements, config, |state| {
+                state.data.get(key).cloned().map(Reverse)
+            })
+        }
+    }
+}
+
+/// Runs [`search::best_within`], scoring the states that meet `requirements` with
+/// `score`
+fn best_plan<K: Key, D: DatumLike, S: PartialOrd>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    budget: Budget,
+    requirements: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    score: impl Fn(&LocalState<K, D>) -> Option<S>,
+) -> Option<Plan<K, D>> {
+    let (max_cost, max_depth) = match budget {
+        Budget::Cost(cost) => (Some(cost), None),
+        Budget::Depth(depth) => (None, Some(depth)),
+    };
+    let (path, _) = search::best_within(
+        &Node::State(start.clone()),
+        (),
+        max_cost,
+        max_depth,
+        &mut PlanStats::default(),
+        |node| {
+            applicable_effects(node.state(), actions, config)
+                .map(|(effect, penalty)| {
+                    let cost = effect.discounted_cost().saturating_add(penalty);
+                    (Node::Effect(effect), (), cost)
+                })
+                .collect::<Vec<_>>()
+        },
+        |node| {
+            if is_goal(node, requirements, &config.computed) {
+                score(node.state())
+            } else {
+                None
+            }
+        },
+    )?;
+
+    let nodes: Vec<Node<K, D>> = path.into_iter().map(|(node, _)| node).collect();
+    let (cost, _) = plan_totals(&nodes);
+    Some((nodes, cost))
+}
@@ -0,0 +1,331 @@
+use alloc::string::String;
+use alloc::{format, vec, vec::Vec};
+
+use crate::{
+    action::Action,
+    compare::compare_entry,
+    computed::ComputedKeys,
+    datum::{Datum, DatumLike},
+    effect::{Effect, MissingKeyPolicy},
+    error::PlanInvalid,
+    goal::{check_goal, Goal},
+    localstate::{Key, LocalState},
+    planner::{effects_of_plan, is_goal, make_plan, plan_totals, preconditions_met, Node, Plan},
+};
+
+/// Whether `plan` can be carried out from `start` and reaches `goal`. Every step has
+/// to be an effect of the action with its key, taken where the action's
+/// preconditions hold, and lead to the state the plan says it does.
+///
+/// Only the actions and the goal are checked, not the rest of a
+/// [`PlannerConfig`](crate::planner::PlannerConfig) like invariants or resources.
+/// Keys missing from a state count as not meeting a precondition or the goal. See
+/// [`check_plan`] for why a plan isn't valid
+pub fn validate_plan<K: Key, D: DatumLike>(
+    plan: &Plan<K, D>,
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> bool {
+    check_plan(plan, start, actions, goal).is_ok()
+}
+
+/// Like [`validate_plan`], but tells which step of the plan can't be carried out
+/// and why, or that it doesn't reach the goal, for plans held on to while the world
+/// changes
+pub fn check_plan<K: Key, D: DatumLike>(
+    plan: &Plan<K, D>,
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> Result<(), PlanInvalid> {
+    let mut state = start.clone();
+    for (index, step) in effects_of_plan(&plan.0).enumerate() {
+        let reached = take_step(&state, actions, index, step)?;
+        if step
+            .state
+            .as_ref()
+            .is_some_and(|recorded| *recorded != reached)
+        {
+            return Err(PlanInvalid::StateMismatch {
+                step: index,
+                action: step.action.clone(),
+            });
+        }
+        state = reached;
+    }
+    if !check_goal(&state, goal) {
+        return Err(PlanInvalid::GoalUnmet);
+    }
+    Ok(())
+}
+
+/// Drops the steps of `plan` it can do without. Every step is tried on its own,
+/// then every pair of the steps left, keeping whatever still passes
+/// [`validate_plan`]. Undoing something only to do it again, like switching a light
+/// on and back off, takes dropping both steps at once.
+///
+/// Takes at most a quadratic number of validations in the length of the plan, and
+/// returns `plan` as it is if it's not valid to begin with. Like [`validate_plan`],
+/// this knows nothing of the rest of a [`PlannerConfig`](crate::planner::PlannerConfig)
+pub fn optimize_plan<K: Key, D: DatumLike>(
+    plan: &Plan<K, D>,
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> Plan<K, D> {
+    if !validate_plan(plan, start, actions, goal) {
+        return plan.clone();
+    }
+    let mut steps: Vec<&Effect<K, D>> = effects_of_plan(&plan.0).collect();
+
+    let mut index = 0;
+    while index < steps.len() {
+        let mut fewer = steps.clone();
+        fewer.remove(index);
+        if valid(start, actions, goal, &fewer) {
+            steps = fewer;
+        } else {
+            index += 1;
+        }
+    }
+
+    let mut first = 0;
+    while first < steps.len() {
+        let mut second = first + 1;
+        while second < steps.len() {
+            let mut fewer = steps.clone();
+            fewer.remove(second);
+            fewer.remove(first);
+            if valid(start, actions, goal, &fewer) {
+                steps = fewer;
+                second = first + 1;
+            } else {
+                second += 1;
+            }
+        }
+        first += 1;
+    }
+
+    let nodes = replay(start, actions, &steps).expect("steps were validated");
+    let (cost, _) = plan_totals(&nodes);
+    (nodes, cost)
+}
+
+/// How [`repair_plan`] fixed up a plan, for telling a plan that carries on as
+/// before apart from one that does something else right away
+#[derive(Clone, Debug, PartialEq)]
+pub enum RepairedPlan<K: Key = String, D: DatumLike = Datum> {
+    /// The plan still works as it is
+    Kept(Plan<K, D>),
+    /// The plan starts with the same step as before, but goes another way from
+    /// where it stopped working
+    Patched(Plan<K, D>),
+    /// The plan starts differently
+    Replaced(Plan<K, D>),
+}
+
+impl<K: Key, D: DatumLike> RepairedPlan<K, D> {
+    pub fn plan(&self) -> &Plan<K, D> {
+        match self {
+            RepairedPlan::Kept(plan)
+            | RepairedPlan::Patched(plan)
+            | RepairedPlan::Replaced(plan) => plan,
+        }
+    }
+
+    pub fn into_plan(self) -> Plan<K, D> {
+        match self {
+            RepairedPlan::Kept(plan)
+            | RepairedPlan::Patched(plan)
+            | RepairedPlan::Replaced(plan) => plan,
+        }
+    }
+}
+
+/// Fixes up `plan` for a world that changed since it was made, with `start` being
+/// where it stands now. The steps of `plan` are taken from `start` for as long as
+/// they can be, and only the rest is planned again with [`make_plan`], from the
+/// state the last of them leads to. If that can't reach `goal` from there, the
+/// whole plan is made again from `start`.
+///
+/// Planning again, the actions the rest of the old plan took cost 1 less to the
+/// search, where that leaves them costing something, so the new plan goes back to
+/// them when it can for about the same cost. The plan's cost is still what its
+/// steps cost.
+///
+/// A plan that still works is returned with its states and cost worked out from
+/// `start`. Like [`validate_plan`], the steps are checked against the actions only.
+/// `None` if there's no plan from `start` at all
+///
+/// # Panics
+///
+/// Like [`make_plan`]
+pub fn repair_plan<K: Key, D: DatumLike>(
+    plan: &Plan<K, D>,
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> Option<RepairedPlan<K, D>> {
+    let steps: Vec<&Effect<K, D>> = effects_of_plan(&plan.0).collect();
+    let mut nodes = replay_while_possible(start, actions, &steps);
+    let taken = nodes.len() - 1;
+    if taken == steps.len() && reaches(&nodes, goal) {
+        let (cost, _) = plan_totals(&nodes);
+        return Some(RepairedPlan::Kept((nodes, cost)));
+    }
+    if !reaches(&nodes, goal) {
+        let preferred = prefer(actions, &steps[taken..]);
+        let diverged = nodes.last().unwrap().state().clone();
+        match make_plan(&diverged, &preferred, goal) {
+            Some((rest, _)) => nodes.extend(rest.into_iter().skip(1)),
+            None if taken > 0 => {
+                let (again, _) = make_plan(start, &prefer(actions, &steps), goal)?;
+                nodes = again;
+            }
+            None => return None,
+        }
+    }
+    let nodes = with_rewards_of(nodes, actions);
+    let (cost, _) = plan_totals(&nodes);
+    let first = effects_of_plan(&nodes).next();
+    let same_start = first
+        .zip(steps.first())
+        .is_some_and(|(new, old)| new.action == old.action && new.mutators == old.mutators);
+    if same_start {
+        Some(RepairedPlan::Patched((nodes, cost)))
+    } else {
+        Some(RepairedPlan::Replaced((nodes, cost)))
+    }
+}
+
+/// `actions` with a reward of 1 more on the effects of the ones `steps` take, see
+/// [`repair_plan`]
+fn prefer<K: Key, D: DatumLike>(
+    actions: &[Action<K, D>],
+    steps: &[&Effect<K, D>],
+) -> Vec<Action<K, D>> {
+    let mut preferred = actions.to_vec();
+    for action in &mut preferred {
+        if action.dynamic_cost.is_some() || !steps.iter().any(|step| step.action == action.key) {
+            continue;
+        }
+        for effect in &mut action.effects {
+            if effect.reward + 1 < effect.cost {
+                effect.reward += 1;
+            }
+        }
+    }
+    preferred
+}
+
+/// `nodes` planned with the actions from [`prefer`], with the rewards the effects
+/// have in `actions`
+fn with_rewards_of<K: Key, D: DatumLike>(
+    nodes: Vec<Node<K, D>>,
+    actions: &[Action<K, D>],
+) -> Vec<Node<K, D>> {
+    nodes
+        .into_iter()
+        .map(|node| match node {
+            Node::Effect(mut effect) => {
+                let original = actions
+                    .iter()
+                    .filter(|action| action.key == effect.action)
+                    .flat_map(|action| &action.effects)
+                    .find(|original| original.mutators == effect.mutators);
+                if let Some(original) = original {
+                    effect.reward = original.reward;
+                }
+                Node::Effect(effect)
+            }
+            node => node,
+        })
+        .collect()
+}
+
+fn valid<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    steps: &[&Effect<K, D>],
+) -> bool {
+    replay(start, actions, steps).is_some_and(|nodes| reaches(&nodes, goal))
+}
+
+fn reaches<K: Key, D: DatumLike>(nodes: &[Node<K, D>], goal: &Goal<K, D>) -> bool {
+    is_goal(nodes.last().unwrap(), goal, &ComputedKeys::default())
+}
+
+/// Takes `steps` one after another from `start`, `None` as soon as one of them
+/// can't be taken
+fn replay<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    steps: &[&Effect<K, D>],
+) -> Option<Vec<Node<K, D>>> {
+    let nodes = replay_while_possible(start, actions, steps);
+    (nodes.len() == steps.len() + 1).then_some(nodes)
+}
+
+/// Takes `steps` one after another from `start`, up to the first one that can't be
+/// taken
+fn replay_while_possible<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    steps: &[&Effect<K, D>],
+) -> Vec<Node<K, D>> {
+    let mut nodes = vec![Node::State(start.clone())];
+    for (index, step) in steps.iter().enumerate() {
+        let state = nodes.last().unwrap().state();
+        let Ok(reached) = take_step(state, actions, index, step) else {
+            break;
+        };
+        nodes.push(Node::Effect(Effect {
+            state: Some(reached),
+            ..(*step).clone()
+        }));
+    }
+    nodes
+}
+
+/// Takes `step`, the step at `index` of a plan, in `state`, returning the state it
+/// leads to
+fn take_step<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    index: usize,
+    step: &Effect<K, D>,
+) -> Result<LocalState<K, D>, PlanInvalid> {
+    let action = step.action.clone();
+    let Some(found) = actions.iter().find(|found| found.key == step.action) else {
+        return Err(PlanInvalid::UnknownAction {
+            step: index,
+            action,
+        });
+    };
+    if !found
+        .effects_in(state)
+        .any(|effect| effect.mutators == step.mutators)
+    {
+        return Err(PlanInvalid::UnknownEffect {
+            step: index,
+            action,
+        });
+    }
+    let missing_keys = Some(MissingKeyPolicy::Insert);
+    if !preconditions_met(state, found, missing_keys, &ComputedKeys::default()) {
+        let key = found
+            .preconditions
+            .iter()
+            .find(|(key, compare)| !compare_entry(compare, state.data.get(key)))
+            .map(|(key, _)| format!("{:?}", key));
+        return Err(PlanInvalid::PreconditionFailed {
+            step: index,
+            action,
+            key,
+        });
+    }
+    // Inserting applies every mutator
+    Ok(step.apply(state, MissingKeyPolicy::Insert).unwrap())
+}
@@ -0,0 +1,187 @@
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::ops::Bound;
+
+use crate::{
+    computed::ComputedKey,
+    datum::{Datum, DatumLike},
+    localstate::LocalState,
+    mutator::Mutator,
+};
+
+/// Why a dot-path like `inventory.wood` isn't a valid one. Paths are plain `String`
+/// keys split into segments by dots, so every segment has to have something in it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathError {
+    /// The path is an empty string
+    Empty,
+    /// Segment `index` of the path is empty, from a dot at the start or two in a row
+    EmptySegment { path: String, index: usize },
+    /// The path ends in a dot. Only the prefixes given to [`LocalState::get_all`] may
+    TrailingDot(String),
+    /// A `*` anywhere but as the last segment of a wildcard like `inventory.*`
+    MisplacedWildcard(String),
+    /// A wildcard has to end in `.*`
+    NotAWildcard(String),
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::Empty => write!(f, "The path is empty"),
+            PathError::EmptySegment { path, index } => {
+                write!(f, "Segment {} of the path {:?} is empty", index, path)
+            }
+            PathError::TrailingDot(path) => write!(f, "The path {:?} ends in a dot", path),
+            PathError::MisplacedWildcard(path) => write!(
+                f,
+                "The path {:?} has a * that isn't the last segment of a wildcard",
+                path
+            ),
+            PathError::NotAWildcard(path) => {
+                write!(f, "The wildcard {:?} doesn't end in .*", path)
+            }
+        }
+    }
+}
+
+impl core::error::Error for PathError {}
+
+/// How [`PlannerConfig::with_wildcard`](crate::planner::PlannerConfig::with_wildcard)
+/// sums up the keys a wildcard like `inventory.*` stands for
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Wildcard {
+    /// How many of the keys hold something: `true`, a number other than zero, or
+    /// any enum value. Always a [`Datum::I64`]
+    #[default]
+    Count,
+    /// What the keys add up to. Keys that can't be added to the ones before them
+    /// are left out, and no keys at all add up to `Datum::I64(0)`
+    Sum,
+}
+
+/// The segments of `path`, like `["inventory", "wood"]` for `inventory.wood`
+pub fn parse_path(path: &str) -> Result<Vec<&str>, PathError> {
+    if path.is_empty() {
+        return Err(PathError::Empty);
+    }
+    if path.ends_with('.') {
+        return Err(PathError::TrailingDot(path.to_string()));
+    }
+    let segments: Vec<&str> = path.split('.').collect();
+    if let Some(index) = segments.iter().position(|segment| segment.is_empty()) {
+        return Err(PathError::EmptySegment {
+            path: path.to_string(),
+            index,
+        });
+    }
+    if segments.iter().any(|segment| segment.contains('*')) {
+        return Err(PathError::MisplacedWildcard(path.to_string()));
+    }
+    Ok(segments)
+}
+
+/// `prefix` with exactly one dot at the end, so it only matches whole segments
+fn parse_prefix(prefix: &str) -> Result<String, PathError> {
+    let path = prefix.strip_suffix('.').unwrap_or(prefix);
+    parse_path(path)?;
+    Ok(path.to_string() + ".")
+}
+
+/// The prefix a wildcard like `inventory.*` stands for the keys under
+fn parse_wildcard(pattern: &str) -> Result<String, PathError> {
+    match pattern.strip_suffix(".*") {
+        Some(path) => parse_prefix(path),
+        None => {
+            parse_path(pattern)?;
+            Err(PathError::NotAWildcard(pattern.to_string()))
+        }
+    }
+}
+
+/// The computed key for a wildcard, see
+/// [`PlannerConfig::with_wildcard`](crate::planner::PlannerConfig::with_wildcard)
+pub(crate) fn wildcard_key(pattern: &str, wildcard: Wildcard) -> Result<ComputedKey, PathError> {
+    let prefix = parse_wildcard(pattern)?;
+    Ok(Arc::new(move |state: &LocalState| {
+        let mut values = state
+            .get_all(&prefix)
+            .expect("prefix was parsed")
+            .map(|(_, value)| value);
+        match wildcard {
+            Wildcard::Count => {
+                Datum::I64(values.filter(|value| holds_something(value)).count() as i64)
+            }
+            Wildcard::Sum => {
+                let Some(first) = values.next() else {
+                    return Datum::I64(0);
+                };
+                values.fold(*first, |sum, value| sum.checked_add(value).unwrap_or(sum))
+            }
+        }
+    }))
+}
+
+fn holds_something(value: &Datum) -> bool {
+    match value {
+        Datum::Bool(value) => *value,
+        Datum::I64(value) => *value != 0,
+        Datum::F64(value) => *value != 0.0,
+        Datum::Enum(_) => true,
+        Datum::Set(bits) => *bits != 0,
+    }
+}
+
+/// Dot-paths are stored as the flat keys they are, so these only check the path
+/// before going to [`LocalState::data`]
+impl<D: DatumLike> LocalState<String, D> {
+    /// The value at `path`, `None` if the state doesn't have it
+    pub fn get_path(&self, path: &str) -> Result<Option<&D>, PathError> {
+        parse_path(path)?;
+        Ok(self.data.get(path))
+    }
+
+    /// Sets the value at `path`, returning the one it replaced
+    pub fn set_path(&mut self, path: &str, value: D) -> Result<Option<D>, PathError> {
+        parse_path(path)?;
+        Ok(self.data.insert(path.to_string(), value))
+    }
+
+    /// Every key under `prefix` with its value, in order. `inventory` and `inventory.`
+    /// both match `inventory.wood` and `inventory.bag.gems`, but not `inventory` itself
+    /// or `inventory_size`
+    pub fn get_all<'a>(
+        &'a self,
+        prefix: &str,
+    ) -> Result<impl Iterator<Item = (&'a str, &'a D)> + 'a, PathError> {
+        let prefix = parse_prefix(prefix)?;
+        let range = (Bound::Included(prefix.as_str()), Bound::Unbounded);
+        Ok(self
+            .data
+            .range::<str, _>(range)
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+            .map(|(key, value)| (key.as_str(), value)))
+    }
+}
+
+impl<D> Mutator<String, D> {
+    /// [`Mutator::Set`] for a dot-path
+    pub fn set_path(path: &str, value: D) -> Result<Self, PathError> {
+        parse_path(path)?;
+        Ok(Mutator::Set(path.to_string(), value))
+    }
+
+    /// [`Mutator::Increment`] for a dot-path
+    pub fn increment_path(path: &str, amount: D) -> Result<Self, PathError> {
+        parse_path(path)?;
+        Ok(Mutator::Increment(path.to_string(), amount))
+    }
+
+    /// [`Mutator::Decrement`] for a dot-path
+    pub fn decrement_path(path: &str, amount: D) -> Result<Self, PathError> {
+        parse_path(path)?;
+        Ok(Mutator::Decrement(path.to_string(), amount))
+    }
+}
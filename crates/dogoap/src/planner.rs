@@ -1,34 +1,91 @@
 use crate::{
     action::Action,
-    compare::{check_preconditions, compare_values},
-    effect::Effect,
+    bidirectional,
+    cancel::CancellationToken,
+    compare::{check_preconditions, compare_entry, Compare},
+    compiled::CompiledAction,
+    computed::{ComputedKey, ComputedKeys},
+    datum::{Datum, DatumLike},
+    effect::{Effect, MissingKeyPolicy},
+    error::PlanError,
     goal::Goal,
-    localstate::LocalState,
-    mutator::{apply_mutator, print_mutators},
+    heuristic::{CustomHeuristic, Heuristic},
+    invariant::{invariants_hold, resources_hold, Invariant},
+    localstate::{Key, LocalState},
+    mutator::Mutator,
+    path::{wildcard_key, Wildcard},
+    regression,
+    search::{self, SearchEvent, Successor, HEURISTIC_SCALE},
+    temporal,
+    trace::{OnExpand, OnFinish, PlannerTrace, PrunedBy, TraceEvent},
 };
 
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::{format, vec, vec::Vec};
+use core::cell::RefCell;
+use core::fmt::Display;
+use core::hash::{Hash, Hasher};
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "bevy_reflect")]
 use bevy_reflect::Reflect;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
 
 /// A Node holds things can return a state, used for path finding
 /// It's either the Initial [`LocalState`], or the [`LocalState`] after applying
 /// the [`Effect`]
-#[derive(Reflect, Clone, Eq, PartialEq, Hash)]
-pub enum Node {
-    Effect(Effect),
-    State(LocalState),
+#[derive(Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "bevy_reflect", derive(Reflect))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(deserialize = "K: Key + Deserialize<'de>, D: Deserialize<'de>"))
+)]
+pub enum Node<K = String, D = Datum> {
+    Effect(Effect<K, D>),
+    State(LocalState<K, D>),
+}
+
+impl<K: Key, D: DatumLike> Hash for Node<K, D> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        match self {
+            Node::Effect(effect) => effect.hash(state),
+            Node::State(local_state) => local_state.hash(state),
+        }
+    }
 }
 
-impl Node {
-    pub fn state(&self) -> &LocalState {
+impl<K, D> Node<K, D> {
+    pub fn state(&self) -> &LocalState<K, D> {
         match self {
-            Node::Effect(effect) => &effect.state,
+            Node::Effect(effect) => effect
+                .state
+                .as_ref()
+                .expect("Effects in plan nodes always have their state filled in"),
             Node::State(state) => state,
         }
     }
+
+    /// The key of the [`Action`] that led to this node, `None` for the initial state.
+    /// The planner fills this in from the [`Action`] it picked, so it can be used to
+    /// look up what to execute.
+    pub fn action_key(&self) -> Option<&str> {
+        match self {
+            Node::Effect(effect) => Some(&effect.action),
+            Node::State(_) => None,
+        }
+    }
 }
 
-impl std::fmt::Debug for Node {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<K: Key, D: DatumLike> core::fmt::Debug for Node<K, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Node::Effect(effect) => effect.fmt(f),
             Node::State(state) => state.fmt(f),
@@ -36,98 +93,1963 @@ impl std::fmt::Debug for Node {
     }
 }
 
-fn heuristic(node: &Node, goal: &Goal) -> usize {
-    let distance = node.state().distance_to_goal(goal) as usize;
-    distance
+/// A step the way [`Effect`] shows it, the initial state as its keys and values
+/// in key order, like `gold = Datum:I64(0), wood = Datum:I64(1)`
+impl<K: Display, D: Display> Display for Node<K, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Node::Effect(effect) => effect.fmt(f),
+            Node::State(state) => {
+                for (index, (key, value)) in state.data.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{} = {}", key, value)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Computes the heuristic of a successor from the heuristic of its parent. Only the
+/// keys touched by `mutators` and the computed keys can have changed, so we swap out their old
+/// contribution to the [`Heuristic`] for their new one instead of walking every
+/// requirement of the [`Goal`] again.
+fn successor_heuristic<K: Key, D: DatumLike>(
+    parent: &LocalState<K, D>,
+    parent_heuristic: usize,
+    child: &LocalState<K, D>,
+    mutators: &[Mutator<K, D>],
+    heuristic: &Heuristic<K, D>,
+) -> usize {
+    // Once the parent's estimate saturated, its parts can't be taken back out of it
+    if !heuristic.is_incremental() || parent_heuristic as u64 == u64::MAX {
+        return heuristic.distance(child) as usize;
+    }
+    let mut touched: SmallVec<[&K; 4]> = SmallVec::new();
+    // Computed keys change along with the keys they're computed from, and limited
+    // ones along with the resources they're limited by
+    for key in mutators
+        .iter()
+        .map(Mutator::key)
+        .chain(heuristic.computed_requirements())
+        .chain(heuristic.limited_requirements())
+    {
+        if !touched.contains(&key) {
+            touched.push(key);
+        }
+    }
+
+    // Wide enough that the sum can't wrap, capped the way the full heuristic is
+    let mut distance = parent_heuristic as u128;
+    for key in touched {
+        distance -= heuristic.key_distance(parent, key) as u128;
+        distance += heuristic.key_distance(child, key) as u128;
+    }
+    let distance = distance.min(u64::MAX as u128) as u64;
+
+    debug_assert_eq!(
+        distance,
+        heuristic.distance(child),
+        "Incremental heuristic drifted from the full heuristic"
+    );
+    distance as usize
 }
 
-fn successors<'a>(
-    node: &'a Node,
-    actions: &'a [Action],
-) -> impl Iterator<Item = (Node, usize)> + 'a {
+/// Applies `effect`, one of the [`Effect`]s of `action`, to `state`, returning a
+/// copy of it that holds the resulting [`LocalState`].
+///
+/// The copy is labelled with the key of `action` rather than whatever label the
+/// effect was built with, so plans always name the action that was actually picked.
+pub(crate) fn apply_action<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    action: &Action<K, D>,
+    effect: &Effect<K, D>,
+    missing_keys: MissingKeyPolicy,
+) -> Option<Effect<K, D>> {
+    let new_state = effect.apply(state, missing_keys)?;
+
+    Some(Effect {
+        action: action.key.clone(),
+        mutators: effect.mutators.clone(),
+        cost: action.cost_in(state, effect),
+        reward: effect.reward,
+        duration: effect.duration,
+        state: Some(new_state),
+    })
+}
+
+/// Yields every [`Node`] reachable from `node` with one [`Action`] without breaking
+/// any of the [`PlannerConfig::invariants`] or overdrawing a resource, together with
+/// the cost of getting there and its heuristic. An action with more than one
+/// [`Effect`] leads to one node per effect, the plan picks whichever it needs.
+/// Actions that would break one are passed to `broke_invariant`
+pub(crate) fn successors<'a, K: Key, D: DatumLike>(
+    node: &'a Node<K, D>,
+    node_heuristic: usize,
+    actions: &'a [CompiledAction<'a, K, D>],
+    heuristic: &'a Heuristic<K, D>,
+    config: &'a PlannerConfig<K, D>,
+    mut broke_invariant: impl FnMut(&Action<K, D>) + 'a,
+) -> impl Iterator<Item = (Node<K, D>, usize, usize)> + 'a {
     let state = node.state();
-    actions.iter().filter_map(move |action| {
-        if check_preconditions(state, action) && !action.effects.is_empty() {
-            let new_state = state.clone();
-            let first_effect = &action.effects[0];
-
-            let mut new_data = new_state.data.clone();
-            for mutator in &first_effect.mutators {
-                apply_mutator(&mut new_data, mutator);
+    let missing_keys = config.missing_keys;
+    actions
+        .iter()
+        .filter(move |compiled| {
+            compiled.action().has_effects()
+                && compiled_preconditions_met(state, compiled, missing_keys, &config.computed)
+        })
+        .map(CompiledAction::action)
+        .flat_map(move |action| action.effects_in(state).map(move |effect| (action, effect)))
+        .filter_map(move |(action, effect)| {
+            let new_effect =
+                apply_action(state, action, &effect, missing_keys.unwrap_or_default())?;
+            let reached = new_effect.state.as_ref().unwrap();
+            if !invariants_hold(reached, &config.invariants)
+                || !resources_hold(state, reached, &config.resources)
+            {
+                broke_invariant(action);
+                return None;
             }
+            let h =
+                successor_heuristic(state, node_heuristic, reached, &effect.mutators, heuristic);
+            let cost = new_effect.discounted_cost();
+            Some((Node::Effect(new_effect), cost, h))
+        })
+}
 
-            let new_effect = Effect {
-                action: first_effect.action.clone(),
-                mutators: first_effect.mutators.clone(),
-                cost: first_effect.cost,
-                state: LocalState { data: new_data },
-            };
-            Some((Node::Effect(new_effect), first_effect.cost))
-        } else {
-            None
-        }
-    })
+/// The effects of every [`Action`] that can be taken in `state` without breaking the
+/// [`PlannerConfig::invariants`] or overdrawing a resource, applied to it, with the penalty of the
+/// [`PlannerConfig::avoid`] rules for where they lead. For the searches that go
+/// without a heuristic
+pub(crate) fn applicable_effects<'a, K: Key, D: DatumLike>(
+    state: &'a LocalState<K, D>,
+    actions: &'a [Action<K, D>],
+    config: &'a PlannerConfig<K, D>,
+) -> impl Iterator<Item = (Effect<K, D>, usize)> + 'a {
+    let missing_keys = config.missing_keys;
+    actions
+        .iter()
+        .filter(move |action| {
+            action.has_effects() && preconditions_met(state, action, missing_keys, &config.computed)
+        })
+        .flat_map(move |action| action.effects_in(state).map(move |effect| (action, effect)))
+        .filter_map(move |(action, effect)| {
+            let effect = apply_action(state, action, &effect, missing_keys.unwrap_or_default())?;
+            let reached = effect.state.as_ref().unwrap();
+            if !invariants_hold(reached, &config.invariants)
+                || !resources_hold(state, reached, &config.resources)
+            {
+                return None;
+            }
+            let penalty = avoid_penalty(reached, &config.avoid);
+            Some((effect, penalty))
+        })
+}
+
+/// Checks the preconditions of `action`, deriving the `computed` keys `state`
+/// doesn't have. Without a [`MissingKeyPolicy`], other keys that aren't in `state`
+/// panic like [`check_preconditions`] does, with one they count as unmet, as the
+/// key could still be created later in the plan.
+pub(crate) fn preconditions_met<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    action: &Action<K, D>,
+    missing_keys: Option<MissingKeyPolicy>,
+    computed: &ComputedKeys<K, D>,
+) -> bool {
+    if missing_keys.is_none() && computed.is_empty() {
+        return check_preconditions(state, action);
+    }
+    action
+        .preconditions_in(state)
+        .all(|(key, compare)| holds(state, key, &compare, missing_keys, computed))
 }
 
-fn is_goal(node: &Node, goal: &Goal) -> bool {
-    goal.requirements.iter().all(|(key, value)| {
-        if let Some(state_val) = node.state().data.get(key) {
-            compare_values(value, state_val)
-        } else {
-            panic!("Couldn't find key {:#?} in LocalState", key);
+/// [`preconditions_met`] for an action compiled for the states of the search
+fn compiled_preconditions_met<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    compiled: &CompiledAction<K, D>,
+    missing_keys: Option<MissingKeyPolicy>,
+    computed: &ComputedKeys<K, D>,
+) -> bool {
+    if missing_keys.is_none() && computed.is_empty() {
+        return compiled.check_preconditions(state);
+    }
+    preconditions_met(state, compiled.action(), missing_keys, computed)
+}
+
+/// Checks whether `node` satisfies `goal`, computed keys included. A key the state
+/// doesn't have is never met, whatever the [`MissingKeyPolicy`], so a goal on a key
+/// that only some action creates is reached once it has
+pub(crate) fn is_goal<K: Key, D: DatumLike>(
+    node: &Node<K, D>,
+    goal: &Goal<K, D>,
+    computed: &ComputedKeys<K, D>,
+) -> bool {
+    meets_goal(node.state(), goal, computed)
+}
+
+/// [`is_goal`] for a state
+fn meets_goal<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    goal: &Goal<K, D>,
+    computed: &ComputedKeys<K, D>,
+) -> bool {
+    goal.is_met(|key, compare| compare_entry(compare, computed.value(state, key).as_deref()))
+}
+
+fn holds<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    key: &K,
+    compare: &Compare<D>,
+    missing_keys: Option<MissingKeyPolicy>,
+    computed: &ComputedKeys<K, D>,
+) -> bool {
+    match computed.value(state, key) {
+        None if missing_keys.is_none() && *compare != Compare::Absent => {
+            panic!("Couldn't find key {:#?} in LocalState", key)
         }
-    })
+        value => compare_entry(compare, value.as_deref()),
+    }
 }
 
 /// Use [`make_plan`] instead
-pub fn make_plan_with_strategy(
+pub fn make_plan_with_strategy<K: Key, D: DatumLike>(
     strategy: PlanningStrategy,
-    start: &LocalState,
-    actions: &[Action],
-    goal: &Goal,
-) -> Option<(Vec<Node>, usize)> {
-    match strategy {
-        PlanningStrategy::StartToGoal => {
-            let start_node = Node::State(start.clone());
-            pathfinding::directed::astar::astar(
-                &start_node,
-                |node| successors(node, actions).collect::<Vec<_>>().into_iter(),
-                |node| heuristic(node, goal),
-                |node| is_goal(node, goal),
-            )
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> Option<(Vec<Node<K, D>>, usize)> {
+    let config = PlannerConfig::default().with_strategy(strategy);
+    make_plan_with_config(start, actions, goal, &config)
+}
+
+/// Like [`make_plan`], but with the options from a [`PlannerConfig`]
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`], use [`try_make_plan_with_config`] to
+/// handle those instead
+pub fn make_plan_with_config<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> Option<Plan<K, D>> {
+    make_plan_with_stats(start, actions, goal, config).0
+}
+
+/// Like [`make_plan_with_config`], but returns a [`PlanError`] instead of panicking
+pub fn try_make_plan_with_config<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> Result<Option<Plan<K, D>>, PlanError<K>> {
+    let actions = validate_actions(actions, config)?;
+    check_keys_exist(start, &actions, goal, config)?;
+    let interrupt = Interrupt::from_config(config);
+    search_plan_or_cancelled(start, &actions, goal, config, interrupt)
+}
+
+/// Like [`make_plan_with_config`], but also returns [`PlanStats`] about the search
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`]
+pub fn make_plan_with_stats<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> (Option<Plan<K, D>>, PlanStats) {
+    let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+    let interrupt = Interrupt::from_config(config);
+    search_plan(start, &actions, goal, config, interrupt, None, None)
+}
+
+/// Like [`make_plan_with_stats`], but also measures how long planning took on the
+/// wall clock, plan or not, in [`PlanStats::elapsed`]
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`]
+pub fn make_plan_instrumented<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> (Option<Plan<K, D>>, PlanStats) {
+    #[cfg(feature = "std")]
+    let started = Instant::now();
+    let (plan, stats) = make_plan_with_stats(start, actions, goal, config);
+    #[cfg(feature = "std")]
+    let stats = PlanStats {
+        elapsed: started.elapsed(),
+        ..stats
+    };
+    (plan, stats)
+}
+
+/// Like [`make_plan_with_config`], but returns the plan as a [`PlanResult`], together
+/// with how much searching it took. Use [`make_plan_with_stats`] for the numbers of
+/// a search that found nothing
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`]
+pub fn make_plan_detailed<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> Option<PlanResult<K, D>> {
+    let (plan, stats) = make_plan_instrumented(start, actions, goal, config);
+    let (nodes, cost) = plan?;
+    Some(PlanResult {
+        nodes,
+        cost,
+        expanded_nodes: stats.expanded_nodes,
+        generated_nodes: stats.generated_nodes,
+        elapsed: stats.elapsed,
+    })
+}
+
+/// Like [`make_plan_with_config`], but also returns a [`PlannerTrace`] of what the
+/// search did, to look through when a plan comes out wrong. Records at most
+/// [`PlannerConfig::max_trace_events`] events.
+///
+/// Only the forward A* search is traced, so this ignores the [`PlanningStrategy`],
+/// and the trace stays empty with [`Rewards::BoundedDepth`] or a goal with a
+/// deadline.
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`]
+pub fn make_plan_with_trace<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> (Option<Plan<K, D>>, PlannerTrace) {
+    let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+    let interrupt = Interrupt::from_config(config);
+    let trace = RefCell::new(PlannerTrace::new(config.max_trace_events));
+    let (plan, _) = search_plan(start, &actions, goal, config, interrupt, Some(&trace), None);
+    (plan, trace.into_inner())
+}
+
+/// What [`make_plan_anytime`] came up with
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlanOutcome<K: Key = String, D: DatumLike = Datum> {
+    /// A plan all the way to the [`Goal`]
+    Complete(Plan<K, D>),
+    /// The search ran out of time or budget, or was cancelled, before reaching the
+    /// [`Goal`]. The plan leads from the start to the state that looked closest to
+    /// it, for carrying out while planning again from where it ends
+    Partial(Plan<K, D>),
+}
+
+impl<K: Key, D: DatumLike> PlanOutcome<K, D> {
+    pub fn is_complete(&self) -> bool {
+        matches!(self, PlanOutcome::Complete(_))
+    }
+
+    pub fn plan(&self) -> &Plan<K, D> {
+        match self {
+            PlanOutcome::Complete(plan) | PlanOutcome::Partial(plan) => plan,
+        }
+    }
+
+    pub fn into_plan(self) -> Plan<K, D> {
+        match self {
+            PlanOutcome::Complete(plan) | PlanOutcome::Partial(plan) => plan,
+        }
+    }
+}
+
+/// Like [`make_plan_with_config`], but a search that gives up because of the
+/// [`PlannerConfig::time_budget`], [`PlannerConfig::max_expanded_nodes`] or the
+/// [`PlannerConfig::cancellation`] returns the way to the state it found closest
+/// to the goal, as a [`PlanOutcome::Partial`]. The closest state is the one with
+/// the lowest heuristic, and it has to be at least one step from the start.
+///
+/// Only the forward A* search keeps track of that, so this ignores the
+/// [`PlanningStrategy`], and never returns a partial plan with
+/// [`Rewards::BoundedDepth`] or a goal with a deadline.
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`]
+pub fn make_plan_anytime<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> Option<PlanOutcome<K, D>> {
+    let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+    let interrupt = Interrupt::from_config(config);
+    let mut closest = None;
+    let (plan, stats) = search_plan(
+        start,
+        &actions,
+        goal,
+        config,
+        interrupt,
+        None,
+        Some(&mut closest),
+    );
+    if let Some(plan) = plan {
+        return Some(PlanOutcome::Complete(plan));
+    }
+    let gave_up = interrupt.is_due()
+        || config
+            .max_expanded_nodes
+            .is_some_and(|max| stats.expanded_nodes >= max);
+    closest
+        .filter(|(nodes, _)| gave_up && nodes.len() > 1)
+        .map(PlanOutcome::Partial)
+}
+
+/// What [`make_plan_or_closest`] came up with
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClosestPlan<K: Key = String, D: DatumLike = Datum> {
+    pub nodes: Vec<Node<K, D>>,
+    pub cost: usize,
+    /// The requirements of the [`Goal`] the last state doesn't meet, empty when the
+    /// plan reaches it. When no group of the [`Goal::alternatives`] is met, the
+    /// group with the fewest unmet requirements is listed as well
+    pub unmet: Vec<(K, Compare<D>)>,
+}
+
+impl<K: Key, D: DatumLike> ClosestPlan<K, D> {
+    pub fn is_complete(&self) -> bool {
+        self.unmet.is_empty()
+    }
+}
+
+impl<K: Key, D: DatumLike> PlanLike<K, D> for ClosestPlan<K, D> {
+    fn nodes(&self) -> &[Node<K, D>] {
+        &self.nodes
+    }
+
+    fn cost(&self) -> usize {
+        self.cost
+    }
+}
+
+/// Like [`make_plan_with_config`], but when the goal can't be reached from `start`
+/// it returns the way to the state that comes closest, together with the
+/// requirements that state still misses. Closest means the lowest heuristic, which
+/// is [`LocalState::distance_to_goal`] unless the config has its own. That can be
+/// the start itself, when nothing gets any closer.
+///
+/// The search has to run out of states to try before giving up, so bound large
+/// domains with [`PlannerConfig::max_expanded_nodes`]. Like [`make_plan_anytime`]
+/// this ignores the [`PlanningStrategy`], and it returns `None` when the start
+/// breaks an [`Invariant`], with [`Rewards::BoundedDepth`] or with a goal with a
+/// deadline.
+///
+/// # Panics
+///
+/// If planning fails with a [`PlanError`]
+pub fn make_plan_or_closest<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> Option<ClosestPlan<K, D>> {
+    let actions = validate_actions(actions, config).unwrap_or_else(|err| panic!("{}", err));
+    let interrupt = Interrupt::from_config(config);
+    let mut closest = None;
+    let (plan, _) = search_plan(
+        start,
+        &actions,
+        goal,
+        config,
+        interrupt,
+        None,
+        Some(&mut closest),
+    );
+    let (nodes, cost) = plan.or(closest)?;
+    let unmet = unmet_requirements(nodes.last()?.state(), goal, &config.computed);
+    Some(ClosestPlan { nodes, cost, unmet })
+}
+
+/// The requirements of `goal` that `state` doesn't meet, see [`ClosestPlan::unmet`]
+fn unmet_requirements<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    goal: &Goal<K, D>,
+    computed: &ComputedKeys<K, D>,
+) -> Vec<(K, Compare<D>)> {
+    let unmet = |requirements: &BTreeMap<K, Compare<D>>| -> Vec<(K, Compare<D>)> {
+        requirements
+            .iter()
+            .filter(|(key, compare)| !compare_entry(compare, computed.value(state, key).as_deref()))
+            .map(|(key, compare)| (key.clone(), compare.clone()))
+            .collect()
+    };
+    let mut missing = unmet(&goal.requirements);
+    let closest_group = goal
+        .alternatives
+        .iter()
+        .map(unmet)
+        .min_by_key(|group| group.len());
+    if let Some(group) = closest_group {
+        missing.extend(group);
+    }
+    missing
+}
+
+/// Checks that the `actions` can be planned with under `config`, see
+/// [`check_action_keys`], [`check_action_rewards`] and [`check_computed_keys`]
+pub(crate) fn validate_actions<'a, K: Key, D: DatumLike>(
+    actions: &'a [Action<K, D>],
+    config: &PlannerConfig<K, D>,
+) -> Result<Cow<'a, [Action<K, D>]>, PlanError<K>> {
+    let actions = check_action_keys(actions, config.duplicate_keys)?;
+    check_action_rewards(&actions, config.rewards)?;
+    check_computed_keys(&actions, &config.computed)?;
+    Ok(actions)
+}
+
+/// Without a [`PlannerConfig::missing_keys`] policy, reading a precondition on a key
+/// a state doesn't have panics, and the preconditions are read in the start state
+/// right away. Makes sure every key they need is there, from the start itself, its
+/// [`PlannerConfig::defaults`] or a computed key. Goal keys may also come from a
+/// mutator, without one the goal could never be met. Dynamic preconditions can read
+/// anything, so they aren't checked
+pub(crate) fn check_keys_exist<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> Result<(), PlanError<K>> {
+    if config.missing_keys.is_some() {
+        return Ok(());
+    }
+    let exists = |key: &K| {
+        start.data.contains_key(key)
+            || config.defaults.contains_key(key)
+            || config.computed.contains(key)
+    };
+    // There's no telling which keys a dynamic effect creates
+    let dynamic = actions.iter().any(Action::has_dynamic_effects);
+    let mutated = |key: &K| {
+        dynamic
+            || actions
+                .iter()
+                .flat_map(|action| &action.effects)
+                .flat_map(|effect| &effect.mutators)
+                .any(|mutator| mutator.key() == key && !matches!(mutator, Mutator::Unset(_)))
+    };
+    // Requiring a key to be absent is fine whether it exists or not
+    let missing = goal
+        .requirements
+        .iter()
+        .filter(|(_, compare)| **compare != Compare::Absent)
+        .map(|(key, _)| key)
+        .find(|key| !exists(key) && !mutated(key))
+        .or_else(|| {
+            actions
+                .iter()
+                .flat_map(|action| action.preconditions.iter())
+                .filter(|(_, compare)| *compare != Compare::Absent)
+                .map(|(key, _)| key)
+                .find(|key| !exists(key))
+        });
+    match missing {
+        Some(key) => Err(PlanError::MissingKey(key.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Makes sure no [`Mutator`] changes a [`PlannerConfig::computed`] key, as its value
+/// only ever comes from the keys it's computed from
+fn check_computed_keys<K: Key, D: DatumLike>(
+    actions: &[Action<K, D>],
+    computed: &ComputedKeys<K, D>,
+) -> Result<(), PlanError<K>> {
+    let mutating = actions.iter().find(|action| {
+        action
+            .effects
+            .iter()
+            .flat_map(|effect| &effect.mutators)
+            .any(|mutator| computed.contains(mutator.key()))
+    });
+    match mutating {
+        Some(action) => Err(PlanError::MutatesComputedKey(action.key.clone())),
+        None => Ok(()),
+    }
+}
+
+/// With [`Rewards::Reject`], makes sure no reward cancels out the cost of its
+/// [`Effect`]. A* needs every step to cost something, or it could go around a
+/// cycle of free or paying actions forever. A dynamic cost is only known once the
+/// search gets to it and could be nothing, so actions with one get no reward at all
+fn check_action_rewards<K: Key, D: DatumLike>(
+    actions: &[Action<K, D>],
+    rewards: Rewards,
+) -> Result<(), PlanError<K>> {
+    if rewards != Rewards::Reject {
+        return Ok(());
+    }
+    let free = actions.iter().find(|action| {
+        action.effects.iter().any(|effect| {
+            effect.reward > 0 && (action.dynamic_cost.is_some() || effect.reward >= effect.cost)
+        })
+    });
+    match free {
+        Some(action) => Err(PlanError::RewardOutweighsCost(action.key.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Makes sure every [`Action`] has its own key, so plans can tell them apart. With
+/// [`DuplicateKeys::Disambiguate`], actions sharing a key are copied and renamed to
+/// `key#0`, `key#1` and so on, in the order they appear.
+fn check_action_keys<K: Key, D: DatumLike>(
+    actions: &[Action<K, D>],
+    duplicates: DuplicateKeys,
+) -> Result<Cow<'_, [Action<K, D>]>, PlanError<K>> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for action in actions {
+        *counts.entry(action.key.as_str()).or_default() += 1;
+    }
+    if counts.values().all(|count| *count == 1) {
+        return Ok(Cow::Borrowed(actions));
+    }
+
+    match duplicates {
+        DuplicateKeys::Reject => {
+            // Report the first duplicate in the order of `actions`
+            let key = actions
+                .iter()
+                .find(|action| counts[action.key.as_str()] > 1)
+                .map(|action| action.key.clone())
+                .unwrap();
+            Err(PlanError::DuplicateActionKey(key))
+        }
+        DuplicateKeys::Disambiguate => {
+            let mut seen: BTreeMap<&str, usize> = BTreeMap::new();
+            let renamed = actions
+                .iter()
+                .map(|original| {
+                    let mut action = original.clone();
+                    if counts[original.key.as_str()] > 1 {
+                        let index = seen.entry(original.key.as_str()).or_default();
+                        action.key = format!("{}#{}", original.key, index);
+                        *index += 1;
+                    }
+                    action
+                })
+                .collect();
+            Ok(Cow::Owned(renamed))
+        }
+    }
+}
+
+/// Why a search should give up before it's done: its deadline passed, it expanded
+/// as many nodes as it may, or it was cancelled from another thread
+#[derive(Clone, Copy)]
+pub(crate) struct Interrupt<'a> {
+    #[cfg(feature = "std")]
+    pub deadline: Option<Instant>,
+    pub cancelled: Option<&'a AtomicBool>,
+    /// How many expansions [`Poll::is_due`] lets pass between checks
+    pub check_interval: usize,
+    /// How many expansions [`Poll::is_due`] allows in total
+    pub max_expansions: Option<usize>,
+}
+
+impl<'a> Interrupt<'a> {
+    /// Starts the clock on the [`PlannerConfig::time_budget`]
+    pub fn from_config<K, D>(config: &'a PlannerConfig<K, D>) -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            deadline: config.time_budget.map(|budget| Instant::now() + budget),
+            cancelled: config.cancellation.as_ref().map(CancellationToken::flag),
+            check_interval: config.check_interval.max(1),
+            max_expansions: config.max_expanded_nodes,
+        }
+    }
+
+    /// What's left of the expansion budget once a search expanded `expanded` nodes,
+    /// for searching on after it
+    pub fn after_expanding(mut self, expanded: usize) -> Self {
+        self.max_expansions = self.max_expansions.map(|max| max.saturating_sub(expanded));
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+            .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    }
+
+    /// Always `false` without std, as there's no clock to check
+    pub fn is_past_deadline(&self) -> bool {
+        #[cfg(feature = "std")]
+        let past = self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+        #[cfg(not(feature = "std"))]
+        let past = false;
+        past
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.is_past_deadline() || self.is_cancelled()
+    }
+
+    /// Keeps track of the expansions of one search, for [`Poll::is_due`]
+    pub fn poll(self) -> Poll<'a> {
+        Poll {
+            interrupt: self,
+            expansions: 0,
+            total: 0,
+            due: false,
+        }
+    }
+}
+
+/// An [`Interrupt`] that's only checked every [`Interrupt::check_interval`]
+/// expansions, apart from the expansion budget. Once it's due it stays due, so
+/// searches that ask it more than once stop too
+pub(crate) struct Poll<'a> {
+    interrupt: Interrupt<'a>,
+    expansions: usize,
+    total: usize,
+    due: bool,
+}
+
+impl Poll<'_> {
+    /// Call once per expansion
+    pub fn is_due(&mut self) -> bool {
+        if !self.due {
+            self.total += 1;
+            self.expansions += 1;
+            if self
+                .interrupt
+                .max_expansions
+                .is_some_and(|max| self.total > max)
+            {
+                self.due = true;
+            } else if self.expansions >= self.interrupt.check_interval {
+                self.expansions = 0;
+                self.due = self.interrupt.is_due();
+            }
+        }
+        self.due
+    }
+}
+
+/// Like [`search_plan`], but a search that gave up because it was cancelled fails
+/// with [`PlanError::Cancelled`] instead of returning `None`, one that ran out of
+/// time with [`PlanError::TimedOut`], and one that only
+/// came across plans over [`PlannerConfig::max_cost`] with [`PlanError::TooExpensive`].
+/// With [`PlannerConfig::retry_without_invariants`], a search that found nothing
+/// searches again without the invariants, and fails with
+/// [`PlanError::BlockedByInvariants`] if that finds a plan
+pub(crate) fn search_plan_or_cancelled<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+) -> Result<Option<Plan<K, D>>, PlanError<K>> {
+    match search_plan(start, actions, goal, config, interrupt, None, None) {
+        (None, stats) if interrupt.is_cancelled() => Err(PlanError::Cancelled(stats)),
+        (None, stats) if interrupt.is_past_deadline() => Err(PlanError::TimedOut(stats)),
+        (None, stats)
+            if config
+                .max_expanded_nodes
+                .is_some_and(|max| stats.expanded_nodes >= max) =>
+        {
+            Err(PlanError::BudgetExceeded(stats))
+        }
+        (None, stats) if stats.pruned_nodes > 0 => Err(PlanError::TooExpensive {
+            cheapest_found: stats.cheapest_over_budget,
+        }),
+        (None, _) if config.retry_without_invariants && !config.invariants.is_empty() => {
+            let relaxed = PlannerConfig {
+                invariants: vec![],
+                ..config.clone()
+            };
+            match search_plan(start, actions, goal, &relaxed, interrupt, None, None) {
+                (Some((_, relaxed_cost)), _) => {
+                    Err(PlanError::BlockedByInvariants { relaxed_cost })
+                }
+                (None, _) => Ok(None),
+            }
+        }
+        (plan, _) => Ok(plan),
+    }
+}
+
+/// Runs the search, giving up once `interrupt` is due. From then on we stop
+/// generating successors, so the search drains what's left and returns `None`
+/// unless it already reached the goal. The forward search records what it does into
+/// `trace`, if there is one, and without a plan leaves the path to the node closest
+/// to the goal in `closest`
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn search_plan<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+    trace: Option<&RefCell<PlannerTrace>>,
+    closest: Option<&mut Option<Plan<K, D>>>,
+) -> (Option<Plan<K, D>>, PlanStats) {
+    let (plan, stats) = run_search(
+        start, actions, None, goal, config, interrupt, trace, closest,
+    );
+    if let Some(on_finish) = &config.on_finish {
+        (on_finish.0)(plan.as_ref(), &stats);
+    }
+    (plan, stats)
+}
+
+/// [`search_plan`] with `actions` compiled ahead of time, for searching with the
+/// same actions over and over. The forward search uses `compiled` instead of
+/// compiling `actions` again, which have to be the actions `compiled` was compiled
+/// from
+pub(crate) fn search_plan_compiled<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    compiled: &[CompiledAction<'_, K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+) -> (Option<Plan<K, D>>, PlanStats) {
+    let (plan, stats) = run_search(
+        start,
+        actions,
+        Some(compiled),
+        goal,
+        config,
+        interrupt,
+        None,
+        None,
+    );
+    if let Some(on_finish) = &config.on_finish {
+        (on_finish.0)(plan.as_ref(), &stats);
+    }
+    (plan, stats)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_search<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    compiled: Option<&[CompiledAction<'_, K, D>]>,
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+    trace: Option<&RefCell<PlannerTrace>>,
+    closest: Option<&mut Option<Plan<K, D>>>,
+) -> (Option<Plan<K, D>>, PlanStats) {
+    let start = &with_defaults(start, &config.defaults);
+    let config = &reading_unset_keys(config, actions);
+    let strategy = match config.strategy {
+        // Searches forwards already, just without a heuristic
+        PlanningStrategy::UniformCost => PlanningStrategy::UniformCost,
+        // The backward search works on partial states, which can't be checked
+        // against avoid rules, invariants or resources, or derive computed keys
+        _ if !config.avoid.is_empty()
+            || !config.invariants.is_empty()
+            || !config.resources.is_empty()
+            || !config.computed.is_empty() =>
+        {
+            PlanningStrategy::StartToGoal
+        }
+        // Nor does it take a custom heuristic
+        _ if config.heuristic.is_some() => PlanningStrategy::StartToGoal,
+        // Nor is it traced
+        _ if trace.is_some() => PlanningStrategy::StartToGoal,
+        // Nor does it keep track of the closest node
+        _ if closest.is_some() => PlanningStrategy::StartToGoal,
+        _ if config.rewards != Rewards::Reject => PlanningStrategy::StartToGoal,
+        // Nor does it keep track of time
+        _ if temporal::is_timed(goal, config) => PlanningStrategy::StartToGoal,
+        // Nor does it know what a dynamic effect does
+        _ if actions.iter().any(Action::has_dynamic_effects) => PlanningStrategy::StartToGoal,
+        // Nor does it know the state a dynamic cost depends on
+        _ if actions.iter().any(|action| action.dynamic_cost.is_some()) => {
+            PlanningStrategy::StartToGoal
+        }
+        // Nor does it split up into alternatives
+        _ if !goal.alternatives.is_empty() => PlanningStrategy::StartToGoal,
+        // Nor can it undo multiplying or dividing
+        _ if actions
+            .iter()
+            .flat_map(|action| &action.effects)
+            .flat_map(|effect| &effect.mutators)
+            .any(Mutator::is_scaling) =>
+        {
+            PlanningStrategy::StartToGoal
+        }
+        // Nor does it know how far a set is from having its members
+        _ if uses_members(actions, goal) => PlanningStrategy::StartToGoal,
+        PlanningStrategy::Auto => auto_strategy(actions, goal),
+        strategy => strategy,
+    };
+    let mut stats = PlanStats {
+        strategy,
+        ..Default::default()
+    };
+    if !invariants_hold(start, &config.invariants) {
+        return (None, stats);
+    }
+    // Nothing to search for, unless rewards could make a plan pay for itself
+    if config.rewards == Rewards::Reject && meets_goal(start, goal, &config.computed) {
+        return (
+            Some((vec![Node::State(start.clone().into_owned())], 0)),
+            stats,
+        );
+    }
+
+    if strategy == PlanningStrategy::GoalToStart {
+        if let Some(plan) =
+            regression::plan_backward(start, actions, goal, config, interrupt, &mut stats)
+        {
+            return (Some(plan), stats);
+        }
+        // Either there's no plan at all, or the backward search couldn't see a
+        // dynamic precondition. Searching forwards settles it either way.
+        stats.strategy = PlanningStrategy::StartToGoal;
+    }
+    if strategy == PlanningStrategy::Bidirectional {
+        if let Some(plan) =
+            bidirectional::plan_bidirectional(start, actions, goal, config, interrupt, &mut stats)
+        {
+            return (plan, stats);
+        }
+        // The plan it found doesn't hold up, which searching forwards settles
+        stats.strategy = PlanningStrategy::StartToGoal;
+    }
+    // The expansion budget is for both searches together
+    let interrupt = interrupt.after_expanding(stats.expanded_nodes);
+
+    if let Rewards::BoundedDepth(max_depth) = config.rewards {
+        let plan = plan_with_rewards(
+            start, actions, goal, config, max_depth, interrupt, &mut stats,
+        );
+        return (plan, stats);
+    }
+
+    let plan = if temporal::is_timed(goal, config) {
+        temporal::plan_timed(start, actions, goal, config, interrupt, &mut stats)
+    } else {
+        plan_forward(
+            start, actions, compiled, goal, config, interrupt, &mut stats, trace, closest,
+        )
+    };
+    (plan, stats)
+}
+
+/// `config`, with the default [`MissingKeyPolicy`] if it has none and any of
+/// `actions` unsets a key. Preconditions on a key that was unset then aren't met,
+/// like goal requirements, instead of panicking
+pub(crate) fn reading_unset_keys<'a, K: Key, D: DatumLike>(
+    config: &'a PlannerConfig<K, D>,
+    actions: &[Action<K, D>],
+) -> Cow<'a, PlannerConfig<K, D>> {
+    let unsets = actions
+        .iter()
+        .flat_map(|action| &action.effects)
+        .flat_map(|effect| &effect.mutators)
+        .any(|mutator| matches!(mutator, Mutator::Unset(_)));
+    if config.missing_keys.is_some() || !unsets {
+        return Cow::Borrowed(config);
+    }
+    Cow::Owned(
+        config
+            .clone()
+            .with_missing_keys(MissingKeyPolicy::default()),
+    )
+}
+
+/// Whether any of `actions` inserts or removes members of a set, or any of them or
+/// `goal` requires some
+fn uses_members<K: Key, D: DatumLike>(actions: &[Action<K, D>], goal: &Goal<K, D>) -> bool {
+    let requires = |(_, compare): (&K, &Compare<D>)| matches!(compare, Compare::Contains(_));
+    actions
+        .iter()
+        .flat_map(|action| &action.effects)
+        .flat_map(|effect| &effect.mutators)
+        .any(Mutator::is_membership)
+        || actions
+            .iter()
+            .flat_map(|action| {
+                action
+                    .preconditions
+                    .iter()
+                    .map(|(key, compare)| (key, compare))
+            })
+            .any(requires)
+        || goal
+            .requirements
+            .iter()
+            .chain(goal.alternatives.iter().flatten())
+            .any(requires)
+}
+
+/// `start` with every key of `defaults` it's missing filled in, so everything after
+/// it (and the states in the plan) sees the defaults as if they had been there all
+/// along
+pub(crate) fn with_defaults<'a, K: Key, D: DatumLike>(
+    start: &'a LocalState<K, D>,
+    defaults: &BTreeMap<K, D>,
+) -> Cow<'a, LocalState<K, D>> {
+    if defaults.keys().all(|key| start.data.contains_key(key)) {
+        return Cow::Borrowed(start);
+    }
+    let mut start = start.clone();
+    for (key, value) in defaults {
+        if !start.data.contains_key(key) {
+            start.data.insert(key.clone(), value.clone());
+        }
+    }
+    Cow::Owned(start)
+}
+
+/// The penalty for passing through `state`: the sum of the penalties of every rule
+/// whose conditions all hold in it
+pub(crate) fn avoid_penalty<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    avoid: &[AvoidRule<K, D>],
+) -> usize {
+    avoid
+        .iter()
+        .filter(|(conditions, _)| {
+            conditions
+                .iter()
+                .all(|(key, compare)| compare_entry(compare, state.data.get(key)))
+        })
+        .fold(0, |total: usize, (_, penalty)| {
+            total.saturating_add(*penalty)
+        })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn plan_forward<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    compiled: Option<&[CompiledAction<'_, K, D>]>,
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+    stats: &mut PlanStats,
+    trace: Option<&RefCell<PlannerTrace>>,
+    closest: Option<&mut Option<Plan<K, D>>>,
+) -> Option<Plan<K, D>> {
+    let mut poll = interrupt.poll();
+    let heuristic = &Heuristic::new(actions, goal, config);
+    let mut start = start.clone();
+    let compiled_here;
+    let compiled = match compiled {
+        Some(compiled) => {
+            if let Some(first) = compiled.first() {
+                first.share_layout(&mut start);
+            }
+            compiled
+        }
+        None => {
+            compiled_here = CompiledAction::compile_all(actions, &start);
+            &compiled_here
+        }
+    };
+    let start = &start;
+    let start_node = Node::State(start.clone());
+    let expand = forward_successors(compiled, heuristic, config, trace);
+    let found = search::astar_closest(
+        &start_node,
+        (),
+        heuristic.distance(start) as usize,
+        config.max_cost,
+        config.tie_seed,
+        stats,
+        || poll.is_due(),
+        expand,
+        |node| is_goal(node, goal, &config.computed),
+        forward_observer(config, trace),
+    );
+    let (path, cost) = match found {
+        Ok(found) => found,
+        Err((path, _)) => {
+            if let Some(closest) = closest {
+                let nodes: Vec<Node<K, D>> = path.into_iter().map(|(node, _)| node).collect();
+                let (raw_cost, _) = plan_totals(&nodes);
+                *closest = Some((nodes, raw_cost));
+            }
+            return None;
+        }
+    };
+    Some(forward_plan(path, cost, stats))
+}
+
+/// The successors of a node in the forward search, which have no edge label
+pub(crate) type ForwardSuccessors<K, D> = Vec<Successor<Node<K, D>, ()>>;
+
+/// Puts the successors of a node in the forward search in the `Vec` it's given,
+/// with the penalties of the [`PlannerConfig::avoid`] rules added to their costs
+pub(crate) fn forward_successors<'a, K: Key, D: DatumLike>(
+    actions: &'a [CompiledAction<'a, K, D>],
+    heuristic: &'a Heuristic<'a, K, D>,
+    config: &'a PlannerConfig<K, D>,
+    trace: Option<&'a RefCell<PlannerTrace>>,
+) -> impl FnMut(&Node<K, D>, usize, &mut ForwardSuccessors<K, D>) + 'a {
+    move |node, h, out| {
+        let broke_invariant = |action: &Action<K, D>| {
+            if let Some(trace) = trace {
+                trace.borrow_mut().pruned(&action.key, PrunedBy::Invariant);
+            }
+        };
+        let next = successors(node, h, actions, heuristic, config, broke_invariant).map(
+            |(node, cost, h)| {
+                let penalty = avoid_penalty(node.state(), &config.avoid);
+                (node, (), cost.saturating_add(penalty), h)
+            },
+        );
+        out.extend(next);
+    }
+}
+
+/// Passes what the forward search does on to [`PlannerConfig::on_expand`] and
+/// `trace`
+pub(crate) fn forward_observer<'a, K: Key, D: DatumLike>(
+    config: &'a PlannerConfig<K, D>,
+    trace: Option<&'a RefCell<PlannerTrace>>,
+) -> impl FnMut(SearchEvent<Node<K, D>>) + 'a {
+    move |event| {
+        if let (
+            Some(on_expand),
+            SearchEvent::Expanded {
+                expanded,
+                cost,
+                heuristic,
+                ..
+            },
+        ) = (&config.on_expand, &event)
+        {
+            (on_expand.0)(expanded, *cost, *heuristic);
         }
-        PlanningStrategy::GoalToStart => {
-            panic!("PlanningStrategy::GoalToStart hasn't been implemented yet!");
+        if let Some(trace) = trace {
+            trace.borrow_mut().record(trace_event(event));
         }
     }
 }
 
-/// Currently, only [`PlanningStrategy::StartToGoal`] is supported, which tries to find the chain of
-/// [`Effect`]s that lead to our [`Goal`] state
-#[derive(Default)]
+/// The plan along the `path` the forward search found, which cost it `cost`
+pub(crate) fn forward_plan<K: Key, D: DatumLike>(
+    path: Vec<(Node<K, D>, ())>,
+    cost: usize,
+    stats: &mut PlanStats,
+) -> Plan<K, D> {
+    // The search cost includes the penalties and rewards, the plan only what the
+    // actions cost
+    let nodes: Vec<Node<K, D>> = path.into_iter().map(|(node, _)| node).collect();
+    let (raw_cost, reward) = plan_totals(&nodes);
+    stats.reward = reward;
+    stats.duration = plan_duration(&nodes);
+    stats.penalty = cost.saturating_add(reward).saturating_sub(raw_cost);
+    (nodes, raw_cost)
+}
+
+fn trace_event<K, D>(event: SearchEvent<Node<K, D>>) -> TraceEvent {
+    let action = |node: &Node<K, D>| node.action_key().unwrap_or_default().to_string();
+    match event {
+        SearchEvent::Expanded {
+            node,
+            cost,
+            heuristic,
+            ..
+        } => TraceEvent::NodeExpanded {
+            node,
+            cost,
+            heuristic,
+        },
+        SearchEvent::Generated {
+            parent,
+            node,
+            successor,
+            cost,
+        } => TraceEvent::SuccessorGenerated {
+            parent,
+            node,
+            action: action(successor),
+            cost,
+        },
+        SearchEvent::Pruned {
+            parent,
+            successor,
+            by,
+        } => TraceEvent::NodePruned {
+            parent,
+            action: action(successor),
+            by,
+        },
+        SearchEvent::GoalReached { node, cost } => TraceEvent::GoalReached { node, cost },
+    }
+}
+
+/// Like [`plan_forward`], but with [`search::bounded_bellman_ford`] instead of A*,
+/// so rewards can outweigh costs. There's no heuristic to guide it, so it looks at
+/// every state within `max_depth` actions of the start
+fn plan_with_rewards<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    max_depth: usize,
+    interrupt: Interrupt,
+    stats: &mut PlanStats,
+) -> Option<Plan<K, D>> {
+    let mut poll = interrupt.poll();
+    let (path, cost) = search::bounded_bellman_ford(
+        &Node::State(start.clone()),
+        (),
+        max_depth,
+        stats,
+        |node| {
+            if poll.is_due() {
+                return vec![];
+            }
+            applicable_effects(node.state(), actions, config)
+                .map(|(effect, penalty)| {
+                    let cost = effect
+                        .net_cost()
+                        .saturating_add(i64::try_from(penalty).unwrap_or(i64::MAX));
+                    (Node::Effect(effect), (), cost)
+                })
+                .collect()
+        },
+        |node| is_goal(node, goal, &config.computed),
+    )?;
+
+    let nodes: Vec<Node<K, D>> = path.into_iter().map(|(node, _)| node).collect();
+    let (raw_cost, reward) = plan_totals(&nodes);
+    stats.reward = reward;
+    stats.duration = plan_duration(&nodes);
+    let penalty = cost
+        .saturating_add(i64::try_from(reward).unwrap_or(i64::MAX))
+        .saturating_sub(i64::try_from(raw_cost).unwrap_or(i64::MAX));
+    stats.penalty = usize::try_from(penalty).unwrap_or(0);
+    Some((nodes, raw_cost))
+}
+
+/// How long the actions of a plan take in total, see [`Effect::duration`]
+pub(crate) fn plan_duration<K, D>(nodes: &[Node<K, D>]) -> usize {
+    nodes.iter().fold(0, |duration, node| match node {
+        Node::Effect(effect) => duration.saturating_add(effect.duration),
+        Node::State(_) => duration,
+    })
+}
+
+/// What the actions of a plan cost in total, and the rewards they paid out. Both
+/// saturate at `usize::MAX`
+pub(crate) fn plan_totals<K, D>(nodes: &[Node<K, D>]) -> (usize, usize) {
+    nodes
+        .iter()
+        .fold((0, 0), |(cost, reward), node| match node {
+            Node::Effect(effect) => (
+                cost.saturating_add(effect.cost),
+                reward.saturating_add(effect.reward),
+            ),
+            Node::State(_) => (cost, reward),
+        })
+}
+
+/// Goals with more requirements than this are always searched forwards, as every
+/// node of the backward search carries (at least) all of them
+const MAX_BACKWARD_GOAL_SIZE: usize = 8;
+
+/// Goals at least this many actions deep are searched from both ends, see
+/// [`goal_depth`]. Searching backwards alone gets lost down long chains, as the
+/// distance from the start only tells requirements apart by how many are left
+const MIN_BIDIRECTIONAL_DEPTH: usize = 8;
+
+/// Picks the [`PlanningStrategy`] that [`PlanningStrategy::Auto`] searches with.
+///
+/// Searching backwards only considers actions that touch what's still required, so
+/// it pays off when few actions touch the goal compared to how many can branch off
+/// every state. It can't see dynamic preconditions though, so any of those keeps
+/// us searching forwards. When the goal is also many actions deep, searching from
+/// both ends meets in the middle after expanding far fewer nodes than either.
+///
+/// [`PlanningStrategy::UniformCost`] is never picked, as it expands more nodes
+/// than StartToGoal for the same plan.
+fn auto_strategy<K: Key, D: DatumLike>(
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> PlanningStrategy {
+    let invertible = actions
+        .iter()
+        .all(|action| action.dynamic_preconditions.is_empty());
+    let effects = || actions.iter().flat_map(|action| &action.effects);
+    let branching = effects().count();
+    let touching_goal = effects()
+        .filter(|effect| {
+            effect
+                .mutators
+                .iter()
+                .any(|mutator| goal.requirements.contains_key(mutator.key()))
+        })
+        .count();
+
+    if !invertible
+        || goal.requirements.len() > MAX_BACKWARD_GOAL_SIZE
+        || touching_goal * 2 >= branching
+    {
+        PlanningStrategy::StartToGoal
+    } else if goal_depth(actions, goal) >= MIN_BIDIRECTIONAL_DEPTH {
+        PlanningStrategy::Bidirectional
+    } else {
+        PlanningStrategy::GoalToStart
+    }
+}
+
+/// How many actions deep the goal is: its keys are one deep, the keys of the
+/// preconditions of actions that change those two deep, and so on until there are
+/// no new keys
+fn goal_depth<K: Key, D: DatumLike>(actions: &[Action<K, D>], goal: &Goal<K, D>) -> usize {
+    let mut seen: Vec<&K> = goal.requirements.keys().collect();
+    let mut layer = seen.clone();
+    let mut depth = 0;
+    while !layer.is_empty() {
+        depth += 1;
+        let mut next = vec![];
+        for action in actions {
+            let changes_layer = action
+                .effects
+                .iter()
+                .flat_map(|effect| &effect.mutators)
+                .any(|mutator| layer.contains(&mutator.key()));
+            if !changes_layer {
+                continue;
+            }
+            for (key, _) in &action.preconditions {
+                if !seen.contains(&key) {
+                    seen.push(key);
+                    next.push(key);
+                }
+            }
+        }
+        layer = next;
+    }
+    depth
+}
+
+/// Picks how the search moves between the start [`LocalState`] and the [`Goal`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum PlanningStrategy {
     #[default]
     /// StartToGoal begins with our current state, and finds the most optimal path to the goal, based on the costs
     /// Might take longer time than GoalToStart, but finds the path with the lowest cost
     StartToGoal,
-    /// GoalToStart begins with the goal state, and works backwards from there, in order to find a path as quick as possible
-    /// Might lead to less-than-optimal paths, but should find a valid path quicker
+    /// GoalToStart begins with the goal state, and works backwards from there, only
+    /// looking at actions that help with what's still required. Quicker when only a
+    /// few of the actions matter for the goal. Dynamic preconditions are only checked
+    /// once a plan is found, falling back to StartToGoal if they don't hold
     GoalToStart,
+    /// Auto looks at the actions and the goal and picks StartToGoal, GoalToStart or
+    /// Bidirectional, whichever should expand the fewest nodes. It never picks
+    /// UniformCost. The one it picked ends up in [`PlanStats::strategy`]
+    Auto,
+    /// UniformCost searches forwards like StartToGoal, but without a heuristic
+    /// (Dijkstra). Expands many more nodes, but finds the cheapest plan even where
+    /// the heuristic overestimates, like a `>=` on a key that one cheap action
+    /// moves a long way. Never picked by Auto
+    UniformCost,
+    /// Bidirectional searches forwards like StartToGoal and backwards like
+    /// GoalToStart at the same time, taking turns, and joins the two once a state
+    /// reached forwards meets what's required at a point reached backwards. Meets in
+    /// the middle of long chains of actions after expanding far fewer nodes than
+    /// either, but the plan isn't always the cheapest. Falls back to StartToGoal
+    /// where GoalToStart would
+    Bidirectional,
+}
+
+/// Numbers about a search, returned by [`make_plan_with_stats`] whether it found a
+/// plan or not. Searching for a plan that isn't there can take much longer than
+/// finding one, so these tell the two apart from a slow frame
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlanStats {
+    /// The [`PlanningStrategy`] that produced the result. Never
+    /// [`PlanningStrategy::Auto`], that's resolved before searching
+    pub strategy: PlanningStrategy,
+    /// How many nodes were taken off the open list and expanded
+    pub expanded_nodes: usize,
+    /// How many successors were generated while expanding
+    pub generated_nodes: usize,
+    /// The total penalty from [`PlannerConfig::avoid`] rules along the plan that was
+    /// found. The cost the search minimized is the plan's cost plus this, minus the
+    /// `reward`
+    pub penalty: usize,
+    /// The total [`Effect::reward`] of the actions in the plan that was found. Plans
+    /// report what their actions cost before rewards
+    pub reward: usize,
+    /// How many successors were dropped for costing more than
+    /// [`PlannerConfig::max_cost`] to reach
+    pub pruned_nodes: usize,
+    /// The cheapest of the dropped successors that reached the goal, by the cost the
+    /// search minimized
+    pub cheapest_over_budget: Option<usize>,
+    /// How long the actions in the plan that was found take in total, see
+    /// [`Effect::duration`]
+    pub duration: usize,
+    /// How long planning took on the wall clock. Only measured by
+    /// [`make_plan_instrumented`], and zero everywhere else so the stats of the same
+    /// search compare equal. Always zero without the `std` feature, as there's no
+    /// clock to read
+    pub elapsed: core::time::Duration,
+}
+
+/// A plan as returned by [`make_plan`]: the [`Node`]s leading from the start
+/// [`LocalState`] to the [`Goal`], and the total cost of getting there
+pub type Plan<K = String, D = Datum> = (Vec<Node<K, D>>, usize);
+
+/// A plan as returned by [`make_plan_detailed`], with how much searching it took.
+/// The helpers taking a slice of [`Node`]s, like [`effects_of_plan`], take
+/// `&result.nodes`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlanResult<K: Key = String, D: DatumLike = Datum> {
+    /// The [`Node`]s leading from the start [`LocalState`] to the [`Goal`]
+    pub nodes: Vec<Node<K, D>>,
+    /// The total cost of the plan
+    pub cost: usize,
+    /// See [`PlanStats::expanded_nodes`]
+    pub expanded_nodes: usize,
+    /// See [`PlanStats::generated_nodes`]
+    pub generated_nodes: usize,
+    /// See [`PlanStats::elapsed`]
+    pub elapsed: core::time::Duration,
+}
+
+impl<K: Key, D: DatumLike> From<PlanResult<K, D>> for Plan<K, D> {
+    fn from(result: PlanResult<K, D>) -> Self {
+        (result.nodes, result.cost)
+    }
+}
+
+/// Anything [`print_plan`] can print: a [`Plan`] or a [`PlanResult`]
+pub trait PlanLike<K, D> {
+    fn nodes(&self) -> &[Node<K, D>];
+    fn cost(&self) -> usize;
+}
+
+impl<K, D> PlanLike<K, D> for Plan<K, D> {
+    fn nodes(&self) -> &[Node<K, D>] {
+        &self.0
+    }
+
+    fn cost(&self) -> usize {
+        self.1
+    }
+}
+
+impl<K: Key, D: DatumLike> PlanLike<K, D> for PlanResult<K, D> {
+    fn nodes(&self) -> &[Node<K, D>] {
+        &self.nodes
+    }
+
+    fn cost(&self) -> usize {
+        self.cost
+    }
+}
+
+/// Options for [`make_plan_with_config`] and [`plan_many`](crate::prelude::plan_many)
+#[derive(Clone, Debug)]
+pub struct PlannerConfig<K = String, D = Datum> {
+    /// Which [`PlanningStrategy`] to search with
+    pub strategy: PlanningStrategy,
+    /// How much wall-clock time planning may take before giving up. A search that runs
+    /// out fails with [`PlanError::TimedOut`] where a [`PlanError`] can be returned,
+    /// and returns `None` everywhere else. For
+    /// [`plan_many`](crate::prelude::plan_many) this is the budget for the whole batch
+    #[cfg(feature = "std")]
+    pub time_budget: Option<Duration>,
+    /// What to do when more than one [`Action`] has the same key
+    pub duplicate_keys: DuplicateKeys,
+    /// States to steer clear of. Whenever a step leads to a state that matches a rule,
+    /// the rule's penalty is added to the cost of that step. Only searching forwards
+    /// supports these, so with any rules the [`PlanningStrategy`] is ignored
+    pub avoid: Vec<AvoidRule<K, D>>,
+    /// Caps what every requirement of the [`Goal`] adds to the heuristic at the cost
+    /// of the cheapest way the [`Action`]s could fix it. Helps when a large gap in
+    /// one number would otherwise drown out everything else the goal asks for
+    pub normalize_heuristic: bool,
+    /// Estimates what reaching the [`Goal`] still costs in place of the built-in
+    /// heuristic, which goes by how far off each requirement is. One that never
+    /// overestimates still finds the cheapest plan. One that does, like a weighted
+    /// distance, makes the search more eager to head for the goal, and the plan it
+    /// finds may cost more than the cheapest one. Only searching forwards supports
+    /// this, so with one the [`PlanningStrategy`] is ignored, and so is
+    /// `normalize_heuristic`
+    pub heuristic: Option<CustomHeuristic<K, D>>,
+    /// Multiplies the heuristic of the forward search, custom or built-in, by this
+    /// much (weighted A*). Above 1 the search heads for the goal more eagerly,
+    /// expanding fewer nodes for a plan that may cost more than the cheapest one,
+    /// at most this many times as much with a heuristic that doesn't overestimate.
+    /// Weights below 1 count as 1, the default. The cost of the plan is still what
+    /// its steps cost
+    pub heuristic_weight: f32,
+    /// How much a step that moves a key by 1 costs at least. The built-in heuristic
+    /// counts every unit a requirement is off as this much cost, 1 by default. Costs
+    /// are whole numbers and there's no float cost type, so for costs with a fraction,
+    /// like distances in meters, give them in fixed point: in hundredths, with
+    /// [`Action::set_cost_f32`] and this set to 100. The heuristic then weighs
+    /// what's left against costs in the same units, instead of underestimating it a
+    /// hundredfold. Plan costs come back in the same hundredths
+    pub cost_scale: usize,
+    /// Values for keys the start [`LocalState`] doesn't have. Preconditions, goals and
+    /// mutators see a missing key as holding its default, and the states in the plan
+    /// include it. Keys without a default stay missing, as before
+    pub defaults: BTreeMap<K, D>,
+    /// What to do with mutators for keys the state doesn't have. With a policy set,
+    /// preconditions and goals on missing keys count as unmet. Without one, mutators
+    /// behave like [`MissingKeyPolicy::Insert`] and reading a missing key panics
+    pub missing_keys: Option<MissingKeyPolicy>,
+    /// Stops the search once cancelled. Searches that give up this way fail with
+    /// [`PlanError::Cancelled`] where a [`PlanError`] can be returned, and return
+    /// `None` everywhere else
+    pub cancellation: Option<CancellationToken>,
+    /// How many nodes the search expands between checking the `cancellation` token
+    /// and the time budget. Checking is cheap, so the default checks every time
+    pub check_interval: usize,
+    /// The most nodes the search may expand before giving up, counted over the whole
+    /// search, so a backward search that falls back to searching forwards shares it.
+    /// Unlike the time budget, the same domain always stops at the same point. A
+    /// search that runs out fails with [`PlanError::BudgetExceeded`] where a
+    /// [`PlanError`] can be returned, and returns `None` everywhere else. Run
+    /// [`make_plan_with_trace`] with the same budget to see how far it got
+    pub max_expanded_nodes: Option<usize>,
+    /// Whether rewards may cancel out the cost of an [`Effect`], and how to search
+    /// when they do
+    pub rewards: Rewards,
+    /// The most a plan may cost, by the cost the search minimizes (see
+    /// [`PlanStats::penalty`]). Paths costing more are dropped as soon as they do, and
+    /// a search that only came across plans over the budget fails with
+    /// [`PlanError::TooExpensive`].
+    ///
+    /// Dropping paths early is what keeps searches for goals out of reach short, but
+    /// it also means the search never finishes the plans it drops. So the cheapest
+    /// plan [`PlanError::TooExpensive`] reports is the cheapest one the search came
+    /// across while still within budget, which need not be the cheapest there is,
+    /// and it's `None` when no dropped path was one step away from the goal. A time
+    /// budget or cancelling cuts this short as well.
+    ///
+    /// Ignored by [`Rewards::BoundedDepth`], where a path over budget could still
+    /// come back under it
+    pub max_cost: Option<usize>,
+    /// Conditions every state of the plan has to meet, the start included. Steps
+    /// that would break one are never taken. Only searching forwards supports these,
+    /// so with any invariants the [`PlanningStrategy`] is ignored
+    pub invariants: Vec<Invariant<K, D>>,
+    /// When no plan meets the `invariants`, search again without them to tell
+    /// whether they're what's in the way. Only planners that return a [`PlanError`]
+    /// do this, and it can make failing take much longer: without the invariants
+    /// there may be far more to search through
+    pub retry_without_invariants: bool,
+    /// Keys whose value is derived from the rest of the state instead of stored in it.
+    /// Preconditions and goals read them like any other key, and a key the state does
+    /// have takes precedence. Actions may not change them. Only searching forwards
+    /// supports these, so with any computed keys the [`PlanningStrategy`] is ignored
+    pub computed: ComputedKeys<K, D>,
+    /// Whether the plan should be as cheap or as fast as possible
+    pub minimize: Minimize,
+    /// Keys like gold or wood that no step of a plan may take below their floor, so
+    /// actions spending them don't each need a precondition for having enough. A
+    /// floor of `None` is zero, of the same kind as the key's value. Only searching
+    /// forwards supports these, so with any resources the [`PlanningStrategy`] is
+    /// ignored
+    pub resources: BTreeMap<K, Option<D>>,
+    /// Picks between equally cheap plans pseudo-randomly, by this seed. The same
+    /// seed always picks the same plan, so agents sharing a domain can each pass
+    /// their own, like an entity id, to not all do the exact same thing. Costs are
+    /// left alone, so the plan is as cheap as it would be without. Ignored by
+    /// [`Rewards::BoundedDepth`] and [`make_plan_for_objective`](crate::prelude::make_plan_for_objective)
+    pub tie_seed: Option<u64>,
+    /// The most events [`make_plan_with_trace`] records, so tracing a large search
+    /// doesn't take up all the memory. Events past it are left out
+    pub max_trace_events: usize,
+    /// Watches the search as it goes, for showing its frontier in an editor. Only
+    /// the forward search expands [`Node`]s, a backward one doesn't call it. It
+    /// can't change what the search does
+    pub on_expand: Option<OnExpand<K, D>>,
+    /// Told how each search ended, whether it found a plan or not
+    pub on_finish: Option<OnFinish<K, D>>,
+}
+
+/// Conditions that all have to hold in a state for the rule to match, and the
+/// penalty for passing through a state that matches
+pub type AvoidRule<K = String, D = Datum> = (Vec<(K, Compare<D>)>, usize);
+
+impl<K, D> Default for PlannerConfig<K, D> {
+    fn default() -> Self {
+        Self {
+            strategy: PlanningStrategy::default(),
+            #[cfg(feature = "std")]
+            time_budget: None,
+            duplicate_keys: DuplicateKeys::default(),
+            avoid: vec![],
+            normalize_heuristic: false,
+            heuristic: None,
+            heuristic_weight: 1.0,
+            cost_scale: 1,
+            defaults: BTreeMap::new(),
+            missing_keys: None,
+            cancellation: None,
+            check_interval: 1,
+            max_expanded_nodes: None,
+            rewards: Rewards::default(),
+            max_cost: None,
+            invariants: vec![],
+            retry_without_invariants: false,
+            computed: ComputedKeys::default(),
+            minimize: Minimize::default(),
+            resources: BTreeMap::new(),
+            tie_seed: None,
+            max_trace_events: 10_000,
+            on_expand: None,
+            on_finish: None,
+        }
+    }
+}
+
+impl PlannerConfig {
+    /// A config for `String` keys. Use [`PlannerConfig::default`] for other keys
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes a wildcard like `inventory.*` a [computed](PlannerConfig::computed) key,
+    /// summing up every key under `inventory.` the way `wildcard` says. Preconditions
+    /// and goals can then ask for things like at least 3 kinds of items.
+    ///
+    /// # Panics
+    ///
+    /// If `pattern` isn't a valid path ending in `.*`, see [`PathError`](crate::path::PathError)
+    pub fn with_wildcard(self, pattern: &str, wildcard: Wildcard) -> Self {
+        match wildcard_key(pattern, wildcard) {
+            Ok(compute) => self.with_computed_key(pattern, compute),
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl<K: Key, D: DatumLike> PlannerConfig<K, D> {
+    pub fn with_strategy(mut self, strategy: PlanningStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    pub fn with_duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> Self {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    pub fn with_normalized_heuristic(mut self, normalize: bool) -> Self {
+        self.normalize_heuristic = normalize;
+        self
+    }
+
+    pub fn with_heuristic(
+        mut self,
+        estimate: impl Fn(&LocalState<K, D>, &Goal<K, D>) -> usize + Send + Sync + 'static,
+    ) -> Self {
+        self.heuristic = Some(CustomHeuristic::new(estimate));
+        self
+    }
+
+    pub fn with_heuristic_weight(mut self, weight: f32) -> Self {
+        self.heuristic_weight = weight;
+        self
+    }
+
+    pub fn with_cost_scale(mut self, cost_scale: usize) -> Self {
+        self.cost_scale = cost_scale;
+        self
+    }
+
+    /// One unit of distance in the units of the built-in heuristic, which are
+    /// `1 / HEURISTIC_SCALE` of a cost
+    pub(crate) fn distance_unit(&self) -> u64 {
+        HEURISTIC_SCALE.saturating_mul(self.cost_scale.max(1) as u64)
+    }
+
+    pub fn with_default(mut self, key: impl Into<K>, value: D) -> Self {
+        self.defaults.insert(key.into(), value);
+        self
+    }
+
+    pub fn with_missing_keys(mut self, missing_keys: MissingKeyPolicy) -> Self {
+        self.missing_keys = Some(missing_keys);
+        self
+    }
+
+    pub fn with_avoid(mut self, conditions: Vec<(K, Compare<D>)>, penalty: usize) -> Self {
+        self.avoid.push((conditions, penalty));
+        self
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    pub fn with_check_interval(mut self, check_interval: usize) -> Self {
+        self.check_interval = check_interval;
+        self
+    }
+
+    pub fn with_max_expanded_nodes(mut self, max_expanded_nodes: usize) -> Self {
+        self.max_expanded_nodes = Some(max_expanded_nodes);
+        self
+    }
+
+    pub fn with_rewards(mut self, rewards: Rewards) -> Self {
+        self.rewards = rewards;
+        self
+    }
+
+    pub fn with_max_cost(mut self, max_cost: usize) -> Self {
+        self.max_cost = Some(max_cost);
+        self
+    }
+
+    pub fn with_invariant(mut self, invariant: Invariant<K, D>) -> Self {
+        self.invariants.push(invariant);
+        self
+    }
+
+    pub fn with_retry_without_invariants(mut self, retry: bool) -> Self {
+        self.retry_without_invariants = retry;
+        self
+    }
+
+    pub fn with_minimize(mut self, minimize: Minimize) -> Self {
+        self.minimize = minimize;
+        self
+    }
+
+    pub fn with_computed_key(mut self, key: impl Into<K>, compute: ComputedKey<K, D>) -> Self {
+        self.computed.keys.insert(key.into(), compute);
+        self
+    }
+
+    pub fn with_tie_seed(mut self, seed: u64) -> Self {
+        self.tie_seed = Some(seed);
+        self
+    }
+
+    pub fn with_max_trace_events(mut self, max_events: usize) -> Self {
+        self.max_trace_events = max_events;
+        self
+    }
+
+    /// Calls `observe` for every node the search expands, see [`PlannerConfig::on_expand`]
+    pub fn with_on_expand(
+        mut self,
+        observe: impl Fn(&Node<K, D>, usize, usize) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_expand = Some(OnExpand::new(observe));
+        self
+    }
+
+    /// Calls `observe` once the search is done, see [`PlannerConfig::on_finish`]
+    pub fn with_on_finish(
+        mut self,
+        observe: impl Fn(Option<&Plan<K, D>>, &PlanStats) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_finish = Some(OnFinish::new(observe));
+        self
+    }
+
+    /// Declares `key` a resource that may not go below zero
+    pub fn with_resource(mut self, key: impl Into<K>) -> Self {
+        self.resources.insert(key.into(), None);
+        self
+    }
+
+    /// Declares `key` a resource that may not go below `floor`
+    pub fn with_resource_floor(mut self, key: impl Into<K>, floor: D) -> Self {
+        self.resources.insert(key.into(), Some(floor));
+        self
+    }
+}
+
+/// What the planner does when more than one [`Action`] has the same key
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Fail with [`PlanError::DuplicateActionKey`], as the plan couldn't say which
+    /// of the actions to execute
+    #[default]
+    Reject,
+    /// Plan with every action, but label them `key#0`, `key#1` and so on in the
+    /// order they're passed in, for when they're meant as variants of each other
+    Disambiguate,
+}
+
+/// What the planner makes as small as possible. Plans report their cost either way,
+/// and [`PlanStats::duration`] how long they take
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Minimize {
+    /// The total cost of the actions
+    #[default]
+    Cost,
+    /// The total [`Effect::duration`] of the actions. This searches without a
+    /// heuristic and ignores [`PlannerConfig::max_cost`] and avoid penalties, ties
+    /// between equally fast plans go to whichever the search finds first
+    Duration,
+}
+
+/// What the planner does with [`Effect::reward`]s that make an action free, or pay
+/// more than it costs. Rewards below the cost are fine either way
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rewards {
+    /// Fail with [`PlanError::RewardOutweighsCost`]. Planning uses A* as usual, which
+    /// could go around a cycle of such actions forever
+    #[default]
+    Reject,
+    /// Search with a bounded-depth Bellman-Ford instead, which finds the cheapest
+    /// plan of up to this many actions even when rewards outweigh costs.
+    ///
+    /// It has no heuristic to guide it, so it goes through every state within
+    /// reach of the start and is much slower than A*. Plans longer than the bound
+    /// aren't found at all. Like [`PlannerConfig::avoid`] rules, this always
+    /// searches forwards. Shifting every cost up until none is negative would keep
+    /// A* usable, but it favours plans with fewer actions and so doesn't find the
+    /// cheapest one
+    BoundedDepth(usize),
 }
 
 /// Returns a path of [`Node`]s that leads from our start [`LocalState`] to our
-/// [`Goal`] state
-pub fn make_plan(
-    start: &LocalState,
-    actions: &[Action],
-    goal: &Goal,
-) -> Option<(Vec<Node>, usize)> {
+/// [`Goal`] state. If the start meets the goal already, that's just the
+/// [`Node::State`] of the start, at a cost of 0 and without any effects
+///
+/// Given the same start, actions (in the same order) and goal, this always returns
+/// the same plan, on every run and platform, even when there are several equally
+/// cheap ones. See [`PlannerConfig::tie_seed`] for picking between those differently
+///
+/// # Panics
+///
+/// If more than one [`Action`] has the same key, see [`DuplicateKeys`]
+pub fn make_plan<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> Option<(Vec<Node<K, D>>, usize)> {
     // Default to using Start -> Goal planning
     make_plan_with_strategy(PlanningStrategy::StartToGoal, start, actions, goal)
 }
 
-/// Returns a Vector of all [`Effect`]s from a given plan
-pub fn get_effects_from_plan(plan: Vec<Node>) -> Vec<Effect> {
+/// Like [`make_plan`], but searches with `estimate` in place of the built-in
+/// heuristic. An estimate that's ever more than what reaching the goal really costs
+/// may get a plan faster, but not always the cheapest one, see
+/// [`PlannerConfig::heuristic`]
+///
+/// # Panics
+///
+/// If more than one [`Action`] has the same key, see [`DuplicateKeys`]
+pub fn make_plan_with_heuristic<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    estimate: impl Fn(&LocalState<K, D>, &Goal<K, D>) -> usize + Send + Sync + 'static,
+) -> Option<Plan<K, D>> {
+    let config = PlannerConfig::default()
+        .with_strategy(PlanningStrategy::StartToGoal)
+        .with_heuristic(estimate);
+    make_plan_with_config(start, actions, goal, &config)
+}
+
+/// Like [`make_plan`], but gives up after expanding `max_nodes` nodes, returning
+/// `None` as if there was no plan. See [`PlannerConfig::max_expanded_nodes`], and
+/// [`try_make_plan_with_config`] to tell the two apart
+///
+/// # Panics
+///
+/// If more than one [`Action`] has the same key, see [`DuplicateKeys`]
+pub fn make_plan_with_budget<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    max_nodes: usize,
+) -> Option<Plan<K, D>> {
+    let config = PlannerConfig::default()
+        .with_strategy(PlanningStrategy::StartToGoal)
+        .with_max_expanded_nodes(max_nodes);
+    make_plan_with_config(start, actions, goal, &config)
+}
+
+/// Like [`make_plan`], but only plans with the actions `filter` keeps, like those
+/// with one of the [`Action::tags`]. The actions are filtered once up front, not at
+/// every node
+///
+/// # Panics
+///
+/// If more than one of the kept actions has the same key, see [`DuplicateKeys`]
+pub fn make_plan_with_filter<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    filter: impl Fn(&Action<K, D>) -> bool,
+) -> Option<Plan<K, D>> {
+    let actions: Vec<Action<K, D>> = actions
+        .iter()
+        .filter(|action| filter(action))
+        .cloned()
+        .collect();
+    make_plan(start, &actions, goal)
+}
+
+/// Like [`make_plan`], but returns a [`PlanError`] instead of panicking, including
+/// [`PlanError::MissingKey`] when a precondition needs a key the start state
+/// doesn't have, or the goal one nothing ever sets
+pub fn try_make_plan<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> Result<Option<Plan<K, D>>, PlanError<K>> {
+    let config = PlannerConfig::default().with_strategy(PlanningStrategy::StartToGoal);
+    try_make_plan_with_config(start, actions, goal, &config)
+}
+
+/// Like [`try_make_plan`], but gives up with [`PlanError::Cancelled`] once
+/// `cancelled` is set, from this thread or any other. It's checked as the search goes,
+/// so a long search stops partway through. To cancel searches with a config, give it
+/// a [`CancellationToken`] instead
+pub fn make_plan_cancellable<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    cancelled: &AtomicBool,
+) -> Result<Option<Plan<K, D>>, PlanError<K>> {
+    let config = PlannerConfig::default().with_strategy(PlanningStrategy::StartToGoal);
+    let actions = validate_actions(actions, &config)?;
+    check_keys_exist(start, &actions, goal, &config)?;
+    let interrupt = Interrupt {
+        cancelled: Some(cancelled),
+        ..Interrupt::from_config(&config)
+    };
+    search_plan_or_cancelled(start, &actions, goal, &config, interrupt)
+}
+
+/// Returns a Vector of all [`Effect`]s from a given plan. Composite steps come back
+/// as they are, pass the plan through [`flatten_plan`](crate::prelude::flatten_plan)
+/// first for the effects of their own plans instead
+#[deprecated(note = "use `effects_of_plan`, which doesn't need its own copy of the plan")]
+pub fn get_effects_from_plan<K, D>(plan: Vec<Node<K, D>>) -> Vec<Effect<K, D>> {
     let mut nodes = vec![];
 
     for node in plan {
@@ -140,32 +2062,269 @@ pub fn get_effects_from_plan(plan: Vec<Node>) -> Vec<Effect> {
     nodes
 }
 
+/// The [`Effect`]s of a plan, in the order they're executed
+pub fn effects_of_plan<K, D>(plan: &[Node<K, D>]) -> impl Iterator<Item = &Effect<K, D>> {
+    plan.iter().filter_map(|node| match node {
+        Node::Effect(effect) => Some(effect),
+        Node::State(_) => None,
+    })
+}
+
+/// The keys of the [`Action`]s of a plan, in the order they're executed
+pub fn action_keys_of_plan<K, D>(plan: &[Node<K, D>]) -> impl Iterator<Item = &str> {
+    plan.iter().filter_map(Node::action_key)
+}
+
+/// The state a plan ends up in. For a plan that's only the initial
+/// [`Node::State`], because the goal already held, that's the initial state. `None`
+/// if there are no nodes at all
+pub fn final_state_of_plan<K, D>(plan: &[Node<K, D>]) -> Option<&LocalState<K, D>> {
+    plan.last().map(Node::state)
+}
+
+/// How [`print_plan_with_format`] lays out a plan
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlanFormat {
+    /// Every step shows the [`Action`], its cost, and the value before and after
+    /// of each key that changed
+    #[default]
+    Diff,
+    /// Every step shows the [`Action`] and its cost, and dumps the [`Mutator`]s of
+    /// its [`Effect`]. Only the initial and final states are shown in full
+    Verbose,
+}
+
 /// Prints a human-readable version of a plan from [`make_plan`] that shows
-/// what [`Action`]s needs to be executed and what the results of each Action is
-pub fn print_plan(plan: (Vec<Node>, usize)) {
-    let nodes = plan.0;
-    let cost = plan.1;
-    let mut last_state = LocalState::new();
+/// what [`Action`]s needs to be executed and what each of them changes. See
+/// [`format_plan`] for getting it as a `String` instead
+#[cfg(feature = "std")]
+pub fn print_plan<K: Key + Display, D: DatumLike + Display>(plan: &impl PlanLike<K, D>) {
+    print_plan_with_format(plan, PlanFormat::Diff)
+}
+
+/// Like [`print_plan`], but laid out according to `format`
+#[cfg(feature = "std")]
+pub fn print_plan_with_format<K: Key + Display, D: DatumLike + Display>(
+    plan: &impl PlanLike<K, D>,
+    format: PlanFormat,
+) {
+    print!("{}", format_plan_with_format(plan, format));
+}
+
+/// What [`print_plan`] prints, for logging it or showing it in game
+pub fn format_plan<K: Key + Display, D: DatumLike + Display>(plan: &impl PlanLike<K, D>) -> String {
+    format_plan_with_format(plan, PlanFormat::Diff)
+}
+
+/// What [`print_plan_with_format`] prints
+pub fn format_plan_with_format<K: Key + Display, D: DatumLike + Display>(
+    plan: &impl PlanLike<K, D>,
+    format: PlanFormat,
+) -> String {
+    let mut out = String::new();
+    // Writing to a String can't fail
+    write_plan(&mut out, plan.nodes(), plan.cost(), format).unwrap();
+    out
+}
+
+pub(crate) fn write_plan<K: Key + Display, D: DatumLike + Display>(
+    out: &mut impl core::fmt::Write,
+    nodes: &[Node<K, D>],
+    cost: usize,
+    format: PlanFormat,
+) -> core::fmt::Result {
+    match format {
+        PlanFormat::Diff => write_plan_diff(out, nodes, cost),
+        PlanFormat::Verbose => write_plan_verbose(out, nodes, cost),
+    }
+}
+
+fn write_state<K: Display, D: Display>(
+    out: &mut impl core::fmt::Write,
+    state: &LocalState<K, D>,
+) -> core::fmt::Result {
+    for (k, v) in &state.data {
+        writeln!(out, "\t\t{} = {}", k, v)?;
+    }
+    Ok(())
+}
+
+fn write_plan_verbose<K: Key + Display, D: DatumLike + Display>(
+    out: &mut impl core::fmt::Write,
+    nodes: &[Node<K, D>],
+    cost: usize,
+) -> core::fmt::Result {
+    let mut last_state = &LocalState::default();
     for node in nodes {
         match node {
             Node::Effect(effect) => {
-                println!("\t\t= DO ACTION {:#?}", effect.action);
-                println!("\t\tMUTATES:");
-                print_mutators(effect.mutators);
-                last_state = effect.state.clone();
+                writeln!(
+                    out,
+                    "\t\t= DO ACTION {:#?} (COST: {})",
+                    effect.action, effect.cost
+                )?;
+                writeln!(out, "\t\tMUTATES:")?;
+                for mutator in &effect.mutators {
+                    writeln!(out, "\t\t{}", mutator)?;
+                }
             }
             Node::State(s) => {
-                println!("\t\t= INITIAL STATE");
-                for (k, v) in &s.data {
-                    println!("\t\t{} = {}", k, v);
-                }
-                last_state = s.clone();
+                writeln!(out, "\t\t= INITIAL STATE")?;
+                write_state(out, s)?;
             }
         }
-        println!("\n\t\t---\n");
+        last_state = node.state();
+        writeln!(out, "\n\t\t---\n")?;
     }
-    println!("\t\t= FINAL STATE (COST: {})", cost);
-    for (k, v) in &last_state.data {
-        println!("\t\t{} = {}", k, v);
+    writeln!(out, "\t\t= FINAL STATE (COST: {})", cost)?;
+    write_state(out, last_state)
+}
+
+/// The keys whose values differ between `before` and `after`, in key order, with
+/// their values before and after (`None` if the key isn't there)
+fn changed_keys<'a, K: Key + Display, D: DatumLike + Display>(
+    before: &'a LocalState<K, D>,
+    after: &'a LocalState<K, D>,
+) -> Vec<(String, Option<&'a D>, Option<&'a D>)> {
+    before
+        .diff(after)
+        .into_iter()
+        // Not every key pads itself when displayed, a String does
+        .map(|(key, before, after)| (key.to_string(), before, after))
+        .collect()
+}
+
+fn write_plan_diff<K: Key + Display, D: DatumLike + Display>(
+    out: &mut impl core::fmt::Write,
+    nodes: &[Node<K, D>],
+    cost: usize,
+) -> core::fmt::Result {
+    let show = |value: Option<&D>| value.map_or("(none)".to_string(), D::to_string);
+
+    // Work out all the changes up front, so the columns line up over the whole plan
+    let steps: Vec<_> = nodes
+        .windows(2)
+        .map(|pair| (&pair[1], changed_keys(pair[0].state(), pair[1].state())))
+        .collect();
+    let changes = steps.iter().flat_map(|(_, changes)| changes);
+    let key_width = changes
+        .clone()
+        .map(|(key, ..)| key.len())
+        .max()
+        .unwrap_or(0);
+    let before_width = changes
+        .map(|(_, before, _)| show(*before).len())
+        .max()
+        .unwrap_or(0);
+
+    if let Some(first) = nodes.first() {
+        writeln!(out, "\t\t= INITIAL STATE")?;
+        write_state(out, first.state())?;
+    }
+    for (step, (node, changes)) in steps.iter().enumerate() {
+        let (action, step_cost) = match node {
+            Node::Effect(effect) => (effect.action.as_str(), effect.cost),
+            Node::State(_) => ("(none)", 0),
+        };
+        writeln!(out, "\n\t\t{}. {} (cost {})", step + 1, action, step_cost)?;
+        for (key, before, after) in changes {
+            writeln!(
+                out,
+                "\t\t   {:<key_width$}  {:<before_width$} -> {}",
+                key,
+                show(*before),
+                show(*after),
+            )?;
+        }
+    }
+    writeln!(out, "\n\t\t= FINAL STATE (COST: {})", cost)?;
+    if let Some(last) = nodes.last() {
+        write_state(out, last.state())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        compare::Compare,
+        datum::Datum,
+        goal::Goal,
+        localstate::LocalState,
+        planner::{make_plan, write_plan, PlanFormat},
+        simple::simple_increment_action,
+    };
+
+    fn plan() -> (Vec<super::Node>, usize) {
+        let start = LocalState::new()
+            .with_datum("gold", Datum::I64(0))
+            .with_datum("energy_of_the_adventurer", Datum::I64(100));
+        let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(2)));
+        let actions = vec![simple_increment_action("mine", "gold", Datum::I64(1))
+            .add_mutator(crate::mutator::Mutator::Decrement(
+                "energy_of_the_adventurer".to_string(),
+                Datum::I64(10),
+            ))
+            .set_cost(3)];
+        make_plan(&start, &actions, &goal).unwrap()
+    }
+
+    fn format(format: PlanFormat) -> String {
+        let (nodes, cost) = plan();
+        let mut out = String::new();
+        write_plan(&mut out, &nodes, cost, format).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_diff_format() {
+        let expected = "\
+\t\t= INITIAL STATE
+\t\tenergy_of_the_adventurer = Datum:I64(100)
+\t\tgold = Datum:I64(0)
+
+\t\t1. mine (cost 3)
+\t\t   energy_of_the_adventurer  Datum:I64(100) -> Datum:I64(90)
+\t\t   gold                      Datum:I64(0)   -> Datum:I64(1)
+
+\t\t2. mine (cost 3)
+\t\t   energy_of_the_adventurer  Datum:I64(90)  -> Datum:I64(80)
+\t\t   gold                      Datum:I64(1)   -> Datum:I64(2)
+
+\t\t= FINAL STATE (COST: 6)
+\t\tenergy_of_the_adventurer = Datum:I64(80)
+\t\tgold = Datum:I64(2)
+";
+        assert_eq!(expected, format(PlanFormat::Diff));
+    }
+
+    #[test]
+    fn test_verbose_format() {
+        let expected = "\
+\t\t= INITIAL STATE
+\t\tenergy_of_the_adventurer = Datum:I64(100)
+\t\tgold = Datum:I64(0)
+
+\t\t---
+
+\t\t= DO ACTION \"mine\" (COST: 3)
+\t\tMUTATES:
+\t\tgold + Datum:I64(1)
+\t\tenergy_of_the_adventurer - Datum:I64(10)
+
+\t\t---
+
+\t\t= DO ACTION \"mine\" (COST: 3)
+\t\tMUTATES:
+\t\tgold + Datum:I64(1)
+\t\tenergy_of_the_adventurer - Datum:I64(10)
+
+\t\t---
+
+\t\t= FINAL STATE (COST: 6)
+\t\tenergy_of_the_adventurer = Datum:I64(80)
+\t\tgold = Datum:I64(2)
+";
+        assert_eq!(expected, format(PlanFormat::Verbose));
     }
 }
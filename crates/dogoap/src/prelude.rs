@@ -1,11 +1,53 @@
 // crate "dogoap" src/prelude.rs
-pub use crate::action::Action;
+pub use crate::action::{
+    Action, DynamicCost, DynamicEffect, DynamicPrecondition, Effects, Preconditions,
+};
+pub use crate::action_set::ActionSet;
+pub use crate::batch::{plan_many, plan_many_with_actions};
+pub use crate::cache::PlanCache;
+pub use crate::cancel::CancellationToken;
 pub use crate::compare::Compare;
-pub use crate::datum::Datum;
-pub use crate::effect::Effect;
-pub use crate::goal::{Goal, check_goal};
-pub use crate::localstate::LocalState;
+pub use crate::compiled::CompiledAction;
+pub use crate::composite::{flatten_plan, Subgoal};
+pub use crate::computed::{ComputedKey, ComputedKeys};
+pub use crate::context::PlanningContext;
+pub use crate::contingent::{make_contingent_plan, Branches, PlanTree};
+pub use crate::cycles::{find_zero_cost_cycles, ZeroCostCycle};
+pub use crate::datum::{Datum, DatumKind, DatumLike, FLOAT_EPSILON};
+pub use crate::diff::{diff_domains, ActionChange, ActionDiff, DomainDiff};
+pub use crate::effect::{Effect, MissingKeyPolicy, Mutators};
+pub use crate::error::{PlanError, PlanInvalid};
+#[cfg(feature = "async")]
+pub use crate::future::{plan_async, PlanFuture};
+pub use crate::goal::{check_goal, select_goal, Goal};
+pub use crate::heuristic::CustomHeuristic;
+pub use crate::incremental::{IncrementalPlanner, StepResult};
+pub use crate::invariant::Invariant;
+pub use crate::k_best::{make_plans, make_plans_with_config};
+pub use crate::localstate::{Key, LocalState};
 pub use crate::mutator::Mutator;
+pub use crate::objective::{make_plan_for_objective, Budget, Objective};
+pub use crate::optimize::{check_plan, optimize_plan, repair_plan, validate_plan, RepairedPlan};
+pub use crate::path::{parse_path, PathError, Wildcard};
+#[allow(deprecated)]
+pub use crate::planner::get_effects_from_plan;
 pub use crate::planner::{
-    get_effects_from_plan, make_plan, make_plan_with_strategy, print_plan, Node, PlanningStrategy,
+    action_keys_of_plan, effects_of_plan, final_state_of_plan, format_plan,
+    format_plan_with_format, make_plan, make_plan_anytime, make_plan_cancellable,
+    make_plan_detailed, make_plan_instrumented, make_plan_or_closest, make_plan_with_budget,
+    make_plan_with_config, make_plan_with_filter, make_plan_with_heuristic, make_plan_with_stats,
+    make_plan_with_strategy, make_plan_with_trace, try_make_plan, try_make_plan_with_config,
+    AvoidRule, ClosestPlan, DuplicateKeys, Minimize, Node, Plan, PlanFormat, PlanLike, PlanOutcome,
+    PlanResult, PlanStats, PlannerConfig, PlanningStrategy, Rewards,
 };
+#[cfg(feature = "std")]
+pub use crate::planner::{print_plan, print_plan_with_format};
+pub use crate::regression::regressed_preconditions;
+pub use crate::relevance::relevant_actions;
+#[cfg(feature = "std")]
+pub use crate::spawn::{spawn_plan, PlanHandle};
+#[cfg(feature = "std")]
+pub use crate::symbol::Symbol;
+pub use crate::trace::{OnExpand, OnFinish, PlannerTrace, PrunedBy, TraceEvent};
+pub use crate::typecheck::{typecheck_domain, TypeIssue, TypeIssueSite};
+pub use smallvec::smallvec;
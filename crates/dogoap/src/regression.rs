@@ -0,0 +1,442 @@
+// crate "dogoap" file regression.rs
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+use crate::{
+    action::Action,
+    compare::{compare_entry, compare_values, Compare},
+    computed::ComputedKeys,
+    datum::DatumLike,
+    effect::Effect,
+    goal::Goal,
+    localstate::{Key, LocalState},
+    mutator::Mutator,
+    planner::{
+        apply_action, is_goal, plan_duration, plan_totals, preconditions_met, Interrupt, Node,
+        Plan, PlanStats, PlannerConfig,
+    },
+    search,
+};
+
+/// A partial state used when searching backwards: everything that has to hold
+/// for the rest of the plan to work. Sorted by key, and a key can show up more
+/// than once (for example `>= 3` together with `<= 10`).
+pub(crate) type Requirements<K, D> = Vec<(K, Compare<D>)>;
+
+/// Adds `compare` for `key` to `requirements`, merging it with what's already
+/// required of `key`. Returns `false` if the two can never hold at the same time.
+fn add_requirement<K: Key, D: DatumLike>(
+    requirements: &mut Requirements<K, D>,
+    key: &K,
+    compare: Compare<D>,
+) -> bool {
+    // A range is the same as a lower and an upper bound
+    if let Some((low, high)) = compare.bounds() {
+        let (low, high) = (low.clone(), high.clone());
+        return add_requirement(requirements, key, Compare::GreaterThanEquals(low))
+            && add_requirement(requirements, key, Compare::LessThanEquals(high));
+    }
+
+    // Something already pins the exact value, so the new requirement either
+    // agrees with it or contradicts it
+    let pinned = requirements.iter().find_map(|(k, c)| match c {
+        Compare::Equals(value) if k == key => Some(value),
+        _ => None,
+    });
+    if let Some(pinned) = pinned {
+        return compare_values(&compare, pinned);
+    }
+
+    match &compare {
+        Compare::Equals(value) => {
+            let agrees = requirements
+                .iter()
+                .filter(|(k, _)| k == key)
+                .all(|(_, c)| compare_values(c, value));
+            if !agrees {
+                return false;
+            }
+            requirements.retain(|(k, _)| k != key);
+        }
+        Compare::GreaterThanEquals(value) => {
+            for (k, c) in requirements.iter() {
+                if k != key {
+                    continue;
+                }
+                match c {
+                    Compare::GreaterThanEquals(lower) if lower >= value => return true,
+                    Compare::LessThanEquals(upper) if upper < value => return false,
+                    _ => {}
+                }
+            }
+            requirements.retain(|(k, c)| !(k == key && matches!(c, Compare::GreaterThanEquals(_))));
+        }
+        Compare::LessThanEquals(value) => {
+            for (k, c) in requirements.iter() {
+                if k != key {
+                    continue;
+                }
+                match c {
+                    Compare::LessThanEquals(upper) if upper <= value => return true,
+                    Compare::GreaterThanEquals(lower) if lower > value => return false,
+                    _ => {}
+                }
+            }
+            requirements.retain(|(k, c)| !(k == key && matches!(c, Compare::LessThanEquals(_))));
+        }
+        Compare::NotEquals(_)
+        | Compare::Contains(_)
+        | Compare::ApproxEquals(..)
+        | Compare::Absent => {
+            if requirements.iter().any(|(k, c)| k == key && *c == compare) {
+                return true;
+            }
+        }
+        Compare::Between(..) => unreachable!("ranges are split into two bounds above"),
+    }
+
+    requirements.push((key.clone(), compare));
+    requirements.sort_by(|(key_a, a), (key_b, b)| {
+        key_a
+            .cmp(key_b)
+            .then(compare_rank(a).cmp(&compare_rank(b)))
+            .then_with(|| match (a, b) {
+                (Compare::Absent, _) | (_, Compare::Absent) => Ordering::Equal,
+                _ => a.value().partial_cmp(&b.value()).unwrap_or(Ordering::Equal),
+            })
+    });
+    true
+}
+
+fn compare_rank<D>(compare: &Compare<D>) -> u8 {
+    match compare {
+        Compare::Equals(_) => 0,
+        Compare::NotEquals(_) => 1,
+        Compare::GreaterThanEquals(_) => 2,
+        Compare::LessThanEquals(_) => 3,
+        Compare::Between(..) => 4,
+        Compare::Contains(_) => 5,
+        Compare::ApproxEquals(..) => 6,
+        Compare::Absent => 7,
+    }
+}
+
+/// Moves a required value by `amount`, undoing an `Increment` (`up == false`) or
+/// a `Decrement` (`up == true`). `None` if the two can't be added together.
+fn shift<D: DatumLike>(value: &D, amount: &D, up: bool) -> Option<D> {
+    if up {
+        value.checked_add(amount)
+    } else {
+        value.checked_sub(amount)
+    }
+}
+
+/// Works out what has to hold before `action` so that `requirements` hold after
+/// `effect`, one of its effects. Returns `None` if the effect doesn't help with any
+/// of the requirements, or if it makes one of them impossible.
+///
+/// Dynamic preconditions depend on the state they're evaluated in, so they're not
+/// part of the result and have to be checked when replaying the plan forwards.
+pub(crate) fn regress<K: Key, D: DatumLike>(
+    requirements: &Requirements<K, D>,
+    action: &Action<K, D>,
+    effect: &Effect<K, D>,
+) -> Option<Requirements<K, D>> {
+    let relevant = effect
+        .mutators
+        .iter()
+        .any(|mutator| requirements.iter().any(|(key, _)| key == mutator.key()));
+    if !relevant {
+        return None;
+    }
+
+    let mut regressed = requirements.clone();
+    // Mutators are applied in order, so undo them in reverse
+    for mutator in effect.mutators.iter().rev() {
+        match mutator {
+            Mutator::Set(key, value) => {
+                let met = regressed
+                    .iter()
+                    .filter(|(k, _)| k == key)
+                    .all(|(_, c)| compare_values(c, value));
+                if !met {
+                    return None;
+                }
+                regressed.retain(|(k, _)| k != key);
+            }
+            Mutator::Increment(key, amount) | Mutator::Decrement(key, amount) => {
+                let up = matches!(mutator, Mutator::Decrement(..));
+                for (k, compare) in regressed.iter_mut() {
+                    if k != key {
+                        continue;
+                    }
+                    if *compare == Compare::Absent {
+                        return None;
+                    }
+                    let value = shift(&compare.value(), amount, up)?;
+                    *compare = match compare {
+                        Compare::Equals(_) => Compare::Equals(value),
+                        Compare::NotEquals(_) => Compare::NotEquals(value),
+                        Compare::GreaterThanEquals(_) => Compare::GreaterThanEquals(value),
+                        Compare::LessThanEquals(_) => Compare::LessThanEquals(value),
+                        Compare::Between(_, high) => {
+                            Compare::Between(value, shift(high, amount, up)?)
+                        }
+                        Compare::ApproxEquals(_, epsilon) => Compare::ApproxEquals(value, *epsilon),
+                        Compare::Contains(_) | Compare::Absent => return None,
+                    };
+                }
+            }
+            Mutator::Toggle(key) => {
+                for (k, compare) in regressed.iter_mut() {
+                    if k != key {
+                        continue;
+                    }
+                    // Before the toggle the value was the opposite of what's required
+                    *compare = match compare {
+                        Compare::Equals(value) => Compare::Equals(value.toggled()?),
+                        Compare::NotEquals(value) => Compare::NotEquals(value.toggled()?),
+                        _ => return None,
+                    };
+                }
+            }
+            Mutator::CopyFrom(key, source) => {
+                // What's required of the key after the copy was required of the
+                // source before it
+                let copied: Vec<Compare<D>> = regressed
+                    .iter()
+                    .filter(|(k, _)| k == key)
+                    .map(|(_, compare)| compare.clone())
+                    .collect();
+                // Copying a missing source leaves the key as it was, so the copy
+                // can't make it absent
+                if copied.contains(&Compare::Absent) {
+                    return None;
+                }
+                regressed.retain(|(k, _)| k != key);
+                for compare in copied {
+                    if !add_requirement(&mut regressed, source, compare) {
+                        return None;
+                    }
+                }
+            }
+            // Nothing but being absent is met by a key that isn't there
+            Mutator::Unset(key) => {
+                if regressed
+                    .iter()
+                    .any(|(k, c)| k == key && *c != Compare::Absent)
+                {
+                    return None;
+                }
+                regressed.retain(|(k, _)| k != key);
+            }
+            // Undoing these would need rounding and flipping comparisons for negative
+            // factors, or knowing which members a set had before, so the search
+            // never plans through them backwards
+            Mutator::Multiply(key, _)
+            | Mutator::Divide(key, _)
+            | Mutator::Insert(key, _)
+            | Mutator::Remove(key, _) => {
+                if regressed.iter().any(|(k, _)| k == key) {
+                    return None;
+                }
+            }
+        }
+    }
+
+    for (key, compare) in &action.preconditions {
+        if !add_requirement(&mut regressed, key, compare.clone()) {
+            return None;
+        }
+    }
+    Some(regressed)
+}
+
+/// What has to hold right before `action` for the requirements of `goal` to hold
+/// right after it, the partial state the backward search steps back to. There's
+/// one for each effect of the action that helps with the goal, and none if it
+/// doesn't help or makes the goal impossible.
+///
+/// Keys come in order, and a key can show up more than once, like `>= 3` together
+/// with `<= 10`. Only [`Goal::requirements`] are looked at, not its alternatives,
+/// and dynamic preconditions are left out as they depend on the state.
+pub fn regressed_preconditions<K: Key, D: DatumLike>(
+    goal: &Goal<K, D>,
+    action: &Action<K, D>,
+) -> Vec<Vec<(K, Compare<D>)>> {
+    let Some(requirements) = goal_requirements(goal) else {
+        return vec![];
+    };
+    action
+        .effects
+        .iter()
+        .filter_map(|effect| regress(&requirements, action, effect))
+        .collect()
+}
+
+fn requirement_met<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    key: &K,
+    compare: &Compare<D>,
+) -> bool {
+    compare_entry(compare, state.data.get(key))
+}
+
+/// Sum of how far `start` is from each unmet requirement, in units of
+/// `1 / unit`, see [`PlannerConfig::distance_unit`]
+pub(crate) fn requirements_distance<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    requirements: &Requirements<K, D>,
+    unit: u64,
+) -> usize {
+    requirements
+        .iter()
+        .filter(|(key, compare)| !requirement_met(start, key, compare))
+        .map(|(key, compare)| match start.data.get(key) {
+            Some(value) if *compare != Compare::Absent => {
+                value.scaled_distance(&compare.value(), unit).max(unit) as usize
+            }
+            _ => unit as usize,
+        })
+        .fold(0, usize::saturating_add)
+}
+
+/// What the [`Goal`] requires, or `None` if its requirements contradict each other
+pub(crate) fn goal_requirements<K: Key, D: DatumLike>(
+    goal: &Goal<K, D>,
+) -> Option<Requirements<K, D>> {
+    let mut requirements = Requirements::new();
+    for (key, compare) in &goal.requirements {
+        if !add_requirement(&mut requirements, key, compare.clone()) {
+            return None;
+        }
+    }
+    Some(requirements)
+}
+
+/// Whether every one of `requirements` holds in `state`
+pub(crate) fn requirements_met<K: Key, D: DatumLike>(
+    state: &LocalState<K, D>,
+    requirements: &Requirements<K, D>,
+) -> bool {
+    requirements
+        .iter()
+        .all(|(key, compare)| requirement_met(state, key, compare))
+}
+
+/// Which effect of which action a step of the backward search undid, `None` for
+/// the goal it started from
+pub(crate) type BackwardStep = Option<(usize, usize)>;
+
+/// Puts what `requirements` regress to through every effect of every action in
+/// `out`, with how far each is from `start`
+pub(crate) fn backward_successors<K: Key, D: DatumLike>(
+    requirements: &Requirements<K, D>,
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    unit: u64,
+    out: &mut Vec<search::Successor<Requirements<K, D>, BackwardStep>>,
+) {
+    let regressed = actions
+        .iter()
+        .enumerate()
+        .flat_map(|(index, action)| (0..action.effects.len()).map(move |effect| (index, effect)))
+        .filter_map(|(index, effect)| {
+            let action = &actions[index];
+            let regressed = regress(requirements, action, &action.effects[effect])?;
+            let h = requirements_distance(start, &regressed, unit);
+            Some((
+                regressed,
+                Some((index, effect)),
+                action.effects[effect].discounted_cost(),
+                h,
+            ))
+        });
+    out.extend(regressed);
+}
+
+/// Searches from the [`Goal`] back towards `start` by regressing the requirements
+/// through the actions, then replays the actions forwards from `start` so the
+/// returned plan looks exactly like one from the forward search.
+///
+/// Returns `None` both when there's no plan and when the plan found backwards
+/// doesn't hold up when replayed (for example because of dynamic preconditions),
+/// so callers can fall back to searching forwards.
+pub(crate) fn plan_backward<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+    stats: &mut PlanStats,
+) -> Option<Plan<K, D>> {
+    let mut poll = interrupt.poll();
+    let unit = config.distance_unit();
+    let goal_requirements = goal_requirements(goal)?;
+
+    let (path, _) = search::astar(
+        &goal_requirements,
+        None,
+        requirements_distance(start, &goal_requirements, unit),
+        config.max_cost,
+        config.tie_seed,
+        stats,
+        || poll.is_due(),
+        |requirements, _h, out| {
+            backward_successors(requirements, start, actions, unit, out);
+        },
+        |requirements| requirements_met(start, requirements),
+        |_| {},
+    )?;
+
+    replay(
+        vec![Node::State(start.clone())],
+        &path,
+        actions,
+        goal,
+        config,
+        stats,
+    )
+}
+
+/// Carries on from the last of `nodes` with the actions along a `path` of the
+/// backward search, which goes from the goal back to where `nodes` ends, so the
+/// last action it found is the first one to execute. Returns `None` if an action
+/// can't be taken after all, or the goal isn't met in the end
+pub(crate) fn replay<K: Key, D: DatumLike>(
+    mut nodes: Vec<Node<K, D>>,
+    path: &[(Requirements<K, D>, BackwardStep)],
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    stats: &mut PlanStats,
+) -> Option<Plan<K, D>> {
+    let missing_keys = config.missing_keys;
+    for (_, step) in path.iter().rev() {
+        let Some((index, effect)) = step else {
+            continue;
+        };
+        let action = &actions[*index];
+        let state = nodes.last().unwrap().state();
+        if !preconditions_met(state, action, missing_keys, &ComputedKeys::default()) {
+            return None;
+        }
+        let effect = apply_action(
+            state,
+            action,
+            &action.effects[*effect],
+            missing_keys.unwrap_or_default(),
+        )?;
+        nodes.push(Node::Effect(effect));
+    }
+
+    if is_goal(nodes.last().unwrap(), goal, &ComputedKeys::default()) {
+        // The search cost is after rewards, the plan reports what the actions cost
+        let (cost, reward) = plan_totals(&nodes);
+        stats.reward = reward;
+        stats.duration = plan_duration(&nodes);
+        Some((nodes, cost))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,716 @@
+// crate "dogoap" file search.rs
+use alloc::collections::BinaryHeap;
+use alloc::{vec, vec::Vec};
+use core::cmp::{Ordering, Reverse};
+use core::hash::{BuildHasher, Hash};
+
+use foldhash::fast::FixedState;
+use indexmap::map::Entry::{Occupied, Vacant};
+
+use crate::{planner::PlanStats, trace::PrunedBy};
+
+/// Heuristic values passed to [`astar`] are in units of `1 / HEURISTIC_SCALE` of a
+/// cost, so gaps of less than a unit still tell nodes apart
+pub(crate) const HEURISTIC_SCALE: u64 = 1000;
+
+/// A* search, behaving like `pathfinding::directed::astar::astar`, with a few
+/// additions the planner needs:
+///
+/// - `successors` gets the heuristic value of the node being expanded and pushes
+///   the heuristic value of every successor together with its cost onto the
+///   `Vec` it's given, which is reused between expansions, so heuristics
+///   can be computed incrementally from the parent. Heuristic values are
+///   fixed-point, in units of `1 / HEURISTIC_SCALE`, costs are not.
+/// - Every successor carries an edge label `E` (what got us there), returned next
+///   to each node of the path. The label of the start node is `start_edge`.
+/// - Expanded and generated nodes are counted into `stats`.
+/// - `interrupted` is asked before every expansion. Once it says so, the search
+///   gives up right away as if there was no path, without expanding that node.
+/// - Costs saturate at `usize::MAX` instead of overflowing, so an edge costing that
+///   much is as expensive as it gets rather than wrapping around to cheap.
+/// - Successors costing more than `max_cost` to reach are dropped, and counted
+///   into `stats` together with the cheapest of them that satisfies `success`.
+/// - With a `tie_seed`, nodes that are estimated to cost the same and have come
+///   equally far are expanded in an order shuffled by the seed, instead of the
+///   order they were pushed in. Equally cheap ways to a node that's yet to be
+///   expanded are picked between by the seed too, instead of keeping the first.
+///   Only the order changes, never the costs.
+/// - Without one, ties go to the node pushed first, and successors are pushed in
+///   the order `successors` gives them. Nodes are kept in an
+///   [`indexmap::IndexMap`] with a fixed hasher, so nothing depends on a random
+///   seed or the platform, and the same search always expands the same nodes and
+///   finds the same path.
+/// - Every decision is passed to `observe`, for tracing the search. Nodes are
+///   numbered in the order they're first reached.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn astar<N, E, FI, FN, FS, FO>(
+    start: &N,
+    start_edge: E,
+    start_heuristic: usize,
+    max_cost: Option<usize>,
+    tie_seed: Option<u64>,
+    stats: &mut PlanStats,
+    interrupted: FI,
+    successors: FN,
+    success: FS,
+    observe: FO,
+) -> Option<(Vec<(N, E)>, usize)>
+where
+    N: Eq + Hash + Clone,
+    E: Clone,
+    FI: FnMut() -> bool,
+    FN: FnMut(&N, usize, &mut Vec<Successor<N, E>>),
+    FS: FnMut(&N) -> bool,
+    FO: FnMut(SearchEvent<N>),
+{
+    astar_closest(
+        start,
+        start_edge,
+        start_heuristic,
+        max_cost,
+        tie_seed,
+        stats,
+        interrupted,
+        successors,
+        success,
+        observe,
+    )
+    .ok()
+}
+
+/// The nodes along a path, each with the edge that led to it, and the cost of it
+type Path<N, E> = (Vec<(N, E)>, usize);
+
+/// A node reached from the one being expanded, the edge that led to it, what it
+/// cost to get there and its heuristic
+pub(crate) type Successor<N, E> = (N, E, usize, usize);
+
+/// [`astar`], but when there's no path to a node that satisfies `success`, fails
+/// with the path to the expanded node with the lowest heuristic instead, the
+/// cheapest one of those if there's a tie. That's `start` if no node looked any
+/// closer
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn astar_closest<N, E, FI, FN, FS, FO>(
+    start: &N,
+    start_edge: E,
+    start_heuristic: usize,
+    max_cost: Option<usize>,
+    tie_seed: Option<u64>,
+    stats: &mut PlanStats,
+    interrupted: FI,
+    successors: FN,
+    success: FS,
+    observe: FO,
+) -> Result<Path<N, E>, Path<N, E>>
+where
+    N: Eq + Hash + Clone,
+    E: Clone,
+    FI: FnMut() -> bool,
+    FN: FnMut(&N, usize, &mut Vec<Successor<N, E>>),
+    FS: FnMut(&N) -> bool,
+    FO: FnMut(SearchEvent<N>),
+{
+    Astar::new(start, start_edge, start_heuristic)
+        .resume(
+            usize::MAX,
+            max_cost,
+            tie_seed,
+            stats,
+            interrupted,
+            successors,
+            success,
+            observe,
+        )
+        .unwrap() // Cannot fail, it's never out of expansions
+}
+
+/// An [`astar_closest`] search that can stop after a number of expansions and carry
+/// on where it left off, expanding the same nodes in the same order as it would
+/// have in one go
+pub(crate) struct Astar<N, E> {
+    to_see: BinaryHeap<SmallestCostHolder>,
+    parents: Parents<N, E>,
+    /// The heuristic and cost of the closest node so far, and where it is
+    closest: (usize, usize, usize),
+    /// Where `successors` puts the successors of the node being expanded, kept
+    /// around so expanding doesn't allocate every time
+    scratch: Vec<Successor<N, E>>,
+    /// How many entries have been pushed onto `to_see`, numbering them in order
+    pushed: usize,
+}
+
+impl<N: Eq + Hash + Clone, E: Clone> Astar<N, E> {
+    pub(crate) fn new(start: &N, start_edge: E, start_heuristic: usize) -> Self {
+        let mut to_see = BinaryHeap::new();
+        to_see.push(SmallestCostHolder {
+            estimated_cost: 0u128,
+            cost: 0,
+            tie: 0,
+            pushed: 0,
+            index: 0,
+        });
+        let mut parents: Parents<N, E> = Parents::default();
+        parents.insert(
+            start.clone(),
+            Parent {
+                index: usize::MAX,
+                edge: start_edge,
+                cost: 0,
+                heuristic: start_heuristic,
+                tie: 0,
+                expanded: false,
+            },
+        );
+        Self {
+            to_see,
+            parents,
+            closest: (start_heuristic, 0, 0),
+            scratch: Vec::new(),
+            pushed: 1,
+        }
+    }
+
+    /// Searches on like [`astar_closest`], but gives up for now with `None` once it
+    /// expanded `max_expansions` nodes. Call it again with the same arguments to
+    /// carry on
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn resume<FI, FN, FS, FO>(
+        &mut self,
+        max_expansions: usize,
+        max_cost: Option<usize>,
+        tie_seed: Option<u64>,
+        stats: &mut PlanStats,
+        mut interrupted: FI,
+        mut successors: FN,
+        mut success: FS,
+        mut observe: FO,
+    ) -> Option<Result<Path<N, E>, Path<N, E>>>
+    where
+        FI: FnMut() -> bool,
+        FN: FnMut(&N, usize, &mut Vec<Successor<N, E>>),
+        FS: FnMut(&N) -> bool,
+        FO: FnMut(SearchEvent<N>),
+    {
+        let Self {
+            to_see,
+            parents,
+            closest,
+            scratch,
+            pushed,
+        } = self;
+        let mut expanded = 0;
+        loop {
+            if expanded == max_expansions {
+                return None;
+            }
+            let Some(SmallestCostHolder { cost, index, .. }) = to_see.pop() else {
+                break;
+            };
+            {
+                let (node, parent) = parents.get_index_mut(index).unwrap(); // Cannot fail
+                if success(node) {
+                    observe(SearchEvent::GoalReached { node: index, cost });
+                    return Some(Ok((reverse_path(parents, index), cost)));
+                }
+                // We may have pushed a node several times if we found a cheaper way
+                // to it, so skip the outdated entries
+                if cost > parent.cost {
+                    continue;
+                }
+                if interrupted() {
+                    break;
+                }
+                stats.expanded_nodes += 1;
+                expanded += 1;
+                parent.expanded = true;
+                if (parent.heuristic, cost) < (closest.0, closest.1) {
+                    *closest = (parent.heuristic, cost, index);
+                }
+                observe(SearchEvent::Expanded {
+                    node: index,
+                    expanded: node,
+                    cost,
+                    heuristic: parent.heuristic,
+                });
+                successors(node, parent.heuristic, scratch);
+            }
+
+            for (position, (successor, edge, move_cost, h)) in scratch.drain(..).enumerate() {
+                stats.generated_nodes += 1;
+                let new_cost = cost.saturating_add(move_cost);
+                if max_cost.is_some_and(|max_cost| new_cost > max_cost) {
+                    stats.pruned_nodes += 1;
+                    if success(&successor) {
+                        stats.cheapest_over_budget = Some(
+                            stats
+                                .cheapest_over_budget
+                                .map_or(new_cost, |cheapest| cheapest.min(new_cost)),
+                        );
+                    }
+                    observe(SearchEvent::Pruned {
+                        parent: index,
+                        successor: &successor,
+                        by: PrunedBy::Limit,
+                    });
+                    continue;
+                }
+                let tie = tie_break(tie_seed, &successor);
+                let new_parent = Parent {
+                    index,
+                    edge,
+                    cost: new_cost,
+                    heuristic: h,
+                    tie: tie_break(tie_seed, &(index, position)),
+                    expanded: false,
+                };
+                let n = match parents.entry(successor) {
+                    Vacant(e) => {
+                        let n = e.index();
+                        e.insert(new_parent);
+                        n
+                    }
+                    Occupied(mut e) => {
+                        let known = e.get();
+                        if known.cost > new_cost {
+                            let n = e.index();
+                            e.insert(new_parent);
+                            n
+                        } else if known.cost == new_cost
+                            && !known.expanded
+                            && known.tie > new_parent.tie
+                        {
+                            // The node is still waiting to be expanded at this cost, so
+                            // swapping the way there doesn't need it pushed again
+                            let n = e.index();
+                            e.insert(new_parent);
+                            let (node, _) = parents.get_index(n).unwrap();
+                            observe(SearchEvent::Generated {
+                                parent: index,
+                                node: n,
+                                successor: node,
+                                cost: new_cost,
+                            });
+                            continue;
+                        } else {
+                            observe(SearchEvent::Pruned {
+                                parent: index,
+                                successor: e.key(),
+                                by: PrunedBy::Duplicate,
+                            });
+                            continue;
+                        }
+                    }
+                };
+                let (node, _) = parents.get_index(n).unwrap();
+                observe(SearchEvent::Generated {
+                    parent: index,
+                    node: n,
+                    successor: node,
+                    cost: new_cost,
+                });
+
+                to_see.push(SmallestCostHolder {
+                    estimated_cost: new_cost as u128 * HEURISTIC_SCALE as u128 + h as u128,
+                    cost: new_cost,
+                    tie,
+                    pushed: *pushed,
+                    index: n,
+                });
+                *pushed += 1;
+            }
+        }
+        // A cheaper way to the closest node may have come up since
+        let (_, parent) = parents.get_index(closest.2).unwrap();
+        Some(Err((reverse_path(parents, closest.2), parent.cost)))
+    }
+
+    /// The cheapest path found so far to `node`, if it has been reached at all
+    pub(crate) fn path_to(&self, node: &N) -> Option<Path<N, E>> {
+        let (index, _, parent) = self.parents.get_full(node)?;
+        Some((reverse_path(&self.parents, index), parent.cost))
+    }
+}
+
+/// A decision of [`astar`], numbering nodes in the order they're first reached
+pub(crate) enum SearchEvent<'s, N> {
+    Expanded {
+        node: usize,
+        expanded: &'s N,
+        cost: usize,
+        heuristic: usize,
+    },
+    /// `successor` was reached, or reached more cheaply
+    Generated {
+        parent: usize,
+        node: usize,
+        successor: &'s N,
+        cost: usize,
+    },
+    Pruned {
+        parent: usize,
+        successor: &'s N,
+        by: PrunedBy,
+    },
+    GoalReached {
+        node: usize,
+        cost: usize,
+    },
+}
+
+/// Where `value` goes among equals with `tie_seed`, 0 for all of them without one
+fn tie_break<T: Hash>(tie_seed: Option<u64>, value: &T) -> u64 {
+    tie_seed.map_or(0, |seed| FixedState::with_seed(seed).hash_one(value))
+}
+
+/// Bounded-depth Bellman-Ford, for when edges can cost less than nothing and A*
+/// could go around a paying cycle forever. Works through the paths of up to
+/// `max_depth` edges one layer at a time, and returns the cheapest that ends in a
+/// node satisfying `success`, preferring the shortest on ties. Paths end at the
+/// first node satisfying `success`, they never go through one.
+///
+/// Reaching a node again is only followed up on when it's cheaper than every
+/// earlier way there. The earlier ways had no more edges, so anything the new one
+/// leads to they already led to for less.
+pub(crate) fn bounded_bellman_ford<N, E, FN, IN, FS>(
+    start: &N,
+    start_edge: E,
+    max_depth: usize,
+    stats: &mut PlanStats,
+    mut successors: FN,
+    mut success: FS,
+) -> Option<(Vec<(N, E)>, i64)>
+where
+    N: Eq + Hash + Clone,
+    E: Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, E, i64)>,
+    FS: FnMut(&N) -> bool,
+{
+    let mut steps: Vec<Step<N, E, i64>> = vec![Step {
+        node: start.clone(),
+        edge: start_edge,
+        parent: usize::MAX,
+        cost: 0,
+    }];
+    if success(start) {
+        return Some((step_path(&steps, 0), 0));
+    }
+    let mut cheapest: indexmap::IndexMap<N, i64, FixedState> = indexmap::IndexMap::default();
+    cheapest.insert(start.clone(), 0);
+    let mut best: Option<usize> = None;
+    let mut layer = vec![0];
+
+    for _ in 0..max_depth {
+        // Indexed by node, so a cheaper way found later in the same layer replaces
+        // the one before it
+        let mut next: indexmap::IndexMap<N, usize, FixedState> = indexmap::IndexMap::default();
+        for index in layer {
+            stats.expanded_nodes += 1;
+            let cost = steps[index].cost;
+            for (successor, edge, move_cost) in successors(&steps[index].node) {
+                stats.generated_nodes += 1;
+                let new_cost = cost.saturating_add(move_cost);
+                if cheapest
+                    .get(&successor)
+                    .is_some_and(|known| *known <= new_cost)
+                {
+                    continue;
+                }
+                cheapest.insert(successor.clone(), new_cost);
+                let reached_goal = success(&successor);
+                steps.push(Step {
+                    node: successor.clone(),
+                    edge,
+                    parent: index,
+                    cost: new_cost,
+                });
+                let n = steps.len() - 1;
+                if !reached_goal {
+                    next.insert(successor, n);
+                } else if best.is_none_or(|best| steps[best].cost > new_cost) {
+                    best = Some(n);
+                }
+            }
+        }
+        layer = next.into_values().collect();
+        if layer.is_empty() {
+            break;
+        }
+    }
+    best.map(|best| (step_path(&steps, best), steps[best].cost))
+}
+
+/// Uniform-cost search through every path within a budget, for when there's no
+/// goal to stop at. Returns the path to the node `score` rates highest, the
+/// cheapest one on ties. Nodes `score` rates `None` can still be passed through.
+///
+/// Paths costing more than `max_cost` or going over `max_depth` edges are
+/// dropped. A node is only reached again when the new way there beats every
+/// earlier one on cost or, with a `max_depth`, on edges.
+pub(crate) fn best_within<N, E, S, FN, IN, FS>(
+    start: &N,
+    start_edge: E,
+    max_cost: Option<usize>,
+    max_depth: Option<usize>,
+    stats: &mut PlanStats,
+    mut successors: FN,
+    mut score: FS,
+) -> Option<(Vec<(N, E)>, usize)>
+where
+    N: Eq + Hash + Clone,
+    E: Clone,
+    S: PartialOrd,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, E, usize)>,
+    FS: FnMut(&N) -> Option<S>,
+{
+    let mut steps: Vec<Step<N, E, usize>> = vec![Step {
+        node: start.clone(),
+        edge: start_edge,
+        parent: usize::MAX,
+        cost: 0,
+    }];
+    // The cost and edges of every way we kept to each node. Without a `max_depth`
+    // the edges don't matter and are left at 0
+    let mut reached: indexmap::IndexMap<N, Vec<(usize, usize)>, FixedState> =
+        indexmap::IndexMap::default();
+    reached.insert(start.clone(), vec![(0, 0)]);
+    let mut to_see: BinaryHeap<Reverse<(usize, usize, usize)>> = BinaryHeap::new();
+    to_see.push(Reverse((0, 0, 0)));
+    let mut best: Option<(S, usize)> = None;
+
+    while let Some(Reverse((cost, depth, index))) = to_see.pop() {
+        // Popped in order of cost, so anything scoring the same later costs more
+        if let Some(new_score) = score(&steps[index].node) {
+            if best.as_ref().is_none_or(|(score, _)| new_score > *score) {
+                best = Some((new_score, index));
+            }
+        }
+        if max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            continue;
+        }
+        stats.expanded_nodes += 1;
+        for (successor, edge, move_cost) in successors(&steps[index].node) {
+            stats.generated_nodes += 1;
+            let new_cost = cost.saturating_add(move_cost);
+            if max_cost.is_some_and(|max_cost| new_cost > max_cost) {
+                continue;
+            }
+            let new_depth = if max_depth.is_some() { depth + 1 } else { 0 };
+            let ways = reached.entry(successor.clone()).or_default();
+            if ways
+                .iter()
+                .any(|(cost, depth)| *cost <= new_cost && *depth <= new_depth)
+            {
+                continue;
+            }
+            ways.push((new_cost, new_depth));
+            steps.push(Step {
+                node: successor,
+                edge,
+                parent: index,
+                cost: new_cost,
+            });
+            to_see.push(Reverse((new_cost, new_depth, steps.len() - 1)));
+        }
+    }
+    best.map(|(_, best)| (step_path(&steps, best), steps[best].cost))
+}
+
+/// Best-first search through paths rather than nodes, for the `k` cheapest paths
+/// to a node satisfying `success` instead of just the one. A path never goes
+/// through the same node twice, by `same`, so a graph only has so many of them.
+/// Every path found is passed to `keep`, and only the ones it keeps count towards
+/// `k`. Returns them cheapest first.
+///
+/// Paths end at the first node satisfying `success`, they never go through one.
+/// Heuristic values are like [`astar`]'s. One that overestimates can find a path
+/// only after a more expensive one, which can leave it out of the `k`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn k_best<N, E, FN, IN, FS, FM, FK>(
+    start: &N,
+    start_edge: E,
+    start_heuristic: usize,
+    k: usize,
+    stats: &mut PlanStats,
+    mut successors: FN,
+    mut success: FS,
+    same: FM,
+    mut keep: FK,
+) -> Vec<Path<N, E>>
+where
+    N: Clone,
+    E: Clone,
+    FN: FnMut(&N, usize) -> IN,
+    IN: IntoIterator<Item = (N, E, usize, usize)>,
+    FS: FnMut(&N) -> bool,
+    FM: Fn(&N, &N) -> bool,
+    FK: FnMut(&[(N, E)]) -> bool,
+{
+    let mut steps: Vec<Step<N, E, usize>> = vec![Step {
+        node: start.clone(),
+        edge: start_edge,
+        parent: usize::MAX,
+        cost: 0,
+    }];
+    let mut heuristics = vec![start_heuristic];
+    let mut to_see = BinaryHeap::new();
+    to_see.push(SmallestCostHolder {
+        estimated_cost: 0,
+        cost: 0,
+        tie: 0,
+        pushed: 0,
+        index: 0,
+    });
+    let mut found = vec![];
+
+    while let Some(SmallestCostHolder { cost, index, .. }) = to_see.pop() {
+        if found.len() >= k {
+            break;
+        }
+        if success(&steps[index].node) {
+            let path = step_path(&steps, index);
+            if keep(&path) {
+                found.push((path, cost));
+            }
+            continue;
+        }
+        stats.expanded_nodes += 1;
+        for (successor, edge, move_cost, h) in successors(&steps[index].node, heuristics[index]) {
+            stats.generated_nodes += 1;
+            if goes_through(&steps, index, &successor, &same) {
+                stats.pruned_nodes += 1;
+                continue;
+            }
+            let new_cost = cost.saturating_add(move_cost);
+            steps.push(Step {
+                node: successor,
+                edge,
+                parent: index,
+                cost: new_cost,
+            });
+            heuristics.push(h);
+            to_see.push(SmallestCostHolder {
+                estimated_cost: new_cost as u128 * HEURISTIC_SCALE as u128 + h as u128,
+                cost: new_cost,
+                tie: 0,
+                pushed: steps.len() - 1,
+                index: steps.len() - 1,
+            });
+        }
+    }
+    found.sort_by_key(|(_, cost)| *cost);
+    found
+}
+
+/// One edge of a path kept by [`bounded_bellman_ford`], [`best_within`] or
+/// [`k_best`]
+struct Step<N, E, C> {
+    node: N,
+    edge: E,
+    parent: usize,
+    cost: C,
+}
+
+/// Whether the path kept in `steps` up to `end` goes through a node `same` as `node`
+fn goes_through<N, E, C>(
+    steps: &[Step<N, E, C>],
+    end: usize,
+    node: &N,
+    same: impl Fn(&N, &N) -> bool,
+) -> bool {
+    let mut i = end;
+    while let Some(step) = steps.get(i) {
+        if same(&step.node, node) {
+            return true;
+        }
+        i = step.parent;
+    }
+    false
+}
+
+fn step_path<N: Clone, E: Clone, C>(steps: &[Step<N, E, C>], end: usize) -> Vec<(N, E)> {
+    let mut path = vec![];
+    let mut i = end;
+    while let Some(step) = steps.get(i) {
+        path.push((step.node.clone(), step.edge.clone()));
+        i = step.parent;
+    }
+    path.reverse();
+    path
+}
+
+/// Every node seen so far, in the order they were first seen. The fixed hasher
+/// doesn't need std to seed it, and the order doesn't depend on it anyway
+type Parents<N, E> = indexmap::IndexMap<N, Parent<E>, FixedState>;
+
+/// How we got to a node during the search
+struct Parent<E> {
+    index: usize,
+    edge: E,
+    cost: usize,
+    heuristic: usize,
+    /// Decides between equally cheap ways to the node, 0 unless seeded
+    tie: u64,
+    /// Whether the node has been expanded since it was last reached more cheaply
+    expanded: bool,
+}
+
+fn reverse_path<N, E>(parents: &Parents<N, E>, start: usize) -> Vec<(N, E)>
+where
+    N: Eq + Hash + Clone,
+    E: Clone,
+{
+    let mut path = vec![];
+    let mut i = start;
+    while let Some((node, parent)) = parents.get_index(i) {
+        path.push((node.clone(), parent.edge.clone()));
+        i = parent.index;
+    }
+    path.reverse();
+    path
+}
+
+struct SmallestCostHolder {
+    /// Cost so far plus the heuristic, both in units of `1 / HEURISTIC_SCALE`
+    estimated_cost: u128,
+    cost: usize,
+    /// Decides between nodes that are otherwise the same, 0 unless seeded
+    tie: u64,
+    /// When it was pushed, the earliest of otherwise equal entries goes first
+    pushed: usize,
+    index: usize,
+}
+
+impl PartialEq for SmallestCostHolder {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimated_cost == other.estimated_cost
+            && self.cost == other.cost
+            && self.tie == other.tie
+            && self.pushed == other.pushed
+    }
+}
+
+impl Eq for SmallestCostHolder {}
+
+impl PartialOrd for SmallestCostHolder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallestCostHolder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // BinaryHeap is a max-heap, so reverse the estimate to pop the cheapest
+        // first, and on equal estimates prefer the node furthest along, then the
+        // one pushed first
+        match other.estimated_cost.cmp(&self.estimated_cost) {
+            Ordering::Equal => self
+                .cost
+                .cmp(&other.cost)
+                .then(self.tie.cmp(&other.tie))
+                .then(other.pushed.cmp(&self.pushed)),
+            s => s,
+        }
+    }
+}
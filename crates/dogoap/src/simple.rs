@@ -1,3 +1,6 @@
+use alloc::string::ToString;
+use alloc::{vec, vec::Vec};
+
 use crate::prelude::*;
 
 pub fn simple_action<T>(name: &str, key_to_mutate: &str, from_value: T) -> Action
@@ -11,55 +14,54 @@ pub fn simple_multi_mutate_action<T>(name: &str, muts: Vec<(&str, T)>) -> Action
 where
     Datum: From<T>,
 {
-    let mut mutators = vec![];
+    let mut effect = Effect::new(name);
 
     for m in muts {
-        mutators.push(Mutator::Set(m.0.to_string(), m.1.into()));
+        effect = effect.with_mutator(Mutator::Set(m.0.to_string(), m.1.into()));
     }
 
-    Action {
-        key: name.to_string(),
-        preconditions: vec![],
-        dynamic_preconditions: vec![],
-        effects: vec![Effect {
-            action: name.to_string(),
-            mutators,
-            state: LocalState::new(),
-            cost: 1,
-        }],
-    }
+    Action::new(name).with_effect(effect)
 }
 
 pub fn simple_increment_action<T>(name: &str, key_to_mutate: &str, from_value: T) -> Action
 where
     Datum: From<T>,
 {
-    let mut action = simple_multi_mutate_action(name, vec![]);
-    action.effects = vec![Effect {
-        action: name.to_string(),
-        mutators: vec![Mutator::Increment(
-            key_to_mutate.to_string(),
-            from_value.into(),
-        )],
-        state: LocalState::new(),
-        cost: 1,
-    }];
-    action
+    Action::new(name).with_effect(Effect::new(name).with_mutator(Mutator::Increment(
+        key_to_mutate.to_string(),
+        from_value.into(),
+    )))
 }
 
 pub fn simple_decrement_action<T>(name: &str, key_to_mutate: &str, from_value: T) -> Action
 where
     Datum: From<T>,
 {
-    let mut action = simple_multi_mutate_action(name, vec![]);
-    action.effects = vec![Effect {
-        action: name.to_string(),
-        mutators: vec![Mutator::Decrement(
-            key_to_mutate.to_string(),
-            from_value.into(),
-        )],
-        state: LocalState::new(),
-        cost: 1,
-    }];
-    action
+    Action::new(name).with_effect(Effect::new(name).with_mutator(Mutator::Decrement(
+        key_to_mutate.to_string(),
+        from_value.into(),
+    )))
+}
+
+pub fn simple_toggle_action(name: &str, key_to_mutate: &str) -> Action {
+    Action::new(name)
+        .with_effect(Effect::new(name).with_mutator(Mutator::Toggle(key_to_mutate.to_string())))
+}
+
+pub fn simple_multiply_action<T>(name: &str, key_to_mutate: &str, factor: T) -> Action
+where
+    Datum: From<T>,
+{
+    Action::new(name).with_effect(
+        Effect::new(name).with_mutator(Mutator::Multiply(key_to_mutate.to_string(), factor.into())),
+    )
+}
+
+pub fn simple_divide_action<T>(name: &str, key_to_mutate: &str, divisor: T) -> Action
+where
+    Datum: From<T>,
+{
+    Action::new(name).with_effect(
+        Effect::new(name).with_mutator(Mutator::Divide(key_to_mutate.to_string(), divisor.into())),
+    )
 }
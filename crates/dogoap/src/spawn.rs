@@ -0,0 +1,128 @@
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::{
+    action::Action,
+    cancel::CancellationToken,
+    compare::Compare,
+    datum::{Datum, DatumLike},
+    effect::Effect,
+    error::PlanError,
+    goal::Goal,
+    localstate::{Key, LocalState},
+    mutator::Mutator,
+    planner::{
+        check_keys_exist, search_plan_or_cancelled, validate_actions, Interrupt, Node, Plan,
+        PlannerConfig,
+    },
+};
+
+// Everything that goes into or comes out of a search can be sent to and shared
+// between threads
+const _: () = {
+    const fn send_sync<T: Send + Sync>() {}
+    send_sync::<Action>();
+    send_sync::<Compare>();
+    send_sync::<Datum>();
+    send_sync::<Effect>();
+    send_sync::<Goal>();
+    send_sync::<LocalState>();
+    send_sync::<Mutator>();
+    send_sync::<Node>();
+    send_sync::<PlannerConfig>();
+    send_sync::<PlanError>();
+    send_sync::<Arc<[Action]>>();
+};
+
+/// Plans on a thread of its own, so the calling thread can get on with other
+/// things. Everything the search needs is moved into the thread, and `actions`
+/// can be shared with other searches without cloning them.
+///
+/// The [`PlannerConfig::time_budget`] starts counting once the thread starts. The
+/// search can be cancelled through the handle, or through the
+/// [`PlannerConfig::cancellation`] token if the config has one.
+pub fn spawn_plan<K: Key + Send + Sync + 'static, D: DatumLike + Send + Sync + 'static>(
+    start: LocalState<K, D>,
+    actions: Arc<[Action<K, D>]>,
+    goal: Goal<K, D>,
+    mut config: PlannerConfig<K, D>,
+) -> PlanHandle<K, D> {
+    let cancellation = config
+        .cancellation
+        .get_or_insert_with(CancellationToken::new)
+        .clone();
+    let (sender, receiver) = mpsc::channel();
+    let thread = thread::spawn(move || {
+        let result = validate_actions(&actions, &config).and_then(|actions| {
+            check_keys_exist(&start, &actions, &goal, &config)?;
+            let interrupt = Interrupt::from_config(&config);
+            search_plan_or_cancelled(&start, &actions, &goal, &config, interrupt)
+        });
+        // Nobody is waiting for the plan anymore if the handle was dropped
+        let _ = sender.send(result);
+    });
+    PlanHandle {
+        receiver,
+        thread: Some(thread),
+        cancellation,
+    }
+}
+
+/// What planning on another thread comes back with
+type Outcome<K, D> = Result<Option<Plan<K, D>>, PlanError<K>>;
+
+/// A plan being made on another thread, see [`spawn_plan`]. Dropping the handle
+/// doesn't stop the search, call [`PlanHandle::cancel`] first for that.
+pub struct PlanHandle<K = String, D = Datum> {
+    receiver: Receiver<Outcome<K, D>>,
+    thread: Option<JoinHandle<()>>,
+    cancellation: CancellationToken,
+}
+
+impl<K, D> PlanHandle<K, D> {
+    /// The outcome of planning if it's done, without blocking. Once it has been
+    /// returned, later calls return `None` again.
+    pub fn try_recv(&mut self) -> Option<Outcome<K, D>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.rethrow_panic();
+                None
+            }
+        }
+    }
+
+    /// Blocks until planning is done and returns its outcome
+    ///
+    /// # Panics
+    ///
+    /// If planning panicked, or the outcome was already returned by
+    /// [`PlanHandle::try_recv`]
+    pub fn wait(mut self) -> Outcome<K, D> {
+        match self.receiver.recv() {
+            Ok(result) => result,
+            Err(_) => {
+                self.rethrow_panic();
+                panic!("The plan was already received with PlanHandle::try_recv");
+            }
+        }
+    }
+
+    /// Asks the search to give up. It stops the next time it checks, and fails
+    /// with [`PlanError::Cancelled`] unless it already found a plan.
+    pub fn cancel(&self) {
+        self.cancellation.cancel();
+    }
+
+    /// The thread only hangs up without sending anything when planning panicked,
+    /// in which case the panic continues on this thread
+    fn rethrow_panic(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            if let Err(panic) = thread.join() {
+                std::panic::resume_unwind(panic);
+            }
+        }
+    }
+}
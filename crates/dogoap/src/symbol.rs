@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, PoisonError, RwLock};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A key that's a number standing in for a name, so it's as quick to copy, compare
+/// and hash as a `u32` but still prints as the name. Use it as the `K` of a
+/// [`LocalState`](crate::localstate::LocalState) and everything else, and build
+/// things from `&str` like you would with `String` keys:
+/// `LocalState::<Symbol>::new().with_datum("gold", Datum::I64(0))`.
+///
+/// The names are kept for as long as the program runs, in one table for all
+/// threads. Symbols sort by when their name was first seen rather than
+/// alphabetically, so a state lists its keys in that order.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Default)]
+struct Symbols {
+    ids: HashMap<&'static str, u32>,
+    names: Vec<&'static str>,
+}
+
+fn symbols() -> &'static RwLock<Symbols> {
+    static SYMBOLS: OnceLock<RwLock<Symbols>> = OnceLock::new();
+    SYMBOLS.get_or_init(Default::default)
+}
+
+impl Symbol {
+    /// The symbol for `name`, the same one every time
+    pub fn new(name: &str) -> Self {
+        let read = symbols().read().unwrap_or_else(PoisonError::into_inner);
+        if let Some(&id) = read.ids.get(name) {
+            return Self(id);
+        }
+        drop(read);
+
+        let mut write = symbols().write().unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have added it in between
+        if let Some(&id) = write.ids.get(name) {
+            return Self(id);
+        }
+        let name: &'static str = Box::leak(name.into());
+        let id = write.names.len() as u32;
+        write.names.push(name);
+        write.ids.insert(name, id);
+        Self(id)
+    }
+
+    /// The name the symbol stands for
+    pub fn as_str(self) -> &'static str {
+        symbols()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .names[self.0 as usize]
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Self::new(&name)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// By name, as the numbers depend on the order names come up in
+#[cfg(feature = "serde")]
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        String::deserialize(deserializer).map(Symbol::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_name_same_symbol() {
+        let gold = Symbol::new("symbol_test_gold");
+        assert_eq!(gold, Symbol::from("symbol_test_gold".to_string()));
+        assert_ne!(gold, Symbol::new("symbol_test_wood"));
+        assert_eq!(gold.as_str(), "symbol_test_gold");
+        assert_eq!(
+            format!("{:?} {}", gold, gold),
+            "\"symbol_test_gold\" symbol_test_gold"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use alloc::vec::Vec;
+
+use crate::{
+    action::Action,
+    compiled::CompiledAction,
+    datum::DatumLike,
+    goal::Goal,
+    heuristic::Heuristic,
+    localstate::{Key, LocalState},
+    planner::{
+        avoid_penalty, is_goal, plan_duration, plan_totals, successors, Interrupt, Minimize, Node,
+        Plan, PlanStats, PlannerConfig,
+    },
+    search,
+};
+
+/// Whether planning for `goal` has to keep track of time, because of a
+/// [`Goal::deadline`] or [`Minimize::Duration`]
+pub(crate) fn is_timed<K: Key, D: DatumLike>(
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+) -> bool {
+    goal.deadline.is_some() || config.minimize == Minimize::Duration
+}
+
+/// Searches forwards like the default search, but keeps track of how much time
+/// passes along every path. Paths that take longer than the [`Goal::deadline`] are
+/// dropped, and with [`Minimize::Duration`] the fastest plan wins instead of the
+/// cheapest.
+///
+/// Time only passes through the [`Effect::duration`](crate::effect::Effect::duration)
+/// of the actions taken, there's no waiting in between.
+pub(crate) fn plan_timed<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+    stats: &mut PlanStats,
+) -> Option<Plan<K, D>> {
+    let heuristic = &Heuristic::new(actions, goal, config);
+    let nodes = match config.minimize {
+        Minimize::Cost => {
+            cheapest_in_time(start, actions, goal, heuristic, config, interrupt, stats)?
+        }
+        Minimize::Duration => fastest(start, actions, goal, heuristic, config, interrupt, stats)?,
+    };
+    let (cost, reward) = plan_totals(&nodes);
+    stats.reward = reward;
+    stats.duration = plan_duration(&nodes);
+    Some((nodes, cost))
+}
+
+/// The cheapest plan that makes the deadline. A cheaper way to a state can take
+/// longer than a more expensive one, so how much time has passed is part of what
+/// the search tells apart, next to the state
+fn cheapest_in_time<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    heuristic: &Heuristic<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+    stats: &mut PlanStats,
+) -> Option<Vec<Node<K, D>>> {
+    let mut poll = interrupt.poll();
+    let deadline = goal.deadline.unwrap_or(usize::MAX);
+    let compiled = CompiledAction::compile_all(actions, start);
+    let (path, cost) = search::astar(
+        &(Node::State(start.clone()), 0_usize),
+        (),
+        heuristic.distance(start) as usize,
+        config.max_cost,
+        config.tie_seed,
+        stats,
+        || poll.is_due(),
+        |(node, elapsed), h, out| {
+            let next = successors(node, h, &compiled, heuristic, config, |_| {}).filter_map(
+                |(node, cost, h)| {
+                    let elapsed = elapsed.saturating_add(duration_of(&node));
+                    if elapsed > deadline {
+                        return None;
+                    }
+                    let penalty = avoid_penalty(node.state(), &config.avoid);
+                    Some(((node, elapsed), (), cost.saturating_add(penalty), h))
+                },
+            );
+            out.extend(next);
+        },
+        |(node, _)| is_goal(node, goal, &config.computed),
+        |_| {},
+    )?;
+
+    let nodes: Vec<Node<K, D>> = path.into_iter().map(|((node, _), _)| node).collect();
+    let (raw_cost, reward) = plan_totals(&nodes);
+    stats.penalty = cost.saturating_add(reward).saturating_sub(raw_cost);
+    Some(nodes)
+}
+
+/// The plan that takes the least time. The search minimizes the time that has
+/// passed, so the first time it reaches a state is also the earliest
+fn fastest<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+    heuristic: &Heuristic<K, D>,
+    config: &PlannerConfig<K, D>,
+    interrupt: Interrupt,
+    stats: &mut PlanStats,
+) -> Option<Vec<Node<K, D>>> {
+    let mut poll = interrupt.poll();
+    let deadline = goal.deadline.unwrap_or(usize::MAX);
+    let compiled = CompiledAction::compile_all(actions, start);
+    let (path, _) = search::astar(
+        &Node::State(start.clone()),
+        (),
+        0,
+        None,
+        config.tie_seed,
+        stats,
+        || poll.is_due(),
+        |node, _, out| {
+            // Distances to the goal say nothing about how long it takes to get
+            // there, so the search goes without a heuristic. Successors still
+            // work theirs out from the parent's
+            let h = heuristic.distance(node.state()) as usize;
+            let next =
+                successors(node, h, &compiled, heuristic, config, |_| {}).map(|(node, _, _)| {
+                    let duration = duration_of(&node);
+                    (node, (), duration, 0)
+                });
+            out.extend(next);
+        },
+        |node| is_goal(node, goal, &config.computed),
+        |_| {},
+    )?;
+
+    let nodes: Vec<Node<K, D>> = path.into_iter().map(|(node, _)| node).collect();
+    if plan_duration(&nodes) > deadline {
+        return None;
+    }
+    Some(nodes)
+}
+
+fn duration_of<K, D>(node: &Node<K, D>) -> usize {
+    match node {
+        Node::Effect(effect) => effect.duration,
+        Node::State(_) => 0,
+    }
+}
@@ -0,0 +1,150 @@
+//! Assertions for checking the plans returned by [`make_plan`](crate::planner::make_plan)
+//! in tests. When they fail, the message shows the whole plan, step by step.
+//!
+//! ```
+//! use dogoap::prelude::*;
+//! use dogoap::simple::simple_increment_action;
+//! use dogoap::testing::*;
+//!
+//! let start = LocalState::new().with_datum("gold", Datum::I64(0));
+//! let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(2)));
+//! let actions = vec![simple_increment_action("mine", "gold", Datum::I64(1))];
+//!
+//! let plan = make_plan(&start, &actions, &goal).unwrap();
+//! assert_plan_actions!(plan, ["mine", "mine"]);
+//! assert_plan_cost!(plan, 2);
+//! assert_plan_reaches!(plan, goal);
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use core::fmt::Display;
+
+use crate::{
+    datum::DatumLike,
+    goal::{check_goal, Goal},
+    localstate::{Key, LocalState},
+    mutator::apply_mutator,
+    planner::{action_keys_of_plan, format_plan, Node},
+};
+
+pub use crate::{assert_plan_actions, assert_plan_cost, assert_plan_reaches};
+
+#[cfg(feature = "proptest")]
+pub mod properties;
+
+/// The keys of the actions in `plan`, in the order they're executed
+pub fn plan_actions<K, D>(plan: &(Vec<Node<K, D>>, usize)) -> Vec<&str> {
+    action_keys_of_plan(&plan.0).collect()
+}
+
+/// Replays the mutators of every step of `plan` on its initial state, returning
+/// the state the plan ends up in. Doesn't trust the states stored in the plan.
+pub fn simulate<K: Key, D: DatumLike>(plan: &(Vec<Node<K, D>>, usize)) -> LocalState<K, D> {
+    let mut state = match plan.0.first() {
+        Some(node) => node.state().clone(),
+        None => LocalState::default(),
+    };
+    for node in plan.0.iter().skip(1) {
+        if let Node::Effect(effect) = node {
+            for mutator in &effect.mutators {
+                apply_mutator(&mut state.data, mutator);
+            }
+        }
+    }
+    state
+}
+
+/// `plan` laid out like [`print_plan`](crate::planner::print_plan) does
+pub fn describe_plan<K: Key + Display, D: DatumLike + Display>(
+    plan: &(Vec<Node<K, D>>, usize),
+) -> String {
+    format_plan(plan)
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn check_actions<K: Key + Display, D: DatumLike + Display, S: AsRef<str>>(
+    plan: &(Vec<Node<K, D>>, usize),
+    expected: impl IntoIterator<Item = S>,
+) {
+    let expected: Vec<S> = expected.into_iter().collect();
+    let expected: Vec<&str> = expected.iter().map(AsRef::as_ref).collect();
+    let actual = plan_actions(plan);
+    if actual != expected {
+        panic!(
+            "plan doesn't consist of the expected actions\n expected: {:?}\n   actual: {:?}\n\n{}",
+            expected,
+            actual,
+            describe_plan(plan)
+        );
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn check_cost<K: Key + Display, D: DatumLike + Display>(
+    plan: &(Vec<Node<K, D>>, usize),
+    expected: usize,
+) {
+    if plan.1 != expected {
+        panic!(
+            "plan doesn't have the expected cost\n expected: {}\n   actual: {}\n\n{}",
+            expected,
+            plan.1,
+            describe_plan(plan)
+        );
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn check_reaches<K: Key + Display, D: DatumLike + Display>(
+    plan: &(Vec<Node<K, D>>, usize),
+    goal: &Goal<K, D>,
+) {
+    let state = simulate(plan);
+    if !check_goal(&state, goal) {
+        panic!(
+            "plan doesn't reach the goal\n     goal: {:?}\n  reached: {:?}\n\n{}",
+            goal.requirements,
+            state.data,
+            describe_plan(plan)
+        );
+    }
+}
+
+/// Asserts that a plan consists of exactly these actions, in this order
+///
+/// `assert_plan_actions!(plan, ["goto", "pick_up", "eat"])`
+#[macro_export]
+macro_rules! assert_plan_actions {
+    ($plan:expr, [] $(,)?) => {
+        $crate::testing::check_actions::<_, _, &str>(&$plan, [])
+    };
+    ($plan:expr, $actions:expr $(,)?) => {
+        $crate::testing::check_actions(&$plan, $actions)
+    };
+}
+
+/// Asserts that a plan has this total cost
+///
+/// `assert_plan_cost!(plan, 7)`
+#[macro_export]
+macro_rules! assert_plan_cost {
+    ($plan:expr, $cost:expr $(,)?) => {
+        $crate::testing::check_cost(&$plan, $cost)
+    };
+}
+
+/// Asserts that replaying the mutators of a plan from its initial state ends up
+/// in a state that satisfies the goal
+///
+/// `assert_plan_reaches!(plan, goal)`
+#[macro_export]
+macro_rules! assert_plan_reaches {
+    ($plan:expr, $goal:expr $(,)?) => {
+        $crate::testing::check_reaches(&$plan, &$goal)
+    };
+}
@@ -0,0 +1,255 @@
+//! [proptest](https://docs.rs/proptest) strategies for small random domains, and
+//! checks of what has to hold for every plan the planner returns in them. The
+//! checks fail the test case with the whole plan in the message.
+//!
+//! ```
+//! use dogoap::testing::properties::*;
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(problem in problem(Bounds::default()))| {
+//!     check_all(&problem)?;
+//! });
+//! ```
+//!
+//! Every action costs at least as much as it moves its key, and keys stay between
+//! 0 and [`Bounds::max_value`], so the heuristics never overestimate and every
+//! strategy finds a cheapest plan.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+
+use crate::{
+    action::Action,
+    compare::Compare,
+    datum::Datum,
+    effect::Effect,
+    goal::{check_goal, Goal},
+    localstate::LocalState,
+    mutator::Mutator,
+    optimize::validate_plan,
+    planner::{make_plan, make_plan_with_config, Node, PlannerConfig, PlanningStrategy},
+    testing::{describe_plan, simulate},
+};
+
+/// How big the domains made by [`problem`] get
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds {
+    /// Most integer keys in the state, named `k0`, `k1` and so on
+    pub keys: usize,
+    /// Most actions in the domain, named `a0`, `a1` and so on
+    pub actions: usize,
+    /// Keys hold values from 0 up to this
+    pub max_value: i64,
+}
+
+impl Default for Bounds {
+    fn default() -> Self {
+        Self {
+            keys: 3,
+            actions: 6,
+            max_value: 4,
+        }
+    }
+}
+
+/// A start state, the actions and a goal, made by [`problem`]
+#[derive(Clone, Debug)]
+pub struct Problem {
+    pub start: LocalState,
+    pub actions: Vec<Action>,
+    pub goal: Goal,
+}
+
+/// Random domains within `bounds`. Every action sets, increments or decrements one
+/// key and may have a precondition on top. Many of them have no plan at all
+pub fn problem(bounds: Bounds) -> impl Strategy<Value = Problem> {
+    let max_value = bounds.max_value.max(0);
+    (1..=bounds.keys.max(1))
+        .prop_flat_map(move |keys| {
+            (
+                prop::collection::vec(0..=max_value, keys),
+                prop::collection::vec(action(keys, max_value), 1..=bounds.actions.max(1)),
+                prop::collection::btree_map(0..keys, compare(max_value), 1..=keys),
+            )
+        })
+        .prop_map(|(values, actions, requirements)| {
+            let start = values
+                .into_iter()
+                .enumerate()
+                .fold(LocalState::new(), |start, (key, value)| {
+                    start.with_datum(key_name(key), Datum::I64(value))
+                });
+            let actions = actions
+                .into_iter()
+                .enumerate()
+                .map(|(index, mut action)| {
+                    action.key = format!("a{}", index);
+                    action.effects[0].action = action.key.clone();
+                    action
+                })
+                .collect();
+            let goal = requirements
+                .into_iter()
+                .fold(Goal::new(), |goal, (key, compare)| {
+                    goal.with_req(key_name(key), compare)
+                });
+            Problem {
+                start,
+                actions,
+                goal,
+            }
+        })
+}
+
+fn key_name(key: usize) -> String {
+    format!("k{}", key)
+}
+
+fn compare(max_value: i64) -> impl Strategy<Value = Compare<Datum>> {
+    (0..3, 0..=max_value).prop_map(|(kind, value)| match kind {
+        0 => Compare::Equals(Datum::I64(value)),
+        1 => Compare::GreaterThanEquals(Datum::I64(value)),
+        _ => Compare::LessThanEquals(Datum::I64(value)),
+    })
+}
+
+/// One unnamed action on one of `keys` keys
+fn action(keys: usize, max_value: i64) -> impl Strategy<Value = Action> {
+    let precondition = prop::option::of((0..keys, compare(max_value)));
+    (
+        0..keys,
+        0..3,
+        0..=max_value,
+        1..=max_value.clamp(1, 2),
+        0..3_usize,
+        precondition,
+    )
+        .prop_map(move |(key, kind, value, step, extra, precondition)| {
+            let name = key_name(key);
+            let (mutator, moved, bound) = match kind {
+                0 => (
+                    Mutator::Set(name.clone(), Datum::I64(value)),
+                    value.max(max_value - value),
+                    None,
+                ),
+                1 => (
+                    Mutator::Increment(name.clone(), Datum::I64(step)),
+                    step,
+                    Some(Compare::LessThanEquals(Datum::I64(max_value - step))),
+                ),
+                _ => (
+                    Mutator::Decrement(name.clone(), Datum::I64(step)),
+                    step,
+                    Some(Compare::GreaterThanEquals(Datum::I64(step))),
+                ),
+            };
+            let mut action = Action::new("").with_effect(Effect::new("").with_mutator(mutator));
+            if let Some(bound) = bound {
+                action = action.with_precondition(name, bound);
+            }
+            if let Some((key, compare)) = precondition {
+                action = action.with_precondition(key_name(key), compare);
+            }
+            action.set_cost((moved as usize).max(1) + extra)
+        })
+}
+
+/// Replaying the plan [`make_plan`] returns ends in a state that meets the goal
+pub fn check_plan_reaches_goal(problem: &Problem) -> Result<(), TestCaseError> {
+    let Some(plan) = make_plan(&problem.start, &problem.actions, &problem.goal) else {
+        return Ok(());
+    };
+    let reached = simulate(&plan);
+    prop_assert!(
+        check_goal(&reached, &problem.goal),
+        "plan doesn't reach the goal\n     goal: {:?}\n  reached: {:?}\n\n{}",
+        problem.goal.requirements,
+        reached.data,
+        describe_plan(&plan)
+    );
+    Ok(())
+}
+
+/// The cost of the plan [`make_plan`] returns is what its steps cost together
+pub fn check_cost_is_sum_of_steps(problem: &Problem) -> Result<(), TestCaseError> {
+    let Some(plan) = make_plan(&problem.start, &problem.actions, &problem.goal) else {
+        return Ok(());
+    };
+    let steps = plan
+        .0
+        .iter()
+        .filter_map(|node| match node {
+            Node::Effect(effect) => Some(effect.cost),
+            Node::State(_) => None,
+        })
+        .fold(0, usize::saturating_add);
+    prop_assert_eq!(
+        plan.1,
+        steps,
+        "plan cost isn't the sum of its steps\n\n{}",
+        describe_plan(&plan)
+    );
+    Ok(())
+}
+
+/// Whenever the forward search finds a plan, every other [`PlanningStrategy`] finds
+/// one for the same cost.
+///
+/// Searching backwards doesn't know the keys stay in range, so without a plan it can
+/// keep going forever. The other strategies get the cost of the forward plan as
+/// [`PlannerConfig::max_cost`], and aren't run at all when there's no plan
+pub fn check_strategies_agree(problem: &Problem) -> Result<(), TestCaseError> {
+    let config = PlannerConfig::default().with_strategy(PlanningStrategy::StartToGoal);
+    let forward = make_plan_with_config(&problem.start, &problem.actions, &problem.goal, &config);
+    let Some(forward) = forward else {
+        return Ok(());
+    };
+    for strategy in [
+        PlanningStrategy::GoalToStart,
+        PlanningStrategy::Auto,
+        PlanningStrategy::UniformCost,
+    ] {
+        let config = PlannerConfig::default()
+            .with_strategy(strategy)
+            .with_max_cost(forward.1);
+        let other = make_plan_with_config(&problem.start, &problem.actions, &problem.goal, &config);
+        prop_assert_eq!(
+            Some(forward.1),
+            other.as_ref().map(|plan| plan.1),
+            "{:?} disagrees with {:?}\n\n{}\n{}",
+            strategy,
+            PlanningStrategy::StartToGoal,
+            describe_plan(&forward),
+            match &other {
+                Some(plan) => describe_plan(plan),
+                None => String::from("no plan\n"),
+            }
+        );
+    }
+    Ok(())
+}
+
+/// [`validate_plan`] accepts the plan [`make_plan`] returns
+pub fn check_plan_validates(problem: &Problem) -> Result<(), TestCaseError> {
+    let Some(plan) = make_plan(&problem.start, &problem.actions, &problem.goal) else {
+        return Ok(());
+    };
+    prop_assert!(
+        validate_plan(&plan, &problem.start, &problem.actions, &problem.goal),
+        "validate_plan rejects the plan\n\n{}",
+        describe_plan(&plan)
+    );
+    Ok(())
+}
+
+/// Every check in this module
+pub fn check_all(problem: &Problem) -> Result<(), TestCaseError> {
+    check_plan_reaches_goal(problem)?;
+    check_cost_is_sum_of_steps(problem)?;
+    check_strategies_agree(problem)?;
+    check_plan_validates(problem)
+}
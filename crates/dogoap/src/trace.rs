@@ -0,0 +1,233 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+use core::fmt::{self, Debug, Display};
+
+use crate::datum::Datum;
+use crate::planner::{Node, Plan, PlanStats};
+use crate::search::HEURISTIC_SCALE;
+
+type Expand<K, D> = dyn Fn(&Node<K, D>, usize, usize) + Send + Sync;
+type Finish<K, D> = dyn Fn(Option<&Plan<K, D>>, &PlanStats) + Send + Sync;
+
+/// Called for every node the forward search expands, with what getting there cost
+/// and the heuristic's estimate of what's left, in thousandths of a cost. See
+/// [`PlannerConfig::on_expand`](crate::planner::PlannerConfig::on_expand)
+pub struct OnExpand<K = String, D = Datum>(pub(crate) Arc<Expand<K, D>>);
+
+/// Called once a search is done, with the plan it found and its stats. See
+/// [`PlannerConfig::on_finish`](crate::planner::PlannerConfig::on_finish)
+pub struct OnFinish<K = String, D = Datum>(pub(crate) Arc<Finish<K, D>>);
+
+impl<K, D> OnExpand<K, D> {
+    pub fn new(observe: impl Fn(&Node<K, D>, usize, usize) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(observe))
+    }
+}
+
+impl<K, D> OnFinish<K, D> {
+    pub fn new(observe: impl Fn(Option<&Plan<K, D>>, &PlanStats) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(observe))
+    }
+}
+
+impl<K, D> Clone for OnExpand<K, D> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<K, D> Clone for OnFinish<K, D> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<K, D> Debug for OnExpand<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OnExpand")
+    }
+}
+
+impl<K, D> Debug for OnFinish<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OnFinish")
+    }
+}
+
+/// What the search did, in order, as recorded by
+/// [`make_plan_with_trace`](crate::planner::make_plan_with_trace). Nodes are numbered
+/// in the order the search first reached them, the start being `#0`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlannerTrace {
+    pub events: Vec<TraceEvent>,
+    /// Whether events were left out for going over
+    /// [`PlannerConfig::max_trace_events`](crate::planner::PlannerConfig::max_trace_events)
+    pub truncated: bool,
+    max_events: usize,
+    /// The node whose successors are being generated
+    expanding: usize,
+}
+
+/// One decision of the search, see [`PlannerTrace`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// The search took `node` off the open list to look at its successors. `cost` is
+    /// what getting there cost, `heuristic` the estimate of what's left, in
+    /// thousandths of a cost
+    NodeExpanded {
+        node: usize,
+        cost: usize,
+        heuristic: usize,
+    },
+    /// Taking `action` in `parent` leads to `node`, for a total of `cost`. A node
+    /// reached again more cheaply gets this again, with its new parent
+    SuccessorGenerated {
+        parent: usize,
+        node: usize,
+        action: String,
+        cost: usize,
+    },
+    /// Taking `action` in `parent` was left out of the search
+    NodePruned {
+        parent: usize,
+        action: String,
+        by: PrunedBy,
+    },
+    /// `node` meets the goal, for a total of `cost`, which ends the search
+    GoalReached { node: usize, cost: usize },
+}
+
+/// Why a successor was left out of the search
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrunedBy {
+    /// It costs more than [`PlannerConfig::max_cost`](crate::planner::PlannerConfig::max_cost)
+    Limit,
+    /// It breaks an [`Invariant`](crate::prelude::Invariant) or overdraws a
+    /// [resource](crate::planner::PlannerConfig::resources)
+    Invariant,
+    /// The search already has a way to the same node that's at least as cheap
+    Duplicate,
+}
+
+impl PlannerTrace {
+    pub(crate) fn new(max_events: usize) -> Self {
+        Self {
+            events: vec![],
+            truncated: false,
+            max_events,
+            expanding: 0,
+        }
+    }
+
+    pub(crate) fn record(&mut self, event: TraceEvent) {
+        if let TraceEvent::NodeExpanded { node, .. } = event {
+            self.expanding = node;
+        }
+        if self.events.len() < self.max_events {
+            self.events.push(event);
+        } else {
+            self.truncated = true;
+        }
+    }
+
+    /// Records that `action` was pruned from the node being expanded
+    pub(crate) fn pruned(&mut self, action: &str, by: PrunedBy) {
+        self.record(TraceEvent::NodePruned {
+            parent: self.expanding,
+            action: action.into(),
+            by,
+        });
+    }
+
+    /// The events about the nodes of the plan that was found: how each was reached
+    /// and expanded, and the goal being reached. Empty if the search found nothing,
+    /// and missing whatever got cut off if the trace is `truncated`
+    pub fn winning_path(&self) -> Vec<&TraceEvent> {
+        let Some(goal) = self.events.iter().rev().find_map(|event| match event {
+            TraceEvent::GoalReached { node, .. } => Some(*node),
+            _ => None,
+        }) else {
+            return vec![];
+        };
+
+        // The last way to each node is the one the plan goes through
+        let mut reached_by = BTreeMap::new();
+        for (index, event) in self.events.iter().enumerate() {
+            if let TraceEvent::SuccessorGenerated { node, parent, .. } = event {
+                reached_by.insert(*node, (index, *parent));
+            }
+        }
+        let mut nodes = BTreeSet::from([goal]);
+        let mut edges = BTreeSet::new();
+        let mut node = goal;
+        while let Some((index, parent)) = reached_by.get(&node) {
+            edges.insert(*index);
+            if !nodes.insert(*parent) {
+                break;
+            }
+            node = *parent;
+        }
+
+        self.events
+            .iter()
+            .enumerate()
+            .filter(|(index, event)| match event {
+                TraceEvent::NodeExpanded { node, .. } => nodes.contains(node),
+                TraceEvent::SuccessorGenerated { .. } => edges.contains(index),
+                TraceEvent::NodePruned { .. } => false,
+                TraceEvent::GoalReached { .. } => true,
+            })
+            .map(|(_, event)| event)
+            .collect()
+    }
+}
+
+impl Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::NodeExpanded {
+                node,
+                cost,
+                heuristic,
+            } => {
+                let scale = HEURISTIC_SCALE as usize;
+                write!(
+                    f,
+                    "expanded #{} (cost {}, heuristic {}.{:03})",
+                    node,
+                    cost,
+                    heuristic / scale,
+                    heuristic % scale
+                )
+            }
+            TraceEvent::SuccessorGenerated {
+                parent,
+                node,
+                action,
+                cost,
+            } => write!(
+                f,
+                "#{} -> #{} with {} (cost {})",
+                parent, node, action, cost
+            ),
+            TraceEvent::NodePruned { parent, action, by } => {
+                write!(f, "#{} pruned {} ({})", parent, action, by)
+            }
+            TraceEvent::GoalReached { node, cost } => {
+                write!(f, "reached the goal at #{} (cost {})", node, cost)
+            }
+        }
+    }
+}
+
+impl Display for PrunedBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrunedBy::Limit => write!(f, "over the cost limit"),
+            PrunedBy::Invariant => write!(f, "breaks an invariant"),
+            PrunedBy::Duplicate => write!(f, "reached no cheaper than before"),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::{vec, vec::Vec};
+use core::fmt::{self, Display};
+
+use crate::{
+    action::Action,
+    compare::Compare,
+    datum::{Datum, DatumLike},
+    goal::Goal,
+    localstate::{Key, LocalState},
+    mutator::Mutator,
+};
+
+/// Where in a domain a [`TypeIssue`] was found
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TypeIssueSite {
+    /// A mutator of the action with this key
+    Mutator(String),
+    /// A precondition of the action with this key
+    Precondition(String),
+    /// A requirement of the goal
+    Goal,
+}
+
+/// A value used with a key whose [`DatumLike::Kind`] is different, found by
+/// [`typecheck_domain`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeIssue<K = String, D: DatumLike = Datum> {
+    pub site: TypeIssueSite,
+    pub key: K,
+    /// The kind the key holds
+    pub expected: D::Kind,
+    /// The kind of the value used with it
+    pub found: D::Kind,
+}
+
+impl<K: Key, D: DatumLike> Display for TypeIssue<K, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.site {
+            TypeIssueSite::Mutator(action) => write!(f, "mutator of action {:?}", action)?,
+            TypeIssueSite::Precondition(action) => {
+                write!(f, "precondition of action {:?}", action)?
+            }
+            TypeIssueSite::Goal => write!(f, "goal requirement")?,
+        }
+        write!(
+            f,
+            " uses a {:?} with key {:?}, which holds a {:?}",
+            self.found, self.key, self.expected
+        )
+    }
+}
+
+/// Checks that every mutator, precondition and goal requirement uses values of
+/// the same [`DatumKind`](crate::datum::DatumKind) as the key it's about. Planning
+/// doesn't check this itself, call it once on a domain before planning with it.
+///
+/// The kind of a key comes from `start`, or for keys that aren't in it, the
+/// first `Mutator::Set` of that key. Dynamic preconditions can't be checked.
+pub fn typecheck_domain<K: Key, D: DatumLike>(
+    start: &LocalState<K, D>,
+    actions: &[Action<K, D>],
+    goal: &Goal<K, D>,
+) -> Vec<TypeIssue<K, D>> {
+    // The kinds of keys the start state doesn't have, from the first action that
+    // sets them. The start state is left alone, it can be much bigger than the
+    // domain
+    let mut created: BTreeMap<&K, D::Kind> = BTreeMap::new();
+    for action in actions {
+        for effect in &action.effects {
+            for mutator in &effect.mutators {
+                if let Mutator::Set(key, value) = mutator {
+                    if !start.data.contains_key(key) {
+                        created.entry(key).or_insert(value.kind());
+                    }
+                }
+            }
+        }
+    }
+    let kind_of = |key: &K| {
+        start
+            .data
+            .get(key)
+            .map(DatumLike::kind)
+            .or_else(|| created.get(key).copied())
+    };
+
+    let mut issues = vec![];
+    let mut check = |site: &TypeIssueSite, key: &K, value: &D| {
+        if let Some(expected) = kind_of(key) {
+            if expected != value.kind() {
+                issues.push(TypeIssue {
+                    site: site.clone(),
+                    key: key.clone(),
+                    expected,
+                    found: value.kind(),
+                });
+            }
+        }
+    };
+
+    for action in actions {
+        let site = TypeIssueSite::Precondition(action.key.clone());
+        for (key, compare) in &action.preconditions {
+            if matches!(compare, Compare::Contains(_) | Compare::Absent) {
+                continue;
+            }
+            check(&site, key, &compare.value());
+            if let Compare::Between(_, high) = compare {
+                check(&site, key, high);
+            }
+        }
+        let site = TypeIssueSite::Mutator(action.key.clone());
+        for effect in &action.effects {
+            for mutator in &effect.mutators {
+                match mutator {
+                    Mutator::Set(key, value)
+                    | Mutator::Increment(key, value)
+                    | Mutator::Decrement(key, value)
+                    | Mutator::Multiply(key, value)
+                    | Mutator::Divide(key, value) => check(&site, key, value),
+                    // A single member isn't of the set's kind, and a copy or an unset
+                    // key has no value of its own
+                    Mutator::Toggle(_)
+                    | Mutator::Insert(..)
+                    | Mutator::Remove(..)
+                    | Mutator::CopyFrom(..)
+                    | Mutator::Unset(_) => {}
+                }
+            }
+        }
+    }
+    for (key, compare) in goal
+        .requirements
+        .iter()
+        .chain(goal.alternatives.iter().flatten())
+    {
+        if matches!(compare, Compare::Contains(_) | Compare::Absent) {
+            continue;
+        }
+        check(&TypeIssueSite::Goal, key, &compare.value());
+        if let Compare::Between(_, high) = compare {
+            check(&TypeIssueSite::Goal, key, high);
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,73 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+fn start() -> LocalState {
+    LocalState::new()
+        .with_datum("has_target", Datum::Bool(true))
+        .with_datum("patrolling", Datum::Bool(false))
+}
+
+fn no_target() -> Goal {
+    Goal::new().with_req("has_target", Compare::Absent)
+}
+
+fn destroy() -> Action {
+    Action::new("destroy")
+        .with_effect(Effect::new("destroy").with_mutator(Mutator::Unset("has_target".to_string())))
+        .set_cost(2)
+}
+
+#[test]
+fn test_present_key_is_unmet() {
+    assert!(!no_target().is_satisfied(&start()));
+    assert_eq!(1, start().distance_to_goal(&no_target()));
+}
+
+#[test]
+fn test_removed_key_is_met() {
+    let mut state = start();
+    state.data.remove("has_target");
+
+    assert!(no_target().is_satisfied(&state));
+    assert_eq!(0, state.distance_to_goal(&no_target()));
+}
+
+#[test]
+fn test_plan_removes_the_key() {
+    let actions = vec![
+        destroy(),
+        simple_action("ignore", "has_target", Datum::Bool(false)),
+    ];
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let config = PlannerConfig::default().with_strategy(strategy);
+        let plan = make_plan_with_config(&start(), &actions, &no_target(), &config).unwrap();
+
+        assert_plan_actions!(plan, ["destroy"]);
+        assert_plan_cost!(plan, 2);
+    }
+}
+
+#[test]
+fn test_precondition_on_absent_key() {
+    let patrol = simple_action("patrol", "patrolling", Datum::Bool(true))
+        .with_precondition("has_target", Compare::Absent);
+    let goal = Goal::new().with_req("patrolling", Compare::Equals(Datum::Bool(true)));
+
+    let plan = make_plan(&start(), &[destroy(), patrol], &goal).unwrap();
+
+    assert_plan_actions!(plan, ["destroy", "patrol"]);
+}
+
+#[test]
+fn test_absent_key_needs_not_exist_to_start_with() {
+    let start = LocalState::new().with_datum("patrolling", Datum::Bool(false));
+    let patrol = simple_action("patrol", "patrolling", Datum::Bool(true))
+        .with_precondition("has_target", Compare::Absent);
+    let goal = Goal::new()
+        .with_req("patrolling", Compare::Equals(Datum::Bool(true)))
+        .with_req("has_target", Compare::Absent);
+
+    let plan = try_make_plan_with_config(&start, &[patrol], &goal, &PlannerConfig::default());
+
+    assert_plan_actions!(plan.unwrap().unwrap(), ["patrol"]);
+}
@@ -0,0 +1,84 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+fn base() -> ActionSet {
+    ActionSet::new()
+        .with_action(simple_action("eat", "hungry", Datum::Bool(false)))
+        .with_action(simple_action("sleep", "tired", Datum::Bool(false)))
+        .with_action(simple_action("flee", "safe", Datum::Bool(true)).set_cost(5))
+}
+
+fn guard() -> ActionSet {
+    ActionSet::new().with_action(simple_action("flee", "safe", Datum::Bool(true)).set_cost(2))
+}
+
+#[test]
+fn test_later_insertions_override_by_key() {
+    let mut actions = base();
+
+    let replaced = actions.insert(simple_action("eat", "hungry", Datum::Bool(false)).set_cost(3));
+
+    assert_eq!(1, replaced.unwrap().effects[0].cost);
+    assert_eq!(3, actions.len());
+    // The override keeps the place of the action it replaced
+    assert_eq!("eat", actions.as_slice()[0].key);
+    assert_eq!(3, actions.get("eat").unwrap().effects[0].cost);
+}
+
+#[test]
+fn test_merge_overrides_the_base() {
+    let mut actions = base();
+
+    actions.merge(guard());
+
+    assert_eq!(
+        vec!["eat", "sleep", "flee"],
+        actions
+            .iter()
+            .map(|action| action.key.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(2, actions.get("flee").unwrap().effects[0].cost);
+}
+
+#[test]
+fn test_merge_strict_rejects_shared_keys() {
+    let mut actions = base();
+
+    let err = actions.merge_strict(guard()).unwrap_err();
+
+    assert_eq!(PlanError::DuplicateActionKey("flee".to_string()), err);
+    assert_eq!(base(), actions);
+
+    let extra = ActionSet::new().with_action(simple_action("hide", "safe", Datum::Bool(true)));
+    actions.merge_strict(extra).unwrap();
+    assert!(actions.contains("hide"));
+}
+
+#[test]
+fn test_remove() {
+    let mut actions = base();
+
+    assert_eq!("sleep", actions.remove("sleep").unwrap().key);
+    assert_eq!(None, actions.remove("sleep"));
+    assert_eq!(2, actions.len());
+}
+
+#[test]
+fn test_planner_sees_one_action_per_key() {
+    let start = LocalState::new().with_datum("safe", Datum::Bool(false));
+    let goal = Goal::new().with_req("safe", Compare::Equals(Datum::Bool(true)));
+    let mut actions = base();
+    actions.merge(guard());
+
+    let config = PlannerConfig::new().with_duplicate_keys(DuplicateKeys::Reject);
+    let plan = try_make_plan_with_config(&start, actions.as_slice(), &goal, &config)
+        .unwrap()
+        .unwrap();
+
+    assert_plan_actions!(plan, ["flee"]);
+    assert_plan_cost!(plan, 2);
+
+    // Collecting overrides the same way
+    let collected: ActionSet = base().into_iter().chain(guard()).collect();
+    assert_eq!(actions, collected);
+}
@@ -0,0 +1,94 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// Being indoors or having a weapon both make for being safe
+fn safety(inside_cost: usize) -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("indoors", Datum::Bool(false))
+        .with_datum("has_weapon", Datum::Bool(false))
+        .with_datum("wood", Datum::I64(0));
+    let actions = vec![
+        simple_action("go_inside", "indoors", Datum::Bool(true)).set_cost(inside_cost),
+        simple_increment_action("chop_wood", "wood", Datum::I64(1)),
+        simple_action("craft_spear", "has_weapon", Datum::Bool(true))
+            .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(2)))
+            .set_cost(2),
+    ];
+    let goal = Goal::any_of([
+        Goal::new().with_req("indoors", Compare::Equals(Datum::Bool(true))),
+        Goal::new().with_req("has_weapon", Compare::Equals(Datum::Bool(true))),
+    ]);
+    (start, actions, goal)
+}
+
+#[test]
+fn test_check_goal_passes_with_either_group() {
+    let (start, _, goal) = safety(1);
+
+    assert!(!check_goal(&start, &goal));
+    let indoors = start.clone().with_datum("indoors", Datum::Bool(true));
+    assert!(check_goal(&indoors, &goal));
+    let armed = start.with_datum("has_weapon", Datum::Bool(true));
+    assert!(check_goal(&armed, &goal));
+}
+
+#[test]
+fn test_plan_takes_the_cheapest_branch() {
+    let (start, actions, goal) = safety(10);
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["chop_wood", "chop_wood", "craft_spear"]);
+    assert_plan_cost!(plan, 4);
+
+    let (start, actions, goal) = safety(3);
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["go_inside"]);
+    assert_plan_reaches!(plan, goal);
+}
+
+#[test]
+fn test_requirements_hold_on_top_of_the_alternatives() {
+    let (start, actions, _) = safety(3);
+    let goal = Goal::new()
+        .with_req("wood", Compare::GreaterThanEquals(Datum::I64(1)))
+        .with_alternative(&[("indoors".to_string(), Compare::Equals(Datum::Bool(true)))])
+        .with_alternative(&[("has_weapon".to_string(), Compare::Equals(Datum::Bool(true)))]);
+
+    assert!(!check_goal(
+        &start.clone().with_datum("indoors", Datum::Bool(true)),
+        &goal
+    ));
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["chop_wood", "go_inside"]);
+    assert_plan_cost!(plan, 4);
+}
+
+#[test]
+fn test_alternatives_are_searched_forwards() {
+    let (start, actions, goal) = safety(10);
+    let config = PlannerConfig::new().with_strategy(PlanningStrategy::GoalToStart);
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    assert_plan_cost!(plan.unwrap(), 4);
+}
+
+#[test]
+fn test_any_of_splits_up_nested_alternatives() {
+    let inner = Goal::new()
+        .with_req("wood", Compare::Equals(Datum::I64(1)))
+        .with_alternative(&[("indoors".to_string(), Compare::Equals(Datum::Bool(true)))])
+        .with_alternative(&[("has_weapon".to_string(), Compare::Equals(Datum::Bool(true)))]);
+    let outer = Goal::new().with_req("wood", Compare::Equals(Datum::I64(5)));
+
+    let goal = Goal::any_of([inner, outer]);
+
+    assert!(goal.requirements.is_empty());
+    assert_eq!(3, goal.alternatives.len());
+    assert_eq!(2, goal.alternatives[0].len());
+    assert_eq!(1, goal.alternatives[2].len());
+}
@@ -0,0 +1,82 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// Plenty of ways to waste time next to the one that gets closer to the goal
+fn stockpiling() -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new().with_datum("wood", Datum::I64(0));
+    let mut actions = vec![simple_increment_action("chop", "wood", Datum::I64(1))];
+    for chore in ["sweep", "cook", "mend", "sing"] {
+        start = start.with_datum(chore, Datum::I64(0));
+        actions.push(simple_increment_action(chore, chore, Datum::I64(1)));
+    }
+    let goal = Goal::new().with_req("wood", Compare::Equals(Datum::I64(30)));
+    (start, actions, goal)
+}
+
+/// A goal met exactly by the state `plan` ends in, so [`validate_plan`] checks
+/// only that its steps can be taken one after another
+fn ends_where_it_ends(plan: &Plan) -> Goal {
+    let reached = final_state_of_plan(&plan.0).unwrap();
+    reached.data.iter().fold(Goal::new(), |goal, (key, value)| {
+        goal.with_req(key.clone(), Compare::Equals(*value))
+    })
+}
+
+#[test]
+fn test_out_of_budget_returns_a_partial_plan() {
+    let (start, actions, goal) = stockpiling();
+    let config = PlannerConfig::new().with_max_expanded_nodes(10);
+
+    let outcome = make_plan_anytime(&start, &actions, &goal, &config).unwrap();
+
+    assert!(!outcome.is_complete());
+    let plan = outcome.into_plan();
+    assert!(plan.0.len() > 1);
+    assert!(validate_plan(
+        &plan,
+        &start,
+        &actions,
+        &ends_where_it_ends(&plan)
+    ));
+    let steps: usize = effects_of_plan(&plan.0).map(|effect| effect.cost).sum();
+    assert_eq!(steps, plan.1);
+    // It heads for the goal rather than doing chores
+    assert!(action_keys_of_plan(&plan.0).all(|key| key == "chop"));
+}
+
+#[test]
+fn test_within_budget_the_plan_is_complete() {
+    let (start, actions, goal) = stockpiling();
+    let config = PlannerConfig::new().with_max_expanded_nodes(1000);
+
+    let outcome = make_plan_anytime(&start, &actions, &goal, &config).unwrap();
+
+    assert!(outcome.is_complete());
+    assert_plan_reaches!(outcome.plan(), goal);
+    assert_eq!(
+        make_plan(&start, &actions, &goal).as_ref(),
+        Some(outcome.plan())
+    );
+}
+
+#[test]
+fn test_no_partial_plan_when_there_is_no_plan_at_all() {
+    let start = LocalState::new().with_datum("wood", Datum::I64(0));
+    let actions = vec![simple_increment_action("chop", "wood", Datum::I64(1))
+        .with_precondition("wood", Compare::LessThanEquals(Datum::I64(4)))];
+    let goal = Goal::new().with_req("wood", Compare::Equals(Datum::I64(10)));
+
+    let outcome = make_plan_anytime(&start, &actions, &goal, &PlannerConfig::new());
+
+    assert_eq!(None, outcome);
+}
+
+#[test]
+fn test_cancelled_before_starting_has_no_partial_plan() {
+    let (start, actions, goal) = stockpiling();
+    let token = CancellationToken::new();
+    token.cancel();
+    let config = PlannerConfig::new().with_cancellation(token);
+
+    // Nothing was expanded, so nothing is closer than the start
+    assert_eq!(None, make_plan_anytime(&start, &actions, &goal, &config));
+}
@@ -0,0 +1,50 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+fn bucket(pour: f64) -> (LocalState, Vec<Action>) {
+    let start = LocalState::new().with_datum("water", Datum::F64(0.0));
+    let actions = vec![simple_increment_action("pour", "water", Datum::F64(pour))];
+    (start, actions)
+}
+
+#[test]
+fn test_goal_reached_through_rounding() {
+    let (start, actions) = bucket(0.1);
+    let goal = Goal::new().with_req("water", Compare::Equals(Datum::F64(1.0)));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["pour"; 10]);
+    assert_plan_reaches!(plan, goal);
+    // Ten pours of 0.1 don't add up to exactly 1.0
+    let water = final_state_of_plan(&plan.0).unwrap().data["water"];
+    assert_ne!(Datum::F64(1.0), water);
+}
+
+#[test]
+fn test_not_equals_uses_the_same_tolerance() {
+    let state = LocalState::new().with_datum("water", Datum::F64(0.1 + 0.2));
+
+    assert!(Goal::new()
+        .with_req("water", Compare::Equals(Datum::F64(0.3)))
+        .is_satisfied(&state));
+    assert!(!Goal::new()
+        .with_req("water", Compare::NotEquals(Datum::F64(0.3)))
+        .is_satisfied(&state));
+    assert!(Goal::new()
+        .with_req("water", Compare::NotEquals(Datum::F64(0.3001)))
+        .is_satisfied(&state));
+}
+
+#[test]
+fn test_approx_equals() {
+    let (start, actions) = bucket(0.25);
+    let goal = Goal::new().with_req("water", Compare::ApproxEquals(Datum::F64(1.0), 0.3));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    // Close enough after three pours
+    assert_plan_actions!(plan, ["pour"; 3]);
+    let goal = Goal::new().with_req("water", Compare::Equals(Datum::F64(1.0)));
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    assert_plan_actions!(plan, ["pour"; 4]);
+}
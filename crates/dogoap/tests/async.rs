@@ -0,0 +1,101 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use dogoap::{prelude::*, simple::simple_increment_action};
+
+fn mining() -> (LocalState, Arc<[Action]>) {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let actions: Arc<[Action]> = Arc::from(vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("rob", "gold", Datum::I64(5)).set_cost(3),
+    ]);
+    (start, actions)
+}
+
+fn gold_goal(amount: i64) -> Goal {
+    Goal::new().with_req("gold", Compare::Equals(Datum::I64(amount)))
+}
+
+#[tokio::test]
+async fn test_plan_async() {
+    let (start, actions) = mining();
+    let goal = gold_goal(7);
+
+    let plan = plan_async(
+        start.clone(),
+        actions.clone(),
+        goal.clone(),
+        PlannerConfig::new(),
+    )
+    .await;
+
+    assert_eq!(Ok(make_plan(&start, &actions, &goal)), plan);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_plan_many_goals_concurrently() {
+    let (start, actions) = mining();
+
+    let tasks: Vec<_> = (1..=8)
+        .map(|amount| {
+            tokio::spawn(plan_async(
+                start.clone(),
+                actions.clone(),
+                gold_goal(amount),
+                PlannerConfig::new(),
+            ))
+        })
+        .collect();
+
+    for (amount, task) in (1..=8).zip(tasks) {
+        let (plan, cost) = task.await.unwrap().unwrap().unwrap();
+        let reached = plan.last().unwrap().state().data.get("gold");
+        assert_eq!(Some(&Datum::I64(amount)), reached);
+        assert_eq!(
+            make_plan(&start, &actions, &gold_goal(amount)).unwrap().1,
+            cost
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_errors_come_back_through_the_future() {
+    let (start, _) = mining();
+    let actions: Arc<[Action]> = Arc::from(vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("mine", "gold", Datum::I64(2)),
+    ]);
+
+    let result = plan_async(start, actions, gold_goal(3), PlannerConfig::new()).await;
+
+    assert!(matches!(result, Err(PlanError::DuplicateActionKey(_))));
+}
+
+#[tokio::test]
+async fn test_dropping_the_future_cancels_the_search() {
+    let (start, actions) = mining();
+    let token = CancellationToken::new();
+    let config = PlannerConfig::new().with_cancellation(token.clone());
+
+    // Gold only goes up, so this search never ends on its own
+    let timed_out = tokio::time::timeout(
+        Duration::from_millis(20),
+        plan_async(start, actions, gold_goal(-1), config),
+    )
+    .await;
+
+    assert!(timed_out.is_err());
+    assert!(token.is_cancelled());
+}
+
+#[tokio::test]
+async fn test_finished_future_leaves_the_token_alone() {
+    let (start, actions) = mining();
+    let token = CancellationToken::new();
+    let config = PlannerConfig::new().with_cancellation(token.clone());
+
+    let plan = plan_async(start, actions, gold_goal(2), config).await;
+
+    assert!(plan.unwrap().is_some());
+    assert!(!token.is_cancelled());
+}
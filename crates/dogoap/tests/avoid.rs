@@ -0,0 +1,88 @@
+use dogoap::{prelude::*, simple::simple_multi_mutate_action, testing::*};
+
+const HOME: usize = 0;
+const ALLEY: usize = 1;
+const ROAD: usize = 2;
+const SHOP: usize = 3;
+
+// Cutting through the alley is short, but means getting into a fight on the way.
+// Taking the road is twice as long but safe.
+fn shop_run() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("at", Datum::Enum(HOME))
+        .with_datum("in_combat", Datum::Bool(false));
+    let go = |name: &str, from: usize, to: usize, in_combat: bool, cost: usize| {
+        simple_multi_mutate_action(
+            name,
+            vec![
+                ("at", Datum::Enum(to)),
+                ("in_combat", Datum::Bool(in_combat)),
+            ],
+        )
+        .with_precondition("at", Compare::Equals(Datum::Enum(from)))
+        .set_cost(cost)
+    };
+    let actions = vec![
+        go("enter_alley", HOME, ALLEY, true, 1),
+        go("leave_alley", ALLEY, SHOP, false, 1),
+        go("take_road", HOME, ROAD, false, 2),
+        go("leave_road", ROAD, SHOP, false, 2),
+    ];
+    let goal = Goal::new().with_req("at", Compare::Equals(Datum::Enum(SHOP)));
+    (start, actions, goal)
+}
+
+fn plan_avoiding_combat(penalty: usize) -> (Plan, PlanStats) {
+    let (start, actions, goal) = shop_run();
+    let config = PlannerConfig::new().with_avoid(
+        vec![("in_combat".to_string(), Compare::Equals(Datum::Bool(true)))],
+        penalty,
+    );
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+    (plan.unwrap(), stats)
+}
+
+#[test]
+fn test_no_avoid_rules_takes_the_short_path() {
+    let (start, actions, goal) = shop_run();
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &PlannerConfig::new());
+    let plan = plan.unwrap();
+
+    assert_plan_actions!(plan, ["enter_alley", "leave_alley"]);
+    assert_plan_cost!(plan, 2);
+    assert_eq!(0, stats.penalty);
+}
+
+#[test]
+fn test_small_penalty_still_takes_the_short_path() {
+    let (plan, stats) = plan_avoiding_combat(1);
+
+    assert_plan_actions!(plan, ["enter_alley", "leave_alley"]);
+    assert_plan_cost!(plan, 2);
+    assert_eq!(1, stats.penalty);
+}
+
+#[test]
+fn test_large_penalty_takes_the_safe_path() {
+    let (plan, stats) = plan_avoiding_combat(5);
+
+    assert_plan_actions!(plan, ["take_road", "leave_road"]);
+    assert_plan_cost!(plan, 4);
+    assert_eq!(0, stats.penalty);
+}
+
+#[test]
+fn test_avoid_rules_search_forwards() {
+    let (start, actions, goal) = shop_run();
+    let config = PlannerConfig::new()
+        .with_strategy(PlanningStrategy::GoalToStart)
+        .with_avoid(
+            vec![("in_combat".to_string(), Compare::Equals(Datum::Bool(true)))],
+            5,
+        );
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    assert_plan_actions!(plan.unwrap(), ["take_road", "leave_road"]);
+}
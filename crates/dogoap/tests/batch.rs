@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use dogoap::{prelude::*, simple::simple_increment_action};
+
+fn gold_requests() -> Vec<(LocalState, Goal)> {
+    (0..20)
+        .map(|i| {
+            let start = LocalState::new().with_datum("gold", Datum::I64(i % 5));
+            // Every fourth request asks for less gold than we start with, which
+            // can't be reached by only incrementing
+            let wanted = if i % 4 == 3 { -1 } else { 10 + i };
+            let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(wanted)));
+            (start, goal)
+        })
+        .collect()
+}
+
+#[test]
+fn test_plan_many_matches_make_plan_in_order() {
+    // Capping gold keeps the state space finite, so unreachable requests end too
+    let actions = vec![
+        simple_increment_action("mine", "gold", Datum::I64(1))
+            .with_precondition("gold", Compare::LessThanEquals(Datum::I64(40))),
+        simple_increment_action("rob", "gold", Datum::I64(5))
+            .set_cost(3)
+            .with_precondition("gold", Compare::LessThanEquals(Datum::I64(40))),
+    ];
+    let requests = gold_requests();
+
+    let plans = plan_many(&requests, &actions, &PlannerConfig::new());
+
+    assert_eq!(requests.len(), plans.len());
+    for ((start, goal), plan) in requests.iter().zip(plans) {
+        assert_eq!(make_plan(start, &actions, goal), plan);
+    }
+}
+
+#[test]
+fn test_plan_many_with_different_keys_matches_make_plan() {
+    let actions = vec![
+        simple_increment_action("mine", "gold", Datum::I64(1))
+            .with_precondition("gold", Compare::LessThanEquals(Datum::I64(40))),
+        simple_increment_action("rob", "gold", Datum::I64(5))
+            .set_cost(3)
+            .with_precondition("gold", Compare::LessThanEquals(Datum::I64(40))),
+    ];
+    // Only some of the agents have energy, so only some have the keys of the first
+    let requests: Vec<(LocalState, Goal)> = gold_requests()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (start, goal))| match i % 3 {
+            0 => (start.with_datum("energy", Datum::I64(0)), goal),
+            _ => (start, goal),
+        })
+        .collect();
+
+    let plans = plan_many(&requests, &actions, &PlannerConfig::new());
+
+    assert_eq!(requests.len(), plans.len());
+    for ((start, goal), plan) in requests.iter().zip(plans) {
+        assert_eq!(make_plan(start, &actions, goal), plan);
+    }
+}
+
+#[test]
+fn test_plan_many_gives_up_when_budget_runs_out() {
+    let actions = vec![simple_increment_action("mine", "gold", Datum::I64(1))];
+    let requests = gold_requests();
+
+    let config = PlannerConfig::new().with_time_budget(Duration::ZERO);
+    let plans = plan_many(&requests, &actions, &config);
+
+    assert_eq!(requests.len(), plans.len());
+    assert!(plans.iter().all(|plan| plan.is_none()));
+}
+
+#[test]
+fn test_plan_many_with_actions_matches_make_plan_in_order() {
+    let miner = vec![simple_increment_action("mine", "gold", Datum::I64(1))
+        .with_precondition("gold", Compare::LessThanEquals(Datum::I64(20)))];
+    let robber = vec![
+        simple_increment_action("rob", "gold", Datum::I64(3))
+            .with_precondition("gold", Compare::LessThanEquals(Datum::I64(20))),
+        simple_increment_action("beg", "gold", Datum::I64(1))
+            .set_cost(2)
+            .with_precondition("gold", Compare::LessThanEquals(Datum::I64(20))),
+    ];
+    let requests: Vec<(LocalState, Goal, &[Action])> = (0..1000)
+        .map(|i| {
+            let start = LocalState::new().with_datum("gold", Datum::I64(i % 7));
+            let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(10 + i % 5)));
+            let actions = if i % 2 == 0 { &miner } else { &robber };
+            (start, goal, actions.as_slice())
+        })
+        .collect();
+
+    let plans = plan_many_with_actions(&requests, &PlannerConfig::new());
+
+    assert_eq!(requests.len(), plans.len());
+    for ((start, goal, actions), plan) in requests.iter().zip(plans) {
+        assert_eq!(make_plan(start, actions, goal), plan);
+    }
+}
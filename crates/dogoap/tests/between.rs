@@ -0,0 +1,77 @@
+use dogoap::{
+    prelude::*,
+    simple::{simple_action, simple_decrement_action, simple_increment_action},
+    testing::*,
+};
+
+fn comfortable(low: i64, high: i64) -> Goal {
+    Goal::new().with_req(
+        "temperature",
+        Compare::Between(Datum::I64(low), Datum::I64(high)),
+    )
+}
+
+fn at(temperature: i64) -> LocalState {
+    LocalState::new().with_datum("temperature", Datum::I64(temperature))
+}
+
+#[test]
+fn test_distance_is_to_the_nearest_bound() {
+    let goal = comfortable(18, 22);
+
+    assert_eq!(5, at(13).distance_to_goal(&goal));
+    assert_eq!(0, at(18).distance_to_goal(&goal));
+    assert_eq!(0, at(20).distance_to_goal(&goal));
+    assert_eq!(0, at(22).distance_to_goal(&goal));
+    assert_eq!(3, at(25).distance_to_goal(&goal));
+}
+
+#[test]
+fn test_inverted_bounds_are_swapped() {
+    assert_eq!(5, at(13).distance_to_goal(&comfortable(22, 18)));
+    assert_eq!(0, at(20).distance_to_goal(&comfortable(22, 18)));
+    assert_eq!(
+        Some((&Datum::I64(18), &Datum::I64(22))),
+        Compare::Between(Datum::I64(22), Datum::I64(18)).bounds()
+    );
+}
+
+#[test]
+fn test_plan_into_a_range() {
+    let actions = vec![
+        simple_increment_action("heat", "temperature", Datum::I64(2)),
+        simple_decrement_action("cool", "temperature", Datum::I64(2)),
+    ];
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        for goal in [comfortable(18, 22), comfortable(22, 18)] {
+            let plan = make_plan_with_strategy(strategy, &at(13), &actions, &goal).unwrap();
+            assert_plan_actions!(plan, ["heat", "heat", "heat"]);
+            assert_plan_reaches!(plan, goal);
+
+            let plan = make_plan_with_strategy(strategy, &at(25), &actions, &goal).unwrap();
+            assert_plan_actions!(plan, ["cool", "cool"]);
+            assert_plan_reaches!(plan, goal);
+        }
+    }
+}
+
+#[test]
+fn test_range_as_a_precondition() {
+    let start = at(10).with_datum("baked", Datum::Bool(false));
+    let actions = vec![
+        simple_increment_action("heat", "temperature", Datum::I64(5)),
+        simple_action("bake", "baked", Datum::Bool(true)).with_precondition(
+            "temperature",
+            Compare::Between(Datum::I64(18), Datum::I64(22)),
+        ),
+    ];
+    let goal = Goal::new().with_req("baked", Compare::Equals(Datum::Bool(true)));
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let plan = make_plan_with_strategy(strategy, &start, &actions, &goal).unwrap();
+
+        // Heating twice overshoots to 20, three times to 25 is too hot
+        assert_plan_actions!(plan, ["heat", "heat", "bake"]);
+    }
+}
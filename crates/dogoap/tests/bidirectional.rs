@@ -0,0 +1,75 @@
+use dogoap::prelude::*;
+
+const TIERS: usize = 16;
+const CHORES: usize = 6;
+
+// Ore is smelted into the first tier, and each tier is worked into the next one
+// up. Chores can be done at any point and never help, but cost as much as a step
+// of the chain, so searching forwards tries every mix of them at every step
+fn crafting_chain() -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new().with_datum("ore", Datum::I64(1));
+    let mut actions = vec![];
+    let mut previous = "ore".to_string();
+    for tier in 0..TIERS {
+        let item = format!("tier_{}", tier);
+        start = start.with_datum(&item, Datum::I64(0));
+        actions.push(
+            Action::new(&format!("craft_{}", item))
+                .with_precondition(&previous, Compare::GreaterThanEquals(Datum::I64(1)))
+                .add_mutator(Mutator::Decrement(previous.clone(), Datum::I64(1)))
+                .add_mutator(Mutator::Increment(item.clone(), Datum::I64(1))),
+        );
+        previous = item;
+    }
+    for chore in 0..CHORES {
+        let done = format!("chore_{}", chore);
+        start = start.with_datum(&done, Datum::Bool(false));
+        actions.push(
+            Action::new(&format!("do_{}", done))
+                .add_mutator(Mutator::Set(done.clone(), Datum::Bool(true))),
+        );
+    }
+    let goal = Goal::new().with_req(&previous, Compare::GreaterThanEquals(Datum::I64(1)));
+    (start, actions, goal)
+}
+
+fn plan_with(strategy: PlanningStrategy, goal: Option<Goal>) -> (Option<Plan>, PlanStats) {
+    let (start, actions, chain_goal) = crafting_chain();
+    let config = PlannerConfig::new().with_strategy(strategy);
+    make_plan_with_stats(&start, &actions, &goal.unwrap_or(chain_goal), &config)
+}
+
+#[test]
+fn test_bidirectional_meets_in_the_middle() {
+    let (start, actions, goal) = crafting_chain();
+
+    let (forward, forward_stats) = plan_with(PlanningStrategy::StartToGoal, None);
+    let (_, backward_stats) = plan_with(PlanningStrategy::GoalToStart, None);
+    let (plan, stats) = plan_with(PlanningStrategy::Bidirectional, None);
+    let plan = plan.unwrap();
+
+    assert_eq!(PlanningStrategy::Bidirectional, stats.strategy);
+    assert!(stats.expanded_nodes < forward_stats.expanded_nodes);
+    assert!(stats.expanded_nodes < backward_stats.expanded_nodes);
+    assert!(validate_plan(&plan, &start, &actions, &goal));
+    // In the order to carry it out
+    let keys: Vec<&str> = action_keys_of_plan(&plan.0).collect();
+    let expected: Vec<String> = (0..TIERS)
+        .map(|tier| format!("craft_tier_{}", tier))
+        .collect();
+    assert_eq!(expected, keys);
+    assert_eq!(forward.unwrap(), plan);
+}
+
+#[test]
+fn test_bidirectional_without_a_plan() {
+    // There's only the one ore
+    let goal = Goal::new().with_req(
+        format!("tier_{}", TIERS - 1),
+        Compare::GreaterThanEquals(Datum::I64(2)),
+    );
+
+    let (plan, _) = plan_with(PlanningStrategy::Bidirectional, Some(goal));
+
+    assert_eq!(None, plan);
+}
@@ -0,0 +1,99 @@
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+// Gathering gives either a log or two sticks, whichever the plan asks for
+fn gathering() -> (LocalState, Vec<Action>) {
+    let start = LocalState::new()
+        .with_datum("wood", Datum::I64(0))
+        .with_datum("sticks", Datum::I64(0))
+        .with_datum("has_fire", Datum::Bool(false));
+    let actions = vec![
+        Action::new("gather_wood")
+            .add_mutator(Mutator::Increment("wood".to_string(), Datum::I64(1)))
+            .add_mutator_at(1, Mutator::Increment("sticks".to_string(), Datum::I64(2)))
+            .set_cost(2)
+            .set_cost_at(1, 3),
+        simple_action("light_fire", "has_fire", Datum::Bool(true))
+            .with_precondition("sticks", Compare::GreaterThanEquals(Datum::I64(2))),
+    ];
+    (start, actions)
+}
+
+fn sticks(amount: i64) -> Mutator {
+    Mutator::Increment("sticks".to_string(), Datum::I64(amount))
+}
+
+#[test]
+fn test_add_mutator_at_starts_a_new_effect() {
+    let (_, actions) = gathering();
+
+    assert_eq!(2, actions[0].effects.len());
+    assert_eq!("gather_wood", actions[0].effects[1].action);
+    assert_eq!(2, actions[0].effects[0].cost);
+    assert_eq!(3, actions[0].effects[1].cost);
+}
+
+#[test]
+fn test_plan_through_a_later_effect() {
+    let (start, actions) = gathering();
+    let goal = Goal::new().with_req("has_fire", Compare::Equals(Datum::Bool(true)));
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let plan = make_plan_with_strategy(strategy, &start, &actions, &goal).unwrap();
+
+        assert_plan_actions!(plan, ["gather_wood", "light_fire"]);
+        // The branch that was picked is the one that gives sticks, at its own cost
+        let gathered = effects_of_plan(&plan.0).next().unwrap();
+        assert_eq!(vec![sticks(2)], gathered.mutators.to_vec());
+        assert_plan_cost!(plan, 4);
+        assert_plan_reaches!(plan, goal);
+    }
+}
+
+#[test]
+fn test_each_branch_has_its_own_cost() {
+    let (start, actions) = gathering();
+    let goal = Goal::new().with_req("wood", Compare::Equals(Datum::I64(1)));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["gather_wood"]);
+    assert_plan_cost!(plan, 2);
+}
+
+#[test]
+fn test_branches_can_be_mixed_in_one_plan() {
+    let (start, actions) = gathering();
+    let goal = Goal::new()
+        .with_req("wood", Compare::Equals(Datum::I64(1)))
+        .with_req("sticks", Compare::Equals(Datum::I64(2)));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["gather_wood", "gather_wood"]);
+    assert_plan_cost!(plan, 5);
+    assert!(validate_plan(&plan, &start, &actions, &goal));
+}
+
+#[test]
+fn test_every_branch_is_explored() {
+    let start = LocalState::new().with_datum("wood", Datum::I64(0));
+    let actions = vec![Action::new("gather")
+        .add_mutator(Mutator::Increment("wood".to_string(), Datum::I64(1)))
+        .add_mutator_at(1, Mutator::Increment("wood".to_string(), Datum::I64(2)))
+        .add_mutator_at(2, Mutator::Increment("wood".to_string(), Datum::I64(3)))
+        .set_cost_at(1, 2)
+        .set_cost_at(2, 3)];
+    let goal = Goal::new().with_req("wood", Compare::Equals(Datum::I64(3)));
+    let config = PlannerConfig::new();
+
+    let (plan, trace) = make_plan_with_trace(&start, &actions, &goal, &config);
+
+    // Expanding the start leads somewhere different with each effect
+    let from_start = trace
+        .events
+        .iter()
+        .filter(|event| matches!(event, TraceEvent::SuccessorGenerated { parent: 0, .. }))
+        .count();
+    assert_eq!(3, from_start);
+    assert_plan_cost!(plan.unwrap(), 3);
+}
@@ -0,0 +1,181 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use dogoap::{prelude::*, simple::simple_increment_action};
+
+// Gold only goes up, so searching for negative gold never ends on its own
+fn endless() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let actions = vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("rob", "gold", Datum::I64(5)).set_cost(3),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(-1)));
+    (start, actions, goal)
+}
+
+/// Cancels `token` from another thread after a little while
+fn cancel_soon(token: &CancellationToken) -> thread::JoinHandle<()> {
+    let token = token.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        token.cancel();
+    })
+}
+
+#[test]
+fn test_cancel_from_another_thread() {
+    let (start, actions, goal) = endless();
+    let token = CancellationToken::new();
+    let config = PlannerConfig::new().with_cancellation(token.clone());
+
+    let started = Instant::now();
+    let canceller = cancel_soon(&token);
+    let result = try_make_plan_with_config(&start, &actions, &goal, &config);
+    canceller.join().unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    match result {
+        Err(PlanError::Cancelled(stats)) => {
+            assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+            assert!(stats.expanded_nodes > 0);
+        }
+        other => panic!("expected the search to be cancelled, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_cancelled_searches_without_errors_return_none() {
+    let (start, actions, goal) = endless();
+    let token = CancellationToken::new();
+    token.cancel();
+    let config = PlannerConfig::new().with_cancellation(token);
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(None, plan);
+    // Checked before the very first expansion
+    assert_eq!(0, stats.expanded_nodes);
+}
+
+#[test]
+fn test_check_interval() {
+    let (start, actions, goal) = endless();
+    let token = CancellationToken::new();
+    token.cancel();
+    let config = PlannerConfig::new()
+        .with_cancellation(token)
+        .with_check_interval(50);
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(None, plan);
+    // Checked before the 50th expansion
+    assert_eq!(49, stats.expanded_nodes);
+}
+
+#[test]
+fn test_uncancelled_token_changes_nothing() {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(3)));
+    let actions = [simple_increment_action("mine", "gold", Datum::I64(1))];
+    let config = PlannerConfig::new().with_cancellation(CancellationToken::new());
+
+    let plan = try_make_plan_with_config(&start, &actions, &goal, &config);
+
+    assert_eq!(Ok(make_plan(&start, &actions, &goal)), plan);
+}
+
+#[test]
+fn test_cancel_spawned_search_through_config() {
+    let (start, actions, goal) = endless();
+    let token = CancellationToken::new();
+    let config = PlannerConfig::new().with_cancellation(token.clone());
+
+    let handle = spawn_plan(start, Arc::from(actions), goal, config);
+    cancel_soon(&token).join().unwrap();
+
+    assert!(matches!(handle.wait(), Err(PlanError::Cancelled(_))));
+}
+
+#[test]
+fn test_cancel_plan_many() {
+    let (start, actions, goal) = endless();
+    let requests = vec![(start, goal); 4];
+    let token = CancellationToken::new();
+    let config = PlannerConfig::new().with_cancellation(token.clone());
+
+    let started = Instant::now();
+    let canceller = cancel_soon(&token);
+    let plans = plan_many(&requests, &actions, &config);
+    canceller.join().unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(vec![None; 4], plans);
+}
+
+#[test]
+fn test_make_plan_cancellable_on_a_worker_thread() {
+    let (start, actions, goal) = endless();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let started = Instant::now();
+    let worker = {
+        let cancelled = cancelled.clone();
+        thread::spawn(move || make_plan_cancellable(&start, &actions, &goal, &cancelled))
+    };
+    thread::sleep(Duration::from_millis(20));
+    cancelled.store(true, Ordering::Relaxed);
+    let result = worker.join().unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    match result {
+        Err(PlanError::Cancelled(stats)) => assert!(stats.expanded_nodes > 0),
+        other => panic!("expected the search to be cancelled, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_make_plan_cancellable_with_the_flag_already_set() {
+    let (start, actions, goal) = endless();
+    let cancelled = AtomicBool::new(true);
+
+    match make_plan_cancellable(&start, &actions, &goal, &cancelled) {
+        Err(PlanError::Cancelled(stats)) => assert_eq!(0, stats.expanded_nodes),
+        other => panic!("expected the search to be cancelled, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_make_plan_cancellable_without_cancelling() {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(3)));
+    let actions = [simple_increment_action("mine", "gold", Datum::I64(1))];
+
+    let plan = make_plan_cancellable(&start, &actions, &goal, &AtomicBool::new(false));
+
+    assert_eq!(try_make_plan(&start, &actions, &goal), plan);
+    assert_eq!(3, plan.unwrap().unwrap().1);
+}
+
+#[test]
+fn test_make_plan_cancellable_stops_when_flag_is_set() {
+    let (start, actions, goal) = endless();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    let started = Instant::now();
+    let canceller = {
+        let cancelled = cancelled.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            cancelled.store(true, Ordering::Relaxed);
+        })
+    };
+    let result = make_plan_cancellable(&start, &actions, &goal, &cancelled);
+    canceller.join().unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(matches!(result, Err(PlanError::Cancelled(_))));
+}
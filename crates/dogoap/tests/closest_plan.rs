@@ -0,0 +1,83 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// Eating needs at least some hunger, so it never gets below 10
+fn eating() -> (LocalState, Vec<Action>) {
+    let start = LocalState::new()
+        .with_datum("hunger", Datum::I64(50))
+        .with_datum("is_clean", Datum::Bool(false));
+    let actions = vec![
+        simple_decrement_action("eat", "hunger", Datum::I64(10))
+            .with_precondition("hunger", Compare::GreaterThanEquals(Datum::I64(20))),
+        simple_action("wash", "is_clean", Datum::Bool(true)),
+    ];
+    (start, actions)
+}
+
+#[test]
+fn test_unreachable_goal_returns_the_closest_plan() {
+    let (start, actions) = eating();
+    let goal = Goal::new().with_req("hunger", Compare::Equals(Datum::I64(0)));
+    assert!(make_plan(&start, &actions, &goal).is_none());
+
+    let closest = make_plan_or_closest(&start, &actions, &goal, &PlannerConfig::new()).unwrap();
+
+    assert!(!closest.is_complete());
+    let plan = (closest.nodes.clone(), closest.cost);
+    assert_plan_actions!(plan, ["eat", "eat", "eat", "eat"]);
+    assert_plan_cost!(plan, 4);
+    let reached = final_state_of_plan(&closest.nodes).unwrap();
+    assert_eq!(Some(&Datum::I64(10)), reached.data.get("hunger"));
+    assert_eq!(
+        vec![("hunger".to_string(), Compare::Equals(Datum::I64(0)))],
+        closest.unmet
+    );
+}
+
+#[test]
+fn test_only_the_unmet_requirements_are_listed() {
+    let (start, actions) = eating();
+    let goal = Goal::new()
+        .with_req("hunger", Compare::Equals(Datum::I64(0)))
+        .with_req("is_clean", Compare::Equals(Datum::Bool(true)));
+
+    let closest = make_plan_or_closest(&start, &actions, &goal, &PlannerConfig::new()).unwrap();
+
+    let reached = final_state_of_plan(&closest.nodes).unwrap();
+    assert_eq!(Some(&Datum::Bool(true)), reached.data.get("is_clean"));
+    assert_eq!(
+        vec![("hunger".to_string(), Compare::Equals(Datum::I64(0)))],
+        closest.unmet
+    );
+}
+
+#[test]
+fn test_reachable_goal_returns_the_complete_plan() {
+    let (start, actions) = eating();
+    let goal = Goal::new().with_req("hunger", Compare::LessThanEquals(Datum::I64(20)));
+
+    let closest = make_plan_or_closest(&start, &actions, &goal, &PlannerConfig::new()).unwrap();
+
+    assert!(closest.is_complete());
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    assert_eq!(plan.0, closest.nodes);
+    assert_eq!(plan.1, closest.cost);
+}
+
+#[test]
+fn test_nothing_closer_returns_just_the_start() {
+    let (start, actions) = eating();
+    // Eating only takes hunger further away
+    let goal = Goal::new().with_req("hunger", Compare::GreaterThanEquals(Datum::I64(60)));
+
+    let closest = make_plan_or_closest(&start, &actions, &goal, &PlannerConfig::new()).unwrap();
+
+    assert_eq!(vec![Node::State(start)], closest.nodes);
+    assert_eq!(0, closest.cost);
+    assert_eq!(
+        vec![(
+            "hunger".to_string(),
+            Compare::GreaterThanEquals(Datum::I64(60))
+        )],
+        closest.unmet
+    );
+}
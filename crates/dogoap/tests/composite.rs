@@ -0,0 +1,94 @@
+use dogoap::prelude::*;
+
+fn cooking() -> Vec<Action> {
+    vec![
+        Action::new("gather_wood")
+            .add_mutator(Mutator::Increment("wood".to_string(), Datum::I64(1))),
+        Action::new("light_fire")
+            .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(2)))
+            .add_mutator(Mutator::Decrement("wood".to_string(), Datum::I64(2)))
+            .add_mutator(Mutator::Set("fire".to_string(), Datum::Bool(true))),
+        Action::new("cook")
+            .with_precondition("fire", Compare::Equals(Datum::Bool(true)))
+            .add_mutator(Mutator::Set(
+                "has_cooked_food".to_string(),
+                Datum::Bool(true),
+            ))
+            .set_cost(2),
+    ]
+}
+
+// Preparing a meal is one step up here, however many it takes to get there
+fn village() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("wood", Datum::I64(0))
+        .with_datum("fire", Datum::Bool(false))
+        .with_datum("has_cooked_food", Datum::Bool(false))
+        .with_datum("hungry", Datum::Bool(true));
+    let actions = vec![
+        Action::new("prepare_meal").with_subgoal(
+            Goal::new().with_req("has_cooked_food", Compare::Equals(Datum::Bool(true))),
+            cooking(),
+        ),
+        Action::new("eat")
+            .with_precondition("has_cooked_food", Compare::Equals(Datum::Bool(true)))
+            .add_mutator(Mutator::Set(
+                "has_cooked_food".to_string(),
+                Datum::Bool(false),
+            ))
+            .add_mutator(Mutator::Set("hungry".to_string(), Datum::Bool(false))),
+    ];
+    let goal = Goal::new().with_req("hungry", Compare::Equals(Datum::Bool(false)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_composite_step_costs_its_sub_plan() {
+    let (start, actions, goal) = village();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    let keys: Vec<&str> = action_keys_of_plan(&plan.0).collect();
+    assert_eq!(vec!["prepare_meal", "eat"], keys);
+    // Two gathers, lighting the fire and cooking, then eating
+    assert_eq!(6, plan.1);
+    let prepared = plan.0[1].state();
+    assert_eq!(Some(&Datum::Bool(true)), prepared.data.get("fire"));
+    assert_eq!(Some(&Datum::I64(0)), prepared.data.get("wood"));
+    assert!(validate_plan(&plan, &start, &actions, &goal));
+}
+
+#[test]
+fn test_flattened_plan_is_primitive() {
+    let (start, actions, goal) = village();
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    let flat = flatten_plan(&plan.0, &actions);
+
+    let keys: Vec<&str> = action_keys_of_plan(&flat).collect();
+    assert_eq!(
+        vec!["gather_wood", "gather_wood", "light_fire", "cook", "eat"],
+        keys
+    );
+    let mut primitive = cooking();
+    primitive.push(actions[1].clone());
+    assert!(validate_plan(
+        &(flat.clone(), plan.1),
+        &start,
+        &primitive,
+        &goal
+    ));
+    assert_eq!(plan.0.last().unwrap().state(), flat.last().unwrap().state());
+}
+
+#[test]
+fn test_composite_needs_a_sub_plan() {
+    let (start, mut actions, goal) = village();
+    // Without gathering there's no wood for the fire
+    actions[0] = Action::new("prepare_meal").with_subgoal(
+        Goal::new().with_req("has_cooked_food", Compare::Equals(Datum::Bool(true))),
+        cooking()[1..].to_vec(),
+    );
+
+    assert_eq!(None, make_plan(&start, &actions, &goal));
+}
@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use dogoap::{prelude::*, simple::simple_increment_action, testing::*};
+
+fn count(state: &LocalState, key: &str) -> i64 {
+    match state.data[key] {
+        Datum::I64(count) => count,
+        _ => 0,
+    }
+}
+
+// Swords weigh 3, shields 2 and potions 1
+fn weight(state: &LocalState) -> Datum {
+    Datum::I64(3 * count(state, "swords") + 2 * count(state, "shields") + count(state, "potions"))
+}
+
+// Picking something up is only possible while it keeps the weight at 10 or below
+fn backpack() -> (LocalState, Vec<Action>, PlannerConfig) {
+    let start = LocalState::new()
+        .with_datum("swords", Datum::I64(2))
+        .with_datum("shields", Datum::I64(0))
+        .with_datum("potions", Datum::I64(0));
+    let room_for = |item_weight: i64| Compare::LessThanEquals(Datum::I64(10 - item_weight));
+    let actions = vec![
+        simple_increment_action("pick_shield", "shields", Datum::I64(1))
+            .with_precondition("weight", room_for(2)),
+        simple_increment_action("pick_potion", "potions", Datum::I64(1))
+            .with_precondition("weight", room_for(1)),
+        Action::new("drop_sword")
+            .with_precondition("swords", Compare::GreaterThanEquals(Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("swords".to_string(), Datum::I64(1))),
+    ];
+    let config = PlannerConfig::new().with_computed_key("weight", Arc::new(weight));
+    (start, actions, config)
+}
+
+#[test]
+fn test_weight_limit_from_three_keys() {
+    let (start, actions, config) = backpack();
+    let goal = Goal::new()
+        .with_req("shields", Compare::Equals(Datum::I64(2)))
+        .with_req("potions", Compare::Equals(Datum::I64(2)));
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    // Two shields and two potions only fit after dropping a sword
+    assert_plan_cost!(plan, 5);
+    let drops = plan
+        .0
+        .iter()
+        .filter(|node| node.action_key() == Some("drop_sword"))
+        .count();
+    assert_eq!(1, drops);
+    assert!(plan
+        .0
+        .iter()
+        .all(|node| weight(node.state()) <= Datum::I64(10)));
+    // Computed keys are never stored in the states of the plan
+    assert!(!plan.0.last().unwrap().state().data.contains_key("weight"));
+}
+
+#[test]
+fn test_goal_on_a_computed_key() {
+    let (start, actions, config) = backpack();
+    let goal = Goal::new().with_req("weight", Compare::Equals(Datum::I64(0)));
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_plan_actions!(plan.unwrap(), ["drop_sword", "drop_sword"]);
+    assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+}
+
+#[test]
+fn test_stored_key_takes_precedence() {
+    let (start, actions, config) = backpack();
+    // An empty stored weight lets everything be picked up, whatever it weighs
+    let start = start.with_datum("weight", Datum::I64(0));
+    let goal = Goal::new()
+        .with_req("shields", Compare::Equals(Datum::I64(2)))
+        .with_req("potions", Compare::Equals(Datum::I64(2)));
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    assert_plan_cost!(plan, 4);
+}
+
+#[test]
+fn test_mutating_a_computed_key_is_rejected() {
+    let (start, mut actions, config) = backpack();
+    actions.push(simple_increment_action("lighten", "weight", Datum::I64(-1)));
+    let goal = Goal::new().with_req("potions", Compare::Equals(Datum::I64(1)));
+
+    let result = try_make_plan_with_config(&start, &actions, &goal, &config);
+
+    assert_eq!(
+        Err(PlanError::MutatesComputedKey("lighten".to_string())),
+        result
+    );
+}
@@ -0,0 +1,108 @@
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+// Searching the chest means prying it open, which leaves us holding the crowbar.
+// Whether the key was inside is only known afterwards.
+fn locked_door() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("chest_searched", Datum::Bool(false))
+        .with_datum("has_crowbar", Datum::Bool(false))
+        .with_datum("found_key", Datum::Bool(false))
+        .with_datum("door_open", Datum::Bool(false));
+    let actions = vec![
+        simple_action("search_chest", "chest_searched", Datum::Bool(true))
+            .with_precondition("chest_searched", Compare::Equals(Datum::Bool(false)))
+            .add_mutator(Mutator::Set("has_crowbar".to_string(), Datum::Bool(true)))
+            .with_uncertain("found_key", [Datum::Bool(true), Datum::Bool(false)]),
+        simple_action("unlock_door", "door_open", Datum::Bool(true))
+            .with_precondition("found_key", Compare::Equals(Datum::Bool(true))),
+        simple_action("pry_door", "door_open", Datum::Bool(true))
+            .with_precondition("has_crowbar", Compare::Equals(Datum::Bool(true)))
+            .set_cost(3),
+    ];
+    let goal = Goal::new().with_req("door_open", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+fn plan_tree(max_branching: usize) -> Option<PlanTree> {
+    let (start, actions, goal) = locked_door();
+    make_contingent_plan(
+        &start,
+        &actions,
+        &goal,
+        &PlannerConfig::new(),
+        max_branching,
+    )
+}
+
+fn observed(found_key: bool) -> LocalState {
+    LocalState::new()
+        .with_datum("chest_searched", Datum::Bool(true))
+        .with_datum("has_crowbar", Datum::Bool(true))
+        .with_datum("found_key", Datum::Bool(found_key))
+        .with_datum("door_open", Datum::Bool(false))
+}
+
+#[test]
+fn test_contingent_plan_forks_on_uncertain_action() {
+    let tree = plan_tree(1).unwrap();
+
+    assert_plan_actions!(tree.plan, []);
+    let branches = tree.branches.as_ref().unwrap();
+    assert_eq!("search_chest", branches.action);
+    assert_eq!(2, branches.outcomes.len());
+    for (outcome, branch) in &branches.outcomes {
+        assert_eq!(outcome.state.as_ref(), Some(branch.plan.0[0].state()));
+        assert_eq!(None, branch.branches);
+    }
+}
+
+#[test]
+fn test_branch_for_observed_state() {
+    let tree = plan_tree(1).unwrap();
+
+    let found = tree.branch_for(&observed(true)).unwrap();
+    assert_plan_actions!(found.plan, ["unlock_door"]);
+    assert_plan_reaches!(found.plan, locked_door().2);
+
+    let not_found = tree.branch_for(&observed(false)).unwrap();
+    assert_plan_actions!(not_found.plan, ["pry_door"]);
+    assert_plan_reaches!(not_found.plan, locked_door().2);
+
+    // Leaves have nothing to branch to
+    assert_eq!(None, found.branch_for(&observed(true)));
+}
+
+#[test]
+fn test_worst_case_cost_and_depth() {
+    let tree = plan_tree(3).unwrap();
+
+    // Searching, then prying the door open when there was no key
+    assert_eq!(4, tree.worst_case_cost());
+    assert_eq!(1, tree.depth());
+}
+
+#[test]
+fn test_branching_bound() {
+    assert_eq!(None, plan_tree(0));
+}
+
+#[test]
+fn test_no_fork_when_the_outcome_is_known() {
+    let (start, actions, goal) = locked_door();
+    let start = start.with_datum("found_key", Datum::Bool(true));
+
+    let tree = make_contingent_plan(&start, &actions, &goal, &PlannerConfig::new(), 1).unwrap();
+
+    assert_plan_actions!(tree.plan, ["unlock_door"]);
+    assert_eq!(None, tree.branches);
+    assert_eq!(1, tree.worst_case_cost());
+}
+
+#[test]
+fn test_other_planners_expect_the_first_outcome() {
+    let (start, actions, goal) = locked_door();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["search_chest", "unlock_door"]);
+}
@@ -0,0 +1,64 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+const FOREST: usize = 0;
+const CAMP: usize = 1;
+const CAVE: usize = 2;
+
+fn scout() -> (LocalState, Vec<Action>) {
+    let start = LocalState::new()
+        .with_datum("current_position", Datum::Enum(CAVE))
+        .with_datum("last_known_position", Datum::Enum(FOREST));
+    let actions = vec![
+        simple_action("walk_to_camp", "current_position", Datum::Enum(CAMP)),
+        Action::new("report_position").add_mutator(Mutator::CopyFrom(
+            "last_known_position".to_string(),
+            "current_position".to_string(),
+        )),
+    ];
+    (start, actions)
+}
+
+#[test]
+fn test_copy_from() {
+    let (start, _) = scout();
+    let effect = Effect::new("report").with_mutator(Mutator::CopyFrom(
+        "last_known_position".to_string(),
+        "current_position".to_string(),
+    ));
+
+    let state = effect.apply(&start, MissingKeyPolicy::Insert).unwrap();
+
+    assert_eq!(
+        state.data.get("current_position"),
+        state.data.get("last_known_position")
+    );
+    assert_eq!(Some(&Datum::Enum(CAVE)), state.data.get("current_position"));
+}
+
+#[test]
+fn test_copy_from_missing_key_does_nothing() {
+    let (start, _) = scout();
+    let effect = Effect::new("report").with_mutator(Mutator::CopyFrom(
+        "last_known_position".to_string(),
+        "target_position".to_string(),
+    ));
+
+    let state = effect.apply(&start, MissingKeyPolicy::Insert).unwrap();
+
+    assert_eq!(start, state);
+    let copy: Mutator = Mutator::CopyFrom("a".to_string(), "b".to_string());
+    assert_eq!("a = b", copy.to_string());
+}
+
+#[test]
+fn test_plan_with_copy_from() {
+    let (start, actions) = scout();
+    let goal = Goal::new().with_req("last_known_position", Compare::Equals(Datum::Enum(CAMP)));
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let plan = make_plan_with_strategy(strategy, &start, &actions, &goal).unwrap();
+
+        assert_plan_actions!(plan, ["walk_to_camp", "report_position"]);
+        assert_plan_reaches!(plan, goal);
+    }
+}
@@ -0,0 +1,75 @@
+use dogoap::{prelude::*, simple::simple_increment_action, testing::*};
+
+// Costs are in centimeters: a step is 1.25m, a leap covers three steps' worth of
+// ground for 4m
+fn walking() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new().with_datum("position", Datum::I64(0));
+    let actions = vec![
+        simple_increment_action("step", "position", Datum::I64(1)).set_cost(125),
+        simple_increment_action("leap", "position", Datum::I64(3)).set_cost(400),
+    ];
+    let goal = Goal::new().with_req("position", Compare::Equals(Datum::I64(10)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_scaled_costs_find_the_cheapest_plan() {
+    let (start, actions, goal) = walking();
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let config = PlannerConfig::new()
+            .with_strategy(strategy)
+            .with_cost_scale(100);
+        let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+        assert_plan_cost!(plan, 1250);
+        assert_plan_reaches!(plan, goal);
+    }
+}
+
+#[test]
+fn test_cost_scale_keeps_the_heuristic_informed() {
+    let (start, actions, goal) = walking();
+    let unscaled = PlannerConfig::new().with_strategy(PlanningStrategy::StartToGoal);
+    let scaled = unscaled.clone().with_cost_scale(100);
+
+    let (plan, unscaled_stats) = make_plan_with_stats(&start, &actions, &goal, &unscaled);
+    let (scaled_plan, scaled_stats) = make_plan_with_stats(&start, &actions, &goal, &scaled);
+
+    assert_eq!(plan.unwrap().1, scaled_plan.unwrap().1);
+    assert!(scaled_stats.expanded_nodes < unscaled_stats.expanded_nodes);
+}
+
+#[test]
+fn test_fractional_costs_round_to_the_scale() {
+    let cost_of = |cost: f32, cost_scale: usize| {
+        simple_increment_action("step", "position", Datum::I64(1))
+            .set_cost_f32(cost, cost_scale)
+            .effects[0]
+            .cost
+    };
+
+    assert_eq!(125, cost_of(1.25, 100));
+    assert_eq!(12, cost_of(1.24, 10));
+    assert_eq!(13, cost_of(1.26, 10));
+    // Halves round up
+    assert_eq!(3, cost_of(2.5, 1));
+    assert_eq!(2, cost_of(0.375, 4));
+    assert_eq!(0, cost_of(0.49, 1));
+    assert_eq!(0, cost_of(-1.5, 100));
+}
+
+#[test]
+fn test_fractional_costs_plan_like_scaled_ones() {
+    let (start, _, goal) = walking();
+    let actions = vec![
+        simple_increment_action("step", "position", Datum::I64(1)).set_cost_f32(1.25, 100),
+        simple_increment_action("leap", "position", Datum::I64(3)).set_cost_f32(4.0, 100),
+    ];
+    let config = PlannerConfig::new().with_cost_scale(100);
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    assert_plan_cost!(plan, 1250);
+    assert_plan_reaches!(plan, goal);
+}
@@ -0,0 +1,80 @@
+use dogoap::{prelude::*, simple::simple_increment_action, testing::*};
+
+// Mining is cheap per step, digging moves more gold for its cost
+fn mining() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let actions = vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("dig", "gold", Datum::I64(5)).set_cost(3),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(10)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_zero_heuristic_finds_the_cheapest_plan() {
+    let (start, actions, goal) = mining();
+
+    let plan = make_plan_with_heuristic(&start, &actions, &goal, |_, _| 0).unwrap();
+
+    assert_plan_actions!(plan, ["dig", "dig"]);
+    assert_eq!(make_plan(&start, &actions, &goal), Some(plan));
+}
+
+#[test]
+fn test_weighted_heuristic_still_returns_a_valid_plan() {
+    let (start, actions, goal) = mining();
+    let weighted = |state: &LocalState, goal: &Goal| state.distance_to_goal(goal) as usize * 5;
+
+    let plan = make_plan_with_heuristic(&start, &actions, &goal, weighted).unwrap();
+
+    assert_plan_reaches!(plan, goal);
+    assert!(validate_plan(&plan, &start, &actions, &goal));
+    assert!(plan.1 >= make_plan(&start, &actions, &goal).unwrap().1);
+}
+
+#[test]
+fn test_heuristic_steers_the_search() {
+    let (start, actions, goal) = mining();
+    let expanded = |config: &PlannerConfig| {
+        let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, config);
+        assert!(plan.is_some());
+        stats.expanded_nodes
+    };
+
+    let blind = expanded(&PlannerConfig::new().with_heuristic(|_, _| 0));
+    let informed = expanded(
+        &PlannerConfig::new()
+            .with_heuristic(|state, goal| state.distance_to_goal(goal) as usize * 3 / 5),
+    );
+
+    assert!(informed < blind);
+}
+
+#[test]
+fn test_domain_knowledge_expands_fewer_nodes() {
+    // Wood costs at least 3 a piece, chopping 10 at a time, so the built-in
+    // heuristic of 1 per missing piece underestimates a lot
+    let start = LocalState::new().with_datum("wood", Datum::I64(0));
+    let actions = vec![
+        simple_increment_action("chop", "wood", Datum::I64(10)).set_cost(30),
+        simple_increment_action("gather", "wood", Datum::I64(1)).set_cost(5),
+    ];
+    let goal = Goal::new().with_req("wood", Compare::Equals(Datum::I64(30)));
+    let config = PlannerConfig::new();
+    let wood_left = |state: &LocalState, goal: &Goal| match (
+        state.data["wood"],
+        goal.requirements["wood"].value(),
+    ) {
+        (Datum::I64(have), Datum::I64(want)) => (want - have).max(0) as usize * 3,
+        _ => 0,
+    };
+
+    let built_in = make_plan_detailed(&start, &actions, &goal, &config).unwrap();
+    let custom = config.clone().with_heuristic(wood_left);
+    let informed = make_plan_detailed(&start, &actions, &goal, &custom).unwrap();
+
+    assert_eq!(built_in.nodes, informed.nodes);
+    assert_eq!(built_in.cost, informed.cost);
+    assert!(informed.expanded_nodes < built_in.expanded_nodes);
+}
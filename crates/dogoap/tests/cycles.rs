@@ -0,0 +1,91 @@
+use dogoap::{prelude::*, simple::simple_action};
+
+fn holding(held: bool) -> Compare {
+    Compare::Equals(Datum::Bool(held))
+}
+
+fn pick_up_put_down(free: bool) -> (LocalState, Vec<Action>) {
+    let start = LocalState::new()
+        .with_datum("holding", Datum::Bool(false))
+        .with_datum("done", Datum::Bool(false));
+    let mut actions = vec![
+        simple_action("pick_up", "holding", Datum::Bool(true))
+            .with_precondition("holding", holding(false)),
+        simple_action("put_down", "holding", Datum::Bool(false))
+            .with_precondition("holding", holding(true)),
+        simple_action("finish", "done", Datum::Bool(true))
+            .with_precondition("holding", holding(true)),
+    ];
+    if free {
+        actions[0] = actions[0].clone().set_free();
+        actions[1] = actions[1].clone().set_free();
+    }
+    (start, actions)
+}
+
+#[test]
+fn test_finds_free_pick_up_and_put_down() {
+    let (start, actions) = pick_up_put_down(true);
+
+    assert_eq!(
+        vec![ZeroCostCycle {
+            actions: vec!["pick_up".to_string(), "put_down".to_string()],
+            keys: vec!["holding".to_string()],
+        }],
+        find_zero_cost_cycles(&start, &actions)
+    );
+}
+
+#[test]
+fn test_actions_that_cost_something_are_fine() {
+    let (start, actions) = pick_up_put_down(false);
+
+    assert_eq!(
+        Vec::<ZeroCostCycle>::new(),
+        find_zero_cost_cycles(&start, &actions)
+    );
+}
+
+#[test]
+fn test_free_actions_without_a_way_back_are_fine() {
+    let (start, mut actions) = pick_up_put_down(false);
+    actions[0] = actions[0].clone().set_free();
+    actions[2] = actions[2].clone().set_free();
+
+    assert_eq!(
+        Vec::<ZeroCostCycle>::new(),
+        find_zero_cost_cycles(&start, &actions)
+    );
+}
+
+#[test]
+fn test_finds_longer_cycles() {
+    let start = LocalState::new().with_datum("at", Datum::Enum(0));
+    let go = |name: &str, from: usize, to: usize| {
+        simple_action(name, "at", Datum::Enum(to))
+            .with_precondition("at", Compare::Equals(Datum::Enum(from)))
+            .set_free()
+    };
+    let actions = vec![go("a_to_b", 0, 1), go("b_to_c", 1, 2), go("c_to_a", 2, 0)];
+
+    let cycles = find_zero_cost_cycles(&start, &actions);
+
+    assert_eq!(1, cycles.len());
+    assert_eq!(vec!["a_to_b", "b_to_c", "c_to_a"], cycles[0].actions);
+    assert_eq!(vec!["at".to_string()], cycles[0].keys);
+    assert_eq!(
+        "actions [\"a_to_b\", \"b_to_c\", \"c_to_a\"] cost nothing and lead back to where they started, changing [\"at\"] back and forth",
+        cycles[0].to_string()
+    );
+}
+
+#[test]
+fn test_planning_still_works_with_free_cycles() {
+    let (start, actions) = pick_up_put_down(true);
+    let goal = Goal::new().with_req("done", Compare::Equals(Datum::Bool(true)));
+
+    let (nodes, cost) = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_eq!(3, nodes.len());
+    assert_eq!(1, cost);
+}
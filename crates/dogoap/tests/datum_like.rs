@@ -0,0 +1,95 @@
+use std::fmt;
+
+use dogoap::{prelude::*, testing::*};
+
+/// Fixed-point money, in cents
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
+struct Cents(i128);
+
+impl DatumLike for Cents {
+    type Kind = ();
+
+    fn kind(&self) {}
+
+    fn distance(&self, other: &Cents) -> u64 {
+        self.0.abs_diff(other.0).div_ceil(100) as u64
+    }
+
+    fn magnitude(&self) -> u64 {
+        self.0.unsigned_abs().div_ceil(100) as u64
+    }
+
+    fn checked_add(&self, other: &Cents) -> Option<Cents> {
+        self.0.checked_add(other.0).map(Cents)
+    }
+
+    fn checked_sub(&self, other: &Cents) -> Option<Cents> {
+        self.0.checked_sub(other.0).map(Cents)
+    }
+}
+
+impl fmt::Display for Cents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+type Domain = (
+    LocalState<String, Cents>,
+    Vec<Action<String, Cents>>,
+    Goal<String, Cents>,
+);
+
+fn shop() -> Domain {
+    let start = LocalState::default()
+        .with_datum("money", Cents(0))
+        .with_datum("debt", Cents(250));
+    let actions = vec![
+        Action::named("work").add_mutator(Mutator::Increment("money".to_string(), Cents(120))),
+        Action::named("pay_off")
+            .with_precondition("money", Compare::GreaterThanEquals(Cents(125)))
+            .add_mutator(Mutator::Decrement("money".to_string(), Cents(125)))
+            .add_mutator(Mutator::Decrement("debt".to_string(), Cents(125))),
+    ];
+    let goal = Goal::default().with_req("debt", Compare::LessThanEquals(Cents(0)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_custom_datum() {
+    let (start, actions, goal) = shop();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["work", "work", "pay_off", "work", "pay_off"]);
+    assert_plan_reaches!(plan, goal);
+    assert_eq!(
+        Some(&Cents(110)),
+        plan.0.last().unwrap().state().data.get("money")
+    );
+}
+
+#[test]
+fn test_custom_datum_with_every_strategy() {
+    let (start, actions, goal) = shop();
+
+    for strategy in [
+        PlanningStrategy::StartToGoal,
+        PlanningStrategy::GoalToStart,
+        PlanningStrategy::Auto,
+    ] {
+        let plan = make_plan_with_strategy(strategy, &start, &actions, &goal).unwrap();
+        assert_plan_cost!(plan, 5);
+        assert_plan_reaches!(plan, goal);
+    }
+}
+
+#[test]
+fn test_custom_datum_typechecks() {
+    let (start, actions, goal) = shop();
+
+    assert_eq!(
+        Vec::<TypeIssue<String, Cents>>::new(),
+        typecheck_domain(&start, &actions, &goal)
+    );
+}
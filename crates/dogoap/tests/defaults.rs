@@ -0,0 +1,52 @@
+use dogoap::{prelude::*, simple::simple_increment_action, testing::*};
+
+// `kills_today` doesn't exist until the first kill
+fn hunt() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new().with_datum("has_weapon", Datum::Bool(true));
+    let actions = vec![
+        simple_increment_action("kill", "kills_today", Datum::I64(1))
+            .with_precondition("has_weapon", Compare::Equals(Datum::Bool(true)))
+            .with_precondition("kills_today", Compare::LessThanEquals(Datum::I64(5))),
+    ];
+    let goal = Goal::new().with_req("kills_today", Compare::GreaterThanEquals(Datum::I64(2)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_missing_keys_use_defaults() {
+    let (start, actions, goal) = hunt();
+    let config = PlannerConfig::new().with_default("kills_today", Datum::I64(0));
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    assert_plan_actions!(plan, ["kill", "kill"]);
+    assert_plan_reaches!(plan, goal);
+    // The defaulted key shows up in the states of the plan too
+    assert_eq!(
+        Some(&Datum::I64(0)),
+        plan.0[0].state().data.get("kills_today")
+    );
+    assert_eq!(
+        Some(&Datum::I64(2)),
+        plan.0[2].state().data.get("kills_today")
+    );
+}
+
+#[test]
+fn test_defaults_dont_override_the_start_state() {
+    let (start, actions, goal) = hunt();
+    let start = start.with_datum("kills_today", Datum::I64(1));
+    let config = PlannerConfig::new().with_default("kills_today", Datum::I64(0));
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    assert_plan_actions!(plan, ["kill"]);
+}
+
+#[test]
+#[should_panic(expected = "Couldn't find key \"kills_today\" in LocalState")]
+fn test_missing_keys_without_defaults_are_strict() {
+    let (start, actions, goal) = hunt();
+
+    make_plan_with_config(&start, &actions, &goal, &PlannerConfig::new());
+}
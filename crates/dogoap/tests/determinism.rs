@@ -0,0 +1,91 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// Plenty of equally cheap ways to get rich: any mix of mining, chopping and
+// fishing, in any order
+fn fortune() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("has_boat", Datum::Bool(false));
+    let actions = vec![
+        simple_increment_action("mine", "gold", Datum::I64(2)).set_cost(2),
+        simple_increment_action("chop", "gold", Datum::I64(1)),
+        simple_increment_action("fish", "gold", Datum::I64(1))
+            .with_precondition("has_boat", Compare::Equals(Datum::Bool(true))),
+        simple_action("build_boat", "has_boat", Datum::Bool(true)),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(6)));
+    (start, actions, goal)
+}
+
+fn plan_text(config: &PlannerConfig) -> String {
+    let (start, actions, goal) = fortune();
+    let plan = make_plan_with_config(&start, &actions, &goal, config).unwrap();
+    format_plan(&plan)
+}
+
+#[test]
+fn test_same_plan_every_time() {
+    for config in [
+        PlannerConfig::new(),
+        PlannerConfig::new().with_strategy(PlanningStrategy::GoalToStart),
+        PlannerConfig::new().with_tie_seed(7),
+    ] {
+        let first = plan_text(&config);
+
+        for _ in 0..100 {
+            assert_eq!(first, plan_text(&config));
+        }
+    }
+}
+
+#[test]
+fn test_same_nodes_whatever_order_the_state_was_built_in() {
+    let (_, actions, goal) = fortune();
+    let keys = [
+        ("gold", Datum::I64(0)),
+        ("has_boat", Datum::Bool(false)),
+        ("hunger", Datum::I64(3)),
+        ("wood", Datum::I64(1)),
+    ];
+    let plan = |run: usize| {
+        let mut start = LocalState::new();
+        for i in 0..keys.len() {
+            let (key, value) = &keys[(i + run) % keys.len()];
+            start = start.with_datum(*key, *value);
+        }
+        make_plan(&start, &actions, &goal).unwrap()
+    };
+
+    let first = plan(0);
+    for run in 1..100 {
+        assert_eq!(first, plan(run));
+    }
+}
+
+#[test]
+fn test_same_plan_on_every_thread() {
+    let first = plan_text(&PlannerConfig::new());
+
+    let plans: Vec<String> = (0..8)
+        .map(|_| std::thread::spawn(|| plan_text(&PlannerConfig::new())))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect();
+
+    assert!(plans.iter().all(|plan| *plan == first));
+}
+
+#[test]
+fn test_ties_go_to_the_action_listed_first() {
+    let start = LocalState::new().with_datum("has_wood", Datum::Bool(false));
+    let goal = Goal::new().with_req("has_wood", Compare::Equals(Datum::Bool(true)));
+    let chop = simple_action("chop", "has_wood", Datum::Bool(true));
+    let saw = simple_action("saw", "has_wood", Datum::Bool(true));
+
+    let plan = make_plan(&start, &[chop.clone(), saw.clone()], &goal).unwrap();
+    assert_plan_actions!(plan, ["chop"]);
+
+    let plan = make_plan(&start, &[saw, chop], &goal).unwrap();
+    assert_plan_actions!(plan, ["saw"]);
+}
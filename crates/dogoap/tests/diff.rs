@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use dogoap::{prelude::*, simple::*};
+
+fn domain() -> Vec<Action> {
+    vec![
+        simple_action("chop", "wood", Datum::I64(1)).set_cost(2),
+        simple_action("craft", "plank", Datum::Bool(true))
+            .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(1)))
+            .with_precondition("has_axe", Compare::Equals(Datum::Bool(true))),
+        simple_action("rest", "tired", Datum::Bool(false)),
+    ]
+}
+
+#[test]
+fn test_same_domain() {
+    let diff = diff_domains(&domain(), &domain());
+
+    assert!(diff.is_empty());
+    assert_eq!("", diff.to_string());
+}
+
+#[test]
+fn test_added_and_removed() {
+    let mut new = domain();
+    new.remove(2);
+    new.push(simple_action("sleep", "tired", Datum::Bool(false)));
+
+    let diff = diff_domains(&domain(), &new);
+
+    assert_eq!(vec!["sleep".to_string()], diff.added);
+    assert_eq!(vec!["rest".to_string()], diff.removed);
+    assert!(diff.modified.is_empty());
+    assert!(diff.affects("rest"));
+    assert!(!diff.affects("sleep"));
+    assert!(!diff.affects("chop"));
+}
+
+#[test]
+fn test_modified_fields() {
+    let mut new = domain();
+    new[0] = simple_action("chop", "wood", Datum::I64(1))
+        .set_cost(3)
+        .set_duration(5);
+    new[1] = simple_action("craft", "plank", Datum::Bool(true))
+        .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(2)))
+        .with_precondition("workbench", Compare::Equals(Datum::Bool(true)))
+        .add_mutator(Mutator::Decrement("wood".to_string(), Datum::I64(2)));
+
+    let diff = diff_domains(&domain(), &new);
+
+    assert_eq!(
+        vec![
+            ActionDiff {
+                key: "chop".to_string(),
+                changes: vec![
+                    ActionChange::CostChanged {
+                        effect: 0,
+                        old: 2,
+                        new: 3
+                    },
+                    ActionChange::DurationChanged {
+                        effect: 0,
+                        old: 0,
+                        new: 5
+                    },
+                ],
+            },
+            ActionDiff {
+                key: "craft".to_string(),
+                changes: vec![
+                    ActionChange::PreconditionRemoved(
+                        "has_axe".to_string(),
+                        Compare::Equals(Datum::Bool(true))
+                    ),
+                    ActionChange::PreconditionAltered {
+                        key: "wood".to_string(),
+                        old: Compare::GreaterThanEquals(Datum::I64(1)),
+                        new: Compare::GreaterThanEquals(Datum::I64(2)),
+                    },
+                    ActionChange::PreconditionAdded(
+                        "workbench".to_string(),
+                        Compare::Equals(Datum::Bool(true))
+                    ),
+                    ActionChange::MutatorAdded {
+                        effect: 0,
+                        mutator: Mutator::Decrement("wood".to_string(), Datum::I64(2)),
+                    },
+                ],
+            },
+        ],
+        diff.modified
+    );
+    assert!(diff.affects("chop"));
+    assert!(!diff.affects("rest"));
+    assert_eq!(
+        "~ chop\n    effect 0: cost 2 -> 3\n    effect 0: duration 0 -> 5\n~ craft\n",
+        diff.to_string()
+            .split("    precondition removed")
+            .next()
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_order_and_labels_dont_count() {
+    let old = vec![Action::new("craft")
+        .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(1)))
+        .with_precondition("has_axe", Compare::Equals(Datum::Bool(true)))
+        .with_effect(
+            Effect::new("old_label")
+                .with_mutator(Mutator::Set("plank".to_string(), Datum::Bool(true)))
+                .with_mutator(Mutator::Decrement("wood".to_string(), Datum::I64(1))),
+        )];
+    let new = vec![Action::new("craft")
+        .with_precondition("has_axe", Compare::Equals(Datum::Bool(true)))
+        .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(1)))
+        .with_effect(
+            Effect::new("craft")
+                .with_mutator(Mutator::Decrement("wood".to_string(), Datum::I64(1)))
+                .with_mutator(Mutator::Set("plank".to_string(), Datum::Bool(true))),
+        )];
+
+    assert!(diff_domains(&old, &new).is_empty());
+}
+
+#[test]
+fn test_effects_and_dynamic_preconditions() {
+    let old = domain();
+    let mut new = domain();
+    let dynamic: DynamicPrecondition = Arc::new(|_| Compare::Equals(Datum::Bool(true)));
+    new[2] = simple_action("rest", "tired", Datum::Bool(false))
+        .add_dynamic_precondition(("sleepy".to_string(), dynamic))
+        .with_effect(Effect::new("rest"));
+
+    let diff = diff_domains(&old, &new);
+
+    assert_eq!(
+        vec![
+            ActionChange::DynamicPreconditionAdded("sleepy".to_string()),
+            ActionChange::EffectAdded(1),
+        ],
+        diff.modified[0].changes
+    );
+}
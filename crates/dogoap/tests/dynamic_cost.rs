@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+fn position(state: &LocalState, key: &str) -> i64 {
+    match state.data[key] {
+        Datum::I64(position) => position,
+        other => panic!("{} isn't a position: {:?}", key, other),
+    }
+}
+
+// Walking to `place` costs as much as it's far from where the agent is now
+fn go_to(place: &'static str) -> Action {
+    Action::new(&format!("go_to_{}", place))
+        .with_effect(Effect::new("").with_mutator(Mutator::Set(
+            "at".to_string(),
+            Datum::Enum(if place == "water" { 1 } else { 2 }),
+        )))
+        .with_dynamic_cost(Arc::new(move |state: &LocalState| {
+            let here = match state.data["at"] {
+                Datum::Enum(1) => position(state, "water"),
+                Datum::Enum(2) => position(state, "food"),
+                _ => 0,
+            };
+            (position(state, place) - here).unsigned_abs() as usize
+        }))
+}
+
+fn errands(water: i64, food: i64) -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("at", Datum::Enum(0))
+        .with_datum("water", Datum::I64(water))
+        .with_datum("food", Datum::I64(food))
+        .with_datum("thirsty", Datum::Bool(true))
+        .with_datum("hungry", Datum::Bool(true));
+    let actions = vec![
+        go_to("water"),
+        go_to("food"),
+        simple_action("drink", "thirsty", Datum::Bool(false))
+            .with_precondition("at", Compare::Equals(Datum::Enum(1))),
+        simple_action("eat", "hungry", Datum::Bool(false))
+            .with_precondition("at", Compare::Equals(Datum::Enum(2))),
+    ];
+    let goal = Goal::new()
+        .with_req("thirsty", Compare::Equals(Datum::Bool(false)))
+        .with_req("hungry", Compare::Equals(Datum::Bool(false)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_nearest_errand_comes_first() {
+    let (start, actions, goal) = errands(2, 8);
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    assert_plan_actions!(plan, ["go_to_water", "drink", "go_to_food", "eat"]);
+    assert_plan_cost!(plan, 2 + 1 + 6 + 1);
+
+    let (start, actions, goal) = errands(8, 2);
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    assert_plan_actions!(plan, ["go_to_food", "eat", "go_to_water", "drink"]);
+    assert_plan_cost!(plan, 2 + 1 + 6 + 1);
+}
+
+#[test]
+fn test_cheaper_branch_when_tired() {
+    let travel = |energy: i64| {
+        let start = LocalState::new()
+            .with_datum("energy", Datum::I64(energy))
+            .with_datum("in_town", Datum::Bool(false));
+        // Walking gets expensive when tired, the cart always costs the same
+        let actions = vec![
+            simple_action("walk", "in_town", Datum::Bool(true)).with_dynamic_cost(Arc::new(
+                |state: &LocalState| match state.data["energy"] {
+                    Datum::I64(energy) if energy < 20 => 10,
+                    _ => 2,
+                },
+            )),
+            simple_action("ride_cart", "in_town", Datum::Bool(true)).set_cost(5),
+        ];
+        let goal = Goal::new().with_req("in_town", Compare::Equals(Datum::Bool(true)));
+        make_plan(&start, &actions, &goal).unwrap()
+    };
+
+    let rested = travel(80);
+    assert_plan_actions!(rested, ["walk"]);
+    assert_plan_cost!(rested, 2);
+
+    let tired = travel(10);
+    assert_plan_actions!(tired, ["ride_cart"]);
+    assert_plan_cost!(tired, 5);
+}
+
+#[test]
+fn test_backward_search_falls_back_to_forwards() {
+    let (start, actions, goal) = errands(2, 8);
+    let config = PlannerConfig::new().with_strategy(PlanningStrategy::GoalToStart);
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    assert_plan_cost!(plan.unwrap(), 10);
+}
+
+#[test]
+fn test_dynamic_cost_counts_for_equality() {
+    let cost: DynamicCost = Arc::new(|_: &LocalState| 1);
+
+    assert_ne!(
+        go_to("water"),
+        simple_action("go_to_water", "at", Datum::Enum(1))
+    );
+    assert_eq!(
+        go_to("water"),
+        Action::new("go_to_water")
+            .with_effect(
+                Effect::new("").with_mutator(Mutator::Set("at".to_string(), Datum::Enum(1)))
+            )
+            .with_dynamic_cost(cost)
+    );
+}
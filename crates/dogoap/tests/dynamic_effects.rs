@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use dogoap::{prelude::*, testing::*};
+
+// Eating restores however much the food at hand is worth
+fn eating(food_value: i64) -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("food_value", Datum::I64(food_value))
+        .with_datum("satiety", Datum::I64(0));
+    let actions = vec![
+        Action::new("eat").with_dynamic_effect(Arc::new(|state: &LocalState| {
+            Effect::new("eat").with_mutator(Mutator::Increment(
+                "satiety".to_string(),
+                state.data["food_value"],
+            ))
+        })),
+    ];
+    let goal = Goal::new().with_req("satiety", Compare::GreaterThanEquals(Datum::I64(10)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_effect_depends_on_the_start_state() {
+    let (start, actions, goal) = eating(10);
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["eat"]);
+    assert_plan_reaches!(plan, goal);
+
+    let (start, actions, goal) = eating(4);
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["eat", "eat", "eat"]);
+    assert_plan_cost!(plan, 3);
+    assert!(validate_plan(&plan, &start, &actions, &goal));
+}
+
+#[test]
+fn test_dynamic_effects_are_branches_next_to_the_static_ones() {
+    let (start, mut actions, goal) = eating(10);
+    let mut effect =
+        Effect::new("eat").with_mutator(Mutator::Increment("satiety".to_string(), Datum::I64(10)));
+    effect.cost = 5;
+    actions[0] = actions[0].clone().with_effect(effect);
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    // The food at hand does as much for less than the static effect
+    assert_plan_actions!(plan, ["eat"]);
+    assert_plan_cost!(plan, 1);
+}
+
+#[test]
+fn test_dynamic_effects_are_searched_forwards() {
+    let (start, actions, goal) = eating(5);
+    let config = PlannerConfig::new().with_strategy(PlanningStrategy::GoalToStart);
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    assert_plan_actions!(plan.unwrap(), ["eat", "eat"]);
+}
+
+#[test]
+fn test_dynamic_effects_count_for_equality() {
+    let (_, actions, _) = eating(1);
+
+    assert_ne!(Action::new("eat"), actions[0]);
+    assert!(format!("{:?}", actions[0]).contains("dynamic_effects: 1"));
+}
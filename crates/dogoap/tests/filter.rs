@@ -0,0 +1,41 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+fn guard() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("has_sword", Datum::Bool(false))
+        .with_datum("intruder_gone", Datum::Bool(false));
+    let actions = vec![
+        simple_action("draw_sword", "has_sword", Datum::Bool(true)).with_tag("combat"),
+        simple_action("attack", "intruder_gone", Datum::Bool(true))
+            .with_tag("combat")
+            .with_precondition("has_sword", Compare::Equals(Datum::Bool(true))),
+        simple_action("ask_nicely", "intruder_gone", Datum::Bool(true))
+            .with_tag("social")
+            .set_cost(5),
+    ];
+    let goal = Goal::new().with_req("intruder_gone", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_filter_by_tag() {
+    let (start, actions, goal) = guard();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    assert_plan_actions!(plan, ["draw_sword", "attack"]);
+
+    let plan =
+        make_plan_with_filter(&start, &actions, &goal, |action| action.has_tag("social")).unwrap();
+    assert_plan_actions!(plan, ["ask_nicely"]);
+}
+
+#[test]
+fn test_filtering_out_a_needed_action() {
+    let (start, actions, goal) = guard();
+
+    let plan = make_plan_with_filter(&start, &actions, &goal, |action| {
+        action.key != "draw_sword" && !action.has_tag("social")
+    });
+
+    assert_eq!(None, plan);
+}
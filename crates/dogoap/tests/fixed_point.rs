@@ -0,0 +1,149 @@
+use dogoap::{prelude::*, simple::simple_increment_action, testing::*};
+
+/// A [`Datum`] that only has whole-unit distances, like every [`Datum`] had before
+/// fractional distances were kept
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Hash)]
+struct Truncated(Datum);
+
+impl DatumLike for Truncated {
+    type Kind = DatumKind;
+
+    fn kind(&self) -> DatumKind {
+        self.0.kind()
+    }
+
+    fn distance(&self, other: &Truncated) -> u64 {
+        self.0.distance(&other.0)
+    }
+
+    fn magnitude(&self) -> u64 {
+        DatumLike::magnitude(&self.0)
+    }
+
+    fn checked_add(&self, other: &Truncated) -> Option<Truncated> {
+        self.0.checked_add(&other.0).map(Truncated)
+    }
+
+    fn checked_sub(&self, other: &Truncated) -> Option<Truncated> {
+        self.0.checked_sub(&other.0).map(Truncated)
+    }
+}
+
+type Domain<D> = (
+    LocalState<String, D>,
+    Vec<Action<String, D>>,
+    Goal<String, D>,
+);
+
+// Filling a bucket takes three pours, with spilling, wandering around and whistling
+// as distractions listed before it. The whole gap is less than a unit
+fn bucket<D: DatumLike>(datum: impl Fn(Datum) -> D) -> Domain<D> {
+    let start = LocalState::default()
+        .with_datum("water", datum(Datum::F64(0.0)))
+        .with_datum("steps", datum(Datum::I64(0)))
+        .with_datum("songs", datum(Datum::I64(0)));
+    let actions = vec![
+        Action::named("spill").add_mutator(Mutator::Decrement(
+            "water".to_string(),
+            datum(Datum::F64(0.25)),
+        )),
+        Action::named("wander").add_mutator(Mutator::Increment(
+            "steps".to_string(),
+            datum(Datum::I64(1)),
+        )),
+        Action::named("whistle").add_mutator(Mutator::Increment(
+            "songs".to_string(),
+            datum(Datum::I64(1)),
+        )),
+        Action::named("pour").add_mutator(Mutator::Increment(
+            "water".to_string(),
+            datum(Datum::F64(0.25)),
+        )),
+    ];
+    let goal = Goal::default().with_req("water", Compare::Equals(datum(Datum::F64(0.75))));
+    (start, actions, goal)
+}
+
+fn plan_bucket<D: DatumLike>(
+    datum: impl Fn(Datum) -> D,
+    config: &PlannerConfig<String, D>,
+) -> (Plan<String, D>, PlanStats) {
+    let (start, actions, goal) = bucket(datum);
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, config);
+    (plan.unwrap(), stats)
+}
+
+fn action_keys<D>(plan: &Plan<String, D>) -> Vec<&str> {
+    plan.0.iter().filter_map(|node| node.action_key()).collect()
+}
+
+#[test]
+fn test_fractional_gaps_guide_the_search() {
+    for normalize in [false, true] {
+        let (plan, stats) = plan_bucket(
+            |datum| datum,
+            &PlannerConfig::new().with_normalized_heuristic(normalize),
+        );
+        let (truncated_plan, truncated_stats) = plan_bucket(
+            Truncated,
+            &PlannerConfig::default().with_normalized_heuristic(normalize),
+        );
+
+        assert_plan_actions!(plan, ["pour", "pour", "pour"]);
+        assert_eq!(action_keys(&plan), action_keys(&truncated_plan));
+        assert_plan_cost!(plan, truncated_plan.1);
+        assert!(
+            truncated_stats.expanded_nodes > stats.expanded_nodes,
+            "{} <= {}",
+            truncated_stats.expanded_nodes,
+            stats.expanded_nodes
+        );
+    }
+}
+
+#[test]
+fn test_backward_search_keeps_fractional_gaps() {
+    let config = PlannerConfig::new().with_strategy(PlanningStrategy::GoalToStart);
+
+    let (plan, stats) = plan_bucket(|datum| datum, &config);
+
+    assert_eq!(PlanningStrategy::GoalToStart, stats.strategy);
+    assert_plan_actions!(plan, ["pour", "pour", "pour"]);
+    assert_plan_cost!(plan, 3);
+}
+
+#[test]
+fn test_integer_plans_are_unchanged() {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let actions = vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("dig", "gold", Datum::I64(3)).set_cost(2),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(7)));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_cost!(plan, 5);
+    assert_plan_reaches!(plan, goal);
+}
+
+#[test]
+fn test_saturated_heuristic_is_recomputed() {
+    // Each gap alone fits the heuristic, both together saturate it
+    let gap = 9.3e15;
+    let start = LocalState::new()
+        .with_datum("north", Datum::F64(0.0))
+        .with_datum("east", Datum::F64(0.0));
+    let actions = vec![
+        simple_increment_action("go_north", "north", Datum::F64(gap)),
+        simple_increment_action("go_east", "east", Datum::F64(gap)),
+    ];
+    let goal = Goal::new()
+        .with_req("north", Compare::Equals(Datum::F64(gap)))
+        .with_req("east", Compare::Equals(Datum::F64(gap)));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_cost!(plan, 2);
+    assert_plan_reaches!(plan, goal);
+}
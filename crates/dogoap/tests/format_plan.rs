@@ -0,0 +1,72 @@
+use dogoap::{prelude::*, simple::*};
+
+fn plan() -> Plan {
+    let start = LocalState::new()
+        .with_datum("wood", Datum::I64(0))
+        .with_datum("has_fire", Datum::Bool(false));
+    let actions = vec![
+        simple_increment_action("chop", "wood", Datum::I64(1)).set_cost(2),
+        simple_action("light_fire", "has_fire", Datum::Bool(true))
+            .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(1))),
+    ];
+    let goal = Goal::new().with_req("has_fire", Compare::Equals(Datum::Bool(true)));
+    make_plan(&start, &actions, &goal).unwrap()
+}
+
+#[test]
+fn test_format_plan() {
+    let expected = "\
+\t\t= INITIAL STATE
+\t\thas_fire = Datum:Bool(false)
+\t\twood = Datum:I64(0)
+
+\t\t1. chop (cost 2)
+\t\t   wood      Datum:I64(0)      -> Datum:I64(1)
+
+\t\t2. light_fire (cost 1)
+\t\t   has_fire  Datum:Bool(false) -> Datum:Bool(true)
+
+\t\t= FINAL STATE (COST: 3)
+\t\thas_fire = Datum:Bool(true)
+\t\twood = Datum:I64(1)
+";
+
+    assert_eq!(expected, format_plan(&plan()));
+}
+
+#[test]
+fn test_verbose_format_shows_the_cost_of_every_step() {
+    let formatted = format_plan_with_format(&plan(), PlanFormat::Verbose);
+
+    assert!(formatted.contains("= DO ACTION \"chop\" (COST: 2)"));
+    assert!(formatted.contains("= DO ACTION \"light_fire\" (COST: 1)"));
+    assert!(formatted.contains("= FINAL STATE (COST: 3)"));
+}
+
+#[test]
+fn test_display_steps() {
+    let (nodes, _) = plan();
+    let steps: Vec<String> = nodes.iter().map(Node::to_string).collect();
+
+    assert_eq!(
+        vec![
+            "has_fire = Datum:Bool(false), wood = Datum:I64(0)",
+            "chop (cost 2): wood + Datum:I64(1)",
+            "light_fire (cost 1): has_fire = Datum:Bool(true)",
+        ],
+        steps
+    );
+}
+
+#[test]
+fn test_plan_is_only_borrowed() {
+    let plan = plan();
+
+    print_plan(&plan);
+    let costs: Vec<usize> = effects_of_plan(&plan.0).map(|effect| effect.cost).collect();
+    let keys: Vec<&str> = action_keys_of_plan(&plan.0).collect();
+
+    assert_eq!(vec![2, 1], costs);
+    assert_eq!(vec!["chop", "light_fire"], keys);
+    assert_eq!(3, plan.1);
+}
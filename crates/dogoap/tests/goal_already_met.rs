@@ -0,0 +1,41 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+fn fed() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("is_hungry", Datum::Bool(false))
+        .with_datum("food", Datum::I64(3));
+    let actions = vec![simple_action("eat", "is_hungry", Datum::Bool(false))];
+    let goal = Goal::new()
+        .with_req("is_hungry", Compare::Equals(Datum::Bool(false)))
+        .with_req("food", Compare::GreaterThanEquals(Datum::I64(1)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_plan_is_just_the_start() {
+    let (start, actions, goal) = fed();
+
+    for strategy in [
+        PlanningStrategy::StartToGoal,
+        PlanningStrategy::GoalToStart,
+        PlanningStrategy::Auto,
+        PlanningStrategy::UniformCost,
+    ] {
+        let plan = make_plan_with_strategy(strategy, &start, &actions, &goal).unwrap();
+
+        assert_eq!((vec![Node::State(start.clone())], 0), plan);
+        assert_eq!(0, effects_of_plan(&plan.0).count());
+        assert_plan_actions!(plan, [] as [&str; 0]);
+    }
+}
+
+#[test]
+fn test_nothing_is_expanded() {
+    let (start, actions, goal) = fed();
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &PlannerConfig::new());
+
+    assert_plan_cost!(plan.unwrap(), 0);
+    assert_eq!(0, stats.expanded_nodes);
+    assert_eq!(0, stats.generated_nodes);
+}
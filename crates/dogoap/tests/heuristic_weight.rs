@@ -0,0 +1,86 @@
+use dogoap::{prelude::*, simple::*};
+
+// Three resources to gather, one at a time or in bulk, with a few actions that
+// lead nowhere to pad out the search
+fn gathering() -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new();
+    let mut actions = vec![];
+    for resource in ["wood", "stone", "gold"] {
+        start = start.with_datum(resource, Datum::I64(0));
+        actions.push(
+            simple_increment_action(&format!("pick_{}", resource), resource, Datum::I64(1))
+                .set_cost(2),
+        );
+        actions.push(
+            simple_increment_action(&format!("haul_{}", resource), resource, Datum::I64(3))
+                .set_cost(5),
+        );
+        actions.push(simple_decrement_action(
+            &format!("drop_{}", resource),
+            resource,
+            Datum::I64(1),
+        ));
+    }
+    let goal = Goal::new()
+        .with_req("wood", Compare::Equals(Datum::I64(8)))
+        .with_req("stone", Compare::Equals(Datum::I64(6)))
+        .with_req("gold", Compare::Equals(Datum::I64(5)));
+    (start, actions, goal)
+}
+
+const WEIGHTS: [f32; 4] = [1.0, 1.25, 1.5, 2.0];
+
+fn plan_weighted(weight: f32) -> PlanResult {
+    let (start, actions, goal) = gathering();
+    let config = PlannerConfig::new().with_heuristic_weight(weight);
+    make_plan_detailed(&start, &actions, &goal, &config).unwrap()
+}
+
+#[test]
+fn test_weight_of_one_plans_as_before() {
+    let (start, actions, goal) = gathering();
+
+    let before = make_plan_detailed(&start, &actions, &goal, &PlannerConfig::new()).unwrap();
+    let weighted = plan_weighted(1.0);
+
+    assert_eq!(before.nodes, weighted.nodes);
+    assert_eq!(before.expanded_nodes, weighted.expanded_nodes);
+}
+
+#[test]
+fn test_weighted_plans_are_valid() {
+    let (start, actions, goal) = gathering();
+
+    for weight in WEIGHTS.into_iter().chain([3.0, 10.0]) {
+        let result = plan_weighted(weight);
+        let steps: usize = effects_of_plan(&result.nodes)
+            .map(|effect| effect.cost)
+            .sum();
+
+        // The cost is what the steps cost, not the inflated estimate
+        assert_eq!(steps, result.cost);
+        let plan: Plan = result.into();
+        assert!(validate_plan(&plan, &start, &actions, &goal));
+    }
+}
+
+#[test]
+fn test_heavier_weights_expand_fewer_nodes() {
+    let expanded: Vec<usize> = WEIGHTS
+        .into_iter()
+        .map(|weight| plan_weighted(weight).expanded_nodes)
+        .collect();
+
+    for pair in expanded.windows(2) {
+        assert!(pair[1] < pair[0], "{:?}", expanded);
+    }
+}
+
+#[test]
+fn test_weights_below_one_count_as_one() {
+    assert_eq!(plan_weighted(1.0).nodes, plan_weighted(0.5).nodes);
+    assert_eq!(
+        plan_weighted(1.0).expanded_nodes,
+        plan_weighted(f32::NAN).expanded_nodes
+    );
+}
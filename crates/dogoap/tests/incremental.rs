@@ -0,0 +1,116 @@
+use dogoap::{prelude::*, simple::*};
+
+fn start() -> LocalState {
+    LocalState::new()
+        .with_datum("wood", Datum::I64(0))
+        .with_datum("planks", Datum::I64(0))
+        .with_datum("has_axe", Datum::Bool(false))
+        .with_datum("has_house", Datum::Bool(false))
+}
+
+fn actions() -> Vec<Action> {
+    vec![
+        simple_action("get_axe", "has_axe", Datum::Bool(true)).set_cost(3),
+        simple_increment_action("gather", "wood", Datum::I64(1)).set_cost(4),
+        simple_increment_action("chop", "wood", Datum::I64(2))
+            .with_precondition("has_axe", Compare::Equals(Datum::Bool(true))),
+        Action::new("saw")
+            .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(1)))
+            .with_effect(
+                Effect::new("saw")
+                    .with_mutator(Mutator::Decrement("wood".to_string(), Datum::I64(1)))
+                    .with_mutator(Mutator::Increment("planks".to_string(), Datum::I64(1))),
+            ),
+        Action::new("build")
+            .with_precondition("planks", Compare::GreaterThanEquals(Datum::I64(4)))
+            .with_effect(
+                Effect::new("build")
+                    .with_mutator(Mutator::Decrement("planks".to_string(), Datum::I64(4)))
+                    .with_mutator(Mutator::Set("has_house".to_string(), Datum::Bool(true))),
+            ),
+    ]
+}
+
+fn goal() -> Goal {
+    Goal::new().with_req("has_house", Compare::Equals(Datum::Bool(true)))
+}
+
+fn step_until_done(planner: &mut IncrementalPlanner, max_expansions: usize) -> (StepResult, usize) {
+    let mut steps = 0;
+    loop {
+        steps += 1;
+        match planner.step(max_expansions) {
+            StepResult::InProgress => continue,
+            result => return (result, steps),
+        }
+    }
+}
+
+#[test]
+fn test_steps_find_the_one_shot_plan() {
+    let config = PlannerConfig::default().with_strategy(PlanningStrategy::StartToGoal);
+    let (expected, stats) = make_plan_with_stats(&start(), &actions(), &goal(), &config);
+    let (nodes, cost) = expected.unwrap();
+
+    for max_expansions in [1, 2, 5, 1000] {
+        let mut planner = IncrementalPlanner::new(&start(), &actions(), &goal(), &config);
+        let (result, steps) = step_until_done(&mut planner, max_expansions);
+
+        assert_eq!(StepResult::Done(nodes.clone(), cost), result);
+        assert_eq!(&stats, planner.stats());
+        assert!(steps >= stats.expanded_nodes / max_expansions);
+    }
+}
+
+#[test]
+fn test_steps_match_make_plan() {
+    let plan = make_plan(&start(), &actions(), &goal()).unwrap();
+    let mut planner =
+        IncrementalPlanner::new(&start(), &actions(), &goal(), &PlannerConfig::default());
+
+    let (result, _) = step_until_done(&mut planner, 3);
+    let StepResult::Done(_, cost) = result else {
+        panic!("Expected a plan");
+    };
+    assert_eq!(plan.1, cost);
+}
+
+#[test]
+fn test_finished_planner_repeats_its_result() {
+    let mut planner =
+        IncrementalPlanner::new(&start(), &actions(), &goal(), &PlannerConfig::default());
+    let (result, _) = step_until_done(&mut planner, 4);
+
+    assert!(planner.is_finished());
+    assert_eq!(result, planner.step(1));
+}
+
+#[test]
+fn test_impossible() {
+    let goal = Goal::new().with_req("has_axe", Compare::Equals(Datum::Bool(false)));
+    let actions = vec![simple_action("get_axe", "has_axe", Datum::Bool(true))];
+    let start = LocalState::new().with_datum("has_axe", Datum::Bool(true));
+    let mut planner = IncrementalPlanner::new(&start, &actions, &goal, &PlannerConfig::default());
+
+    assert_eq!(StepResult::Impossible, step_until_done(&mut planner, 1).0);
+}
+
+#[test]
+fn test_expansion_budget_spans_every_step() {
+    let config = PlannerConfig::default()
+        .with_strategy(PlanningStrategy::StartToGoal)
+        .with_max_expanded_nodes(5);
+    let mut planner = IncrementalPlanner::new(&start(), &actions(), &goal(), &config);
+
+    assert!(make_plan_with_config(&start(), &actions(), &goal(), &config).is_none());
+    assert_eq!(StepResult::Impossible, step_until_done(&mut planner, 2).0);
+}
+
+#[test]
+fn test_no_expansions_stays_in_progress() {
+    let mut planner =
+        IncrementalPlanner::new(&start(), &actions(), &goal(), &PlannerConfig::default());
+
+    assert_eq!(StepResult::InProgress, planner.step(0));
+    assert_eq!(0, planner.stats().expanded_nodes);
+}
@@ -0,0 +1,113 @@
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+// Crossing the lava is cheap, but leaves no health until the potion on the other
+// side, which only helps the badly hurt. Walking around is safe, but costs more
+fn lava() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("health", Datum::I64(2))
+        .with_datum("across", Datum::Bool(false))
+        .with_datum("treasure", Datum::Bool(false));
+    let across = |value| Compare::Equals(Datum::Bool(value));
+    let actions = vec![
+        simple_action("cross_lava", "across", Datum::Bool(true))
+            .with_precondition("across", across(false))
+            .add_mutator(Mutator::Decrement("health".to_string(), Datum::I64(2))),
+        simple_action("walk_around", "across", Datum::Bool(true))
+            .with_precondition("across", across(false))
+            .set_cost(5),
+        Action::new("drink_potion")
+            .with_precondition("across", across(true))
+            .with_precondition("health", Compare::LessThanEquals(Datum::I64(0)))
+            .add_mutator(Mutator::Increment("health".to_string(), Datum::I64(2))),
+        simple_action("grab", "treasure", Datum::Bool(true))
+            .with_precondition("across", across(true)),
+    ];
+    let goal = Goal::new()
+        .with_req("treasure", Compare::Equals(Datum::Bool(true)))
+        .with_req("health", Compare::GreaterThanEquals(Datum::I64(2)));
+    (start, actions, goal)
+}
+
+fn alive() -> Invariant {
+    Invariant::new().with_condition("health", Compare::GreaterThanEquals(Datum::I64(1)))
+}
+
+#[test]
+fn test_cheap_path_without_invariants() {
+    let (start, actions, goal) = lava();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["cross_lava", "drink_potion", "grab"]);
+    assert_plan_cost!(plan, 3);
+}
+
+#[test]
+fn test_detour_keeps_to_the_invariant() {
+    let (start, actions, goal) = lava();
+    for config in [
+        PlannerConfig::new(),
+        PlannerConfig::new().with_strategy(PlanningStrategy::GoalToStart),
+        PlannerConfig::new().with_rewards(Rewards::BoundedDepth(4)),
+    ] {
+        let config = config.with_invariant(alive());
+
+        let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+        let plan = plan.unwrap();
+
+        assert_plan_actions!(plan, ["walk_around", "grab"]);
+        assert_plan_cost!(plan, 6);
+        assert!(plan.0.iter().all(|node| alive().holds(node.state())));
+        assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    }
+}
+
+#[test]
+fn test_start_breaking_an_invariant_has_no_plan() {
+    let (start, actions, goal) = lava();
+    let start = start.with_datum("health", Datum::I64(0));
+    let config = PlannerConfig::new().with_invariant(alive());
+
+    assert_eq!(
+        Ok(None),
+        try_make_plan_with_config(&start, &actions, &goal, &config)
+    );
+}
+
+#[test]
+fn test_retry_tells_when_invariants_block_the_plan() {
+    let (start, mut actions, goal) = lava();
+    actions.retain(|action| action.key != "walk_around");
+    let config = PlannerConfig::new().with_invariant(alive());
+
+    assert_eq!(
+        Ok(None),
+        try_make_plan_with_config(&start, &actions, &goal, &config)
+    );
+
+    let config = config.with_retry_without_invariants(true);
+    let result = try_make_plan_with_config(&start, &actions, &goal, &config);
+
+    assert_eq!(
+        Err(PlanError::BlockedByInvariants { relaxed_cost: 3 }),
+        result
+    );
+    assert_eq!(
+        "No plan keeps to the invariants, without them there's one costing 3",
+        result.unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_retry_without_a_plan_either_way() {
+    let (start, mut actions, goal) = lava();
+    actions.retain(|action| action.key != "grab");
+    let config = PlannerConfig::new()
+        .with_invariant(alive())
+        .with_retry_without_invariants(true);
+
+    assert_eq!(
+        Ok(None),
+        try_make_plan_with_config(&start, &actions, &goal, &config)
+    );
+}
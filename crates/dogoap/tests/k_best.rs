@@ -0,0 +1,97 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// Three ways to get food, each costing a different amount. Anything else takes a
+// detour that costs more than all three
+fn foraging() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("has_food", Datum::Bool(false))
+        .with_datum("has_bow", Datum::Bool(false))
+        .with_datum("gold", Datum::I64(0));
+    let actions = vec![
+        simple_action("gather_berries", "has_food", Datum::Bool(true)).set_cost(6),
+        simple_action("make_bow", "has_bow", Datum::Bool(true)).set_cost(2),
+        simple_action("hunt", "has_food", Datum::Bool(true))
+            .set_cost(2)
+            .with_precondition("has_bow", Compare::Equals(Datum::Bool(true))),
+        simple_action("sell_pelts", "gold", Datum::I64(5)).set_cost(3),
+        simple_action("buy_food", "has_food", Datum::Bool(true))
+            .set_cost(2)
+            .with_precondition("gold", Compare::GreaterThanEquals(Datum::I64(5))),
+    ];
+    let goal = Goal::new().with_req("has_food", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_plans_come_cheapest_first() {
+    let (start, actions, goal) = foraging();
+
+    let plans = make_plans(&start, &actions, &goal, 3);
+
+    assert_eq!(3, plans.len());
+    assert_plan_actions!(plans[0], ["make_bow", "hunt"]);
+    assert_plan_cost!(plans[0], 4);
+    assert_plan_actions!(plans[1], ["sell_pelts", "buy_food"]);
+    assert_plan_cost!(plans[1], 5);
+    assert_plan_actions!(plans[2], ["gather_berries"]);
+    assert_plan_cost!(plans[2], 6);
+    for plan in &plans {
+        assert!(validate_plan(plan, &start, &actions, &goal));
+    }
+    assert_eq!(make_plan(&start, &actions, &goal), plans.first().cloned());
+}
+
+#[test]
+fn test_fewer_plans_than_asked_for() {
+    let (start, actions, goal) = foraging();
+    let without_gold: Vec<Action> = actions
+        .into_iter()
+        .filter(|action| action.key != "sell_pelts")
+        .collect();
+
+    let plans = make_plans(&start, &without_gold, &goal, 10);
+
+    // Making a bow and then gathering berries anyway is the last way left
+    assert_eq!(3, plans.len());
+    assert_plan_actions!(plans[0], ["make_bow", "hunt"]);
+    assert_plan_actions!(plans[1], ["gather_berries"]);
+    assert_plan_actions!(plans[2], ["make_bow", "gather_berries"]);
+}
+
+#[test]
+fn test_same_actions_in_another_order_are_another_plan() {
+    let start = LocalState::new()
+        .with_datum("is_dressed", Datum::Bool(false))
+        .with_datum("is_fed", Datum::Bool(false));
+    let actions = vec![
+        simple_action("dress", "is_dressed", Datum::Bool(true)),
+        simple_action("eat", "is_fed", Datum::Bool(true)),
+    ];
+    let goal = Goal::new()
+        .with_req("is_dressed", Compare::Equals(Datum::Bool(true)))
+        .with_req("is_fed", Compare::Equals(Datum::Bool(true)));
+
+    let plans = make_plans(&start, &actions, &goal, 5);
+
+    let mut orders: Vec<Vec<&str>> = plans
+        .iter()
+        .map(|plan| action_keys_of_plan(&plan.0).collect())
+        .collect();
+    orders.sort();
+    assert_eq!(vec![vec!["dress", "eat"], vec!["eat", "dress"]], orders);
+}
+
+#[test]
+fn test_branches_of_one_action_are_the_same_plan() {
+    let start = LocalState::new().with_datum("wood", Datum::I64(0));
+    let actions = vec![Action::new("gather")
+        .add_mutator(Mutator::Set("wood".to_string(), Datum::I64(1)))
+        .add_mutator_at(1, Mutator::Set("wood".to_string(), Datum::I64(2)))
+        .set_cost_at(1, 2)];
+    let goal = Goal::new().with_req("wood", Compare::GreaterThanEquals(Datum::I64(1)));
+
+    let plans = make_plans(&start, &actions, &goal, 5);
+
+    assert_eq!(1, plans.len());
+    assert_plan_cost!(plans[0], 1);
+}
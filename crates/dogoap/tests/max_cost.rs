@@ -0,0 +1,96 @@
+use dogoap::{prelude::*, simple::simple_increment_action, testing::*};
+
+// The cheapest plan mines four times, for a cost of 4
+fn mining() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let actions = vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("dig", "gold", Datum::I64(2)).set_cost(3),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(4)));
+    (start, actions, goal)
+}
+
+fn plan_within(max_cost: usize, strategy: PlanningStrategy) -> Result<Option<Plan>, PlanError> {
+    let (start, actions, goal) = mining();
+    let config = PlannerConfig::new()
+        .with_strategy(strategy)
+        .with_max_cost(max_cost);
+    try_make_plan_with_config(&start, &actions, &goal, &config)
+}
+
+#[test]
+fn test_budget_just_above_the_cheapest_plan() {
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let plan = plan_within(5, strategy).unwrap().unwrap();
+
+        assert_plan_actions!(plan, ["mine", "mine", "mine", "mine"]);
+    }
+}
+
+#[test]
+fn test_budget_matching_the_cheapest_plan() {
+    let plan = plan_within(4, PlanningStrategy::StartToGoal)
+        .unwrap()
+        .unwrap();
+
+    assert_plan_cost!(plan, 4);
+}
+
+#[test]
+fn test_budget_just_below_the_cheapest_plan() {
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let result = plan_within(3, strategy);
+
+        assert_eq!(
+            Err(PlanError::TooExpensive {
+                cheapest_found: Some(4)
+            }),
+            result,
+            "{:?}",
+            strategy
+        );
+    }
+}
+
+#[test]
+fn test_budget_far_below_the_cheapest_plan() {
+    // No path got within one step of the goal before going over budget
+    let result = plan_within(1, PlanningStrategy::StartToGoal);
+
+    assert_eq!(
+        Err(PlanError::TooExpensive {
+            cheapest_found: None
+        }),
+        result
+    );
+}
+
+#[test]
+fn test_stats_show_the_pruning() {
+    let (start, actions, goal) = mining();
+    let config = PlannerConfig::new().with_max_cost(3);
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(None, plan);
+    assert!(stats.pruned_nodes > 0);
+    assert_eq!(Some(4), stats.cheapest_over_budget);
+}
+
+#[test]
+fn test_budget_ends_endless_searches() {
+    let (start, actions, _) = mining();
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(-1)));
+    let config = PlannerConfig::new().with_max_cost(10);
+
+    // Gold only goes up, so this would search forever without the budget
+    let result = try_make_plan_with_config(&start, &actions, &goal, &config);
+
+    assert_eq!(
+        Err(PlanError::TooExpensive {
+            cheapest_found: None
+        }),
+        result
+    );
+}
@@ -0,0 +1,147 @@
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+// `has_sword` only exists once the sword has been crafted
+fn duel() -> (LocalState, Vec<Action>) {
+    let start = LocalState::new().with_datum("won", Datum::Bool(false));
+    let actions = vec![
+        simple_action("craft_sword", "has_sword", Datum::Bool(true)),
+        simple_action("fight", "won", Datum::Bool(true))
+            .with_precondition("has_sword", Compare::Equals(Datum::Bool(true))),
+    ];
+    (start, actions)
+}
+
+fn plan(policy: MissingKeyPolicy, goal: &Goal) -> Option<Plan> {
+    let (start, actions) = duel();
+    let config = PlannerConfig::new().with_missing_keys(policy);
+    make_plan_with_config(&start, &actions, goal, &config)
+}
+
+#[test]
+fn test_insert_creates_keys_mid_plan() {
+    let goal = Goal::new().with_req("won", Compare::Equals(Datum::Bool(true)));
+
+    let plan = plan(MissingKeyPolicy::Insert, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["craft_sword", "fight"]);
+    assert_plan_reaches!(plan, goal);
+}
+
+#[test]
+fn test_insert_reaches_goals_on_keys_created_mid_plan() {
+    let goal = Goal::new().with_req("has_sword", Compare::Equals(Datum::Bool(true)));
+
+    let plan = plan(MissingKeyPolicy::Insert, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["craft_sword"]);
+}
+
+#[test]
+fn test_ignore_never_creates_keys() {
+    let goal = Goal::new().with_req("won", Compare::Equals(Datum::Bool(true)));
+
+    assert_eq!(None, plan(MissingKeyPolicy::Ignore, &goal));
+}
+
+#[test]
+fn test_reject_skips_actions_writing_missing_keys() {
+    let goal = Goal::new().with_req("won", Compare::Equals(Datum::Bool(true)));
+
+    assert_eq!(None, plan(MissingKeyPolicy::Reject, &goal));
+}
+
+#[test]
+fn test_effect_apply_with_each_policy() {
+    let state = LocalState::new().with_datum("gold", Datum::I64(3));
+    let effect = Effect::new("work")
+        .with_mutator(Mutator::Increment("gold".to_string(), Datum::I64(2)))
+        .with_mutator(Mutator::Set("tired".to_string(), Datum::Bool(true)))
+        .with_mutator(Mutator::Increment("xp".to_string(), Datum::I64(1)));
+
+    assert_eq!(None, effect.apply(&state, MissingKeyPolicy::Reject));
+    assert_eq!(
+        Some(LocalState::new().with_datum("gold", Datum::I64(5))),
+        effect.apply(&state, MissingKeyPolicy::Ignore)
+    );
+    // There's no value to increment `xp` from, so only `tired` gets created
+    assert_eq!(
+        Some(
+            LocalState::new()
+                .with_datum("gold", Datum::I64(5))
+                .with_datum("tired", Datum::Bool(true))
+        ),
+        effect.apply(&state, MissingKeyPolicy::Insert)
+    );
+}
+
+#[test]
+fn test_try_make_plan_reports_missing_goal_keys() {
+    let (start, actions) = duel();
+    let goal = Goal::new().with_req("has_shield", Compare::Equals(Datum::Bool(true)));
+
+    assert_eq!(
+        Err(PlanError::MissingKey("has_shield".to_string())),
+        try_make_plan(&start, &actions, &goal)
+    );
+}
+
+#[test]
+fn test_try_make_plan_reports_missing_precondition_keys() {
+    let (start, actions) = duel();
+    let goal = Goal::new().with_req("won", Compare::Equals(Datum::Bool(true)));
+
+    // Without a policy, `fight` can't be checked before the sword is crafted
+    assert_eq!(
+        Err(PlanError::MissingKey("has_sword".to_string())),
+        try_make_plan(&start, &actions, &goal)
+    );
+}
+
+#[test]
+fn test_try_make_plan_accepts_keys_with_a_default_or_a_policy() {
+    let (start, actions) = duel();
+    let goal = Goal::new().with_req("won", Compare::Equals(Datum::Bool(true)));
+
+    let config = PlannerConfig::new().with_default("has_sword", Datum::Bool(false));
+    let plan = try_make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+    assert_plan_actions!(plan.unwrap(), ["craft_sword", "fight"]);
+
+    let config = PlannerConfig::new().with_missing_keys(MissingKeyPolicy::Insert);
+    let plan = try_make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+    assert_plan_actions!(plan.unwrap(), ["craft_sword", "fight"]);
+}
+
+#[test]
+fn test_goal_on_a_key_the_state_never_has_is_unmet() {
+    let start = LocalState::new().with_datum("won", Datum::Bool(false));
+    let actions = vec![simple_action("fight", "won", Datum::Bool(true))];
+    let goal = Goal::new()
+        .with_req("won", Compare::Equals(Datum::Bool(true)))
+        .with_req("has_shield", Compare::Equals(Datum::Bool(true)));
+
+    assert!(!check_goal(&start, &goal));
+    assert!(!goal.is_satisfied(&start));
+    assert_eq!(None, make_plan(&start, &actions, &goal));
+}
+
+#[test]
+fn test_is_satisfied_once_the_missing_key_is_there() {
+    let goal = Goal::new().with_req("has_shield", Compare::Equals(Datum::Bool(true)));
+
+    assert!(!goal.is_satisfied(&LocalState::new()));
+    assert!(goal.is_satisfied(&LocalState::new().with_datum("has_shield", Datum::Bool(true))));
+}
+
+#[test]
+fn test_goal_on_a_key_created_mid_plan_is_reached_without_a_policy() {
+    let start = LocalState::new().with_datum("won", Datum::Bool(false));
+    let actions = vec![simple_action("craft_sword", "has_sword", Datum::Bool(true))];
+    let goal = Goal::new().with_req("has_sword", Compare::Equals(Datum::Bool(true)));
+
+    assert!(!check_goal(&start, &goal));
+    // A missing key counts as one step away
+    assert_eq!(1, start.distance_to_goal(&goal));
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    assert_plan_actions!(plan, ["craft_sword"]);
+    assert_plan_reaches!(plan, goal);
+}
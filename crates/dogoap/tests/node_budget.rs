@@ -0,0 +1,84 @@
+use dogoap::{prelude::*, simple::simple_increment_action, testing::*};
+
+// Counting up never ends and nothing ever sets `done`, so the search would go on
+// forever without a budget
+fn counting(keys: usize) -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new().with_datum("done", Datum::Bool(false));
+    let mut actions = vec![];
+    for key in 0..keys {
+        let name = format!("k{}", key);
+        start = start.with_datum(name.clone(), Datum::I64(0));
+        actions.push(simple_increment_action(
+            &format!("count_{}", key),
+            &name,
+            Datum::I64(1),
+        ));
+    }
+    let goal = Goal::new().with_req("done", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+fn over_budget(max_nodes: usize, strategy: PlanningStrategy) -> PlanStats {
+    let (start, actions, goal) = counting(6);
+    let config = PlannerConfig::new()
+        .with_strategy(strategy)
+        .with_max_expanded_nodes(max_nodes);
+    match try_make_plan_with_config(&start, &actions, &goal, &config) {
+        Err(PlanError::BudgetExceeded(stats)) => stats,
+        other => panic!("expected the budget to run out, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_explosive_search_stops_at_the_budget() {
+    let stats = over_budget(100, PlanningStrategy::StartToGoal);
+
+    assert_eq!(100, stats.expanded_nodes);
+    // Only the nodes within budget had their successors generated
+    assert_eq!(100 * 6, stats.generated_nodes);
+}
+
+#[test]
+fn test_search_expands_exactly_the_budget() {
+    for max_nodes in [1, 10, 1000] {
+        let stats = over_budget(max_nodes, PlanningStrategy::StartToGoal);
+
+        assert_eq!(max_nodes, stats.expanded_nodes);
+    }
+}
+
+#[test]
+fn test_budget_stops_at_the_same_point_every_time() {
+    assert_eq!(
+        over_budget(50, PlanningStrategy::StartToGoal),
+        over_budget(50, PlanningStrategy::StartToGoal)
+    );
+}
+
+#[test]
+fn test_budget_is_shared_with_the_forward_fallback() {
+    let stats = over_budget(100, PlanningStrategy::GoalToStart);
+
+    assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    assert!(stats.generated_nodes <= 100 * 6);
+}
+
+#[test]
+fn test_make_plan_with_budget_returns_none_when_it_runs_out() {
+    let (start, actions, goal) = counting(6);
+
+    assert_eq!(None, make_plan_with_budget(&start, &actions, &goal, 100));
+}
+
+#[test]
+fn test_plan_within_budget_is_the_same_plan() {
+    let (start, actions, _) = counting(2);
+    let goal = Goal::new()
+        .with_req("k0", Compare::Equals(Datum::I64(2)))
+        .with_req("k1", Compare::Equals(Datum::I64(1)));
+
+    let plan = make_plan_with_budget(&start, &actions, &goal, 100).unwrap();
+
+    assert_eq!(make_plan(&start, &actions, &goal), Some(plan.clone()));
+    assert_plan_cost!(plan, 3);
+}
@@ -0,0 +1,101 @@
+use dogoap::{prelude::*, testing::*};
+
+// Working pays well while rested, then hardly at all until a long rest
+fn shifts() -> (LocalState, Vec<Action>) {
+    let start = LocalState::new()
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("tired", Datum::I64(0));
+    let actions = vec![
+        Action::new("work")
+            .with_precondition("tired", Compare::LessThanEquals(Datum::I64(1)))
+            .add_mutator(Mutator::Increment("gold".to_string(), Datum::I64(4)))
+            .add_mutator(Mutator::Increment("tired".to_string(), Datum::I64(1)))
+            .set_cost(2),
+        Action::new("work_tired")
+            .with_precondition("tired", Compare::GreaterThanEquals(Datum::I64(2)))
+            .add_mutator(Mutator::Increment("gold".to_string(), Datum::I64(1)))
+            .set_cost(2),
+        Action::new("rest")
+            .add_mutator(Mutator::Set("tired".to_string(), Datum::I64(0)))
+            .set_cost(5),
+    ];
+    (start, actions)
+}
+
+fn most_gold(budget: Budget) -> Plan {
+    let (start, actions) = shifts();
+    make_plan_for_objective(
+        &start,
+        &actions,
+        &Objective::Maximize("gold".to_string()),
+        budget,
+        &Goal::new(),
+        &PlannerConfig::new(),
+    )
+    .unwrap()
+}
+
+fn final_value(plan: &Plan, key: &str) -> Datum {
+    plan.0.last().unwrap().state().data[key]
+}
+
+#[test]
+fn test_tight_budget_works_on_tired() {
+    let plan = most_gold(Budget::Cost(8));
+
+    // Resting doesn't fit in the budget
+    assert_plan_actions!(plan, ["work", "work", "work_tired", "work_tired"]);
+    assert_plan_cost!(plan, 8);
+    assert_eq!(Datum::I64(10), final_value(&plan, "gold"));
+}
+
+#[test]
+fn test_larger_budget_pays_for_resting() {
+    let plan = most_gold(Budget::Cost(13));
+
+    assert_plan_actions!(plan, ["work", "work", "rest", "work", "work"]);
+    assert_eq!(Datum::I64(16), final_value(&plan, "gold"));
+}
+
+#[test]
+fn test_ties_go_to_the_cheaper_plan() {
+    // Working tired four times earns as much as resting and working once more,
+    // but costs one more
+    let plan = most_gold(Budget::Cost(12));
+
+    assert_eq!(Datum::I64(12), final_value(&plan, "gold"));
+    assert_plan_cost!(plan, 11);
+}
+
+#[test]
+fn test_depth_budget() {
+    let plan = most_gold(Budget::Depth(3));
+
+    assert_plan_actions!(plan, ["work", "work", "work_tired"]);
+    assert_eq!(Datum::I64(9), final_value(&plan, "gold"));
+}
+
+#[test]
+fn test_minimize_with_requirements() {
+    let (start, actions) = shifts();
+    let requirements = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(8)));
+    let least_tired = |budget| {
+        make_plan_for_objective(
+            &start,
+            &actions,
+            &Objective::Minimize("tired".to_string()),
+            budget,
+            &requirements,
+            &PlannerConfig::new(),
+        )
+    };
+
+    let plan = least_tired(Budget::Depth(3)).unwrap();
+    assert_plan_actions!(plan, ["work", "work", "rest"]);
+    assert_plan_reaches!(plan, requirements);
+
+    let plan = least_tired(Budget::Depth(2)).unwrap();
+    assert_eq!(Datum::I64(2), final_value(&plan, "tired"));
+
+    assert_eq!(None, least_tired(Budget::Depth(1)));
+}
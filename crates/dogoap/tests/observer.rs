@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex};
+
+use dogoap::{prelude::*, simple::*};
+
+fn mine() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("has_pickaxe", Datum::Bool(false));
+    let actions = vec![
+        simple_action("buy_pickaxe", "has_pickaxe", Datum::Bool(true)),
+        simple_increment_action("mine", "gold", Datum::I64(2))
+            .with_precondition("has_pickaxe", Compare::Equals(Datum::Bool(true))),
+        simple_increment_action("beg", "gold", Datum::I64(1)).set_cost(2),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(4)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_every_expanded_node_is_observed() {
+    let (start, actions, goal) = mine();
+    let expanded = Arc::new(Mutex::new(vec![]));
+    let seen = expanded.clone();
+    let config = PlannerConfig::new().with_on_expand(move |node: &Node, cost, _| {
+        seen.lock()
+            .unwrap()
+            .push((node.action_key().map(str::to_string), cost));
+    });
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    let expanded = expanded.lock().unwrap();
+    assert_eq!(stats.expanded_nodes, expanded.len());
+    assert_eq!((None, 0), expanded[0]);
+    // The hook only watches
+    assert_eq!(make_plan(&start, &actions, &goal), plan);
+}
+
+#[test]
+fn test_outcome_is_observed() {
+    let (start, actions, goal) = mine();
+    let outcomes = Arc::new(Mutex::new(vec![]));
+    let seen = outcomes.clone();
+    let config = PlannerConfig::new().with_on_finish(move |plan, stats| {
+        seen.lock()
+            .unwrap()
+            .push((plan.map(|plan| plan.1), stats.expanded_nodes));
+    });
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+    let out_of_budget = goal
+        .clone()
+        .with_req("has_pickaxe", Compare::Equals(Datum::Bool(false)))
+        .with_req("gold", Compare::GreaterThanEquals(Datum::I64(100)));
+    let config = config.with_max_expanded_nodes(50);
+    assert_eq!(
+        None,
+        make_plan_with_config(&start, &actions, &out_of_budget, &config)
+    );
+
+    let outcomes = outcomes.lock().unwrap();
+    assert_eq!(2, outcomes.len());
+    assert_eq!(Some(plan.1), outcomes[0].0);
+    assert_eq!(None, outcomes[1].0);
+}
@@ -0,0 +1,168 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// A lamp that can be switched on and has to end up off, and a door that needs no
+// light
+fn room() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("lamp", Datum::Bool(false))
+        .with_datum("door", Datum::Bool(false))
+        .with_datum("coins", Datum::I64(0));
+    let lamp = |value| Compare::Equals(Datum::Bool(value));
+    let actions = vec![
+        simple_action("switch_on", "lamp", Datum::Bool(true))
+            .with_precondition("lamp", lamp(false)),
+        simple_action("switch_off", "lamp", Datum::Bool(false))
+            .with_precondition("lamp", lamp(true)),
+        simple_action("open_door", "door", Datum::Bool(true)).set_cost(2),
+        simple_increment_action("earn", "coins", Datum::I64(1)),
+        simple_action("win_jackpot", "coins", Datum::I64(3)).set_cost(3),
+    ];
+    let goal = Goal::new()
+        .with_req("lamp", Compare::Equals(Datum::Bool(false)))
+        .with_req("door", Compare::Equals(Datum::Bool(true)))
+        .with_req("coins", Compare::Equals(Datum::I64(3)));
+    (start, actions, goal)
+}
+
+/// The plan that takes `steps` in order, like a search that took a detour would
+fn plan_of(start: &LocalState, actions: &[Action], steps: &[&str]) -> Plan {
+    let mut nodes = vec![Node::State(start.clone())];
+    let mut cost = 0;
+    for step in steps {
+        let action = actions.iter().find(|action| action.key == *step).unwrap();
+        let effect = &action.effects[0];
+        let state = effect
+            .apply(nodes.last().unwrap().state(), MissingKeyPolicy::Insert)
+            .unwrap();
+        cost += effect.cost;
+        let mut effect = effect.clone();
+        effect.action = action.key.clone();
+        effect.state = Some(state);
+        nodes.push(Node::Effect(effect));
+    }
+    (nodes, cost)
+}
+
+#[test]
+fn test_validate_plan() {
+    let (start, actions, goal) = room();
+
+    let valid = plan_of(
+        &start,
+        &actions,
+        &["switch_on", "switch_off", "open_door", "win_jackpot"],
+    );
+    assert!(validate_plan(&valid, &start, &actions, &goal));
+
+    // Doesn't reach the goal
+    let short = plan_of(&start, &actions, &["open_door"]);
+    assert!(!validate_plan(&short, &start, &actions, &goal));
+
+    // Switching off a lamp that's off
+    let mut broken = plan_of(&start, &actions, &["open_door", "win_jackpot"]);
+    let off = plan_of(&start, &actions, &["switch_on", "switch_off"]);
+    broken.0.insert(1, off.0[2].clone());
+    assert!(!validate_plan(&broken, &start, &actions, &goal));
+
+    // An action that isn't in the domain
+    assert!(!validate_plan(&valid, &start, &actions[1..], &goal));
+}
+
+#[test]
+fn test_drops_toggled_pair() {
+    let (start, actions, goal) = room();
+    let raw = plan_of(
+        &start,
+        &actions,
+        &["switch_on", "open_door", "switch_off", "win_jackpot"],
+    );
+    assert!(validate_plan(&raw, &start, &actions, &goal));
+
+    let plan = optimize_plan(&raw, &start, &actions, &goal);
+
+    assert_plan_actions!(plan, ["open_door", "win_jackpot"]);
+    assert_plan_cost!(plan, 5);
+    assert!(validate_plan(&plan, &start, &actions, &goal));
+}
+
+#[test]
+fn test_drops_overshoot_fixed_by_later_set() {
+    let (start, actions, goal) = room();
+    let raw = plan_of(
+        &start,
+        &actions,
+        &["earn", "earn", "open_door", "win_jackpot"],
+    );
+
+    let plan = optimize_plan(&raw, &start, &actions, &goal);
+
+    assert_plan_actions!(plan, ["open_door", "win_jackpot"]);
+    assert!(validate_plan(&plan, &start, &actions, &goal));
+}
+
+#[test]
+fn test_keeps_needed_steps() {
+    let (start, actions, goal) = room();
+    let raw = make_plan(&start, &actions, &goal).unwrap();
+
+    let plan = optimize_plan(&raw, &start, &actions, &goal);
+
+    assert_eq!(raw, plan);
+}
+
+#[test]
+fn test_leaves_invalid_plan_alone() {
+    let (start, actions, goal) = room();
+    let raw = plan_of(&start, &actions, &["switch_on", "switch_off"]);
+
+    let plan = optimize_plan(&raw, &start, &actions, &goal);
+
+    assert_eq!(raw, plan);
+}
+
+#[test]
+fn test_check_plan_tells_why() {
+    let (start, actions, goal) = room();
+    let valid = plan_of(
+        &start,
+        &actions,
+        &["switch_on", "switch_off", "open_door", "win_jackpot"],
+    );
+    assert_eq!(Ok(()), check_plan(&valid, &start, &actions, &goal));
+
+    let short = plan_of(&start, &actions, &["open_door"]);
+    assert_eq!(
+        Err(PlanInvalid::GoalUnmet),
+        check_plan(&short, &start, &actions, &goal)
+    );
+
+    // The lamp was switched on by someone else since
+    let lit = start.clone().with_datum("lamp", Datum::Bool(true));
+    assert_eq!(
+        Err(PlanInvalid::PreconditionFailed {
+            step: 0,
+            action: "switch_on".to_string(),
+            key: Some("\"lamp\"".to_string()),
+        }),
+        check_plan(&valid, &lit, &actions, &goal)
+    );
+
+    // Coins found on the way make the jackpot leave a different amount
+    let rich = plan_of(&start, &actions, &["open_door", "win_jackpot"]);
+    let found = start.clone().with_datum("coins", Datum::I64(1));
+    assert_eq!(
+        Err(PlanInvalid::StateMismatch {
+            step: 0,
+            action: "open_door".to_string(),
+        }),
+        check_plan(&rich, &found, &actions, &goal)
+    );
+
+    assert_eq!(
+        Err(PlanInvalid::UnknownAction {
+            step: 0,
+            action: "switch_on".to_string(),
+        }),
+        check_plan(&valid, &start, &actions[1..], &goal)
+    );
+}
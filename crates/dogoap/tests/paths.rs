@@ -0,0 +1,145 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+fn inventory() -> LocalState {
+    LocalState::new()
+        .with_datum("inventory.wood", Datum::I64(2))
+        .with_datum("inventory.stone", Datum::I64(0))
+        .with_datum("inventory.bag.gems", Datum::I64(5))
+        .with_datum("inventory_size", Datum::I64(10))
+        .with_datum("stats.hp", Datum::I64(7))
+}
+
+#[test]
+fn test_get_and_set_by_path() {
+    let mut state = inventory();
+
+    assert_eq!(Ok(Some(&Datum::I64(7))), state.get_path("stats.hp"));
+    assert_eq!(Ok(None), state.get_path("stats.mana"));
+
+    assert_eq!(Ok(None), state.set_path("pos.x", Datum::I64(3)));
+    assert_eq!(
+        Ok(Some(Datum::I64(3))),
+        state.set_path("pos.x", Datum::I64(4))
+    );
+    assert_eq!(Some(&Datum::I64(4)), state.data.get("pos.x"));
+}
+
+#[test]
+fn test_invalid_paths() {
+    let state = inventory();
+
+    assert_eq!(Err(PathError::Empty), state.get_path(""));
+    assert_eq!(
+        Err(PathError::TrailingDot("stats.".to_string())),
+        state.get_path("stats.")
+    );
+    assert_eq!(
+        Err(PathError::EmptySegment {
+            path: ".hp".to_string(),
+            index: 0
+        }),
+        state.get_path(".hp")
+    );
+    assert_eq!(
+        Err(PathError::EmptySegment {
+            path: "stats..hp".to_string(),
+            index: 1
+        }),
+        state.get_path("stats..hp")
+    );
+    assert_eq!(
+        Err(PathError::MisplacedWildcard("inventory.*".to_string())),
+        state.get_path("inventory.*")
+    );
+    assert_eq!(
+        Err(PathError::EmptySegment {
+            path: "stats..hp".to_string(),
+            index: 1
+        }),
+        Mutator::increment_path("stats..hp", Datum::I64(1))
+    );
+    assert_eq!(
+        Ok(vec!["inventory", "bag", "gems"]),
+        parse_path("inventory.bag.gems")
+    );
+}
+
+#[test]
+fn test_get_all_under_a_prefix() {
+    let state = inventory();
+    let keys = |prefix| {
+        state
+            .get_all(prefix)
+            .unwrap()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>()
+    };
+
+    // Nested keys are included, keys that only start with the same letters aren't
+    let expected = vec!["inventory.bag.gems", "inventory.stone", "inventory.wood"];
+    assert_eq!(expected, keys("inventory."));
+    assert_eq!(expected, keys("inventory"));
+    assert_eq!(vec!["inventory.bag.gems"], keys("inventory.bag"));
+    assert!(keys("pos").is_empty());
+
+    assert!(matches!(
+        state.get_all("inventory.."),
+        Err(PathError::TrailingDot(_))
+    ));
+}
+
+// Crafting a tool takes three kinds of materials, whichever they are
+fn workshop() -> (LocalState, Vec<Action>, PlannerConfig) {
+    let start = LocalState::new()
+        .with_datum("inventory.wood", Datum::I64(1))
+        .with_datum("inventory.stone", Datum::I64(0))
+        .with_datum("inventory.iron", Datum::I64(0))
+        .with_datum("inventory.gold", Datum::I64(0))
+        .with_datum("has_tool", Datum::Bool(false));
+    let actions = vec![
+        Action::new("mine_stone")
+            .add_mutator(Mutator::increment_path("inventory.stone", Datum::I64(1)).unwrap())
+            .set_cost(1),
+        Action::new("mine_iron")
+            .add_mutator(Mutator::increment_path("inventory.iron", Datum::I64(1)).unwrap())
+            .set_cost(2),
+        Action::new("mine_gold")
+            .add_mutator(Mutator::increment_path("inventory.gold", Datum::I64(1)).unwrap())
+            .set_cost(5),
+        simple_action("craft", "has_tool", Datum::Bool(true))
+            .with_precondition("inventory.*", Compare::GreaterThanEquals(Datum::I64(3))),
+    ];
+    let config = PlannerConfig::new().with_wildcard("inventory.*", Wildcard::Count);
+    (start, actions, config)
+}
+
+#[test]
+fn test_wildcard_count_precondition() {
+    let (start, actions, config) = workshop();
+    let goal = Goal::new().with_req("has_tool", Compare::Equals(Datum::Bool(true)));
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    // Wood is already there, the two cheapest other kinds make three
+    let mut steps = plan_actions(&plan);
+    steps.sort();
+    assert_eq!(vec!["craft", "mine_iron", "mine_stone"], steps);
+    assert_plan_cost!(plan, 4);
+}
+
+#[test]
+fn test_wildcard_sum_goal() {
+    let (start, actions, _) = workshop();
+    let config = PlannerConfig::new().with_wildcard("inventory.*", Wildcard::Sum);
+    let goal = Goal::new().with_req("inventory.*", Compare::Equals(Datum::I64(4)));
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    assert_plan_actions!(plan, ["mine_stone", "mine_stone", "mine_stone"]);
+}
+
+#[test]
+#[should_panic(expected = "doesn't end in .*")]
+fn test_wildcard_has_to_end_in_a_star() {
+    PlannerConfig::new().with_wildcard("inventory", Wildcard::Count);
+}
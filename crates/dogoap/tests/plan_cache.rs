@@ -0,0 +1,87 @@
+use dogoap::{prelude::*, simple::*};
+
+fn start() -> LocalState {
+    LocalState::new().with_datum("wood", Datum::I64(0))
+}
+
+fn goal(wood: i64) -> Goal {
+    Goal::new().with_req("wood", Compare::GreaterThanEquals(Datum::I64(wood)))
+}
+
+fn actions(cost: usize) -> Vec<Action> {
+    vec![simple_increment_action("chop", "wood", Datum::I64(1)).set_cost(cost)]
+}
+
+#[test]
+fn test_hit_returns_the_same_plan() {
+    let mut cache = PlanCache::new(4);
+    let first = cache.make_plan(&start(), &actions(1), &goal(2)).unwrap();
+    let second = cache.make_plan(&start(), &actions(1), &goal(2)).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(make_plan(&start(), &actions(1), &goal(2)).unwrap(), first);
+    assert_eq!(1, cache.len());
+}
+
+#[test]
+fn test_changing_an_action_makes_a_new_plan() {
+    let mut cache = PlanCache::new(4);
+    let cheap = cache.make_plan(&start(), &actions(1), &goal(2)).unwrap();
+    let expensive = cache.make_plan(&start(), &actions(3), &goal(2)).unwrap();
+
+    assert_eq!(2, cheap.1);
+    assert_eq!(6, expensive.1);
+    assert_eq!(2, cache.len());
+}
+
+#[test]
+fn test_no_plan_is_remembered() {
+    let mut cache = PlanCache::new(4);
+    let start = LocalState::new()
+        .with_datum("resting", Datum::Bool(false))
+        .with_datum("fed", Datum::Bool(false));
+    let actions = vec![simple_action("sit", "resting", Datum::Bool(true))];
+    let unreachable = Goal::new().with_req("fed", Compare::Equals(Datum::Bool(true)));
+
+    assert!(cache.make_plan(&start, &actions, &unreachable).is_none());
+    assert_eq!(1, cache.len());
+    assert!(cache.make_plan(&start, &actions, &unreachable).is_none());
+    assert_eq!(1, cache.len());
+}
+
+#[test]
+fn test_least_recently_used_is_forgotten() {
+    let mut cache = PlanCache::new(2);
+    cache.make_plan(&start(), &actions(1), &goal(1));
+    cache.make_plan(&start(), &actions(1), &goal(2));
+    // Using the first plan again makes the second the oldest
+    cache.make_plan(&start(), &actions(1), &goal(1));
+    cache.make_plan(&start(), &actions(1), &goal(3));
+    assert_eq!(2, cache.len());
+
+    cache.invalidate(&start(), &goal(1));
+    cache.invalidate(&start(), &goal(3));
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_invalidate_and_clear() {
+    let mut cache = PlanCache::new(4);
+    cache.make_plan(&start(), &actions(1), &goal(1));
+    cache.make_plan(&start(), &actions(3), &goal(1));
+    cache.make_plan(&start(), &actions(1), &goal(2));
+
+    cache.invalidate(&start(), &goal(1));
+    assert_eq!(1, cache.len());
+
+    cache.clear();
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_zero_capacity_remembers_nothing() {
+    let mut cache = PlanCache::new(0);
+
+    assert!(cache.make_plan(&start(), &actions(1), &goal(2)).is_some());
+    assert!(cache.is_empty());
+}
@@ -0,0 +1,72 @@
+use dogoap::{prelude::*, simple::simple_increment_action, testing::*};
+
+fn mining() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let actions = vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("dig", "gold", Datum::I64(2)).set_cost(3),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(3)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_detailed_plan_matches_plan_and_stats() {
+    let (start, actions, goal) = mining();
+    let config = PlannerConfig::new();
+
+    let result = make_plan_detailed(&start, &actions, &goal, &config).unwrap();
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(stats.expanded_nodes, result.expanded_nodes);
+    assert_eq!(stats.generated_nodes, result.generated_nodes);
+    assert!(result.expanded_nodes > 0);
+    assert_eq!(plan, Some(result.into()));
+}
+
+#[test]
+fn test_detailed_plan_works_with_the_plan_helpers() {
+    let (start, actions, goal) = mining();
+
+    let result = make_plan_detailed(&start, &actions, &goal, &PlannerConfig::new()).unwrap();
+
+    assert_eq!(3, result.cost());
+    assert_eq!(
+        vec!["mine", "mine", "mine"],
+        action_keys_of_plan(&result.nodes).collect::<Vec<_>>()
+    );
+    assert_eq!(3, effects_of_plan(&result.nodes).count());
+    print_plan(&result);
+    print_plan_with_format(&result, PlanFormat::Verbose);
+
+    let plan: Plan = result.into();
+    assert_plan_cost!(plan, 3);
+}
+
+#[test]
+fn test_no_detailed_plan_without_a_plan() {
+    let (start, actions, _) = mining();
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(-1)));
+    let config = PlannerConfig::new().with_max_cost(10);
+
+    assert_eq!(None, make_plan_detailed(&start, &actions, &goal, &config));
+}
+
+#[test]
+fn test_instrumented_counts_searches_with_and_without_a_plan() {
+    let (start, actions, goal) = mining();
+    let config = PlannerConfig::new();
+
+    let (plan, stats) = make_plan_instrumented(&start, &actions, &goal, &config);
+    assert_plan_cost!(plan.unwrap(), 3);
+    assert!(stats.expanded_nodes > 1);
+    assert!(stats.generated_nodes > stats.expanded_nodes);
+
+    // Gold only ever goes up, so the search runs out of states below the goal
+    let unreachable = Goal::new().with_req("gold", Compare::LessThanEquals(Datum::I64(-1)));
+    let config = config.with_max_cost(10);
+    let (plan, stats) = make_plan_instrumented(&start, &actions, &unreachable, &config);
+    assert_eq!(None, plan);
+    assert!(stats.expanded_nodes > 1);
+    assert!(stats.generated_nodes > 0);
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use dogoap::prelude::*;
+
+// Keys named so that their order isn't the order they come up in, and plenty of
+// equally cheap plans to pick between
+fn woodcutter() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("z_energy", Datum::I64(10))
+        .with_datum("hunger", Datum::I64(60))
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("has_axe", Datum::Bool(false))
+        .with_datum("a_wood", Datum::I64(0));
+    let actions = vec![
+        Action::new("chop")
+            .with_precondition("has_axe", Compare::Equals(Datum::Bool(true)))
+            .with_precondition("z_energy", Compare::GreaterThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Increment("a_wood".to_string(), Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("z_energy".to_string(), Datum::I64(10))),
+        Action::new("buy_axe")
+            .with_precondition("gold", Compare::GreaterThanEquals(Datum::I64(2)))
+            .add_mutator(Mutator::Set("has_axe".to_string(), Datum::Bool(true)))
+            .add_mutator(Mutator::Decrement("gold".to_string(), Datum::I64(2))),
+        Action::new("work")
+            .with_precondition("z_energy", Compare::GreaterThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Increment("gold".to_string(), Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("z_energy".to_string(), Datum::I64(10))),
+        Action::new("sleep")
+            .add_mutator(Mutator::Increment("z_energy".to_string(), Datum::I64(20))),
+        Action::new("eat")
+            .with_precondition("gold", Compare::GreaterThanEquals(Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("hunger".to_string(), Datum::I64(30)))
+            .add_mutator(Mutator::Decrement("gold".to_string(), Datum::I64(1))),
+    ];
+    let goal = Goal::new()
+        .with_req("a_wood", Compare::GreaterThanEquals(Datum::I64(2)))
+        .with_req("hunger", Compare::LessThanEquals(Datum::I64(30)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_same_plan_as_with_strings() {
+    let (start, actions, goal) = woodcutter();
+    for strategy in [
+        PlanningStrategy::StartToGoal,
+        PlanningStrategy::GoalToStart,
+        PlanningStrategy::UniformCost,
+        PlanningStrategy::Auto,
+    ] {
+        let config = PlannerConfig::new().with_strategy(strategy);
+        let context = PlanningContext::new(&start, &actions, &goal, &config);
+
+        assert!(context.is_interned());
+        assert_eq!(
+            make_plan_with_config(&start, &actions, &goal, &config),
+            context.make_plan()
+        );
+    }
+}
+
+#[test]
+fn test_config_keys_are_swapped_too() {
+    let (start, mut actions, goal) = woodcutter();
+    actions.push(
+        Action::new("stash_wood")
+            .add_mutator(Mutator::CopyFrom("stash".to_string(), "a_wood".to_string()))
+            .add_mutator(Mutator::Unset("hunger".to_string())),
+    );
+    let config = PlannerConfig::new()
+        .with_default("stash", Datum::I64(0))
+        .with_avoid(
+            vec![("gold".to_string(), Compare::Equals(Datum::I64(3)))],
+            5,
+        )
+        .with_invariant(
+            Invariant::new().with_condition("z_energy", Compare::LessThanEquals(Datum::I64(40))),
+        )
+        .with_resource("gold");
+    let context = PlanningContext::new(&start, &actions, &goal, &config);
+
+    assert!(context.is_interned());
+    assert_eq!(
+        make_plan_with_stats(&start, &actions, &goal, &config),
+        context.make_plan_with_stats()
+    );
+}
+
+#[test]
+fn test_closures_plan_with_strings() {
+    let (start, mut actions, goal) = woodcutter();
+    actions[3] = actions[3]
+        .clone()
+        .with_dynamic_cost(Arc::new(|state: &LocalState| {
+            match state.data["z_energy"] {
+                Datum::I64(energy) if energy < 10 => 1,
+                _ => 5,
+            }
+        }));
+    let config = PlannerConfig::new();
+    let context = PlanningContext::new(&start, &actions, &goal, &config);
+
+    assert!(!context.is_interned());
+    assert_eq!(
+        make_plan_with_config(&start, &actions, &goal, &config),
+        context.make_plan()
+    );
+    let seeded = PlannerConfig::new().with_tie_seed(3);
+    assert!(!PlanningContext::new(&start, &woodcutter().1, &goal, &seeded).is_interned());
+}
+
+#[test]
+fn test_missing_key_is_reported_by_name() {
+    let (_, actions, goal) = woodcutter();
+    let start = LocalState::new().with_datum("z_energy", Datum::I64(10));
+    let config = PlannerConfig::new();
+    let context = PlanningContext::new(&start, &actions, &goal, &config);
+
+    let expected = try_make_plan_with_config(&start, &actions, &goal, &config);
+    assert!(matches!(expected, Err(PlanError::MissingKey(_))));
+    assert_eq!(expected, context.try_make_plan());
+}
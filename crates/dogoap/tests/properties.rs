@@ -0,0 +1,29 @@
+use dogoap::testing::properties::*;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_plans_reach_the_goal(problem in problem(Bounds::default())) {
+        check_plan_reaches_goal(&problem)?;
+    }
+
+    #[test]
+    fn test_plan_cost_is_sum_of_steps(problem in problem(Bounds::default())) {
+        check_cost_is_sum_of_steps(&problem)?;
+    }
+
+    #[test]
+    fn test_strategies_agree_on_cost(problem in problem(Bounds::default())) {
+        check_strategies_agree(&problem)?;
+    }
+
+    #[test]
+    fn test_plans_validate(problem in problem(Bounds::default())) {
+        check_plan_validates(&problem)?;
+    }
+
+    #[test]
+    fn test_larger_domains(problem in problem(Bounds { keys: 4, actions: 10, max_value: 6 })) {
+        check_all(&problem)?;
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use dogoap::prelude::*;
+
+const CHATTER: usize = 95;
+
+// Five actions about getting wood and ninety-five about gossip, which never helps.
+// Gossip costs more than the whole plan, so it's never expanded, but without
+// pruning every state still has to try each of them
+fn village() -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new()
+        .with_datum("energy", Datum::I64(10))
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("has_axe", Datum::Bool(false))
+        .with_datum("wood", Datum::I64(0));
+    let mut actions = vec![
+        Action::new("chop")
+            .with_precondition("has_axe", Compare::Equals(Datum::Bool(true)))
+            .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Increment("wood".to_string(), Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("energy".to_string(), Datum::I64(10))),
+        Action::new("buy_axe")
+            .with_precondition("gold", Compare::GreaterThanEquals(Datum::I64(2)))
+            .add_mutator(Mutator::Set("has_axe".to_string(), Datum::Bool(true)))
+            .add_mutator(Mutator::Decrement("gold".to_string(), Datum::I64(2))),
+        Action::new("work")
+            .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Increment("gold".to_string(), Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("energy".to_string(), Datum::I64(10))),
+        Action::new("sleep")
+            .with_precondition("energy", Compare::LessThanEquals(Datum::I64(30)))
+            .add_mutator(Mutator::Increment("energy".to_string(), Datum::I64(20))),
+        Action::new("sell_wood")
+            .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("wood".to_string(), Datum::I64(1)))
+            .add_mutator(Mutator::Increment("gold".to_string(), Datum::I64(3))),
+    ];
+    for i in 0..CHATTER {
+        let topic = format!("gossip_{}", i);
+        start = start.with_datum(&topic, Datum::I64(0));
+        actions.push(
+            Action::new(&format!("chat_{}", i))
+                .with_precondition(&topic, Compare::LessThanEquals(Datum::I64(1)))
+                .add_mutator(Mutator::Increment(topic, Datum::I64(1)))
+                .set_cost(50),
+        );
+    }
+    let goal = Goal::new().with_req("wood", Compare::GreaterThanEquals(Datum::I64(2)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_only_actions_that_lead_to_the_goal_are_kept() {
+    let (start, actions, goal) = village();
+    let relevant = relevant_actions(&actions, &goal);
+
+    let keys: Vec<&str> = relevant.iter().map(|action| action.key.as_str()).collect();
+    assert_eq!(vec!["chop", "buy_axe", "work", "sleep", "sell_wood"], keys);
+
+    let config = PlannerConfig::new();
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+    let (pruned_plan, pruned_stats) = make_plan_with_stats(&start, &relevant, &goal, &config);
+    assert_eq!(plan, pruned_plan);
+    assert!(
+        pruned_stats.generated_nodes * 10 < stats.generated_nodes,
+        "{} nodes generated with only the relevant actions, {} with all of them",
+        pruned_stats.generated_nodes,
+        stats.generated_nodes
+    );
+}
+
+#[test]
+fn test_copied_keys_and_alternatives_count() {
+    let actions = vec![
+        Action::new("fill_bucket").add_mutator(Mutator::Set("bucket".to_string(), Datum::I64(1))),
+        Action::new("pour").add_mutator(Mutator::CopyFrom(
+            "trough".to_string(),
+            "bucket".to_string(),
+        )),
+        Action::new("whistle").add_mutator(Mutator::Toggle("tune".to_string())),
+        Action::new("feed").add_mutator(Mutator::Set("fed".to_string(), Datum::Bool(true))),
+    ];
+    let goal = Goal::new()
+        .with_req("trough", Compare::Equals(Datum::I64(1)))
+        .with_alternative(&[("fed".to_string(), Compare::Equals(Datum::Bool(true)))]);
+
+    let keys: Vec<String> = relevant_actions(&actions, &goal)
+        .into_iter()
+        .map(|action| action.key)
+        .collect();
+    assert_eq!(vec!["fill_bucket", "pour", "feed"], keys);
+}
+
+#[test]
+fn test_closures_keep_everything() {
+    let (_, mut actions, goal) = village();
+    actions[3] = actions[3]
+        .clone()
+        .with_dynamic_cost(Arc::new(|_: &LocalState| 2));
+
+    assert_eq!(actions, relevant_actions(&actions, &goal));
+}
@@ -0,0 +1,118 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// Wood for a fire comes quickest from chopping trees, once there's an axe
+fn campfire(axe_in_shed: bool, trees: i64) -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("axe_in_shed", Datum::Bool(axe_in_shed))
+        .with_datum("has_axe", Datum::Bool(false))
+        .with_datum("trees", Datum::I64(trees))
+        .with_datum("wood", Datum::I64(0))
+        .with_datum("has_fire", Datum::Bool(false));
+    let actions = vec![
+        simple_action("get_axe", "has_axe", Datum::Bool(true))
+            .with_precondition("axe_in_shed", Compare::Equals(Datum::Bool(true)))
+            .with_precondition("has_axe", Compare::Equals(Datum::Bool(false))),
+        simple_increment_action("chop", "wood", Datum::I64(1))
+            .add_mutator(Mutator::Decrement("trees".to_string(), Datum::I64(1)))
+            .with_precondition("has_axe", Compare::Equals(Datum::Bool(true)))
+            .with_precondition("trees", Compare::GreaterThanEquals(Datum::I64(1))),
+        simple_increment_action("gather_sticks", "wood", Datum::I64(1)).set_cost(3),
+        simple_action("light_fire", "has_fire", Datum::Bool(true))
+            .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(2))),
+    ];
+    let goal = Goal::new().with_req("has_fire", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_plan_that_still_works_is_kept() {
+    let (start, actions, goal) = campfire(true, 5);
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    assert_plan_actions!(plan, ["get_axe", "chop", "chop", "light_fire"]);
+
+    let repaired = repair_plan(&plan, &start, &actions, &goal).unwrap();
+
+    assert_eq!(RepairedPlan::Kept(plan), repaired);
+}
+
+#[test]
+fn test_only_the_broken_part_is_planned_again() {
+    let (start, actions, goal) = campfire(true, 5);
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    // The trees were cut down by someone else, so the second step can't be taken
+    let (changed, _, _) = campfire(true, 0);
+
+    let repaired = repair_plan(&plan, &changed, &actions, &goal).unwrap();
+
+    assert!(matches!(repaired, RepairedPlan::Patched(_)));
+    let repaired = repaired.into_plan();
+    assert_plan_actions!(
+        repaired,
+        ["get_axe", "gather_sticks", "gather_sticks", "light_fire"]
+    );
+    assert_plan_cost!(repaired, 8);
+    assert!(validate_plan(&repaired, &changed, &actions, &goal));
+}
+
+#[test]
+fn test_plan_broken_from_the_start_is_made_again() {
+    let (start, actions, goal) = campfire(true, 5);
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    let (changed, _, _) = campfire(false, 0);
+
+    let repaired = repair_plan(&plan, &changed, &actions, &goal).unwrap();
+
+    let RepairedPlan::Replaced(repaired) = repaired else {
+        panic!("expected a new plan, got {:?}", repaired);
+    };
+    assert_eq!(make_plan(&changed, &actions, &goal).unwrap(), repaired);
+    assert_plan_actions!(repaired, ["gather_sticks", "gather_sticks", "light_fire"]);
+}
+
+#[test]
+fn test_no_plan_from_the_new_start() {
+    let (start, actions, goal) = campfire(true, 5);
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    let (changed, _, _) = campfire(false, 0);
+    let without_sticks: Vec<Action> = actions
+        .into_iter()
+        .filter(|action| action.key != "gather_sticks")
+        .collect();
+
+    assert_eq!(None, repair_plan(&plan, &changed, &without_sticks, &goal));
+}
+
+#[test]
+fn test_prefers_the_actions_of_the_old_plan() {
+    let (start, mut actions, goal) = campfire(true, 5);
+    // Held on to from before there was a lighter
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    for action in &mut actions {
+        if action.key == "light_fire" {
+            *action = action.clone().set_cost(2);
+        }
+    }
+    actions.insert(
+        0,
+        simple_action("use_lighter", "has_fire", Datum::Bool(true))
+            .set_cost(2)
+            .with_precondition("wood", Compare::GreaterThanEquals(Datum::I64(2))),
+    );
+    let (changed, _, _) = campfire(true, 1);
+    // Where the old plan stops working, after getting the axe and chopping once
+    let diverged = changed
+        .clone()
+        .with_datum("has_axe", Datum::Bool(true))
+        .with_datum("trees", Datum::I64(0))
+        .with_datum("wood", Datum::I64(1));
+    let fresh = make_plan(&diverged, &actions, &goal).unwrap();
+    assert_plan_actions!(fresh, ["gather_sticks", "use_lighter"]);
+
+    let repaired = repair_plan(&plan, &changed, &actions, &goal).unwrap();
+
+    // Just as cheap, but closer to what was going on before
+    let repaired = repaired.into_plan();
+    assert_plan_actions!(repaired, ["get_axe", "chop", "gather_sticks", "light_fire"]);
+    assert_plan_cost!(repaired, 2 + fresh.1);
+    assert!(effects_of_plan(&repaired.0).all(|effect| effect.reward == 0));
+}
@@ -0,0 +1,144 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// Every plank takes 3 wood and a table 2 planks, but there's only 2 wood to start
+// with. Chopping more is what costs
+fn workshop(wood: i64) -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("wood", Datum::I64(wood))
+        .with_datum("planks", Datum::I64(0))
+        .with_datum("table", Datum::Bool(false));
+    let actions = vec![
+        simple_increment_action("chop", "wood", Datum::I64(2)).set_cost(2),
+        simple_increment_action("craft_plank", "planks", Datum::I64(1))
+            .add_mutator(Mutator::Decrement("wood".to_string(), Datum::I64(3))),
+        simple_action("build_table", "table", Datum::Bool(true))
+            .with_precondition("planks", Compare::GreaterThanEquals(Datum::I64(2)))
+            .add_mutator(Mutator::Decrement("planks".to_string(), Datum::I64(2))),
+    ];
+    let goal = Goal::new().with_req("table", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+fn count(plan: &Plan, action: &str) -> usize {
+    effects_of_plan(&plan.0)
+        .filter(|effect| effect.action == action)
+        .count()
+}
+
+fn lowest_wood(plan: &Plan) -> i64 {
+    plan.0
+        .iter()
+        .map(|node| match node.state().data["wood"] {
+            Datum::I64(wood) => wood,
+            _ => unreachable!(),
+        })
+        .min()
+        .unwrap()
+}
+
+#[test]
+fn test_overdraws_without_resources() {
+    let (start, actions, goal) = workshop(2);
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["craft_plank", "craft_plank", "build_table"]);
+    assert_eq!(-4, lowest_wood(&plan));
+}
+
+#[test]
+fn test_gathers_before_crafting() {
+    let (start, actions, goal) = workshop(2);
+    for config in [
+        PlannerConfig::new(),
+        PlannerConfig::new().with_strategy(PlanningStrategy::GoalToStart),
+        PlannerConfig::new().with_rewards(Rewards::BoundedDepth(5)),
+    ] {
+        let config = config.with_resource("wood");
+
+        let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+        let plan = plan.unwrap();
+
+        assert_plan_cost!(plan, 7);
+        assert_eq!(2, count(&plan, "chop"));
+        assert_eq!(0, lowest_wood(&plan));
+        assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    }
+}
+
+#[test]
+fn test_resource_floor() {
+    let (start, actions, goal) = workshop(2);
+    let config = PlannerConfig::new().with_resource_floor("wood", Datum::I64(1));
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    assert_eq!(3, count(&plan, "chop"));
+    assert_eq!(1, lowest_wood(&plan));
+}
+
+#[test]
+fn test_starting_below_the_floor() {
+    // Chopping still helps while the wood stays below zero, crafting doesn't
+    let (start, actions, goal) = workshop(-3);
+    let config = PlannerConfig::new().with_resource("wood");
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    assert_eq!(5, count(&plan, "chop"));
+    assert_eq!(-3, lowest_wood(&plan));
+}
+
+// All 8 wood goes into 4 planks and 4 fires. Sawing big is cheaper per plank, but
+// wastes wood the fires need. A shop that never opens makes the wood look like it
+// could be bought, so there's no telling how far it goes
+fn campsite(shop: bool) -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("wood", Datum::I64(8))
+        .with_datum("planks", Datum::I64(0))
+        .with_datum("warmth", Datum::I64(0))
+        .with_datum("shop_open", Datum::Bool(false));
+    let spend = |amount| Mutator::Decrement("wood".to_string(), Datum::I64(amount));
+    let mut actions = vec![
+        simple_increment_action("burn", "warmth", Datum::I64(1)).add_mutator(spend(1)),
+        simple_increment_action("saw", "planks", Datum::I64(1))
+            .set_cost(2)
+            .add_mutator(spend(1)),
+        simple_increment_action("saw_big", "planks", Datum::I64(2)).add_mutator(spend(3)),
+    ];
+    if shop {
+        actions.push(
+            simple_increment_action("buy_wood", "wood", Datum::I64(1))
+                .with_precondition("shop_open", Compare::Equals(Datum::Bool(true))),
+        );
+    }
+    let goal = Goal::new()
+        .with_req("planks", Compare::GreaterThanEquals(Datum::I64(4)))
+        .with_req("warmth", Compare::GreaterThanEquals(Datum::I64(4)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_stock_left_bounds_the_heuristic() {
+    let config = PlannerConfig::new()
+        .with_resource("wood")
+        .with_normalized_heuristic(true);
+    let plan = |shop| {
+        let (start, actions, goal) = campsite(shop);
+        let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+        (plan.unwrap(), stats.expanded_nodes)
+    };
+
+    let (bounded, bounded_expanded) = plan(false);
+    let (unbounded, unbounded_expanded) = plan(true);
+
+    assert_plan_cost!(bounded, 12);
+    assert_eq!(0, count(&bounded, "saw_big"));
+    assert_eq!(bounded.1, unbounded.1);
+    assert!(
+        bounded_expanded < unbounded_expanded,
+        "{} >= {}",
+        bounded_expanded,
+        unbounded_expanded
+    );
+}
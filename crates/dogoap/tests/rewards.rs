@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+// Selling loot pays more than it costs, and loot can be bought back for a gold,
+// so buying and selling over and over keeps getting cheaper without end.
+fn market(sell_reward: usize) -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("loot", Datum::I64(0));
+    let actions = vec![
+        simple_action("gather", "loot", Datum::I64(1))
+            .with_precondition("loot", Compare::Equals(Datum::I64(0)))
+            .set_cost(2),
+        Action::new("sell_loot")
+            .with_precondition("loot", Compare::Equals(Datum::I64(1)))
+            .add_mutator(Mutator::Set("loot".to_string(), Datum::I64(0)))
+            .add_mutator(Mutator::Increment("gold".to_string(), Datum::I64(1)))
+            .set_reward(sell_reward),
+        Action::new("buy_loot")
+            .with_precondition("loot", Compare::Equals(Datum::I64(0)))
+            .with_precondition("gold", Compare::GreaterThanEquals(Datum::I64(1)))
+            .add_mutator(Mutator::Set("loot".to_string(), Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("gold".to_string(), Datum::I64(1))),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(2)));
+    (start, actions, goal)
+}
+
+fn plan_with_depth(max_depth: usize) -> (Option<Plan>, PlanStats) {
+    let (start, actions, goal) = market(4);
+    let config = PlannerConfig::new().with_rewards(Rewards::BoundedDepth(max_depth));
+    make_plan_with_stats(&start, &actions, &goal, &config)
+}
+
+#[test]
+fn test_rewards_outweighing_costs_are_rejected() {
+    let (start, actions, goal) = market(4);
+
+    let result = try_make_plan_with_config(&start, &actions, &goal, &PlannerConfig::new());
+
+    assert_eq!(
+        Err(PlanError::RewardOutweighsCost("sell_loot".to_string())),
+        result
+    );
+}
+
+#[test]
+fn test_rewards_cancelling_out_costs_are_rejected() {
+    let (start, actions, goal) = market(1);
+
+    let result = try_make_plan_with_config(&start, &actions, &goal, &PlannerConfig::new());
+
+    assert!(matches!(result, Err(PlanError::RewardOutweighsCost(_))));
+}
+
+#[test]
+fn test_rewards_on_free_actions_are_rejected() {
+    let (start, mut actions, goal) = market(0);
+    actions[1] = actions[1].clone().set_free().set_reward(1);
+
+    let result = try_make_plan_with_config(&start, &actions, &goal, &PlannerConfig::new());
+
+    assert!(matches!(result, Err(PlanError::RewardOutweighsCost(_))));
+}
+
+#[test]
+fn test_rewards_on_dynamic_costs_are_rejected() {
+    let (start, mut actions, goal) = market(0);
+    // Costs nothing as soon as there's loot, whatever the cost of its effect says
+    actions[1] = actions[1]
+        .clone()
+        .set_cost(5)
+        .set_reward(1)
+        .with_dynamic_cost(Arc::new(|_: &LocalState| 0));
+
+    let result = try_make_plan_with_config(&start, &actions, &goal, &PlannerConfig::new());
+
+    assert!(matches!(result, Err(PlanError::RewardOutweighsCost(_))));
+}
+
+#[test]
+fn test_rewards_below_the_cost_discount_actions() {
+    let (start, mut actions, goal) = market(0);
+    actions[0] = actions[0].clone().set_cost(3).set_reward(2);
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &PlannerConfig::new());
+    let plan = plan.unwrap();
+
+    assert_plan_actions!(plan, ["gather", "sell_loot", "gather", "sell_loot"]);
+    // Plans report what their actions cost before rewards
+    assert_plan_cost!(plan, 8);
+    assert_eq!(4, stats.reward);
+}
+
+#[test]
+fn test_bounded_depth_plans_with_paying_cycles() {
+    let (plan, stats) = plan_with_depth(4);
+    let plan = plan.unwrap();
+
+    assert_plan_actions!(plan, ["gather", "sell_loot", "gather", "sell_loot"]);
+    assert_plan_cost!(plan, 6);
+    assert_eq!(8, stats.reward);
+    assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+}
+
+#[test]
+fn test_bounded_depth_takes_the_cheapest_plan_within_the_bound() {
+    let (plan, stats) = plan_with_depth(6);
+    let plan = plan.unwrap();
+
+    // Buying back loot to sell it again pays off more than it costs
+    assert_plan_actions!(
+        plan,
+        [
+            "gather",
+            "sell_loot",
+            "buy_loot",
+            "sell_loot",
+            "gather",
+            "sell_loot"
+        ]
+    );
+    assert_plan_cost!(plan, 8);
+    assert_eq!(12, stats.reward);
+}
+
+#[test]
+fn test_bounded_depth_without_a_plan_in_reach() {
+    let (plan, stats) = plan_with_depth(3);
+
+    assert_eq!(None, plan);
+    assert!(stats.expanded_nodes > 0);
+}
+
+#[test]
+fn test_bounded_depth_matches_astar_without_rewards() {
+    let (start, actions, goal) = market(0);
+    let config = PlannerConfig::new().with_rewards(Rewards::BoundedDepth(10));
+
+    let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+    assert_plan_cost!(plan, make_plan(&start, &actions, &goal).unwrap().1);
+}
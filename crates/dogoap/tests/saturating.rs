@@ -0,0 +1,76 @@
+use dogoap::{
+    prelude::*,
+    simple::{simple_action, simple_increment_action},
+    testing::*,
+};
+
+// Walking takes three steps, teleporting takes one that costs as much as possible
+fn travel(teleport_cost: usize) -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new().with_datum("at", Datum::Enum(0));
+    let walk = |name: &str, from: usize, to: usize| {
+        simple_action(name, "at", Datum::Enum(to))
+            .with_precondition("at", Compare::Equals(Datum::Enum(from)))
+    };
+    let actions = vec![
+        walk("walk_1", 0, 1),
+        walk("walk_2", 1, 2),
+        walk("walk_3", 2, 3),
+        simple_action("teleport", "at", Datum::Enum(3)).set_cost(teleport_cost),
+    ];
+    let goal = Goal::new().with_req("at", Compare::Equals(Datum::Enum(3)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_finite_plan_beats_max_cost_action() {
+    for teleport_cost in [usize::MAX, usize::MAX - 1] {
+        for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+            let (start, actions, goal) = travel(teleport_cost);
+            let config = PlannerConfig::new().with_strategy(strategy);
+
+            let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+            assert_plan_actions!(plan, ["walk_1", "walk_2", "walk_3"]);
+            assert_plan_cost!(plan, 3);
+        }
+    }
+}
+
+#[test]
+fn test_set_cost_max_is_a_last_resort() {
+    let (start, mut actions, goal) = travel(1);
+    actions[3] = actions[3].clone().set_cost_max();
+    assert_eq!(usize::MAX, actions[3].effects[0].cost);
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["walk_1", "walk_2", "walk_3"]);
+}
+
+#[test]
+fn test_max_cost_action_still_plans_when_needed() {
+    let (start, mut actions, goal) = travel(1);
+    // Without the last step, only teleporting gets there
+    actions.remove(2);
+    actions[2] = actions[2].clone().set_cost_max();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["teleport"]);
+    assert_plan_cost!(plan, usize::MAX);
+}
+
+#[test]
+fn test_costs_saturate_instead_of_wrapping() {
+    let start = LocalState::new().with_datum("steps", Datum::I64(0));
+    let actions =
+        vec![simple_increment_action("step", "steps", Datum::I64(1)).set_cost(usize::MAX / 2 + 1)];
+    let goal = Goal::new().with_req("steps", Compare::Equals(Datum::I64(3)));
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &PlannerConfig::new());
+    let plan = plan.unwrap();
+
+    assert_plan_actions!(plan, ["step", "step", "step"]);
+    assert_plan_cost!(plan, usize::MAX);
+    assert_eq!(0, stats.penalty);
+}
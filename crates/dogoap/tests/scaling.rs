@@ -0,0 +1,97 @@
+use dogoap::{
+    prelude::*,
+    simple::{simple_decrement_action, simple_divide_action, simple_multiply_action},
+    testing::*,
+};
+
+fn scaled(value: Datum, mutator: Mutator) -> Datum {
+    let state = LocalState::new().with_datum("energy", value);
+    let effect = Effect::new("scale").with_mutator(mutator);
+    effect.apply(&state, MissingKeyPolicy::Reject).unwrap().data["energy"]
+}
+
+fn multiply(factor: Datum) -> Mutator {
+    Mutator::Multiply("energy".to_string(), factor)
+}
+
+fn divide(divisor: Datum) -> Mutator {
+    Mutator::Divide("energy".to_string(), divisor)
+}
+
+#[test]
+fn test_integers_round_towards_zero() {
+    assert_eq!(
+        Datum::I64(21),
+        scaled(Datum::I64(7), multiply(Datum::I64(3)))
+    );
+    assert_eq!(Datum::I64(3), scaled(Datum::I64(7), divide(Datum::I64(2))));
+    assert_eq!(
+        Datum::I64(-3),
+        scaled(Datum::I64(-7), divide(Datum::I64(2)))
+    );
+    assert_eq!(
+        Datum::I64(i64::MAX),
+        scaled(Datum::I64(i64::MAX / 2 + 1), multiply(Datum::I64(2)))
+    );
+}
+
+#[test]
+fn test_floats_keep_their_fraction() {
+    assert_eq!(
+        Datum::F64(90.0),
+        scaled(Datum::F64(100.0), multiply(Datum::F64(0.9)))
+    );
+    assert_eq!(
+        Datum::F64(3.5),
+        scaled(Datum::F64(7.0), divide(Datum::F64(2.0)))
+    );
+}
+
+#[test]
+fn test_dividing_by_zero_changes_nothing() {
+    assert_eq!(Datum::I64(7), scaled(Datum::I64(7), divide(Datum::I64(0))));
+    assert_eq!(
+        Datum::F64(7.5),
+        scaled(Datum::F64(7.5), divide(Datum::F64(0.0)))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Can't multiply")]
+fn test_multiplying_different_kinds_panics() {
+    scaled(Datum::I64(7), multiply(Datum::F64(0.5)));
+}
+
+#[test]
+fn test_plan_with_scaling_actions() {
+    let start = LocalState::new().with_datum("gold", Datum::I64(3));
+    let actions = vec![
+        simple_multiply_action("invest", "gold", Datum::I64(10)).set_cost(2),
+        simple_divide_action("split", "gold", Datum::I64(2)),
+        simple_decrement_action("spend", "gold", Datum::I64(1)),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(15)));
+
+    // Searching backwards can't undo scaling, so every strategy searches forwards.
+    // Scaling moves gold a long way in one step, so only the normalized heuristic
+    // doesn't overestimate what's left
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let config = PlannerConfig::new()
+            .with_strategy(strategy)
+            .with_normalized_heuristic(true);
+        let plan = make_plan_with_config(&start, &actions, &goal, &config).unwrap();
+
+        assert_plan_actions!(plan, ["invest", "split"]);
+        assert_plan_cost!(plan, 3);
+        assert_plan_reaches!(plan, goal);
+    }
+}
+
+#[test]
+fn test_display() {
+    assert_eq!("energy * Datum:I64(2)", multiply(Datum::I64(2)).to_string());
+    assert_eq!(
+        "energy / Datum:F64(0.5)",
+        divide(Datum::F64(0.5)).to_string()
+    );
+}
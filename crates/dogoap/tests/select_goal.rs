@@ -0,0 +1,61 @@
+use dogoap::{prelude::*, simple::*};
+
+fn villager() -> (LocalState, Vec<Action>) {
+    let start = LocalState::new()
+        .with_datum("is_hungry", Datum::Bool(true))
+        .with_datum("is_rested", Datum::Bool(true))
+        .with_datum("has_crown", Datum::Bool(false));
+    let actions = vec![simple_action("eat", "is_hungry", Datum::Bool(false))];
+    (start, actions)
+}
+
+fn goal(key: &str, priority: usize) -> Goal {
+    Goal::new()
+        .with_req(key, Compare::Equals(Datum::Bool(key != "is_hungry")))
+        .with_priority(priority)
+}
+
+#[test]
+fn test_most_important_goal_within_reach() {
+    let (start, actions) = villager();
+    let goals = [
+        goal("is_hungry", 1),
+        // Nothing gets a crown
+        goal("has_crown", 5),
+        // Already rested
+        goal("is_rested", 3),
+    ];
+
+    let (index, plan) = select_goal(&goals, &start, &actions).unwrap();
+
+    assert_eq!(0, index);
+    assert_eq!(
+        vec!["eat"],
+        action_keys_of_plan(&plan.0).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_ties_go_to_the_goal_added_first() {
+    let (start, actions) = villager();
+    let eat = goal("is_hungry", 2);
+    let goals = [
+        goal("has_crown", 2),
+        eat.clone(),
+        eat.clone().with_priority(1),
+        eat,
+    ];
+
+    let (index, _) = select_goal(&goals, &start, &actions).unwrap();
+
+    assert_eq!(1, index);
+}
+
+#[test]
+fn test_nothing_to_do() {
+    let (start, actions) = villager();
+    let goals = [goal("is_rested", 1), goal("has_crown", 2)];
+
+    assert!(select_goal(&goals, &start, &actions).is_none());
+    assert!(select_goal(&[], &start, &actions).is_none());
+}
@@ -0,0 +1,108 @@
+use dogoap::{
+    prelude::*,
+    simple::{simple_action, simple_increment_action},
+};
+use std::sync::Arc;
+
+#[test]
+fn test_goal_round_trips_through_json() {
+    let goal = Goal::new()
+        .with_req("gold", Compare::GreaterThanEquals(Datum::I64(3)))
+        .with_req("is_hungry", Compare::Equals(Datum::Bool(false)))
+        .with_req("mood", Compare::NotEquals(Datum::Enum(2)));
+
+    let json = serde_json::to_string(&goal).unwrap();
+    let read: Goal = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(goal.requirements, read.requirements);
+    // Requirements are kept in order, so the same goal always writes the same JSON
+    assert_eq!(json, serde_json::to_string(&read).unwrap());
+}
+
+#[test]
+fn test_action_round_trips_without_its_dynamic_preconditions() {
+    let action = simple_action("eat", "is_hungry", Datum::Bool(false))
+        .with_precondition("has_food", Compare::Equals(Datum::Bool(true)))
+        .add_dynamic_precondition((
+            "energy".to_string(),
+            Arc::new(|_: &LocalState| Compare::GreaterThanEquals(Datum::F64(0.5))),
+        ))
+        .set_cost(2);
+
+    let json = serde_json::to_string(&action).unwrap();
+    let read: Action = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(action.key, read.key);
+    assert_eq!(action.preconditions, read.preconditions);
+    assert_eq!(action.effects, read.effects);
+    assert!(read.dynamic_preconditions.is_empty());
+}
+
+#[test]
+fn test_domain_written_by_hand_plans() {
+    let start: LocalState =
+        serde_json::from_str(r#"{ "data": { "gold": { "I64": 0 } } }"#).unwrap();
+    let actions: Vec<Action> = serde_json::from_str(
+        r#"[{
+            "key": "mine",
+            "preconditions": [],
+            "effects": [{
+                "action": "mine",
+                "mutators": [{ "Increment": ["gold", { "I64": 1 }] }],
+                "cost": 1
+            }]
+        }]"#,
+    )
+    .unwrap();
+    let goal: Goal =
+        serde_json::from_str(r#"{ "requirements": { "gold": { "Equals": { "I64": 2 } } } }"#)
+            .unwrap();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_eq!(2, plan.1);
+}
+
+fn recorded_plan() -> (Vec<Node>, usize) {
+    let start = LocalState::new()
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("has_pickaxe", Datum::Bool(false));
+    let actions = vec![
+        simple_action("buy_pickaxe", "has_pickaxe", Datum::Bool(true)),
+        simple_increment_action("mine", "gold", Datum::I64(2))
+            .with_precondition("has_pickaxe", Compare::Equals(Datum::Bool(true))),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(3)));
+    make_plan(&start, &actions, &goal).unwrap()
+}
+
+#[test]
+fn test_plan_round_trips_through_json() {
+    let plan = recorded_plan();
+
+    let json = serde_json::to_string(&plan).unwrap();
+    let read: (Vec<Node>, usize) = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(plan, read);
+    assert_eq!(4, read.0.len());
+}
+
+#[test]
+fn test_plan_round_trips_through_ron() {
+    let plan = recorded_plan();
+
+    let text = ron::to_string(&plan).unwrap();
+    let read: (Vec<Node>, usize) = ron::from_str(&text).unwrap();
+
+    assert_eq!(plan, read);
+}
+
+#[test]
+fn test_action_written_without_dynamic_preconditions() {
+    let action: Action =
+        serde_json::from_str(r#"{ "key": "rest", "preconditions": [], "effects": [] }"#).unwrap();
+
+    assert!(action.dynamic_preconditions.is_empty());
+    assert!(action.tags.is_empty());
+    assert_eq!(Action::new("rest"), action);
+}
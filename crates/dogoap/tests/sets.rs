@@ -0,0 +1,108 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+#[derive(Clone, Copy)]
+enum Item {
+    Axe,
+    Rope,
+    Torch,
+}
+
+fn item(item: Item) -> Datum {
+    Datum::Enum(item as usize)
+}
+
+fn items(items: &[Item]) -> Datum {
+    Datum::set_of(items.iter().map(|item| *item as usize))
+}
+
+fn pick_up(name: &str, picked: Item) -> Action {
+    Action::new(name).add_mutator(Mutator::Insert("inventory".to_string(), item(picked)))
+}
+
+fn expedition() -> (LocalState, Vec<Action>) {
+    let start = LocalState::new()
+        .with_datum("inventory", items(&[Item::Torch]))
+        .with_datum("at_cliff_top", Datum::Bool(false));
+    let actions = vec![
+        pick_up("take_axe", Item::Axe),
+        pick_up("take_rope", Item::Rope),
+        Action::new("drop_torch")
+            .add_mutator(Mutator::Remove("inventory".to_string(), item(Item::Torch))),
+        simple_action("climb", "at_cliff_top", Datum::Bool(true))
+            .with_precondition("inventory", Compare::Contains(item(Item::Rope))),
+    ];
+    (start, actions)
+}
+
+fn carries(members: Datum) -> Goal {
+    Goal::new().with_req("inventory", Compare::Contains(members))
+}
+
+#[test]
+fn test_contains() {
+    let state = LocalState::new().with_datum("inventory", items(&[Item::Axe, Item::Torch]));
+
+    assert!(carries(item(Item::Axe)).is_satisfied(&state));
+    assert!(!carries(item(Item::Rope)).is_satisfied(&state));
+    assert!(carries(items(&[Item::Axe, Item::Torch])).is_satisfied(&state));
+    assert!(!carries(items(&[Item::Axe, Item::Rope])).is_satisfied(&state));
+    // Only sets have members
+    let state = LocalState::new().with_datum("inventory", item(Item::Axe));
+    assert!(!carries(item(Item::Axe)).is_satisfied(&state));
+}
+
+#[test]
+fn test_insert_and_remove() {
+    let (start, _) = expedition();
+    let effect = Effect::new("swap")
+        .with_mutator(Mutator::Insert(
+            "inventory".to_string(),
+            items(&[Item::Axe, Item::Rope]),
+        ))
+        .with_mutator(Mutator::Remove("inventory".to_string(), item(Item::Torch)));
+
+    let state = effect.apply(&start, MissingKeyPolicy::Insert).unwrap();
+
+    assert_eq!(
+        Some(&items(&[Item::Axe, Item::Rope])),
+        state.data.get("inventory")
+    );
+    assert_eq!(
+        "Datum:Set(0, 1)",
+        items(&[Item::Axe, Item::Rope]).to_string()
+    );
+}
+
+#[test]
+fn test_distance_counts_missing_members() {
+    let (start, _) = expedition();
+    let goal = carries(items(&[Item::Axe, Item::Rope, Item::Torch]));
+
+    assert_eq!(2, start.distance_to_goal(&goal));
+}
+
+#[test]
+fn test_plan_for_members() {
+    let (start, actions) = expedition();
+    let goal = Goal::new()
+        .with_req("at_cliff_top", Compare::Equals(Datum::Bool(true)))
+        .with_req("inventory", Compare::Contains(item(Item::Axe)));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_cost!(plan, 3);
+    assert_plan_reaches!(plan, goal);
+    assert!(action_keys_of_plan(&plan.0).all(|key| key != "drop_torch"));
+}
+
+#[test]
+fn test_plan_to_leave_a_member_behind() {
+    let (start, actions) = expedition();
+    let goal = Goal::new().with_req("inventory", Compare::Equals(items(&[Item::Rope])));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    let mut steps: Vec<&str> = action_keys_of_plan(&plan.0).collect();
+    steps.sort();
+    assert_eq!(vec!["drop_torch", "take_rope"], steps);
+}
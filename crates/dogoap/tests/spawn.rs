@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use dogoap::{prelude::*, simple::simple_increment_action};
+
+fn mine_actions() -> Arc<[Action]> {
+    Arc::from(vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("rob", "gold", Datum::I64(5)).set_cost(3),
+    ])
+}
+
+#[test]
+fn test_plans_on_another_thread_while_polling() {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(23)));
+    let actions = mine_actions();
+
+    let mut handle = spawn_plan(
+        start.clone(),
+        actions.clone(),
+        goal.clone(),
+        PlannerConfig::new(),
+    );
+    let plan = loop {
+        if let Some(result) = handle.try_recv() {
+            break result.unwrap();
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+
+    assert_eq!(make_plan(&start, &actions, &goal), plan);
+    assert!(plan.is_some());
+    // The outcome is only handed out once
+    assert!(handle.try_recv().is_none());
+}
+
+#[test]
+fn test_wait_blocks_until_done() {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(12)));
+
+    let handle = spawn_plan(start, mine_actions(), goal, PlannerConfig::new());
+
+    assert_eq!(8, handle.wait().unwrap().unwrap().1);
+}
+
+#[test]
+fn test_cancel_stops_the_search() {
+    // Gold only goes up, so this search would never end on its own
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(-1)));
+
+    let handle = spawn_plan(start, mine_actions(), goal, PlannerConfig::new());
+    thread::sleep(Duration::from_millis(10));
+    handle.cancel();
+
+    match handle.wait() {
+        Err(PlanError::Cancelled(stats)) => assert!(stats.expanded_nodes > 0),
+        other => panic!("expected the search to be cancelled, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_errors_are_returned() {
+    let start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let goal = Goal::new().with_req("gold", Compare::Equals(Datum::I64(1)));
+    let actions: Arc<[Action]> = Arc::from(vec![
+        simple_increment_action("mine", "gold", Datum::I64(1)),
+        simple_increment_action("mine", "gold", Datum::I64(2)),
+    ]);
+
+    let handle = spawn_plan(start, actions, goal, PlannerConfig::new());
+
+    assert_eq!(
+        Err(PlanError::DuplicateActionKey("mine".to_string())),
+        handle.wait()
+    );
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use dogoap::{
+    prelude::*,
+    simple::{simple_action, simple_increment_action},
+};
+
+fn plan_with(
+    strategy: PlanningStrategy,
+    start: &LocalState,
+    actions: &[Action],
+    goal: &Goal,
+) -> (Option<Plan>, PlanStats) {
+    let config = PlannerConfig::new().with_strategy(strategy);
+    make_plan_with_stats(start, actions, goal, &config)
+}
+
+// Lots of actions that can be done from the start but have nothing to do with the
+// goal, and a short chain of actions that actually gets us there
+fn favors_backward() -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new()
+        .with_datum("prepared", Datum::Bool(false))
+        .with_datum("cooked", Datum::Bool(false))
+        .with_datum("done", Datum::Bool(false));
+    let mut actions = vec![
+        simple_action("prepare", "prepared", Datum::Bool(true)),
+        simple_action("cook", "cooked", Datum::Bool(true))
+            .with_precondition("prepared", Compare::Equals(Datum::Bool(true))),
+        simple_action("finish", "done", Datum::Bool(true))
+            .with_precondition("cooked", Compare::Equals(Datum::Bool(true))),
+    ];
+    for i in 0..30 {
+        let key = format!("toggle_{}", i);
+        start = start.with_datum(&key, Datum::Bool(false));
+        actions.push(simple_action(&key, &key, Datum::Bool(true)));
+    }
+    let goal = Goal::new().with_req("done", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+// Every action helps with the goal, but only the expensive one can be done from
+// the start. Searching backwards keeps trying the cheap ones first.
+fn favors_forward() -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new().with_datum("gold", Datum::I64(0));
+    let mut actions = vec![simple_increment_action("mine", "gold", Datum::I64(1)).set_cost(3)];
+    for i in 0..8 {
+        let tool = format!("has_tool_{}", i);
+        start = start.with_datum(&tool, Datum::Bool(false));
+        actions.push(
+            simple_increment_action(&format!("steal_{}", i), "gold", Datum::I64(1))
+                .with_precondition(&tool, Compare::Equals(Datum::Bool(true))),
+        );
+    }
+    let goal = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(2)));
+    (start, actions, goal)
+}
+
+// A long chain of crafting steps, each turning the item before it into the next
+// one, and chores that never help but can be done at any point. Searching forwards
+// tries every mix of chores at every step, and searching backwards can't tell how
+// far down the chain it is
+fn favors_bidirectional() -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new().with_datum("ore", Datum::I64(1));
+    let mut actions = vec![];
+    let mut previous = "ore".to_string();
+    for tier in 0..12 {
+        let item = format!("tier_{}", tier);
+        start = start.with_datum(&item, Datum::I64(0));
+        actions.push(
+            Action::new(&format!("craft_{}", item))
+                .with_precondition(&previous, Compare::GreaterThanEquals(Datum::I64(1)))
+                .add_mutator(Mutator::Decrement(previous.clone(), Datum::I64(1)))
+                .add_mutator(Mutator::Increment(item.clone(), Datum::I64(1))),
+        );
+        previous = item;
+    }
+    for i in 0..6 {
+        let key = format!("chore_{}", i);
+        start = start.with_datum(&key, Datum::Bool(false));
+        actions.push(simple_action(&key, &key, Datum::Bool(true)));
+    }
+    let goal = Goal::new().with_req(&previous, Compare::GreaterThanEquals(Datum::I64(1)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_auto_picks_backward_when_few_actions_matter() {
+    let (start, actions, goal) = favors_backward();
+
+    let (forward, forward_stats) =
+        plan_with(PlanningStrategy::StartToGoal, &start, &actions, &goal);
+    let (backward, backward_stats) =
+        plan_with(PlanningStrategy::GoalToStart, &start, &actions, &goal);
+    let (auto, auto_stats) = plan_with(PlanningStrategy::Auto, &start, &actions, &goal);
+
+    assert!(backward_stats.expanded_nodes < forward_stats.expanded_nodes);
+    assert_eq!(PlanningStrategy::GoalToStart, auto_stats.strategy);
+    assert_eq!(backward_stats, auto_stats);
+
+    let (backward_nodes, backward_cost) = backward.unwrap();
+    assert_eq!(forward.unwrap().1, backward_cost);
+    assert_eq!(auto.unwrap(), (backward_nodes.clone(), backward_cost));
+
+    let keys: Vec<&str> = action_keys_of_plan(&backward_nodes).collect();
+    assert_eq!(vec!["prepare", "cook", "finish"], keys);
+}
+
+#[test]
+fn test_auto_picks_forward_when_most_actions_touch_the_goal() {
+    let (start, actions, goal) = favors_forward();
+
+    let (forward, forward_stats) =
+        plan_with(PlanningStrategy::StartToGoal, &start, &actions, &goal);
+    let (backward, backward_stats) =
+        plan_with(PlanningStrategy::GoalToStart, &start, &actions, &goal);
+    let (auto, auto_stats) = plan_with(PlanningStrategy::Auto, &start, &actions, &goal);
+
+    assert!(forward_stats.expanded_nodes < backward_stats.expanded_nodes);
+    assert_eq!(PlanningStrategy::StartToGoal, auto_stats.strategy);
+    assert_eq!(forward_stats, auto_stats);
+
+    let forward = forward.unwrap();
+    assert_eq!(6, forward.1);
+    assert_eq!(forward.1, backward.unwrap().1);
+    assert_eq!(forward, auto.unwrap());
+}
+
+#[test]
+fn test_auto_picks_bidirectional_for_long_chains() {
+    let (start, actions, goal) = favors_bidirectional();
+
+    let (forward, forward_stats) =
+        plan_with(PlanningStrategy::StartToGoal, &start, &actions, &goal);
+    let (_, backward_stats) = plan_with(PlanningStrategy::GoalToStart, &start, &actions, &goal);
+    let (_, bidirectional_stats) =
+        plan_with(PlanningStrategy::Bidirectional, &start, &actions, &goal);
+    let (auto, auto_stats) = plan_with(PlanningStrategy::Auto, &start, &actions, &goal);
+
+    assert!(bidirectional_stats.expanded_nodes < forward_stats.expanded_nodes);
+    assert!(bidirectional_stats.expanded_nodes < backward_stats.expanded_nodes);
+    assert_eq!(PlanningStrategy::Bidirectional, auto_stats.strategy);
+    assert_eq!(bidirectional_stats, auto_stats);
+    assert_eq!(forward.unwrap(), auto.unwrap());
+}
+
+#[test]
+fn test_auto_searches_forward_with_dynamic_preconditions() {
+    let (start, mut actions, goal) = favors_backward();
+    // Only prepare while we're not done yet
+    actions[0] = actions[0].clone().add_dynamic_precondition((
+        "done".to_string(),
+        Arc::new(|_: &LocalState| Compare::Equals(Datum::Bool(false))),
+    ));
+
+    let (plan, stats) = plan_with(PlanningStrategy::Auto, &start, &actions, &goal);
+
+    assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    assert_eq!(3, plan.unwrap().1);
+}
+
+#[test]
+fn test_regressed_preconditions() {
+    let goal = Goal::new()
+        .with_req("gold", Compare::GreaterThanEquals(Datum::I64(5)))
+        .with_req("rested", Compare::Equals(Datum::Bool(true)));
+    let mine = simple_increment_action("mine", "gold", Datum::I64(2))
+        .with_precondition("has_pickaxe", Compare::Equals(Datum::Bool(true)));
+
+    assert_eq!(
+        vec![vec![
+            (
+                "gold".to_string(),
+                Compare::GreaterThanEquals(Datum::I64(3))
+            ),
+            (
+                "has_pickaxe".to_string(),
+                Compare::Equals(Datum::Bool(true))
+            ),
+            ("rested".to_string(), Compare::Equals(Datum::Bool(true))),
+        ]],
+        regressed_preconditions(&goal, &mine)
+    );
+    // Nothing to do with the goal
+    let sleep = simple_action("sleep", "dreamt", Datum::Bool(true));
+    assert!(regressed_preconditions(&goal, &sleep).is_empty());
+    // Undoes what the goal asks for
+    let nap = simple_action("nap", "rested", Datum::Bool(false));
+    assert!(regressed_preconditions(&goal, &nap).is_empty());
+}
+
+#[test]
+fn test_strategies_agree_when_overshooting_is_cheaper() {
+    // Going past the bound is cheaper than landing right on it
+    let start = LocalState::new().with_datum("wood", Datum::I64(2));
+    let actions = vec![
+        simple_increment_action("chop", "wood", Datum::I64(1)).set_cost(3),
+        simple_increment_action("chop_twice", "wood", Datum::I64(2)).set_cost(2),
+    ];
+    let goal = Goal::new().with_req("wood", Compare::GreaterThanEquals(Datum::I64(3)));
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let (plan, _) = plan_with(strategy, &start, &actions, &goal);
+        let plan = plan.unwrap();
+        assert_eq!(2, plan.1, "{:?}", strategy);
+        assert_eq!(Some("chop_twice"), plan.0[1].action_key());
+    }
+}
@@ -0,0 +1,60 @@
+use dogoap::prelude::*;
+
+fn woodcutter<K: Key + for<'a> From<&'a str>>() -> (LocalState<K>, Vec<Action<K>>, Goal<K>) {
+    let start = LocalState::default()
+        .with_datum("energy", Datum::I64(10))
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("has_axe", Datum::Bool(false))
+        .with_datum("wood", Datum::I64(0));
+    let actions = vec![
+        Action::named("chop")
+            .with_precondition("has_axe", Compare::Equals(Datum::Bool(true)))
+            .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Increment("wood".into(), Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("energy".into(), Datum::I64(10))),
+        Action::named("buy_axe")
+            .with_precondition("gold", Compare::GreaterThanEquals(Datum::I64(2)))
+            .add_mutator(Mutator::Set("has_axe".into(), Datum::Bool(true)))
+            .add_mutator(Mutator::Decrement("gold".into(), Datum::I64(2))),
+        Action::named("work")
+            .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Increment("gold".into(), Datum::I64(1)))
+            .add_mutator(Mutator::Decrement("energy".into(), Datum::I64(10))),
+        Action::named("sleep").add_mutator(Mutator::Increment("energy".into(), Datum::I64(20))),
+    ];
+    let goal = Goal::default().with_req("wood", Compare::GreaterThanEquals(Datum::I64(2)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_same_plan_as_with_strings() {
+    let (start, actions, goal) = woodcutter::<String>();
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    let (start, actions, goal) = woodcutter::<Symbol>();
+    let symbol_plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_eq!(plan.cost(), symbol_plan.cost());
+    assert_eq!(action_names(&plan), action_names(&symbol_plan));
+}
+
+fn action_names<K: Key>(plan: &Plan<K, Datum>) -> Vec<String> {
+    plan.nodes()
+        .iter()
+        .filter_map(|node| match node {
+            Node::Effect(effect) => Some(effect.action.clone()),
+            Node::State(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_plans_print_the_names() {
+    let (start, actions, goal) = woodcutter::<Symbol>();
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    let printed = format_plan(&plan);
+
+    assert!(printed.contains("wood"), "{}", printed);
+    assert!(format!("{:?}", start).contains("\"has_axe\""));
+    assert_eq!(start.data[&Symbol::new("gold")], Datum::I64(0));
+}
@@ -0,0 +1,88 @@
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+// Walking to the shop is cheap but slow, a taxi is fast but expensive
+fn delivery() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("at_shop", Datum::Bool(false))
+        .with_datum("delivered", Datum::Bool(false));
+    let actions = vec![
+        simple_action("walk", "at_shop", Datum::Bool(true)).set_duration(200),
+        simple_action("taxi", "at_shop", Datum::Bool(true))
+            .set_cost(5)
+            .set_duration(30),
+        simple_action("deliver", "delivered", Datum::Bool(true))
+            .with_precondition("at_shop", Compare::Equals(Datum::Bool(true)))
+            .set_duration(10),
+    ];
+    let goal = Goal::new().with_req("delivered", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+fn plan_delivery(goal: &Goal, config: &PlannerConfig) -> (Option<Plan>, PlanStats) {
+    let (start, actions, _) = delivery();
+    make_plan_with_stats(&start, &actions, goal, config)
+}
+
+#[test]
+fn test_durations_are_reported() {
+    let (_, _, goal) = delivery();
+
+    let (plan, stats) = plan_delivery(&goal, &PlannerConfig::new());
+
+    let plan = plan.unwrap();
+    assert_plan_actions!(plan, ["walk", "deliver"]);
+    assert_plan_cost!(plan, 2);
+    assert_eq!(210, stats.duration);
+}
+
+#[test]
+fn test_cheapest_plan_within_the_deadline() {
+    let (_, _, goal) = delivery();
+
+    let (plan, stats) = plan_delivery(&goal.with_deadline(300), &PlannerConfig::new());
+
+    assert_plan_actions!(plan.unwrap(), ["walk", "deliver"]);
+    assert_eq!(210, stats.duration);
+}
+
+#[test]
+fn test_missed_deadline_takes_the_faster_plan() {
+    let (_, _, goal) = delivery();
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let config = PlannerConfig::new().with_strategy(strategy);
+
+        let (plan, stats) = plan_delivery(&goal.clone().with_deadline(100), &config);
+
+        let plan = plan.unwrap();
+        assert_plan_actions!(plan, ["taxi", "deliver"]);
+        assert_plan_cost!(plan, 6);
+        assert_eq!(40, stats.duration);
+        assert_eq!(PlanningStrategy::StartToGoal, stats.strategy);
+    }
+}
+
+#[test]
+fn test_impossible_deadline() {
+    let (_, _, goal) = delivery();
+    for minimize in [Minimize::Cost, Minimize::Duration] {
+        let config = PlannerConfig::new().with_minimize(minimize);
+
+        let (plan, _) = plan_delivery(&goal.clone().with_deadline(39), &config);
+
+        assert_eq!(None, plan);
+    }
+}
+
+#[test]
+fn test_minimize_duration() {
+    let (_, _, goal) = delivery();
+    let config = PlannerConfig::new().with_minimize(Minimize::Duration);
+
+    let (plan, stats) = plan_delivery(&goal, &config);
+
+    // Plans still report what they cost
+    let plan = plan.unwrap();
+    assert_plan_actions!(plan, ["taxi", "deliver"]);
+    assert_plan_cost!(plan, 6);
+    assert_eq!(40, stats.duration);
+}
@@ -3,6 +3,7 @@ use dogoap::{
     simple::{
         simple_action, simple_decrement_action, simple_increment_action, simple_multi_mutate_action,
     },
+    testing::*,
 };
 
 // One action that sets one field
@@ -14,31 +15,36 @@ fn test_basic_bool_setting() {
 
     let eat_mutator = Mutator::Set("is_hungry".to_string(), Datum::Bool(false));
 
-    let eat_consequence = Effect {
-        action: "eat".to_string(),
-        mutators: vec![eat_mutator.clone()],
-        state: LocalState::new(),
-        cost: 1,
-    };
+    // Built the way struct literals used to build it
+    #[allow(deprecated)]
+    let eat_consequence =
+        Effect::from_parts("eat", vec![eat_mutator.clone()], LocalState::new(), 1);
 
     let eat_action = Action {
         key: "eat".to_string(),
-        preconditions: vec![],
-        effects: vec![eat_consequence],
+        preconditions: smallvec![],
+        dynamic_preconditions: vec![],
+        effects: smallvec![eat_consequence],
+        dynamic_effects: vec![],
+        dynamic_cost: None,
+        subgoal: None,
+        tags: vec![],
     };
 
     let actions: Vec<Action> = vec![eat_action];
 
-    let plan = get_effects_from_plan(make_plan(&start, &actions[..], &goal).unwrap().0);
-    assert_eq!(1, plan.len());
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, ["eat"]);
+    assert_plan_cost!(plan, 1);
+    assert_plan_reaches!(plan, goal);
 
-    let cons = plan.get(0).unwrap();
-    assert_eq!("eat", cons.action);
+    let plan = effects_of_plan(&plan.0).collect::<Vec<_>>();
+    let cons = plan.first().unwrap();
     assert_eq!(1, cons.mutators.len());
-    assert_eq!(eat_mutator, cons.mutators.get(0).unwrap().clone());
+    assert_eq!(eat_mutator, cons.mutators[0]);
 
     let expected_state = LocalState::new().with_datum("is_hungry", Datum::Bool(false));
-    assert_eq!(expected_state, cons.state);
+    assert_eq!(Some(expected_state), cons.state);
 }
 
 // The state is already what we need!
@@ -50,27 +56,29 @@ fn test_no_actions_needed() {
 
     let eat_mutator = Mutator::Set("is_hungry".to_string(), Datum::Bool(false));
 
-    let eat_consequence = Effect {
-        action: "eat".to_string(),
-        mutators: vec![eat_mutator.clone()],
-        state: LocalState::new(),
-        cost: 1,
-    };
+    let eat_consequence = Effect::new("eat").with_mutator(eat_mutator.clone());
 
     let eat_action = Action {
         key: "eat".to_string(),
-        preconditions: vec![],
-        effects: vec![eat_consequence],
+        preconditions: smallvec![],
+        dynamic_preconditions: vec![],
+        effects: smallvec![eat_consequence],
+        dynamic_effects: vec![],
+        dynamic_cost: None,
+        subgoal: None,
+        tags: vec![],
     };
 
     let actions: Vec<Action> = vec![eat_action];
 
-    let (plan, plan_cost) = make_plan(&start, &actions[..], &goal).unwrap();
-    assert_eq!(1, plan.len());
-    assert_eq!(0, plan_cost);
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, []);
+    assert_plan_cost!(plan, 0);
+    assert_plan_reaches!(plan, goal);
 
     let expected_state = LocalState::new().with_datum("is_hungry", Datum::Bool(false));
-    assert_eq!(expected_state, plan.first().unwrap().state().clone());
+    assert_eq!(1, plan.0.len());
+    assert_eq!(expected_state, plan.0.first().unwrap().state().clone());
 }
 
 // Shorthand for one action that sets one field
@@ -86,14 +94,15 @@ fn test_simple_action() {
 
     let actions: Vec<Action> = vec![eat_action];
 
-    let plan = get_effects_from_plan(make_plan(&start, &actions[..], &goal).unwrap().0);
-    assert_eq!(1, plan.len());
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, ["eat"]);
+    assert_plan_reaches!(plan, goal);
 
-    let cons = plan.get(0).unwrap();
-    assert_eq!("eat", cons.action);
+    let plan = effects_of_plan(&plan.0).collect::<Vec<_>>();
+    let cons = plan.first().unwrap();
     assert_eq!(1, cons.mutators.len());
-    assert_eq!(eat_mutator, cons.mutators.get(0).unwrap().clone());
-    assert_eq!(expected_state, cons.state);
+    assert_eq!(eat_mutator, cons.mutators[0]);
+    assert_eq!(Some(expected_state), cons.state);
 }
 
 // State with two fields + two actions each mutating their fields
@@ -117,19 +126,14 @@ fn test_two_bools() {
     let actions: Vec<Action> = vec![eat_action, sleep_action];
 
     let plan = make_plan(&start, &actions[..], &goal).unwrap();
-
-    let cons = get_effects_from_plan(plan.0);
-    assert_eq!(2, cons.len());
-
-    let first_cons = cons.get(0).unwrap();
-    assert_eq!("eat", first_cons.action);
-    assert_eq!(1, first_cons.mutators.len());
-
-    let second_cons = cons.get(1).unwrap();
-    assert_eq!("sleep", second_cons.action);
-    assert_eq!(1, second_cons.mutators.len());
-
-    assert_eq!(expected_state, second_cons.state);
+    assert_plan_actions!(plan, ["eat", "sleep"]);
+    assert_plan_cost!(plan, 2);
+    assert_plan_reaches!(plan, goal);
+
+    let cons = effects_of_plan(&plan.0).collect::<Vec<_>>();
+    assert_eq!(1, cons[0].mutators.len());
+    assert_eq!(1, cons[1].mutators.len());
+    assert_eq!(Some(expected_state), cons[1].state);
 }
 
 // State with two fields + two actions each mutating their fields
@@ -181,32 +185,22 @@ fn test_four_bools() {
     let actions: Vec<Action> = vec![eat_action, sleep_action, train_action, shower_action];
 
     let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, ["train", "shower", "sleep", "eat"]);
+    assert_plan_cost!(plan, 4);
+    assert_plan_reaches!(plan, goal);
 
-    let cons = get_effects_from_plan(plan.0);
-    assert_eq!(4, cons.len());
-
-    let first_cons = cons.get(0).unwrap();
-    assert_eq!("train", first_cons.action);
-    assert_eq!(3, first_cons.mutators.len());
-
-    let second_cons = cons.get(1).unwrap();
-    assert_eq!("shower", second_cons.action);
-    assert_eq!(2, second_cons.mutators.len());
-
-    let third_cons = cons.get(2).unwrap();
-    assert_eq!("sleep", third_cons.action);
-    assert_eq!(2, third_cons.mutators.len());
-
-    let fourth_cons = cons.get(3).unwrap();
-    assert_eq!("eat", fourth_cons.action);
-    assert_eq!(1, fourth_cons.mutators.len());
+    let cons = effects_of_plan(&plan.0).collect::<Vec<_>>();
+    assert_eq!(3, cons[0].mutators.len());
+    assert_eq!(2, cons[1].mutators.len());
+    assert_eq!(2, cons[2].mutators.len());
+    assert_eq!(1, cons[3].mutators.len());
 
     let expected_state = LocalState::new()
         .with_datum("is_hungry", Datum::Bool(false))
         .with_datum("is_tired", Datum::Bool(false))
         .with_datum("is_fit", Datum::Bool(true))
         .with_datum("is_dirty", Datum::Bool(false));
-    assert_eq!(expected_state, cons.last().unwrap().state);
+    assert_eq!(Some(expected_state), cons.last().unwrap().state);
 }
 
 enum TestLocation {
@@ -240,25 +234,17 @@ fn test_enums() {
 
     let actions: Vec<Action> = vec![go_outside_action, go_to_market_action, go_to_ramen_action];
 
-    let plan = make_plan(&start, &actions[..], &goal);
-    let effects = get_effects_from_plan(plan.unwrap().0);
-
-    assert_eq!(3, effects.len());
-
-    let cons = effects.get(0).unwrap();
-    assert_eq!("go_outside", cons.action);
-    assert_eq!(1, cons.mutators.len());
-
-    let cons = effects.get(1).unwrap();
-    assert_eq!("go_to_market", cons.action);
-    assert_eq!(1, cons.mutators.len());
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, ["go_outside", "go_to_market", "go_to_ramen"]);
+    assert_plan_reaches!(plan, goal);
 
-    let cons = effects.get(2).unwrap();
-    assert_eq!("go_to_ramen", cons.action);
-    assert_eq!(1, cons.mutators.len());
+    let effects = effects_of_plan(&plan.0).collect::<Vec<_>>();
+    for cons in &effects {
+        assert_eq!(1, cons.mutators.len());
+    }
 
     // Take only the last one
-    assert_eq!(expected_state, cons.state);
+    assert_eq!(Some(expected_state), effects.last().unwrap().state);
 }
 
 // // eat action can only be done with not tired
@@ -289,23 +275,19 @@ fn test_preconditions() {
 
     let actions: Vec<Action> = vec![eat_action, sleep_action];
 
-    let plan = get_effects_from_plan(make_plan(&start, &actions[..], &goal).unwrap().0);
-    assert_eq!(3, plan.len());
-
-    let first_cons = plan.get(0).unwrap();
-    assert_eq!("sleep", first_cons.action);
-    assert_eq!(1, first_cons.mutators.len());
-
-    let second_cons = plan.get(1).unwrap();
-    assert_eq!("eat", second_cons.action);
-    assert_eq!(2, second_cons.mutators.len());
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, ["sleep", "eat", "sleep"]);
+    assert_plan_cost!(plan, 3);
+    assert_plan_reaches!(plan, goal);
 
-    let third_cons = plan.get(2).unwrap();
-    assert_eq!("sleep", third_cons.action);
-    assert_eq!(1, third_cons.mutators.len());
+    let plan = effects_of_plan(&plan.0).collect::<Vec<_>>();
+    assert_eq!(1, plan[0].mutators.len());
+    assert_eq!(2, plan[1].mutators.len());
+    assert_eq!(1, plan[2].mutators.len());
 
     assert_eq!(
-        expected_state, third_cons.state,
+        Some(expected_state),
+        plan[2].state,
         "Final state wasn't what we expected"
     );
 }
@@ -324,16 +306,18 @@ fn test_int_increment() {
 
     let actions: Vec<Action> = vec![eat_action];
 
-    let plan = get_effects_from_plan(make_plan(&start, &actions[..], &goal).unwrap().0);
-    assert_eq!(5, plan.len());
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, ["eat"; 5]);
+    assert_plan_cost!(plan, 5);
+    assert_plan_reaches!(plan, goal);
 
+    let plan = effects_of_plan(&plan.0).collect::<Vec<_>>();
     for cons in &plan {
-        assert_eq!("eat", cons.action);
         assert_eq!(1, cons.mutators.len());
-        assert_eq!(eat_mutator, cons.mutators.get(0).unwrap().clone());
+        assert_eq!(eat_mutator, cons.mutators[0]);
     }
 
-    assert_eq!(expected_state, plan.last().unwrap().state);
+    assert_eq!(Some(expected_state), plan.last().unwrap().state);
 }
 
 #[test]
@@ -348,16 +332,18 @@ fn test_int_decrement() {
 
     let actions: Vec<Action> = vec![eat_action];
 
-    let plan = get_effects_from_plan(make_plan(&start, &actions[..], &goal).unwrap().0);
-    assert_eq!(7, plan.len());
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, ["eat"; 7]);
+    assert_plan_cost!(plan, 7);
+    assert_plan_reaches!(plan, goal);
 
+    let plan = effects_of_plan(&plan.0).collect::<Vec<_>>();
     for cons in &plan {
-        assert_eq!("eat", cons.action);
         assert_eq!(1, cons.mutators.len());
-        assert_eq!(eat_mutator, cons.mutators.get(0).unwrap().clone());
+        assert_eq!(eat_mutator, cons.mutators[0]);
     }
 
-    assert_eq!(expected_state, plan.last().unwrap().state);
+    assert_eq!(Some(expected_state), plan.last().unwrap().state);
 }
 
 #[test]
@@ -372,16 +358,18 @@ fn test_float_increment() {
 
     let actions: Vec<Action> = vec![eat_action];
 
-    let plan = get_effects_from_plan(make_plan(&start, &actions[..], &goal).unwrap().0);
-    assert_eq!(5, plan.len());
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_actions!(plan, ["eat"; 5]);
+    assert_plan_cost!(plan, 5);
+    assert_plan_reaches!(plan, goal);
 
+    let plan = effects_of_plan(&plan.0).collect::<Vec<_>>();
     for cons in &plan {
-        assert_eq!("eat", cons.action);
         assert_eq!(1, cons.mutators.len());
-        assert_eq!(eat_mutator, cons.mutators.get(0).unwrap().clone());
+        assert_eq!(eat_mutator, cons.mutators[0]);
     }
 
-    assert_eq!(expected_state, plan.last().unwrap().state);
+    assert_eq!(Some(expected_state), plan.last().unwrap().state);
 }
 
 // GreaterThanEquals can be useful sometimes too!
@@ -397,20 +385,20 @@ fn test_greater_than_equals() {
     let actions: Vec<Action> = vec![eat_action];
 
     let plan = make_plan(&start, &actions[..], &goal).unwrap();
-    let effects = get_effects_from_plan(plan.0.clone());
-
-    assert_eq!(9, effects.len());
+    assert_plan_actions!(plan, ["eat"; 9]);
+    assert_plan_cost!(plan, 9);
+    assert_plan_reaches!(plan, goal);
 
+    let effects = effects_of_plan(&plan.0).collect::<Vec<_>>();
     for cons in &effects {
-        assert_eq!("eat", cons.action);
         assert_eq!(1, cons.mutators.len());
         assert_eq!(
             Mutator::Increment("energy".to_string(), Datum::I64(6)),
-            cons.mutators.get(0).unwrap().clone()
+            cons.mutators[0]
         );
     }
 
-    assert_eq!(expected_state, effects.last().unwrap().state);
+    assert_eq!(Some(expected_state), effects.last().unwrap().state);
 }
 
 #[test]
@@ -433,30 +421,44 @@ fn test_long_plan() {
         .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(50)));
 
     let rob_people = simple_increment_action("rob", "gold", Datum::I64(1))
-        .with_effect(Effect {
-            action: "rob".to_string(),
-            mutators: vec![
-                Mutator::Decrement("energy".to_string(), Datum::I64(20)),
-                Mutator::Increment("hunger".to_string(), Datum::I64(20)),
-            ],
-            state: LocalState::default(),
-            cost: 1,
-        })
+        .with_effect(
+            Effect::new("rob")
+                .with_mutator(Mutator::Decrement("energy".to_string(), Datum::I64(20)))
+                .with_mutator(Mutator::Increment("hunger".to_string(), Datum::I64(20))),
+        )
         .with_precondition("hunger", Compare::LessThanEquals(Datum::I64(50)))
         .with_precondition("energy", Compare::GreaterThanEquals(Datum::I64(50)));
 
     let actions: Vec<Action> = vec![sleep_action, eat_action, rob_people];
 
-    let plan = get_effects_from_plan(make_plan(&start, &actions[..], &goal).unwrap().0);
+    let plan = make_plan(&start, &actions[..], &goal).unwrap();
+    assert_plan_cost!(plan, 50);
+    assert_plan_reaches!(plan, goal);
 
+    let plan = effects_of_plan(&plan.0).collect::<Vec<_>>();
     assert_eq!(50, plan.len());
+    assert_eq!(Some(expected_state), plan.last().unwrap().state);
+}
 
-    assert_eq!(expected_state, plan.last().unwrap().state);
+#[test]
+fn test_plan_labels_effects_with_the_action_key() {
+    let start = LocalState::new().with_datum("is_hungry", Datum::Bool(true));
+    let goal = Goal::new().with_req("is_hungry", Compare::Equals(Datum::Bool(false)));
+
+    // The effect was built by hand with a label that doesn't match the action
+    let eat_action = Action::new("eat").with_effect(
+        Effect::new("drink")
+            .with_mutator(Mutator::Set("is_hungry".to_string(), Datum::Bool(false))),
+    );
+
+    let (nodes, _cost) = make_plan(&start, &[eat_action], &goal).unwrap();
+
+    assert_eq!(None, nodes[0].action_key());
+    assert_eq!(Some("eat"), nodes[1].action_key());
+    assert_eq!("eat", effects_of_plan(&nodes).next().unwrap().action);
 }
 
-// TODO haven't implemented `PlanningStrategy::GoalToStart` yet
 #[test]
-#[should_panic]
 fn test_reverse_strategy() {
     let start = LocalState::new().with_datum("is_hungry", Datum::Bool(true));
     let expected_state = LocalState::new().with_datum("is_hungry", Datum::Bool(false));
@@ -468,18 +470,16 @@ fn test_reverse_strategy() {
 
     let actions: Vec<Action> = vec![eat_action];
 
-    let plan = get_effects_from_plan(
-        make_plan_with_strategy(PlanningStrategy::GoalToStart, &start, &actions[..], &goal)
-            .unwrap()
-            .0,
-    );
-    assert_eq!(1, plan.len());
+    let plan = make_plan_with_strategy(PlanningStrategy::GoalToStart, &start, &actions[..], &goal)
+        .unwrap();
+    assert_plan_actions!(plan, ["eat"]);
+    assert_plan_reaches!(plan, goal);
 
-    let cons = plan.get(0).unwrap();
-    assert_eq!("eat", cons.action);
+    let plan = effects_of_plan(&plan.0).collect::<Vec<_>>();
+    let cons = plan.first().unwrap();
     assert_eq!(1, cons.mutators.len());
-    assert_eq!(eat_mutator, cons.mutators.get(0).unwrap().clone());
-    assert_eq!(expected_state, cons.state);
+    assert_eq!(eat_mutator, cons.mutators[0]);
+    assert_eq!(Some(expected_state), cons.state);
 }
 
 #[test]
@@ -507,14 +507,102 @@ fn test_prefer_lower_cost_plan() {
     let actions = vec![cheap_action, expensive_action];
 
     let plan = make_plan(&start, &actions[..], &goal).unwrap();
-    let effects = get_effects_from_plan(plan.0.clone());
+    assert_plan_actions!(plan, ["cheap_action"; 10]);
+    assert_plan_cost!(plan, 10);
+    assert_plan_reaches!(plan, goal);
 
-    println!("Found plan:");
-    println!("{:#?}", plan);
+    let effects = effects_of_plan(&plan.0).collect::<Vec<_>>();
+    assert_eq!(Some(expected_state), effects.last().unwrap().state);
+}
 
-    assert_eq!(10, effects.len());
-    for cons in &effects {
-        assert_eq!("cheap_action", cons.action);
-    }
-    assert_eq!(expected_state, effects.last().unwrap().state);
+#[test]
+#[should_panic(expected = "expected: [\"sleep\"]\n   actual: [\"eat\"]")]
+fn test_assert_plan_actions_shows_the_actual_plan() {
+    let start = LocalState::new().with_datum("is_hungry", Datum::Bool(true));
+    let goal = Goal::new().with_req("is_hungry", Compare::Equals(Datum::Bool(false)));
+    let actions = vec![simple_action("eat", "is_hungry", Datum::Bool(false))];
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+    assert_plan_actions!(plan, ["sleep"]);
+}
+
+fn two_attacks() -> Vec<Action> {
+    vec![
+        simple_decrement_action("attack", "enemy_health", Datum::I64(10)).set_cost(2),
+        simple_decrement_action("attack", "enemy_health", Datum::I64(30)).set_cost(5),
+    ]
+}
+
+#[test]
+fn test_duplicate_action_keys_are_rejected() {
+    let start = LocalState::new().with_datum("enemy_health", Datum::I64(30));
+    let goal = Goal::new().with_req("enemy_health", Compare::LessThanEquals(Datum::I64(0)));
+
+    let result = try_make_plan_with_config(&start, &two_attacks(), &goal, &PlannerConfig::new());
+
+    assert_eq!(
+        Err(PlanError::DuplicateActionKey("attack".to_string())),
+        result
+    );
+}
+
+#[test]
+#[should_panic(expected = "More than one action has the key \"attack\"")]
+fn test_make_plan_panics_on_duplicate_action_keys() {
+    let start = LocalState::new().with_datum("enemy_health", Datum::I64(30));
+    let goal = Goal::new().with_req("enemy_health", Compare::LessThanEquals(Datum::I64(0)));
+
+    make_plan(&start, &two_attacks(), &goal);
+}
+
+#[test]
+fn test_duplicate_action_keys_can_be_disambiguated() {
+    let start = LocalState::new().with_datum("enemy_health", Datum::I64(30));
+    let goal = Goal::new().with_req("enemy_health", Compare::LessThanEquals(Datum::I64(0)));
+    let config = PlannerConfig::new().with_duplicate_keys(DuplicateKeys::Disambiguate);
+
+    let plan = try_make_plan_with_config(&start, &two_attacks(), &goal, &config)
+        .unwrap()
+        .unwrap();
+
+    assert_plan_actions!(plan, ["attack#1"]);
+    assert_plan_cost!(plan, 5);
+}
+
+#[test]
+fn test_plan_accessors_borrow_the_plan() {
+    let start = LocalState::new()
+        .with_datum("is_hungry", Datum::Bool(true))
+        .with_datum("is_tired", Datum::Bool(true));
+    let goal = Goal::new()
+        .with_req("is_hungry", Compare::Equals(Datum::Bool(false)))
+        .with_req("is_tired", Compare::Equals(Datum::Bool(false)));
+    let actions = vec![
+        simple_action("eat", "is_hungry", Datum::Bool(false)),
+        simple_action("sleep", "is_tired", Datum::Bool(false))
+            .with_precondition("is_hungry", Compare::Equals(Datum::Bool(false))),
+    ];
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_eq!(
+        vec!["eat", "sleep"],
+        action_keys_of_plan(&plan.0).collect::<Vec<_>>()
+    );
+    let costs: Vec<usize> = effects_of_plan(&plan.0).map(|effect| effect.cost).collect();
+    assert_eq!(vec![1, 1], costs);
+    let end = final_state_of_plan(&plan.0).unwrap();
+    assert_eq!(Some(&Datum::Bool(false)), end.data.get("is_tired"));
+}
+
+#[test]
+fn test_final_state_of_plan_without_actions() {
+    let start = LocalState::new().with_datum("is_hungry", Datum::Bool(false));
+    let goal = Goal::new().with_req("is_hungry", Compare::Equals(Datum::Bool(false)));
+
+    let plan = make_plan(&start, &[], &goal).unwrap();
+
+    assert_eq!(Some(&start), final_state_of_plan(&plan.0));
+    assert_eq!(0, effects_of_plan(&plan.0).count());
+    assert_eq!(None, final_state_of_plan::<String, Datum>(&[]));
 }
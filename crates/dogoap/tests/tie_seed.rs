@@ -0,0 +1,74 @@
+use std::collections::BTreeSet;
+
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+// Plenty of ways to lunch that cost the same, and one that costs more
+fn lunch() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("fed", Datum::Bool(false))
+        .with_datum("seated", Datum::Bool(false));
+    let seated = Compare::Equals(Datum::Bool(true));
+    let actions = vec![
+        simple_action("sit_on_bench", "seated", Datum::Bool(true)),
+        simple_action("sit_on_grass", "seated", Datum::Bool(true)),
+        simple_action("eat_apple", "fed", Datum::Bool(true))
+            .with_precondition("seated", seated.clone()),
+        simple_action("eat_bread", "fed", Datum::Bool(true))
+            .with_precondition("seated", seated.clone()),
+        simple_action("eat_cheese", "fed", Datum::Bool(true)).with_precondition("seated", seated),
+        simple_action("eat_cake", "fed", Datum::Bool(true)).set_cost(3),
+    ];
+    let goal = Goal::new().with_req("fed", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+fn plan_with_seed(strategy: PlanningStrategy, seed: u64) -> Plan {
+    let (start, actions, goal) = lunch();
+    let config = PlannerConfig::new()
+        .with_strategy(strategy)
+        .with_tie_seed(seed);
+    make_plan_with_config(&start, &actions, &goal, &config).unwrap()
+}
+
+fn steps(plan: &Plan) -> Vec<String> {
+    plan_actions(plan).into_iter().map(String::from).collect()
+}
+
+#[test]
+fn test_seeds_vary_plans() {
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let mut seen = BTreeSet::new();
+        for seed in 0..50 {
+            let plan = plan_with_seed(strategy, seed);
+
+            // Never at the expense of the cost
+            assert_plan_cost!(plan, 2);
+            seen.insert(steps(&plan));
+        }
+
+        assert!(seen.len() > 1, "{:?} always made {:?}", strategy, seen);
+    }
+}
+
+#[test]
+fn test_same_seed_same_plan() {
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        for seed in [0, 7, u64::MAX] {
+            let plan = plan_with_seed(strategy, seed);
+
+            for _ in 0..5 {
+                assert_eq!(plan, plan_with_seed(strategy, seed));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_no_seed_no_change() {
+    let (start, actions, goal) = lunch();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_cost!(plan, 2);
+    assert_eq!(plan, make_plan(&start, &actions, &goal).unwrap());
+}
@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use dogoap::{prelude::*, simple::simple_increment_action};
+
+// Counting up never ends and nothing ever sets `done`, so there's no plan, but the
+// search can't tell before it runs out of time
+fn counting(keys: usize) -> (LocalState, Vec<Action>, Goal) {
+    let mut start = LocalState::new().with_datum("done", Datum::Bool(false));
+    let mut actions = vec![];
+    for key in 0..keys {
+        let name = format!("k{}", key);
+        start = start.with_datum(name.clone(), Datum::I64(0));
+        actions.push(simple_increment_action(
+            &format!("count_{}", key),
+            &name,
+            Datum::I64(1),
+        ));
+    }
+    let goal = Goal::new().with_req("done", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_large_search_gives_up_within_the_time_budget() {
+    let (start, actions, goal) = counting(20);
+    let config = PlannerConfig::new()
+        .with_time_budget(Duration::from_millis(50))
+        .with_check_interval(64);
+
+    let started = Instant::now();
+    let result = try_make_plan_with_config(&start, &actions, &goal, &config);
+
+    assert!(started.elapsed() < Duration::from_secs(5));
+    match result {
+        Err(PlanError::TimedOut(stats)) => assert!(stats.expanded_nodes > 0),
+        other => panic!("expected the search to time out, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_no_plan_is_not_a_timeout() {
+    let start = LocalState::new()
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("done", Datum::Bool(false));
+    let actions = vec![simple_increment_action("mine", "gold", Datum::I64(1))
+        .with_precondition("gold", Compare::LessThanEquals(Datum::I64(2)))];
+    let goal = Goal::new().with_req("done", Compare::Equals(Datum::Bool(true)));
+    let config = PlannerConfig::new().with_time_budget(Duration::from_secs(60));
+
+    assert_eq!(
+        Ok(None),
+        try_make_plan_with_config(&start, &actions, &goal, &config)
+    );
+}
+
+#[test]
+fn test_timed_out_search_returns_none_without_an_error() {
+    let (start, actions, goal) = counting(20);
+    let config = PlannerConfig::new().with_time_budget(Duration::from_millis(10));
+
+    assert_eq!(
+        None,
+        make_plan_with_config(&start, &actions, &goal, &config)
+    );
+}
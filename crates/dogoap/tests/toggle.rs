@@ -0,0 +1,56 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+fn door(open: bool) -> LocalState {
+    LocalState::new()
+        .with_datum("door_open", Datum::Bool(open))
+        .with_datum("is_inside", Datum::Bool(false))
+}
+
+fn doorway() -> Vec<Action> {
+    vec![
+        simple_toggle_action("use_door", "door_open"),
+        simple_action("walk_in", "is_inside", Datum::Bool(true))
+            .with_precondition("door_open", Compare::Equals(Datum::Bool(true))),
+    ]
+}
+
+#[test]
+fn test_toggle_from_false_to_true() {
+    let goal = Goal::new().with_req("door_open", Compare::Equals(Datum::Bool(true)));
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let plan = make_plan_with_strategy(strategy, &door(false), &doorway(), &goal).unwrap();
+
+        assert_plan_actions!(plan, ["use_door"]);
+        assert_plan_reaches!(plan, goal);
+    }
+}
+
+#[test]
+fn test_toggle_flips_whatever_the_value_is() {
+    let goal = Goal::new()
+        .with_req("is_inside", Compare::Equals(Datum::Bool(true)))
+        .with_req("door_open", Compare::Equals(Datum::Bool(false)));
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        // Open it, walk in and close it behind us
+        let plan = make_plan_with_strategy(strategy, &door(false), &doorway(), &goal).unwrap();
+        assert_plan_actions!(plan, ["use_door", "walk_in", "use_door"]);
+
+        // Already open, so only closing it
+        let plan = make_plan_with_strategy(strategy, &door(true), &doorway(), &goal).unwrap();
+        assert_plan_actions!(plan, ["walk_in", "use_door"]);
+    }
+}
+
+#[test]
+fn test_toggle_leaves_other_values_alone() {
+    let start = LocalState::new().with_datum("gold", Datum::I64(3));
+    let effect = Effect::new("flip").with_mutator(Mutator::Toggle("gold".to_string()));
+
+    let state = effect.apply(&start, MissingKeyPolicy::Insert).unwrap();
+
+    assert_eq!(start, state);
+    let toggle: Mutator = Mutator::Toggle("door_open".to_string());
+    assert_eq!("door_open = !door_open", toggle.to_string());
+}
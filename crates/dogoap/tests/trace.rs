@@ -0,0 +1,169 @@
+use dogoap::{prelude::*, simple::simple_action, testing::*};
+
+// Crossing the lava is cheap but hurts, walking around is safe but costs more
+fn lava() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("health", Datum::I64(2))
+        .with_datum("across", Datum::Bool(false))
+        .with_datum("treasure", Datum::Bool(false));
+    let across = |value| Compare::Equals(Datum::Bool(value));
+    let actions = vec![
+        simple_action("cross_lava", "across", Datum::Bool(true))
+            .with_precondition("across", across(false))
+            .add_mutator(Mutator::Decrement("health".to_string(), Datum::I64(2))),
+        simple_action("walk_around", "across", Datum::Bool(true))
+            .with_precondition("across", across(false))
+            .set_cost(5),
+        simple_action("grab", "treasure", Datum::Bool(true))
+            .with_precondition("across", across(true)),
+    ];
+    let goal = Goal::new().with_req("treasure", Compare::Equals(Datum::Bool(true)));
+    (start, actions, goal)
+}
+
+fn alive() -> Invariant {
+    Invariant::new().with_condition("health", Compare::GreaterThanEquals(Datum::I64(1)))
+}
+
+fn generated(events: &[&TraceEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::SuccessorGenerated { action, .. } => Some(action.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_trace_ends_at_the_goal() {
+    let (start, actions, goal) = lava();
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let config = PlannerConfig::new().with_strategy(strategy);
+
+        let (plan, trace) = make_plan_with_trace(&start, &actions, &goal, &config);
+        let plan = plan.unwrap();
+
+        assert_plan_actions!(plan, ["cross_lava", "grab"]);
+        assert!(!trace.truncated);
+        assert_eq!(
+            Some(&TraceEvent::GoalReached { node: 3, cost: 2 }),
+            trace.events.last()
+        );
+        assert_eq!(
+            TraceEvent::NodeExpanded {
+                node: 0,
+                cost: 0,
+                heuristic: 1000
+            },
+            trace.events[0]
+        );
+    }
+}
+
+#[test]
+fn test_winning_path() {
+    let (start, actions, goal) = lava();
+
+    let (plan, trace) = make_plan_with_trace(&start, &actions, &goal, &PlannerConfig::new());
+    let path = trace.winning_path();
+
+    assert_eq!(plan_actions(&plan.unwrap()), generated(&path));
+    // walk_around was generated, but isn't on the way
+    assert_eq!(3, generated(&trace.events.iter().collect::<Vec<_>>()).len());
+    assert_eq!(
+        vec![
+            "expanded #0 (cost 0, heuristic 1.000)",
+            "#0 -> #1 with cross_lava (cost 1)",
+            "expanded #1 (cost 1, heuristic 1.000)",
+            "#1 -> #3 with grab (cost 2)",
+            "reached the goal at #3 (cost 2)",
+        ],
+        path.iter()
+            .map(|event| event.to_string())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_pruned_by_invariant() {
+    let (start, actions, goal) = lava();
+    let config = PlannerConfig::new().with_invariant(alive());
+
+    let (plan, trace) = make_plan_with_trace(&start, &actions, &goal, &config);
+
+    assert_plan_actions!(plan.unwrap(), ["walk_around", "grab"]);
+    let pruned = TraceEvent::NodePruned {
+        parent: 0,
+        action: "cross_lava".to_string(),
+        by: PrunedBy::Invariant,
+    };
+    assert!(trace.events.contains(&pruned));
+    assert_eq!(
+        "#0 pruned cross_lava (breaks an invariant)",
+        pruned.to_string()
+    );
+}
+
+#[test]
+fn test_pruned_by_limit() {
+    let (start, actions, goal) = lava();
+    let config = PlannerConfig::new()
+        .with_invariant(alive())
+        .with_max_cost(4);
+
+    let (plan, trace) = make_plan_with_trace(&start, &actions, &goal, &config);
+
+    assert_eq!(None, plan);
+    assert!(trace.events.contains(&TraceEvent::NodePruned {
+        parent: 0,
+        action: "walk_around".to_string(),
+        by: PrunedBy::Limit,
+    }));
+    assert!(trace.winning_path().is_empty());
+}
+
+#[test]
+fn test_pruned_as_duplicate() {
+    // Switching the light back on reaches the same node as the first time, only
+    // at a higher cost, and nothing ever repairs it
+    let start = LocalState::new()
+        .with_datum("light", Datum::Bool(false))
+        .with_datum("repaired", Datum::Bool(false));
+    let actions = vec![
+        simple_action("switch_on", "light", Datum::Bool(true)),
+        simple_action("switch_off", "light", Datum::Bool(false)),
+    ];
+    let goal = Goal::new().with_req("repaired", Compare::Equals(Datum::Bool(true)));
+
+    let (plan, trace) = make_plan_with_trace(&start, &actions, &goal, &PlannerConfig::new());
+
+    assert_eq!(None, plan);
+    let duplicates = trace
+        .events
+        .iter()
+        .filter(|event| {
+            matches!(
+                event,
+                TraceEvent::NodePruned {
+                    by: PrunedBy::Duplicate,
+                    ..
+                }
+            )
+        })
+        .count();
+    assert!(duplicates > 0);
+}
+
+#[test]
+fn test_trace_is_capped() {
+    let (start, actions, goal) = lava();
+    let config = PlannerConfig::new().with_max_trace_events(2);
+
+    let (plan, trace) = make_plan_with_trace(&start, &actions, &goal, &config);
+
+    // The search itself isn't cut short
+    assert_plan_actions!(plan.unwrap(), ["cross_lava", "grab"]);
+    assert_eq!(2, trace.events.len());
+    assert!(trace.truncated);
+}
@@ -0,0 +1,100 @@
+use dogoap::{prelude::*, simple::simple_action};
+
+fn issue(site: TypeIssueSite, key: &str, expected: DatumKind, found: DatumKind) -> TypeIssue {
+    TypeIssue {
+        site,
+        key: key.to_string(),
+        expected,
+        found,
+    }
+}
+
+#[test]
+fn test_consistent_domain_has_no_issues() {
+    let start = LocalState::new()
+        .with_datum("energy", Datum::I64(50))
+        .with_datum("is_hungry", Datum::Bool(true));
+    let actions = vec![
+        simple_action("eat", "is_hungry", Datum::Bool(false)),
+        Action::new("sleep")
+            .with_precondition("energy", Compare::LessThanEquals(Datum::I64(10)))
+            .add_mutator(Mutator::Increment("energy".to_string(), Datum::I64(50))),
+        simple_action("find_bed", "has_bed", Datum::Bool(true)),
+    ];
+    let goal = Goal::new()
+        .with_req("is_hungry", Compare::Equals(Datum::Bool(false)))
+        .with_req("has_bed", Compare::Equals(Datum::Bool(true)));
+
+    assert_eq!(
+        Vec::<TypeIssue>::new(),
+        typecheck_domain(&start, &actions, &goal)
+    );
+}
+
+#[test]
+fn test_flags_mutators_with_the_wrong_kind() {
+    let start = LocalState::new().with_datum("is_tired", Datum::Bool(true));
+    let actions =
+        vec![Action::new("rest")
+            .add_mutator(Mutator::Increment("is_tired".to_string(), Datum::I64(1)))];
+
+    assert_eq!(
+        vec![issue(
+            TypeIssueSite::Mutator("rest".to_string()),
+            "is_tired",
+            DatumKind::Bool,
+            DatumKind::I64,
+        )],
+        typecheck_domain(&start, &actions, &Goal::new())
+    );
+}
+
+#[test]
+fn test_infers_kinds_of_keys_missing_from_the_start_from_set() {
+    let actions = vec![
+        simple_action("craft", "swords", Datum::I64(1)),
+        simple_action("break", "swords", Datum::F64(0.0)),
+    ];
+    let goal = Goal::new().with_req("swords", Compare::GreaterThanEquals(Datum::Bool(true)));
+
+    assert_eq!(
+        vec![
+            issue(
+                TypeIssueSite::Mutator("break".to_string()),
+                "swords",
+                DatumKind::I64,
+                DatumKind::F64,
+            ),
+            issue(
+                TypeIssueSite::Goal,
+                "swords",
+                DatumKind::I64,
+                DatumKind::Bool
+            ),
+        ],
+        typecheck_domain(&LocalState::new(), &actions, &goal)
+    );
+}
+
+#[test]
+fn test_flags_preconditions_with_the_wrong_kind() {
+    let start = LocalState::new().with_datum("location", Datum::Enum(0));
+    let actions = vec![simple_action("go_home", "location", Datum::Enum(1))
+        .with_precondition("location", Compare::NotEquals(Datum::I64(1)))];
+
+    let issues = typecheck_domain(&start, &actions, &Goal::new());
+
+    assert_eq!(
+        vec![issue(
+            TypeIssueSite::Precondition("go_home".to_string()),
+            "location",
+            DatumKind::Enum,
+            DatumKind::I64,
+        )],
+        issues
+    );
+    assert_eq!(
+        "precondition of action \"go_home\" uses a I64 with key \"location\", which holds a Enum",
+        issues[0].to_string()
+    );
+}
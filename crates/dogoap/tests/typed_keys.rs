@@ -0,0 +1,75 @@
+use std::fmt;
+
+use dogoap::{prelude::*, testing::*};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Key {
+    Wood,
+    Planks,
+    HasAxe,
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+fn carpentry() -> (LocalState<Key>, Vec<Action<Key>>, Goal<Key>) {
+    let start = LocalState::default()
+        .with_datum(Key::Wood, Datum::I64(0))
+        .with_datum(Key::Planks, Datum::I64(0))
+        .with_datum(Key::HasAxe, Datum::Bool(false));
+    let actions = vec![
+        Action::named("get_axe").add_mutator(Mutator::Set(Key::HasAxe, Datum::Bool(true))),
+        Action::named("chop")
+            .with_precondition(Key::HasAxe, Compare::Equals(Datum::Bool(true)))
+            .add_mutator(Mutator::Increment(Key::Wood, Datum::I64(1))),
+        Action::named("saw")
+            .with_precondition(Key::Wood, Compare::GreaterThanEquals(Datum::I64(1)))
+            .add_mutator(Mutator::Decrement(Key::Wood, Datum::I64(1)))
+            .add_mutator(Mutator::Increment(Key::Planks, Datum::I64(2))),
+    ];
+    let goal = Goal::default().with_req(Key::Planks, Compare::GreaterThanEquals(Datum::I64(4)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_enum_keys() {
+    let (start, actions, goal) = carpentry();
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["get_axe", "chop", "saw", "chop", "saw"]);
+    assert_plan_reaches!(plan, goal);
+    assert_eq!(
+        Some(&Datum::I64(4)),
+        plan.0.last().unwrap().state().data.get(&Key::Planks)
+    );
+}
+
+#[test]
+fn test_enum_keys_with_every_strategy() {
+    let (start, actions, goal) = carpentry();
+
+    for strategy in [
+        PlanningStrategy::StartToGoal,
+        PlanningStrategy::GoalToStart,
+        PlanningStrategy::Auto,
+    ] {
+        let plan = make_plan_with_strategy(strategy, &start, &actions, &goal).unwrap();
+        assert_plan_cost!(plan, 5);
+        assert_plan_reaches!(plan, goal);
+    }
+}
+
+#[test]
+fn test_static_str_keys() {
+    let start = LocalState::<&str>::default().with_datum("is_hungry", Datum::Bool(true));
+    let actions = [Action::named("eat").add_mutator(Mutator::Set("is_hungry", Datum::Bool(false)))];
+    let goal = Goal::default().with_req("is_hungry", Compare::Equals(Datum::Bool(false)));
+
+    let plan = make_plan(&start, &actions, &goal).unwrap();
+
+    assert_plan_actions!(plan, ["eat"]);
+}
@@ -0,0 +1,51 @@
+use dogoap::{prelude::*, simple::*, testing::*};
+
+// Gold is far from the goal by the numbers, but with a pickaxe one cheap swing
+// gets all of it, so counting the distance as cost overestimates by a lot
+fn mining() -> (LocalState, Vec<Action>, Goal) {
+    let start = LocalState::new()
+        .with_datum("gold", Datum::I64(0))
+        .with_datum("has_pickaxe", Datum::Bool(false));
+    let actions = vec![
+        simple_increment_action("pan_for_gold", "gold", Datum::I64(100)).set_cost(10),
+        simple_action("get_pickaxe", "has_pickaxe", Datum::Bool(true)),
+        simple_increment_action("mine", "gold", Datum::I64(100))
+            .with_precondition("has_pickaxe", Compare::Equals(Datum::Bool(true))),
+    ];
+    let goal = Goal::new().with_req("gold", Compare::GreaterThanEquals(Datum::I64(100)));
+    (start, actions, goal)
+}
+
+#[test]
+fn test_uniform_cost_finds_the_cheapest_plan() {
+    let (start, actions, goal) = mining();
+
+    let greedy = make_plan(&start, &actions, &goal).unwrap();
+    let plan =
+        make_plan_with_strategy(PlanningStrategy::UniformCost, &start, &actions, &goal).unwrap();
+
+    // The heuristic makes the pickaxe look like a detour
+    assert_plan_cost!(greedy, 10);
+    assert_plan_actions!(plan, ["get_pickaxe", "mine"]);
+    assert_plan_cost!(plan, 2);
+    assert_plan_reaches!(plan, goal);
+}
+
+#[test]
+fn test_uniform_cost_isnt_overridden_by_forward_only_options() {
+    let (start, actions, goal) = mining();
+    let config = PlannerConfig::new()
+        .with_strategy(PlanningStrategy::UniformCost)
+        .with_avoid(
+            vec![(
+                "gold".to_string(),
+                Compare::GreaterThanEquals(Datum::I64(1000)),
+            )],
+            5,
+        );
+
+    let (plan, stats) = make_plan_with_stats(&start, &actions, &goal, &config);
+
+    assert_eq!(PlanningStrategy::UniformCost, stats.strategy);
+    assert_eq!(2, effects_of_plan(&plan.unwrap().0).count());
+}
@@ -0,0 +1,72 @@
+use dogoap::{prelude::*, testing::*};
+
+fn destroy() -> Action {
+    Action::new("destroy").with_effect(
+        Effect::new("destroy")
+            .with_mutator(Mutator::Unset("target".to_string()))
+            .with_mutator(Mutator::Set("destroyed".to_string(), Datum::Bool(true))),
+    )
+}
+
+fn start() -> LocalState {
+    LocalState::new()
+        .with_datum("target", Datum::I64(3))
+        .with_datum("destroyed", Datum::Bool(false))
+        .with_datum("looted", Datum::Bool(false))
+}
+
+#[test]
+fn test_unset_removes_the_key() {
+    let goal = Goal::new().with_req("destroyed", Compare::Equals(Datum::Bool(true)));
+    let plan = make_plan(&start(), &[destroy()], &goal).unwrap();
+
+    assert_plan_actions!(plan, ["destroy"]);
+    let Node::Effect(effect) = plan.0.last().unwrap() else {
+        panic!("Expected an effect");
+    };
+    let state = effect.state.as_ref().unwrap();
+    assert!(!state.data.contains_key("target"));
+    assert_eq!(Some(&Datum::Bool(true)), state.data.get("destroyed"));
+}
+
+#[test]
+fn test_unset_key_is_unmet_instead_of_panicking() {
+    // Looting needs the target, so it has to happen before destroying it
+    let loot = Action::new("loot")
+        .with_precondition("target", Compare::GreaterThanEquals(Datum::I64(1)))
+        .with_effect(
+            Effect::new("loot").with_mutator(Mutator::Set("looted".to_string(), Datum::Bool(true))),
+        );
+    let goal = Goal::new()
+        .with_req("destroyed", Compare::Equals(Datum::Bool(true)))
+        .with_req("looted", Compare::Equals(Datum::Bool(true)));
+
+    for strategy in [PlanningStrategy::StartToGoal, PlanningStrategy::GoalToStart] {
+        let config = PlannerConfig::default().with_strategy(strategy);
+        let plan = make_plan_with_config(&start(), &[destroy(), loot.clone()], &goal, &config);
+
+        assert_plan_actions!(plan.unwrap(), ["loot", "destroy"]);
+    }
+}
+
+#[test]
+fn test_goal_on_unset_key_is_not_met() {
+    let goal = Goal::new().with_req("target", Compare::NotEquals(Datum::I64(3)));
+    let state = destroy().effects[0]
+        .apply(&start(), MissingKeyPolicy::Reject)
+        .unwrap();
+
+    assert!(!goal.is_satisfied(&state));
+    assert!(make_plan(&start(), &[destroy()], &goal).is_none());
+}
+
+#[test]
+fn test_unsetting_a_missing_key_is_not_rejected() {
+    let state = LocalState::new().with_datum("destroyed", Datum::Bool(false));
+    let reached = destroy().effects[0].apply(&state, MissingKeyPolicy::Reject);
+
+    assert_eq!(
+        Some(&Datum::Bool(true)),
+        reached.unwrap().data.get("destroyed")
+    );
+}